@@ -0,0 +1,26 @@
+-- Your SQL goes here
+CREATE TABLE kline_gap (
+                           id             VARCHAR(250) PRIMARY KEY COMMENT 'exchange+symbol+time_frame+store+gap_start base64编码',
+
+                           exchange       VARCHAR(64)  NOT NULL COMMENT '交易所名称，例如 binance',
+                           symbol         VARCHAR(64)  NOT NULL COMMENT '交易对名称，例如 BTCUSDT',
+                           time_frame     VARCHAR(16)  NOT NULL COMMENT 'K线周期，例如 1m、5m、1h',
+                           store          VARCHAR(16)  NOT NULL COMMENT '存储位置：mysql / clickhouse',
+
+                           gap_start      BIGINT       NOT NULL COMMENT '第一根缺失K线的 close_time（毫秒）',
+                           gap_end        BIGINT       NOT NULL COMMENT '最后一根缺失K线的 close_time（毫秒）',
+                           missing_count  BIGINT       NOT NULL COMMENT '缺失K线数量',
+                           filled_count   BIGINT       NOT NULL DEFAULT 0 COMMENT '已补齐K线数量',
+
+                           status         VARCHAR(16)  NOT NULL COMMENT '状态：found / filled / unfillable',
+                           attempts       INT UNSIGNED NOT NULL DEFAULT 0 COMMENT '修复尝试次数',
+                           detected_at    DATETIME(3)  NOT NULL COMMENT '发现时间',
+                           updated_at     DATETIME(3)  NOT NULL COMMENT '最后更新时间',
+
+                           INDEX idx_gap_market (exchange, symbol, time_frame),
+                           INDEX idx_gap_status (status)
+)
+    ENGINE=InnoDB
+    DEFAULT CHARSET = utf8mb4
+    COLLATE = utf8mb4_0900_ai_ci
+    COMMENT = 'K线缺口检测与修复记录表';
@@ -0,0 +1,32 @@
+-- This file should undo anything in `up.sql`
+-- 仅保留 U本位数据，避免不同市场的同名交易对在旧主键下冲突
+
+DELETE FROM kline_gap WHERE market_type <> 'usdm';
+
+UPDATE kline_gap
+SET id = REPLACE(TO_BASE64(CONCAT(exchange, '|', symbol, '|', time_frame, '|', store, '|', gap_start)), '\n', '');
+
+ALTER TABLE kline_gap
+    DROP INDEX idx_gap_market,
+    ADD INDEX idx_gap_market (exchange, symbol, time_frame),
+    DROP COLUMN market_type;
+
+DELETE FROM market_kline WHERE market_type <> 'usdm';
+
+UPDATE market_kline
+SET id = REPLACE(TO_BASE64(CONCAT(exchange, '|', symbol, '|', time_frame, '|', close_time)), '\n', '');
+
+ALTER TABLE market_kline
+    DROP INDEX uq_exchange_market_type_symbol_time_frame,
+    ADD UNIQUE KEY uq_exchange_symbol_time_frame (exchange, symbol, time_frame, close_time),
+    DROP COLUMN market_type;
+
+DELETE FROM market_symbol WHERE market_type <> 'usdm';
+
+UPDATE market_symbol
+SET id = REPLACE(TO_BASE64(CONCAT(exchange, '|', symbol)), '\n', '');
+
+ALTER TABLE market_symbol
+    DROP INDEX uq_exchange_market_type_symbol,
+    ADD UNIQUE KEY uq_exchange_symbol (exchange, symbol),
+    DROP COLUMN market_type;
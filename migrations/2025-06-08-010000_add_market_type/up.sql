@@ -0,0 +1,26 @@
+-- Your SQL goes here
+-- 现有数据均来自 U本位合约（fapi），默认 usdm，并按新的主键格式 exchange|market_type|... 重新编码 id
+
+ALTER TABLE market_symbol
+    ADD COLUMN market_type VARCHAR(16) NOT NULL DEFAULT 'usdm' COMMENT '市场类型：spot / usdm / coinm' AFTER exchange,
+    DROP INDEX uq_exchange_symbol,
+    ADD UNIQUE KEY uq_exchange_market_type_symbol (exchange, market_type, symbol);
+
+UPDATE market_symbol
+SET id = REPLACE(TO_BASE64(CONCAT(exchange, '|', market_type, '|', symbol)), '\n', '');
+
+ALTER TABLE market_kline
+    ADD COLUMN market_type VARCHAR(16) NOT NULL DEFAULT 'usdm' COMMENT '市场类型：spot / usdm / coinm' AFTER exchange,
+    DROP INDEX uq_exchange_symbol_time_frame,
+    ADD UNIQUE KEY uq_exchange_market_type_symbol_time_frame (exchange, market_type, symbol, time_frame, close_time);
+
+UPDATE market_kline
+SET id = REPLACE(TO_BASE64(CONCAT(exchange, '|', market_type, '|', symbol, '|', time_frame, '|', close_time)), '\n', '');
+
+ALTER TABLE kline_gap
+    ADD COLUMN market_type VARCHAR(16) NOT NULL DEFAULT 'usdm' COMMENT '市场类型：spot / usdm / coinm' AFTER exchange,
+    DROP INDEX idx_gap_market,
+    ADD INDEX idx_gap_market (exchange, market_type, symbol, time_frame);
+
+UPDATE kline_gap
+SET id = REPLACE(TO_BASE64(CONCAT(exchange, '|', market_type, '|', symbol, '|', time_frame, '|', store, '|', gap_start)), '\n', '');
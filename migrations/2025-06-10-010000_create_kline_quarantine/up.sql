@@ -0,0 +1,25 @@
+-- Your SQL goes here
+CREATE TABLE kline_quarantine (
+                                  id            VARCHAR(250) PRIMARY KEY COMMENT 'exchange+market_type+symbol+time_frame+close_time+rule base64编码',
+
+                                  exchange      VARCHAR(64)  NOT NULL COMMENT '交易所名称，例如 binance',
+                                  market_type   VARCHAR(16)  NOT NULL COMMENT '市场类型：spot / usdm / coinm',
+                                  symbol        VARCHAR(64)  NOT NULL COMMENT '交易对名称，例如 BTCUSDT',
+                                  time_frame    VARCHAR(16)  NOT NULL COMMENT 'K线周期，例如 1m、5m、1h',
+                                  direction     VARCHAR(16)  NOT NULL COMMENT '归档方向：forward / backward',
+
+                                  open_time     BIGINT       NOT NULL COMMENT 'K线开盘时间（毫秒）',
+                                  close_time    BIGINT       NOT NULL COMMENT 'K线收盘时间（毫秒）',
+                                  rule          VARCHAR(32)  NOT NULL COMMENT '未通过的校验规则',
+                                  detail        VARCHAR(255) NOT NULL COMMENT '失败原因描述',
+                                  payload       JSON         NOT NULL COMMENT '被拒绝的原始K线',
+
+                                  detected_at   DATETIME(3)  NOT NULL COMMENT '发现时间',
+
+                                  INDEX idx_quarantine_market (exchange, market_type, symbol, time_frame),
+                                  INDEX idx_quarantine_rule (rule)
+)
+    ENGINE=InnoDB
+    DEFAULT CHARSET = utf8mb4
+    COLLATE = utf8mb4_0900_ai_ci
+    COMMENT = 'K线数据质量校验隔离表';
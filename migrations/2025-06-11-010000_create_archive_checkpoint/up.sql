@@ -0,0 +1,28 @@
+-- Your SQL goes here
+CREATE TABLE archive_checkpoint (
+                                    id            VARCHAR(250) PRIMARY KEY COMMENT 'exchange+market_type+symbol+time_frame+direction+window_start base64编码',
+
+                                    exchange      VARCHAR(64)  NOT NULL COMMENT '交易所名称，例如 binance',
+                                    market_type   VARCHAR(16)  NOT NULL COMMENT '市场类型：spot / usdm / coinm',
+                                    symbol        VARCHAR(64)  NOT NULL COMMENT '交易对名称，例如 BTCUSDT',
+                                    time_frame    VARCHAR(16)  NOT NULL COMMENT 'K线周期，例如 1m、5m、1h',
+                                    direction     VARCHAR(16)  NOT NULL COMMENT '归档方向：forward / backward',
+
+                                    window_start  BIGINT       NOT NULL COMMENT '窗口起点（毫秒，含）',
+                                    window_end    BIGINT       NOT NULL COMMENT '窗口终点（毫秒）',
+
+                                    status        VARCHAR(16)  NOT NULL COMMENT '状态：pending / fetched / flushed / failed',
+                                    attempts      INT UNSIGNED NOT NULL DEFAULT 0 COMMENT '失败次数',
+                                    kline_count   BIGINT       NOT NULL DEFAULT 0 COMMENT '拉取到的K线数量',
+                                    last_error    VARCHAR(255) NULL COMMENT '最近一次失败原因',
+
+                                    created_at    DATETIME(3)  NOT NULL COMMENT '创建时间',
+                                    updated_at    DATETIME(3)  NOT NULL COMMENT '最后更新时间',
+
+                                    INDEX idx_checkpoint_task (exchange, market_type, symbol, time_frame, direction),
+                                    INDEX idx_checkpoint_status (status)
+)
+    ENGINE=InnoDB
+    DEFAULT CHARSET = utf8mb4
+    COLLATE = utf8mb4_0900_ai_ci
+    COMMENT = 'K线归档窗口检查点表';
@@ -2,13 +2,26 @@ use crate::collector::archive::types::ArchiveDirection;
 use crate::domain::model::market_kline::NewOrUpdateMarketKline as MarketKlineInsertMySQL;
 use crate::infra::external::binance::market::KlineSummary;
 use crate::model::cex::kline::MarketKline as MarketKlineInsertCK;
+use crate::model::market_type::MarketType;
+use serde::{Deserialize, Serialize};
 
+pub mod backfill;
+pub mod checkpoint;
+pub mod dead_letter;
+pub mod derivatives;
 pub mod dispatch_worker;
+pub mod dispatcher;
+pub mod dump_import;
 pub mod fetch;
 pub mod flush;
+pub mod gap;
 pub mod kline_buffer;
+pub mod listing;
 pub mod sink;
-mod types;
+pub mod tiering;
+pub mod types;
+pub mod universe;
+pub mod validate;
 
 /// Trait：将 KlineMessage 转换为不同目标数据库的批量插入结构
 pub trait IntoSinkRows<T> {
@@ -16,7 +29,7 @@ pub trait IntoSinkRows<T> {
 }
 
 /// 表示一批 K线数据及其元信息（交易对、交易所、时间周期）
-#[derive(Default, Clone, Debug)]
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
 pub struct KlineMessage {
     /// K线数据摘要列表
     pub datas: Vec<KlineSummary>,
@@ -27,11 +40,17 @@ pub struct KlineMessage {
     /// 交易所名称，例如 "binance"
     pub exchange: String,
 
+    /// 市场类型：现货 / U本位 / 币本位，区分同名交易对
+    pub market_type: MarketType,
+
     /// K线周期，例如 "1m", "5m", "1h"
     pub time_frame: String,
 
     /// 数据归档方向
     pub archive_direction: ArchiveDirection,
+
+    /// 归档窗口检查点 ID，写库后据此标记窗口完成；实时流与重采样消息为 None
+    pub checkpoint_id: Option<String>,
 }
 
 impl IntoSinkRows<MarketKlineInsertMySQL> for KlineMessage {
@@ -42,6 +61,7 @@ impl IntoSinkRows<MarketKlineInsertMySQL> for KlineMessage {
                 let base: MarketKlineInsertMySQL = (
                     k,
                     self.exchange.as_str(),
+                    self.market_type.as_str(),
                     self.symbol.as_str(),
                     self.time_frame.as_str(),
                 )
@@ -60,6 +80,7 @@ impl IntoSinkRows<MarketKlineInsertCK> for KlineMessage {
                 let base: MarketKlineInsertCK = (
                     k,
                     self.exchange.as_str(),
+                    self.market_type.as_str(),
                     self.symbol.as_str(),
                     self.time_frame.as_str(),
                 )
@@ -0,0 +1,545 @@
+use crate::collector::archive::fetch::execute_archive_messages;
+use crate::collector::archive::fetch::helper::create_aligned_windows_with_limit;
+use crate::collector::archive::fetch::kline_fetcher;
+use crate::collector::archive::flush::flush_to;
+use crate::collector::archive::gap::GapStore;
+use crate::collector::archive::sink::sink_by_name;
+use crate::collector::archive::types::{ArchiveTask, ArchiveWindow};
+use crate::common::utils::{get_env_bool, get_env_or};
+use crate::global::{get_binance_limiter, get_kline_validator, get_shutdown};
+use crate::infra::external::rate_limiter::binance_limiter::BinanceLimiter;
+use crate::infra::external::rate_limiter::request_limiter::RequestLimiter;
+use crate::infra::external::rate_limiter::{request_budget, RateBudget};
+use crate::model::market_type::MarketType;
+use crate::model::TimeFrame;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{watch, RwLock};
+use tracing::{info, warn};
+
+/// 单个窗口覆盖的K线数量，与常规归档一致
+const KLINES_PER_WINDOW: i64 = 1000;
+
+/// 回补任务状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackfillStatus {
+    Running,
+    Paused,
+    Cancelled,
+    Completed,
+}
+
+/// 对回补任务的控制操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackfillAction {
+    Pause,
+    Resume,
+    Cancel,
+}
+
+impl BackfillStatus {
+    pub fn is_finished(&self) -> bool {
+        matches!(self, BackfillStatus::Cancelled | BackfillStatus::Completed)
+    }
+
+    /// 状态迁移：仅运行中可暂停，仅暂停中可恢复，结束前均可取消
+    pub fn apply(self, action: BackfillAction) -> Result<BackfillStatus, String> {
+        match (self, action) {
+            (BackfillStatus::Running, BackfillAction::Pause) => Ok(BackfillStatus::Paused),
+            (BackfillStatus::Paused, BackfillAction::Resume) => Ok(BackfillStatus::Running),
+            (BackfillStatus::Running | BackfillStatus::Paused, BackfillAction::Cancel) => {
+                Ok(BackfillStatus::Cancelled)
+            }
+            (status, action) => Err(format!("Cannot {:?} a {:?} backfill job", action, status)),
+        }
+    }
+}
+
+/// 发起回补的请求参数，时间为毫秒时间戳，区间为 [start, end)
+#[derive(Debug, Clone, Deserialize)]
+pub struct BackfillRequest {
+    pub exchange: String,
+    #[serde(default)]
+    pub market_type: MarketType,
+    pub symbol: String,
+    pub time_frame: String,
+    pub start: i64,
+    pub end: i64,
+    /// 写入位置：mysql / clickhouse，默认启用 ClickHouse 时写入 ClickHouse
+    pub store: Option<String>,
+    /// 占用交易所限流预算的比例 (0, 1]，默认取 `BACKFILL_RATE_SHARE`
+    pub rate_share: Option<f64>,
+}
+
+impl BackfillRequest {
+    /// 统一为归档使用的写法：交易所小写、交易对大写，与归档、查询与实时推送读取的序列一致
+    fn normalized(mut self) -> Self {
+        self.exchange = self.exchange.trim().to_lowercase();
+        self.symbol = self.symbol.trim().to_uppercase();
+        self
+    }
+}
+
+/// 回补任务进度
+#[derive(Debug, Clone, Serialize)]
+pub struct BackfillProgress {
+    pub id: String,
+    pub exchange: String,
+    pub market_type: MarketType,
+    pub symbol: String,
+    pub time_frame: String,
+    pub start: i64,
+    pub end: i64,
+    pub store: String,
+    pub rate_share: f64,
+    pub status: BackfillStatus,
+    pub total_windows: usize,
+    pub completed_windows: usize,
+    pub failed_windows: usize,
+    pub klines_written: usize,
+    /// 已处理窗口百分比
+    pub percent: f64,
+    /// 正在处理的窗口起点
+    pub current_window_start: Option<i64>,
+    pub last_error: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl BackfillProgress {
+    /// 已结束且超过保留时长的任务可被清理
+    fn is_expired(&self, now: i64, retention_ms: i64) -> bool {
+        self.status.is_finished() && now - self.updated_at >= retention_ms
+    }
+
+    /// 按已处理窗口更新完成百分比
+    fn record_window(&mut self, result: &anyhow::Result<usize>) {
+        match result {
+            Ok(count) => {
+                self.completed_windows += 1;
+                self.klines_written += count;
+            }
+            Err(e) => {
+                self.failed_windows += 1;
+                self.last_error = Some(e.to_string());
+            }
+        }
+        let processed = self.completed_windows + self.failed_windows;
+        self.percent = (processed as f64 * 100.0 / self.total_windows.max(1) as f64).min(100.0);
+        self.updated_at = Utc::now().timestamp_millis();
+    }
+}
+
+struct BackfillJob {
+    progress: RwLock<BackfillProgress>,
+    control: watch::Sender<BackfillStatus>,
+}
+
+/// 交易所当前每分钟可发出的K线请求数
+///
+/// Binance 取限流器当前生效的权重上限（会随 exchangeInfo 同步调整）按单次请求权重折算，其余交易所取固定预算
+pub fn current_requests_per_minute(exchange: &str, market_type: MarketType) -> u32 {
+    match exchange {
+        "binance" => {
+            let (_, per_request) = request_budget(exchange);
+            get_binance_limiter().weight_limit(market_type)
+                / BinanceLimiter::weight_for_limit(per_request)
+        }
+        _ => request_budget(exchange).0,
+    }
+}
+
+/// 按占比计算回补任务自身每分钟可发出的请求数，至少 1 次
+pub fn paced_requests_per_minute(per_minute: u32, rate_share: f64) -> u32 {
+    ((per_minute as f64 * rate_share).floor() as u32).max(1)
+}
+
+/// 单个窗口需要的请求次数
+pub fn requests_per_window(exchange: &str) -> u32 {
+    let (_, per_request) = request_budget(exchange);
+    (KLINES_PER_WINDOW as u32).div_ceil(per_request)
+}
+
+/// 按需回补管理器：在指定时间区间内规划对齐窗口并限速拉取，支持暂停、恢复与取消
+///
+/// - `BACKFILL_RATE_SHARE`：默认占用交易所限流预算的比例，默认 0.25
+/// - `BACKFILL_JOB_RETENTION_SECS`：已结束任务保留查询的时长，默认 86400，超时后在启动新任务时清理
+pub struct BackfillManager {
+    jobs: RwLock<BTreeMap<String, Arc<BackfillJob>>>,
+    sequence: AtomicU64,
+    retention_ms: i64,
+}
+
+impl Default for BackfillManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BackfillManager {
+    pub fn new() -> Self {
+        Self {
+            jobs: RwLock::new(BTreeMap::new()),
+            sequence: AtomicU64::new(1),
+            retention_ms: get_env_or("BACKFILL_JOB_RETENTION_SECS", 86_400i64).max(0) * 1000,
+        }
+    }
+
+    /// 校验参数并启动回补任务
+    pub async fn start(&self, req: BackfillRequest) -> Result<BackfillProgress, String> {
+        let req = req.normalized();
+        let tf = TimeFrame::from_str(&req.time_frame)?;
+        if req.start >= req.end {
+            return Err("start must be earlier than end".to_string());
+        }
+
+        let fetcher = kline_fetcher(&req.exchange, req.market_type).map_err(|e| e.to_string())?;
+        if !fetcher.supports(&tf) {
+            return Err(format!(
+                "{} does not provide {} klines",
+                req.exchange, req.time_frame
+            ));
+        }
+
+        let clickhouse_enabled = get_env_bool("ENABLE_CLICKHOUSE", true);
+        let store = match req.store.as_deref() {
+            None if clickhouse_enabled => GapStore::ClickHouse,
+            None => GapStore::MySql,
+            Some(name) => {
+                GapStore::from_name(name).ok_or_else(|| format!("Unsupported store: {}", name))?
+            }
+        };
+        if store == GapStore::ClickHouse && !clickhouse_enabled {
+            return Err("ClickHouse is disabled (ENABLE_CLICKHOUSE=false)".to_string());
+        }
+
+        let rate_share = req
+            .rate_share
+            .unwrap_or_else(|| get_env_or("BACKFILL_RATE_SHARE", 0.25));
+        if !(rate_share > 0.0 && rate_share <= 1.0) {
+            return Err("rate_share must be in (0, 1]".to_string());
+        }
+
+        let period_ms = tf.to_millis();
+        let windows = create_aligned_windows_with_limit(
+            req.start,
+            req.end,
+            KLINES_PER_WINDOW * period_ms,
+            period_ms,
+        );
+        if windows.is_empty() {
+            return Err("No complete period in the requested range".to_string());
+        }
+
+        let now = Utc::now().timestamp_millis();
+        let mut jobs = self.jobs.write().await;
+        let mut expired = vec![];
+        for (id, job) in jobs.iter() {
+            if job.progress.read().await.is_expired(now, self.retention_ms) {
+                expired.push(id.clone());
+            }
+        }
+        for id in expired {
+            jobs.remove(&id);
+        }
+
+        for job in jobs.values() {
+            let p = job.progress.read().await;
+            if !p.status.is_finished()
+                && p.exchange == req.exchange
+                && p.market_type == req.market_type
+                && p.symbol == req.symbol
+                && p.time_frame == req.time_frame
+                && p.store == store.as_str()
+            {
+                return Err(format!(
+                    "Backfill {} is already active for this market",
+                    p.id
+                ));
+            }
+        }
+
+        let id = format!(
+            "bf-{}-{}",
+            now,
+            self.sequence.fetch_add(1, Ordering::Relaxed)
+        );
+        let progress = BackfillProgress {
+            id: id.clone(),
+            exchange: req.exchange,
+            market_type: req.market_type,
+            symbol: req.symbol,
+            time_frame: req.time_frame,
+            start: req.start,
+            end: req.end,
+            store: store.as_str().to_string(),
+            rate_share,
+            status: BackfillStatus::Running,
+            total_windows: windows.len(),
+            completed_windows: 0,
+            failed_windows: 0,
+            klines_written: 0,
+            percent: 0.0,
+            current_window_start: None,
+            last_error: None,
+            created_at: now,
+            updated_at: now,
+        };
+
+        let job = Arc::new(BackfillJob {
+            progress: RwLock::new(progress.clone()),
+            control: watch::Sender::new(BackfillStatus::Running),
+        });
+        jobs.insert(id.clone(), job.clone());
+
+        info!(
+            "Started backfill {}: {} {} {} {} [{}, {}) in {} windows to {}",
+            id,
+            progress.exchange,
+            progress.market_type,
+            progress.symbol,
+            progress.time_frame,
+            progress.start,
+            progress.end,
+            progress.total_windows,
+            progress.store
+        );
+        tokio::spawn(run_job(job, tf, store, windows));
+
+        Ok(progress)
+    }
+
+    pub async fn list(&self) -> Vec<BackfillProgress> {
+        let jobs = self.jobs.read().await;
+        let mut list = Vec::with_capacity(jobs.len());
+        for job in jobs.values() {
+            list.push(job.progress.read().await.clone());
+        }
+        list
+    }
+
+    pub async fn get(&self, id: &str) -> Option<BackfillProgress> {
+        let job = self.jobs.read().await.get(id).cloned()?;
+        let progress = job.progress.read().await.clone();
+        Some(progress)
+    }
+
+    /// 暂停、恢复或取消任务，任务不存在返回 Ok(None)
+    pub async fn control(
+        &self,
+        id: &str,
+        action: BackfillAction,
+    ) -> Result<Option<BackfillProgress>, String> {
+        let Some(job) = self.jobs.read().await.get(id).cloned() else {
+            return Ok(None);
+        };
+
+        let mut progress = job.progress.write().await;
+        let next = progress.status.apply(action)?;
+        progress.status = next;
+        progress.updated_at = Utc::now().timestamp_millis();
+        job.control.send_replace(next);
+
+        info!("Backfill {} is now {:?}", id, next);
+        Ok(Some(progress.clone()))
+    }
+}
+
+/// 等待任务可继续执行：暂停时阻塞，取消或进程退出时返回 false
+async fn wait_until_runnable(control: &mut watch::Receiver<BackfillStatus>) -> bool {
+    let shutdown = get_shutdown();
+    tokio::select! {
+        status = control.wait_for(|s| *s != BackfillStatus::Paused) => {
+            matches!(status.as_deref(), Ok(BackfillStatus::Running))
+        }
+        _ = shutdown.wait() => false,
+    }
+}
+
+async fn run_job(
+    job: Arc<BackfillJob>,
+    tf: TimeFrame,
+    store: GapStore,
+    windows: Vec<ArchiveWindow>,
+) {
+    let shutdown = get_shutdown();
+    let _guard = shutdown.track();
+
+    let (id, exchange, market_type, symbol, rate_share) = {
+        let p = job.progress.read().await;
+        (
+            p.id.clone(),
+            p.exchange.clone(),
+            p.market_type,
+            p.symbol.clone(),
+            p.rate_share,
+        )
+    };
+
+    // 回补任务单独限速，交易所全局限流器仍在 fetcher 内生效；交易所限额变化后按新限额重建
+    let mut per_minute = current_requests_per_minute(&exchange, market_type);
+    let mut pacer = RequestLimiter::new(
+        paced_requests_per_minute(per_minute, rate_share),
+        Duration::from_secs(60),
+    );
+    let requests = requests_per_window(&exchange);
+    let validator = get_kline_validator();
+    let sink = sink_by_name(store.as_str()).expect("backfill store must be a known sink");
+    let tf = Arc::new(tf);
+    let mut control = job.control.subscribe();
+
+    for window in windows {
+        if shutdown.is_triggered() || !wait_until_runnable(&mut control).await {
+            break;
+        }
+        let limit = current_requests_per_minute(&exchange, market_type);
+        if limit != per_minute {
+            per_minute = limit;
+            pacer = RequestLimiter::new(
+                paced_requests_per_minute(per_minute, rate_share),
+                Duration::from_secs(60),
+            );
+        }
+        for _ in 0..requests {
+            pacer.acquire().await;
+        }
+
+        job.progress.write().await.current_window_start = window.start_time;
+
+        let task = ArchiveTask {
+            symbol: symbol.clone(),
+            exchange: exchange.clone(),
+            market_type,
+            tf: tf.clone(),
+            window: vec![window],
+            direction: store.direction(),
+        };
+        let result = match execute_archive_messages(&[task]).await {
+            Ok(messages) => {
+                let count: usize = messages.iter().map(|m| m.datas.len()).sum();
+                flush_to(&validator, sink.as_ref(), messages)
+                    .await
+                    .map(|_| count)
+            }
+            Err(e) => Err(e.into()),
+        };
+
+        if let Err(e) = &result {
+            warn!(?e, "Backfill {} window failed", id);
+        }
+        job.progress.write().await.record_window(&result);
+    }
+
+    let mut progress = job.progress.write().await;
+    progress.current_window_start = None;
+    progress.updated_at = Utc::now().timestamp_millis();
+    if progress.completed_windows + progress.failed_windows == progress.total_windows {
+        progress.status = BackfillStatus::Completed;
+    } else if !progress.status.is_finished() {
+        // 进程退出中断的任务视为取消
+        progress.status = BackfillStatus::Cancelled;
+        progress.last_error = Some("interrupted by shutdown".to_string());
+    }
+    job.control.send_replace(progress.status);
+
+    info!(
+        "Backfill {} {:?}: {}/{} windows, {} failed, {} klines written",
+        id,
+        progress.status,
+        progress.completed_windows,
+        progress.total_windows,
+        progress.failed_windows,
+        progress.klines_written
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_transitions() {
+        use BackfillAction::*;
+        use BackfillStatus::*;
+
+        assert_eq!(Running.apply(Pause), Ok(Paused));
+        assert_eq!(Paused.apply(Resume), Ok(Running));
+        assert_eq!(Paused.apply(Cancel), Ok(Cancelled));
+        assert_eq!(Running.apply(Cancel), Ok(Cancelled));
+        assert!(Running.apply(Resume).is_err());
+        assert!(Paused.apply(Pause).is_err());
+        assert!(Completed.apply(Cancel).is_err());
+        assert!(Cancelled.apply(Resume).is_err());
+    }
+
+    #[test]
+    fn test_request_is_normalized() {
+        let req = BackfillRequest {
+            exchange: " Binance".to_string(),
+            market_type: MarketType::UsdM,
+            symbol: "btcusdt ".to_string(),
+            time_frame: "1m".to_string(),
+            start: 0,
+            end: 60_000,
+            store: None,
+            rate_share: None,
+        }
+        .normalized();
+        assert_eq!(req.exchange, "binance");
+        assert_eq!(req.symbol, "BTCUSDT");
+    }
+
+    #[test]
+    fn test_rate_share_of_exchange_budget() {
+        // Binance：480 次/分钟的四分之一
+        assert_eq!(paced_requests_per_minute(480, 0.25), 120);
+        assert_eq!(requests_per_window("binance"), 1);
+        // exchangeInfo 同步到更高的权重上限后按新限额折算
+        assert_eq!(paced_requests_per_minute(6000 / 5, 0.25), 300);
+        // OKX 每页 100 根，一个窗口需要 10 次请求
+        assert_eq!(
+            paced_requests_per_minute(current_requests_per_minute("okx", MarketType::UsdM), 0.5),
+            300
+        );
+        assert_eq!(requests_per_window("okx"), 10);
+        // 极小占比至少保留 1 次/分钟
+        assert_eq!(paced_requests_per_minute(480, 0.0001), 1);
+    }
+
+    #[test]
+    fn test_finished_jobs_expire_after_retention() {
+        let mut progress = BackfillProgress {
+            id: "bf-1".to_string(),
+            exchange: "binance".to_string(),
+            market_type: MarketType::UsdM,
+            symbol: "BTCUSDT".to_string(),
+            time_frame: "1m".to_string(),
+            start: 0,
+            end: 60_000,
+            store: "mysql".to_string(),
+            rate_share: 0.25,
+            status: BackfillStatus::Running,
+            total_windows: 1,
+            completed_windows: 0,
+            failed_windows: 0,
+            klines_written: 0,
+            percent: 0.0,
+            current_window_start: None,
+            last_error: None,
+            created_at: 0,
+            updated_at: 1_000,
+        };
+        // 运行中的任务不清理
+        assert!(!progress.is_expired(10_000_000, 3_600_000));
+
+        progress.status = BackfillStatus::Completed;
+        assert!(!progress.is_expired(1_000 + 3_599_999, 3_600_000));
+        assert!(progress.is_expired(1_000 + 3_600_000, 3_600_000));
+    }
+}
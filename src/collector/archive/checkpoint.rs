@@ -0,0 +1,337 @@
+use crate::collector::archive::types::{ArchiveDirection, ArchiveTask, ArchiveWindow};
+use crate::collector::archive::KlineMessage;
+use crate::common::utils::get_env_or;
+use crate::domain::model::archive_checkpoint::{
+    encode_archive_checkpoint_pk, ArchiveCheckpoint, NewOrUpdateArchiveCheckpoint,
+    CHECKPOINT_STATUS_FLUSHED, CHECKPOINT_STATUS_PENDING,
+};
+use crate::domain::repository::archive_checkpoint_repository::ArchiveCheckpointRepository;
+use crate::domain::service::archive_checkpoint_service::ArchiveCheckpointService;
+use crate::global::get_mysql_pool;
+use crate::model::cex::kline::MinMaxCloseTime;
+use crate::model::market_type::MarketType;
+use crate::model::TimeFrame;
+use chrono::Utc;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// 单个窗口的失败次数上限，达到后不再自动续跑，仅保留在检查点报告中
+///
+/// - `ARCHIVE_CHECKPOINT_MAX_ATTEMPTS`：默认 5
+pub fn max_attempts() -> u32 {
+    get_env_or("ARCHIVE_CHECKPOINT_MAX_ATTEMPTS", 5)
+}
+
+/// 窗口检查点 ID，同一任务同一窗口起点唯一
+pub fn checkpoint_id(task: &ArchiveTask, window_start: i64) -> String {
+    encode_archive_checkpoint_pk(
+        &task.exchange,
+        task.market_type.as_str(),
+        &task.symbol,
+        task.tf.to_str(),
+        task.direction.as_str(),
+        window_start,
+    )
+}
+
+/// 一批消息中携带的检查点 ID（去重）
+pub fn checkpoint_ids(messages: &[KlineMessage]) -> Vec<String> {
+    let mut ids: Vec<String> = messages
+        .iter()
+        .filter_map(|m| m.checkpoint_id.clone())
+        .collect();
+    ids.sort();
+    ids.dedup();
+    ids
+}
+
+/// 用已登记窗口的范围修正存储推断的进度
+///
+/// 交易所无数据的窗口不会写入K线，仅凭 min/max close_time 会反复规划同一区间
+pub fn merge_progress(
+    progress: MinMaxCloseTime,
+    bounds: Option<(i64, i64)>,
+    direction: ArchiveDirection,
+) -> MinMaxCloseTime {
+    let Some((first_start, last_end)) = bounds else {
+        return progress;
+    };
+    match direction {
+        ArchiveDirection::Forward => MinMaxCloseTime {
+            min_close_time: progress.min_close_time,
+            max_close_time: progress.max_close_time.max(last_end),
+        },
+        ArchiveDirection::Backward => MinMaxCloseTime {
+            min_close_time: progress.min_close_time.min(first_start),
+            max_close_time: progress.max_close_time,
+        },
+    }
+}
+
+/// 未完成的检查点转换为待拉取窗口
+fn windows_from_checkpoints(rows: &[ArchiveCheckpoint]) -> Vec<ArchiveWindow> {
+    rows.iter()
+        .filter(|row| row.status != CHECKPOINT_STATUS_FLUSHED)
+        .map(|row| ArchiveWindow {
+            start_time: Some(row.window_start),
+            end_time: Some(row.window_end),
+        })
+        .collect()
+}
+
+/// 存在未完成的窗口时，优先续跑这些窗口，而不是按存储进度规划新窗口
+pub fn resume_task(
+    symbol: &str,
+    exchange: &str,
+    market_type: MarketType,
+    tf: Arc<TimeFrame>,
+    direction: ArchiveDirection,
+) -> Option<ArchiveTask> {
+    let rows = match with_service(|service| {
+        Ok(service.list_resumable(
+            exchange,
+            market_type.as_str(),
+            symbol,
+            tf.to_str(),
+            direction.as_str(),
+            max_attempts(),
+        )?)
+    }) {
+        Ok(rows) => rows,
+        Err(e) => {
+            warn!(?e, "Failed to load archive checkpoints");
+            return None;
+        }
+    };
+
+    let windows = windows_from_checkpoints(&rows);
+    if windows.is_empty() {
+        return None;
+    }
+
+    info!(
+        "Resuming {} unfinished {} windows for {} - {} - {} - {}",
+        windows.len(),
+        direction.as_str(),
+        symbol,
+        exchange,
+        market_type,
+        tf.to_str()
+    );
+    Some(ArchiveTask {
+        symbol: symbol.to_string(),
+        exchange: exchange.to_string(),
+        market_type,
+        tf,
+        window: windows,
+        direction,
+    })
+}
+
+/// 查询已登记窗口的范围 (最早起点, 最晚终点)
+pub fn window_bounds(
+    symbol: &str,
+    exchange: &str,
+    market_type: MarketType,
+    tf: &TimeFrame,
+    direction: ArchiveDirection,
+) -> Option<(i64, i64)> {
+    with_service(|service| {
+        Ok(service.window_bounds(
+            exchange,
+            market_type.as_str(),
+            symbol,
+            tf.to_str(),
+            direction.as_str(),
+        )?)
+    })
+    .unwrap_or_else(|e| {
+        warn!(?e, "Failed to load archive checkpoint bounds");
+        None
+    })
+}
+
+/// 将新规划的窗口登记为 pending
+pub fn register_task(task: &ArchiveTask) {
+    let now = Utc::now().naive_utc();
+    let rows: Vec<NewOrUpdateArchiveCheckpoint> = task
+        .window
+        .iter()
+        .filter_map(|w| Some((w.start_time?, w.end_time?)))
+        .map(|(start, end)| NewOrUpdateArchiveCheckpoint {
+            id: checkpoint_id(task, start),
+            exchange: task.exchange.clone(),
+            market_type: task.market_type.as_str().to_string(),
+            symbol: task.symbol.clone(),
+            time_frame: task.tf.to_str().to_string(),
+            direction: task.direction.as_str().to_string(),
+            window_start: start,
+            window_end: end,
+            status: CHECKPOINT_STATUS_PENDING.to_string(),
+            attempts: 0,
+            kline_count: 0,
+            last_error: None,
+            created_at: now,
+            updated_at: now,
+        })
+        .collect();
+
+    if let Err(e) = with_service(|service| service.register_windows(rows)) {
+        warn!(?e, "Failed to register archive checkpoints");
+    }
+}
+
+/// 查询窗口检查点，失败时按不存在处理
+pub fn find(id: &str) -> Option<ArchiveCheckpoint> {
+    with_service(|service| Ok(service.get_by_id(id)?)).unwrap_or_else(|e| {
+        warn!(?e, "Failed to load archive checkpoint {}", id);
+        None
+    })
+}
+
+/// 将离线导入的窗口直接登记为已写库，已存在的窗口扩展到导入范围
+///
+/// 与 `register_task` 不同，导入窗口在写库成功后才登记，失败的导入不会被当作待续跑窗口
+pub fn record_imported(task: &ArchiveTask, kline_count: usize) {
+    let now = Utc::now().naive_utc();
+    for (start, end) in task
+        .window
+        .iter()
+        .filter_map(|w| Some((w.start_time?, w.end_time?)))
+    {
+        let id = checkpoint_id(task, start);
+        let result = with_service(|service| {
+            let existing = service.get_by_id(&id)?;
+            let row = NewOrUpdateArchiveCheckpoint {
+                id: id.clone(),
+                exchange: task.exchange.clone(),
+                market_type: task.market_type.as_str().to_string(),
+                symbol: task.symbol.clone(),
+                time_frame: task.tf.to_str().to_string(),
+                direction: task.direction.as_str().to_string(),
+                window_start: start,
+                window_end: existing.as_ref().map_or(end, |e| e.window_end.max(end)),
+                status: CHECKPOINT_STATUS_FLUSHED.to_string(),
+                attempts: 0,
+                kline_count: kline_count as i64,
+                last_error: None,
+                created_at: existing.as_ref().map_or(now, |e| e.created_at),
+                updated_at: now,
+            };
+            match existing {
+                Some(_) => service.update(&row)?,
+                None => service.insert(&row)?,
+            };
+            Ok(())
+        });
+        if let Err(e) = result {
+            warn!(?e, "Failed to record imported archive window {}", id);
+        }
+    }
+}
+
+pub fn mark_fetched(id: &str, kline_count: usize) {
+    if let Err(e) = with_service(|service| Ok(service.mark_fetched(id, kline_count as i64)?)) {
+        warn!(?e, "Failed to mark archive checkpoint fetched");
+    }
+}
+
+pub fn mark_flushed(ids: &[String]) {
+    if let Err(e) = with_service(|service| Ok(service.mark_flushed(ids)?)) {
+        warn!(?e, "Failed to mark archive checkpoints flushed");
+    }
+}
+
+pub fn mark_failed(ids: &[String], error: &str) {
+    if let Err(e) = with_service(|service| Ok(service.mark_failed(ids, error)?)) {
+        warn!(?e, "Failed to mark archive checkpoints failed");
+    }
+}
+
+/// 永久错误（如交易对已下架）：窗口失败次数置为上限，不再断点续传
+pub fn mark_abandoned(ids: &[String], error: &str) {
+    if let Err(e) =
+        with_service(|service| Ok(service.mark_abandoned(ids, error, max_attempts())?))
+    {
+        warn!(?e, "Failed to mark archive checkpoints abandoned");
+    }
+}
+
+fn with_service<T>(
+    f: impl FnOnce(&mut ArchiveCheckpointService) -> anyhow::Result<T>,
+) -> anyhow::Result<T> {
+    let mut conn = get_mysql_pool().get()?;
+    let repo = ArchiveCheckpointRepository::new(&mut conn);
+    let mut service = ArchiveCheckpointService { repo };
+    f(&mut service)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn progress(min: i64, max: i64) -> MinMaxCloseTime {
+        MinMaxCloseTime {
+            min_close_time: min,
+            max_close_time: max,
+        }
+    }
+
+    #[test]
+    fn test_merge_progress_skips_windows_without_data() {
+        // 最新窗口已完成但交易所无数据，向前归档应从窗口终点继续
+        let merged = merge_progress(
+            progress(1_000, 5_000),
+            Some((500, 8_000)),
+            ArchiveDirection::Forward,
+        );
+        assert_eq!(merged.max_close_time, 8_000);
+        assert_eq!(merged.min_close_time, 1_000);
+
+        let merged = merge_progress(
+            progress(1_000, 5_000),
+            Some((500, 8_000)),
+            ArchiveDirection::Backward,
+        );
+        assert_eq!(merged.min_close_time, 500);
+        assert_eq!(merged.max_close_time, 5_000);
+
+        // 存储进度领先于检查点时以存储为准
+        let merged = merge_progress(
+            progress(100, 9_000),
+            Some((500, 8_000)),
+            ArchiveDirection::Forward,
+        );
+        assert_eq!(merged.max_close_time, 9_000);
+    }
+
+    #[test]
+    fn test_only_unfinished_checkpoints_are_resumed() {
+        let now = Utc::now().naive_utc();
+        let row = |start: i64, status: &str| ArchiveCheckpoint {
+            id: start.to_string(),
+            exchange: "binance".to_string(),
+            market_type: "usdm".to_string(),
+            symbol: "BTCUSDT".to_string(),
+            time_frame: "1m".to_string(),
+            direction: "forward".to_string(),
+            window_start: start,
+            window_end: start + 60_000,
+            status: status.to_string(),
+            attempts: 0,
+            kline_count: 0,
+            last_error: None,
+            created_at: now,
+            updated_at: now,
+        };
+
+        let windows = windows_from_checkpoints(&[
+            row(0, CHECKPOINT_STATUS_FLUSHED),
+            row(60_000, "fetched"),
+            row(120_000, "failed"),
+            row(180_000, CHECKPOINT_STATUS_PENDING),
+        ]);
+        let starts: Vec<i64> = windows.iter().filter_map(|w| w.start_time).collect();
+        assert_eq!(starts, vec![60_000, 120_000, 180_000]);
+    }
+}
@@ -0,0 +1,286 @@
+use crate::collector::archive::flush::write_batch;
+use crate::collector::archive::sink::sink_by_name;
+use crate::collector::archive::KlineMessage;
+use crate::common::utils::get_env_or;
+use crate::global::{get_kline_validator, get_kv};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+/// 死信存储的 Redis 哈希表，字段为死信 ID
+const DEAD_LETTER_KEY: &str = "archive:dead_letter";
+
+/// 自动重试的初始与最大退避时间
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(30);
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(3600);
+
+static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+/// 写库失败的一批K线及错误信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadLetter {
+    /// 唯一标识，如 mysql-1717200000000000-3
+    pub id: String,
+
+    /// 目标存储：mysql / clickhouse
+    pub sink: String,
+
+    /// 最近一次失败原因
+    pub error: String,
+
+    /// 失败次数（含首次写入）
+    pub attempts: u32,
+
+    /// 首次失败时间（毫秒）
+    pub first_failed_at: i64,
+
+    /// 最近一次失败时间（毫秒）
+    pub last_failed_at: i64,
+
+    /// 下次自动重试时间（毫秒）
+    pub next_retry_at: i64,
+
+    /// K线数量
+    pub kline_count: usize,
+
+    /// 写入失败的原始消息
+    pub messages: Vec<KlineMessage>,
+}
+
+/// 不含消息体的死信摘要，用于列表查询
+#[derive(Debug, Clone, Serialize)]
+pub struct DeadLetterSummary {
+    pub id: String,
+    pub sink: String,
+    pub error: String,
+    pub attempts: u32,
+    pub first_failed_at: i64,
+    pub last_failed_at: i64,
+    pub next_retry_at: i64,
+    pub kline_count: usize,
+    /// 涉及的市场，如 binance:usdm:BTCUSDT:1m
+    pub markets: Vec<String>,
+}
+
+impl From<&DeadLetter> for DeadLetterSummary {
+    fn from(d: &DeadLetter) -> Self {
+        let mut markets: Vec<String> = d
+            .messages
+            .iter()
+            .map(|m| {
+                format!(
+                    "{}:{}:{}:{}",
+                    m.exchange, m.market_type, m.symbol, m.time_frame
+                )
+            })
+            .collect();
+        markets.sort();
+        markets.dedup();
+
+        DeadLetterSummary {
+            id: d.id.clone(),
+            sink: d.sink.clone(),
+            error: d.error.clone(),
+            attempts: d.attempts,
+            first_failed_at: d.first_failed_at,
+            last_failed_at: d.last_failed_at,
+            next_retry_at: d.next_retry_at,
+            kline_count: d.kline_count,
+            markets,
+        }
+    }
+}
+
+impl DeadLetter {
+    pub fn new(sink: &str, error: &str, messages: Vec<KlineMessage>) -> Self {
+        let now = Utc::now().timestamp_millis();
+        let id = format!(
+            "{}-{}-{}",
+            sink,
+            Utc::now().timestamp_micros(),
+            SEQUENCE.fetch_add(1, Ordering::Relaxed)
+        );
+        DeadLetter {
+            id,
+            sink: sink.to_string(),
+            error: error.to_string(),
+            attempts: 1,
+            first_failed_at: now,
+            last_failed_at: now,
+            next_retry_at: now + retry_delay(1).as_millis() as i64,
+            kline_count: messages.iter().map(|m| m.datas.len()).sum(),
+            messages,
+        }
+    }
+
+    /// 记录一次重放失败并推迟下次自动重试
+    pub fn record_failure(&mut self, error: &str) {
+        let now = Utc::now().timestamp_millis();
+        self.attempts += 1;
+        self.error = error.to_string();
+        self.last_failed_at = now;
+        self.next_retry_at = now + retry_delay(self.attempts).as_millis() as i64;
+    }
+
+    pub fn is_due(&self, now: i64, max_attempts: u32) -> bool {
+        self.attempts < max_attempts && self.next_retry_at <= now
+    }
+}
+
+/// 第 n 次失败后的退避时间：30s、60s、120s ... 最长 1 小时
+pub fn retry_delay(attempts: u32) -> Duration {
+    let exp = attempts.saturating_sub(1).min(16);
+    (RETRY_BASE_DELAY * 2u32.pow(exp)).min(RETRY_MAX_DELAY)
+}
+
+/// 自动重试次数上限，超过后仅保留等待人工重放或丢弃
+///
+/// - `DEAD_LETTER_MAX_ATTEMPTS`：默认 10
+pub fn max_attempts() -> u32 {
+    get_env_or("DEAD_LETTER_MAX_ATTEMPTS", 10)
+}
+
+/// 保存写库失败的批次；Redis 也不可用时只能记录错误日志
+pub async fn store(sink: &str, error: &anyhow::Error, messages: Vec<KlineMessage>) {
+    let letter = DeadLetter::new(sink, &error.to_string(), messages);
+    match get_kv().hset(DEAD_LETTER_KEY, &letter.id, &letter).await {
+        Ok(()) => warn!(
+            "Dead-lettered {} klines for {} as {}: {}",
+            letter.kline_count, sink, letter.id, letter.error
+        ),
+        Err(e) => error!(
+            ?e,
+            "Failed to dead-letter {} klines for {}, data lost: {}",
+            letter.kline_count,
+            sink,
+            letter.error
+        ),
+    }
+}
+
+/// 列出所有死信摘要，按首次失败时间升序
+pub async fn list() -> anyhow::Result<Vec<DeadLetterSummary>> {
+    let mut letters: Vec<DeadLetter> = get_kv().hvals(DEAD_LETTER_KEY).await?;
+    letters.sort_by_key(|d| d.first_failed_at);
+    Ok(letters.iter().map(DeadLetterSummary::from).collect())
+}
+
+pub async fn get(id: &str) -> anyhow::Result<Option<DeadLetter>> {
+    get_kv().hget(DEAD_LETTER_KEY, id).await
+}
+
+/// 丢弃死信，返回是否存在
+pub async fn discard(id: &str) -> anyhow::Result<bool> {
+    let removed = get_kv().hdel(DEAD_LETTER_KEY, id).await?;
+    if removed {
+        info!("Discarded dead letter {}", id);
+    }
+    Ok(removed)
+}
+
+/// 重放单条死信：成功后删除，失败则累加次数并推迟下次自动重试
+pub async fn replay(id: &str) -> anyhow::Result<bool> {
+    let Some(letter) = get(id).await? else {
+        return Ok(false);
+    };
+    replay_letter(letter).await?;
+    Ok(true)
+}
+
+async fn replay_letter(mut letter: DeadLetter) -> anyhow::Result<()> {
+    let sink = sink_by_name(&letter.sink)
+        .ok_or_else(|| anyhow::anyhow!("Unknown dead letter sink: {}", letter.sink))?;
+
+    let kv = get_kv();
+    match write_batch(
+        &get_kline_validator(),
+        sink.as_ref(),
+        letter.messages.clone(),
+    )
+    .await
+    {
+        Ok(()) => {
+            kv.hdel(DEAD_LETTER_KEY, &letter.id).await?;
+            info!(
+                "Replayed dead letter {} ({} klines to {})",
+                letter.id, letter.kline_count, letter.sink
+            );
+            Ok(())
+        }
+        Err(e) => {
+            letter.record_failure(&e.to_string());
+            kv.hset(DEAD_LETTER_KEY, &letter.id, &letter).await?;
+            Err(e)
+        }
+    }
+}
+
+/// 重放所有死信（忽略退避与次数上限），返回 (成功数, 失败数)
+pub async fn replay_all() -> anyhow::Result<(usize, usize)> {
+    let letters: Vec<DeadLetter> = get_kv().hvals(DEAD_LETTER_KEY).await?;
+    replay_many(letters).await
+}
+
+/// 自动重试到期的死信，返回 (成功数, 失败数)
+pub async fn retry_due() -> anyhow::Result<(usize, usize)> {
+    let now = Utc::now().timestamp_millis();
+    let max_attempts = max_attempts();
+    let letters: Vec<DeadLetter> = get_kv()
+        .hvals::<DeadLetter>(DEAD_LETTER_KEY)
+        .await?
+        .into_iter()
+        .filter(|d| d.is_due(now, max_attempts))
+        .collect();
+    replay_many(letters).await
+}
+
+async fn replay_many(mut letters: Vec<DeadLetter>) -> anyhow::Result<(usize, usize)> {
+    letters.sort_by_key(|d| d.first_failed_at);
+
+    let (mut replayed, mut failed) = (0, 0);
+    for letter in letters {
+        let id = letter.id.clone();
+        match replay_letter(letter).await {
+            Ok(()) => replayed += 1,
+            Err(e) => {
+                warn!(?e, "Failed to replay dead letter {}", id);
+                failed += 1;
+            }
+        }
+    }
+    Ok((replayed, failed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_delay_backs_off_exponentially() {
+        assert_eq!(retry_delay(1), Duration::from_secs(30));
+        assert_eq!(retry_delay(2), Duration::from_secs(60));
+        assert_eq!(retry_delay(4), Duration::from_secs(240));
+        assert_eq!(retry_delay(10), RETRY_MAX_DELAY);
+        assert_eq!(retry_delay(u32::MAX), RETRY_MAX_DELAY);
+    }
+
+    #[test]
+    fn test_due_respects_backoff_and_attempt_limit() {
+        let mut letter = DeadLetter::new("mysql", "connection refused", vec![]);
+        assert!(!letter.is_due(letter.last_failed_at, 3));
+        assert!(letter.is_due(letter.next_retry_at, 3));
+
+        letter.record_failure("connection refused");
+        letter.record_failure("connection refused");
+        assert_eq!(letter.attempts, 3);
+        assert!(!letter.is_due(letter.next_retry_at, 3));
+
+        // 序列化往返保持不变，保证写入 Redis 后可重放
+        let json = serde_json::to_string(&letter).unwrap();
+        let decoded: DeadLetter = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.id, letter.id);
+        assert_eq!(decoded.attempts, 3);
+    }
+}
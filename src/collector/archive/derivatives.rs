@@ -0,0 +1,615 @@
+use crate::collector::archive::fetch::helper::{
+    create_aligned_windows_with_limit, create_aligned_windows_with_limit_backward,
+    should_skip_archiving_due_to_old_data, valid_window_range,
+};
+use crate::collector::archive::listing::{self, ListingBounds};
+use crate::collector::archive::sink::ClickhouseSink;
+use crate::collector::archive::types::{ArchiveDirection, ArchiveError, ArchiveWindow};
+use crate::common::utils::{get_env_bool, get_env_list, get_env_or};
+use crate::global::{get_binance_limiter, get_ck_db};
+use crate::infra::external::binance::error::BinanceError;
+use crate::infra::external::binance::futures::{LongShortRatioKind, PriceKlineKind};
+use crate::infra::external::binance::DefaultBinanceExchange;
+use crate::infra::external::rate_limiter::binance_limiter::{BinanceLimiter, EndpointQuota};
+use crate::model::cex::derivatives::{
+    FundingRateRow, LongShortRatioRow, OpenInterestRow, PriceKlineRow, SeriesLocator,
+};
+use crate::model::cex::kline::MinMaxCloseTime;
+use crate::model::market_type::MarketType;
+use crate::model::TimeFrame;
+use backoff::{future::retry, ExponentialBackoff};
+use chrono::Utc;
+use std::fmt;
+use std::str::FromStr;
+use tracing::{error, info, warn};
+
+/// 衍生品序列目前只从 Binance U本位合约归档
+const EXCHANGE: &str = "binance";
+
+/// 每个任务每次调度规划的窗口数，与K线归档的单轮跨度相当
+const WINDOWS_PER_TASK: i64 = 5;
+
+const DAY_MS: i64 = 86_400_000;
+
+/// 无历史进度时的默认起点：当前时间往前推 90 天
+const DEFAULT_LOOKBACK_DAYS: i64 = 90;
+
+/// 衍生品归档序列（Binance U本位合约）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DerivativeSeries {
+    FundingRate,
+    OpenInterest,
+    LongShortRatio(LongShortRatioKind),
+    PriceKline(PriceKlineKind),
+}
+
+impl DerivativeSeries {
+    pub const ALL: [DerivativeSeries; 7] = [
+        DerivativeSeries::FundingRate,
+        DerivativeSeries::OpenInterest,
+        DerivativeSeries::LongShortRatio(LongShortRatioKind::GlobalAccount),
+        DerivativeSeries::LongShortRatio(LongShortRatioKind::TopAccount),
+        DerivativeSeries::LongShortRatio(LongShortRatioKind::TopPosition),
+        DerivativeSeries::PriceKline(PriceKlineKind::Mark),
+        DerivativeSeries::PriceKline(PriceKlineKind::Index),
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DerivativeSeries::FundingRate => "funding_rate",
+            DerivativeSeries::OpenInterest => "open_interest",
+            DerivativeSeries::LongShortRatio(LongShortRatioKind::GlobalAccount) => {
+                "global_long_short"
+            }
+            DerivativeSeries::LongShortRatio(LongShortRatioKind::TopAccount) => {
+                "top_long_short_account"
+            }
+            DerivativeSeries::LongShortRatio(LongShortRatioKind::TopPosition) => {
+                "top_long_short_position"
+            }
+            DerivativeSeries::PriceKline(PriceKlineKind::Mark) => "mark_price",
+            DerivativeSeries::PriceKline(PriceKlineKind::Index) => "index_price",
+        }
+    }
+
+    /// 单次请求的条数上限：持仓量与多空比接口最多 500 条
+    pub fn request_limit(&self) -> u16 {
+        match self {
+            DerivativeSeries::OpenInterest | DerivativeSeries::LongShortRatio(_) => 500,
+            DerivativeSeries::FundingRate | DerivativeSeries::PriceKline(_) => 1000,
+        }
+    }
+
+    /// 交易所保留的历史天数：持仓量与多空比只提供最近 30 天
+    pub fn retention_days(&self) -> Option<i64> {
+        match self {
+            DerivativeSeries::OpenInterest | DerivativeSeries::LongShortRatio(_) => Some(30),
+            DerivativeSeries::FundingRate | DerivativeSeries::PriceKline(_) => None,
+        }
+    }
+
+    /// 窗口对齐步长：资金费率结算周期为 1h / 4h / 8h，按 1 小时切分保证单个窗口不超过请求条数上限
+    pub fn step(&self, period: &TimeFrame) -> TimeFrame {
+        match self {
+            DerivativeSeries::FundingRate => TimeFrame::H1,
+            _ => period.clone(),
+        }
+    }
+
+    /// 单次请求的 IP 权重；资金费率与 /futures/data 接口不计权重，按接口单独限流
+    pub fn request_weight(&self) -> u32 {
+        match self {
+            DerivativeSeries::PriceKline(_) => {
+                BinanceLimiter::weight_for_limit(self.request_limit() as u32)
+            }
+            _ => 1,
+        }
+    }
+
+    /// 序列在 ClickHouse 中的位置，用于查询归档进度
+    pub fn locator(&self, period: &TimeFrame) -> SeriesLocator {
+        let period = ("period", period.to_str().to_string());
+        match self {
+            DerivativeSeries::FundingRate => SeriesLocator {
+                table: "funding_rates",
+                time_column: "funding_time",
+                filters: vec![],
+            },
+            DerivativeSeries::OpenInterest => SeriesLocator {
+                table: "open_interest_hist",
+                time_column: "timestamp",
+                filters: vec![period],
+            },
+            DerivativeSeries::LongShortRatio(kind) => SeriesLocator {
+                table: "long_short_ratios",
+                time_column: "timestamp",
+                filters: vec![("ratio_type", kind.as_str().to_string()), period],
+            },
+            DerivativeSeries::PriceKline(kind) => SeriesLocator {
+                table: "price_klines",
+                time_column: "close_time",
+                filters: vec![("price_type", kind.as_str().to_string()), period],
+            },
+        }
+    }
+
+    /// 序列完成标记字段：K线字段后追加序列名
+    fn completion_field(
+        &self,
+        symbol: &str,
+        period: &TimeFrame,
+        direction: ArchiveDirection,
+    ) -> String {
+        format!(
+            "{}:{}",
+            listing::series_field(
+                EXCHANGE,
+                MarketType::UsdM,
+                symbol,
+                &self.step(period),
+                direction
+            ),
+            self.as_str()
+        )
+    }
+}
+
+impl fmt::Display for DerivativeSeries {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for DerivativeSeries {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|series| series.as_str() == s)
+            .ok_or_else(|| format!("Unsupported derivative series: {}", s))
+    }
+}
+
+/// 衍生品归档配置（来自环境变量）
+///
+/// - `ARCHIVE_DERIVATIVES`：序列列表，如 `funding_rate,open_interest,mark_price`，未配置时不归档
+/// - `ARCHIVE_DERIVATIVES_PERIOD`：持仓量、多空比与价格K线的周期，默认 1h（持仓量与多空比只支持 5m ~ 1d）
+#[derive(Debug, Clone)]
+pub struct DerivativeConfig {
+    pub series: Vec<DerivativeSeries>,
+    pub period: TimeFrame,
+}
+
+impl DerivativeConfig {
+    pub fn from_env() -> Self {
+        let series = get_env_list("ARCHIVE_DERIVATIVES")
+            .iter()
+            .filter_map(|s| match s.parse() {
+                Ok(series) => Some(series),
+                Err(e) => {
+                    warn!("{}", e);
+                    None
+                }
+            })
+            .collect();
+        let period = get_env_or("ARCHIVE_DERIVATIVES_PERIOD", TimeFrame::H1);
+        Self { series, period }
+    }
+
+    /// 衍生品只写入 ClickHouse，未启用时不归档
+    pub fn is_enabled(&self) -> bool {
+        !self.series.is_empty() && get_env_bool("ENABLE_CLICKHOUSE", true)
+    }
+}
+
+/// 衍生品归档任务
+#[derive(Debug, Clone)]
+pub struct DerivativeTask {
+    pub symbol: String,
+    pub series: DerivativeSeries,
+    pub period: TimeFrame,
+    pub window: Vec<ArchiveWindow>,
+    pub direction: ArchiveDirection,
+}
+
+/// 一个窗口拉取到的数据，按序列写入对应的 ClickHouse 表
+#[derive(Debug, Clone)]
+pub enum DerivativeBatch {
+    FundingRate(Vec<FundingRateRow>),
+    OpenInterest(Vec<OpenInterestRow>),
+    LongShortRatio(Vec<LongShortRatioRow>),
+    PriceKline(Vec<PriceKlineRow>),
+}
+
+impl DerivativeBatch {
+    pub fn len(&self) -> usize {
+        match self {
+            DerivativeBatch::FundingRate(rows) => rows.len(),
+            DerivativeBatch::OpenInterest(rows) => rows.len(),
+            DerivativeBatch::LongShortRatio(rows) => rows.len(),
+            DerivativeBatch::PriceKline(rows) => rows.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// 按窗口数预估一组衍生品任务的请求权重，每个窗口一次请求
+pub fn estimate_weight(tasks: &[DerivativeTask]) -> u32 {
+    tasks
+        .iter()
+        .map(|t| (t.window.len() as u32).saturating_mul(t.series.request_weight()))
+        .sum()
+}
+
+/// 单个窗口的跨度：请求条数上限 × 步长
+fn chunk_size_ms(series: DerivativeSeries, period: &TimeFrame) -> i64 {
+    series.request_limit() as i64 * series.step(period).to_millis()
+}
+
+/// 交易所仍保留数据的最早时间；不限保留期时为 None
+fn retention_floor(series: DerivativeSeries, now_ms: i64) -> Option<i64> {
+    series.retention_days().map(|days| now_ms - days * DAY_MS)
+}
+
+/// 构建双向任务，窗口受交易对上市/交割时间与交易所保留期约束
+pub async fn build_derivative_tasks(
+    symbol: &str,
+    series: DerivativeSeries,
+    period: &TimeFrame,
+    listing: ListingBounds,
+) -> Vec<DerivativeTask> {
+    let progress = get_or_init_progress(symbol, series, period).await;
+
+    let mut tasks = vec![];
+    if let Some(task) = build_forward_task(symbol, series, period, listing, &progress).await {
+        tasks.push(task);
+    }
+    if let Some(task) = build_backward_task(symbol, series, period, listing, &progress).await {
+        tasks.push(task);
+    }
+    tasks
+}
+
+/// 从已归档数据读取进度，无记录时使用默认起点（不早于交易所保留期）
+async fn get_or_init_progress(
+    symbol: &str,
+    series: DerivativeSeries,
+    period: &TimeFrame,
+) -> MinMaxCloseTime {
+    match get_ck_db()
+        .get_series_time_range(EXCHANGE, symbol, &series.locator(period))
+        .await
+    {
+        Ok(Some(progress)) => return progress,
+        Ok(None) => {}
+        Err(err) => error!(?err, "ClickHouse: Failed to get {} progress", series),
+    }
+
+    let now_ms = Utc::now().timestamp_millis();
+    let lookback = now_ms - DEFAULT_LOOKBACK_DAYS * DAY_MS;
+    let step_ms = series.step(period).to_millis();
+    let start = retention_floor(series, now_ms).map_or(lookback, |floor| {
+        // 保留期边界上的数据随时可能过期，留出一个步长的余量
+        lookback.max(floor + step_ms)
+    });
+    let fallback = start - start % step_ms;
+    info!(
+        "No {} progress found. Using fallback time for {} - {}",
+        series, symbol, fallback
+    );
+    MinMaxCloseTime {
+        min_close_time: fallback,
+        max_close_time: fallback,
+    }
+}
+
+/// 构建回溯任务：回溯到上市时间或交易所保留期为止，到达后标记序列完成
+async fn build_backward_task(
+    symbol: &str,
+    series: DerivativeSeries,
+    period: &TimeFrame,
+    listing: ListingBounds,
+    progress: &MinMaxCloseTime,
+) -> Option<DerivativeTask> {
+    let direction = ArchiveDirection::Backward;
+    let field = series.completion_field(symbol, period, direction);
+    if listing::is_field_complete(&field).await {
+        return None;
+    }
+
+    let step = series.step(period);
+    let step_ms = step.to_millis();
+    let floor = match (
+        listing.onboard_time,
+        retention_floor(series, Utc::now().timestamp_millis()),
+    ) {
+        (Some(onboard), Some(retention)) => Some(onboard.max(retention)),
+        (onboard, retention) => onboard.or(retention),
+    };
+
+    match floor {
+        Some(floor) if progress.min_close_time <= floor + step_ms => {
+            listing::mark_field_complete(&field, floor).await;
+            return None;
+        }
+        None if should_skip_archiving_due_to_old_data(
+            progress.min_close_time,
+            symbol,
+            EXCHANGE,
+            &step,
+        ) =>
+        {
+            return None;
+        }
+        _ => {}
+    }
+
+    let chunk_ms = chunk_size_ms(series, period);
+    let end = progress.min_close_time;
+    let start = end - chunk_ms * WINDOWS_PER_TASK;
+    let start = floor.map_or(start, |floor| start.max(floor));
+
+    let windows = create_aligned_windows_with_limit_backward(start, end, chunk_ms, step_ms);
+    if windows.is_empty() {
+        return None;
+    }
+
+    Some(DerivativeTask {
+        symbol: symbol.to_string(),
+        series,
+        period: period.clone(),
+        window: windows,
+        direction,
+    })
+}
+
+/// 构建追溯任务：有交割时间的合约追溯到交割为止，到达后标记序列完成
+async fn build_forward_task(
+    symbol: &str,
+    series: DerivativeSeries,
+    period: &TimeFrame,
+    listing: ListingBounds,
+    progress: &MinMaxCloseTime,
+) -> Option<DerivativeTask> {
+    let direction = ArchiveDirection::Forward;
+    let field = series.completion_field(symbol, period, direction);
+    if listing::is_field_complete(&field).await {
+        return None;
+    }
+    if listing.reached_delivery(progress.max_close_time) {
+        let delivery_time = listing.delivery_time.unwrap_or_default();
+        listing::mark_field_complete(&field, delivery_time).await;
+        return None;
+    }
+
+    let step_ms = series.step(period).to_millis();
+    let chunk_ms = chunk_size_ms(series, period);
+
+    // 长时间未运行时，保留期之前的数据已不可得，从保留期边界继续
+    let start = retention_floor(series, Utc::now().timestamp_millis())
+        .map_or(progress.max_close_time, |floor| {
+            progress.max_close_time.max(floor + step_ms)
+        });
+    let end = listing.clamp_end(start + chunk_ms * WINDOWS_PER_TASK);
+
+    let windows = create_aligned_windows_with_limit(start, end, chunk_ms, step_ms);
+    if windows.is_empty() {
+        return None;
+    }
+
+    Some(DerivativeTask {
+        symbol: symbol.to_string(),
+        series,
+        period: period.clone(),
+        window: windows,
+        direction,
+    })
+}
+
+/// 执行一组衍生品任务，逐窗口拉取并写入 ClickHouse
+///
+/// 衍生品行不经过K线校验、检查点与死信队列，进度只取自已写入数据的最早/最晚时间。
+/// 因此窗口从已有数据向外连续推进（见 `execution_order`），某个窗口失败即结束该任务，
+/// 其后的窗口在下一轮调度时按相同的进度重新规划，不会留下空洞
+pub async fn run_derivative_tasks(tasks: Vec<DerivativeTask>) {
+    for task in &tasks {
+        match execute_derivative_task(task).await {
+            Ok(count) => info!(
+                "Archived {} {} rows for {} ({})",
+                count,
+                task.series,
+                task.symbol,
+                task.direction.as_str()
+            ),
+            Err(e) => error!(
+                ?e,
+                "Failed to archive {} for {} ({})",
+                task.series,
+                task.symbol,
+                task.direction.as_str()
+            ),
+        }
+    }
+}
+
+/// 窗口执行顺序：追溯按时间升序，回溯从最新的窗口开始降序，保证已写入的数据始终与进度边界相连
+fn execution_order(task: &DerivativeTask) -> Vec<ArchiveWindow> {
+    let mut windows: Vec<ArchiveWindow> =
+        task.window.iter().filter_map(valid_window_range).collect();
+    windows.sort_by_key(|w| w.start_time);
+    if task.direction == ArchiveDirection::Backward {
+        windows.reverse();
+    }
+    windows
+}
+
+async fn execute_derivative_task(task: &DerivativeTask) -> Result<usize, ArchiveError> {
+    let sink = ClickhouseSink;
+    let mut total = 0;
+    let mut earliest = None;
+
+    for window in execution_order(task) {
+        let (start, end) = (window.start_time.unwrap(), window.end_time.unwrap());
+        earliest = Some(earliest.map_or(start, |e: i64| e.min(start)));
+
+        let batch = fetch_series_with_retry(task, start, end).await?;
+        if batch.is_empty() {
+            continue;
+        }
+        total += batch.len();
+        sink.write_derivatives(&batch)
+            .await
+            .map_err(|e| ArchiveError::DatabaseError(e.to_string()))?;
+    }
+
+    // 回溯整轮无数据：交易所不再提供更早的历史，之后不再回溯
+    if task.direction == ArchiveDirection::Backward && total == 0 {
+        if let Some(bound) = earliest {
+            let field = task
+                .series
+                .completion_field(&task.symbol, &task.period, task.direction);
+            listing::mark_field_complete(&field, bound).await;
+        }
+    }
+
+    Ok(total)
+}
+
+/// 使用 backoff 拉取单个窗口，仅重试临时错误
+async fn fetch_series_with_retry(
+    task: &DerivativeTask,
+    start: i64,
+    end: i64,
+) -> Result<DerivativeBatch, ArchiveError> {
+    retry(ExponentialBackoff::default(), || async {
+        fetch_series(task.series, &task.symbol, &task.period, start, end)
+            .await
+            .map_err(|e| {
+                let e = ArchiveError::from(e);
+                if e.is_transient() {
+                    warn!(?e, "Failed to fetch {}, retrying...", task.series);
+                    backoff::Error::transient(e)
+                } else {
+                    warn!(
+                        ?e,
+                        "Failed to fetch {} for {}, giving up", task.series, task.symbol
+                    );
+                    backoff::Error::permanent(e)
+                }
+            })
+    })
+    .await
+}
+
+/// 按序列扣减限流预算后请求对应接口，并转换为 ClickHouse 行
+async fn fetch_series(
+    series: DerivativeSeries,
+    symbol: &str,
+    period: &TimeFrame,
+    start: i64,
+    end: i64,
+) -> Result<DerivativeBatch, BinanceError> {
+    let limiter = get_binance_limiter();
+    let dbe = DefaultBinanceExchange::for_market(MarketType::UsdM);
+    let limit = series.request_limit();
+    let period_str = period.to_str();
+
+    let batch = match series {
+        DerivativeSeries::FundingRate => {
+            limiter.acquire_endpoint(EndpointQuota::FundingRate).await;
+            let rows = dbe.get_funding_rates(symbol, limit, start, end).await?;
+            DerivativeBatch::FundingRate(rows.iter().map(|r| (r, EXCHANGE).into()).collect())
+        }
+        DerivativeSeries::OpenInterest => {
+            limiter.acquire_endpoint(EndpointQuota::FuturesData).await;
+            let rows = dbe
+                .get_open_interest_hist(symbol, period_str, limit, start, end)
+                .await?;
+            DerivativeBatch::OpenInterest(
+                rows.iter()
+                    .map(|r| (r, EXCHANGE, period_str).into())
+                    .collect(),
+            )
+        }
+        DerivativeSeries::LongShortRatio(kind) => {
+            limiter.acquire_endpoint(EndpointQuota::FuturesData).await;
+            let rows = dbe
+                .get_long_short_ratio(kind, symbol, period_str, limit, start, end)
+                .await?;
+            DerivativeBatch::LongShortRatio(
+                rows.iter()
+                    .map(|r| (r, EXCHANGE, kind.as_str(), period_str).into())
+                    .collect(),
+            )
+        }
+        DerivativeSeries::PriceKline(kind) => {
+            limiter
+                .acquire_with_limit(MarketType::UsdM, limit as u32)
+                .await;
+            let rows = dbe
+                .get_price_klines(kind, symbol, period_str, limit, start, end)
+                .await?;
+            DerivativeBatch::PriceKline(
+                rows.iter()
+                    .map(|k| (k, EXCHANGE, symbol, kind.as_str(), period_str).into())
+                    .collect(),
+            )
+        }
+    };
+    Ok(batch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_series_windows_fit_request_limits() {
+        for series in DerivativeSeries::ALL {
+            assert_eq!(series.as_str().parse::<DerivativeSeries>(), Ok(series));
+        }
+        assert!("klines".parse::<DerivativeSeries>().is_err());
+
+        // 资金费率按 1 小时步长切分，8 小时周期配置不影响窗口大小
+        let funding = DerivativeSeries::FundingRate;
+        assert_eq!(chunk_size_ms(funding, &TimeFrame::H8), 1000 * 3_600_000);
+        assert_eq!(funding.request_weight(), 1);
+
+        let oi = DerivativeSeries::OpenInterest;
+        assert_eq!(chunk_size_ms(oi, &TimeFrame::M5), 500 * 300_000);
+        assert_eq!(retention_floor(oi, 31 * DAY_MS), Some(DAY_MS));
+        assert_eq!(retention_floor(funding, 31 * DAY_MS), None);
+
+        let mark = DerivativeSeries::PriceKline(PriceKlineKind::Mark);
+        assert_eq!(mark.request_weight(), 5);
+        assert_eq!(mark.locator(&TimeFrame::H1).filters.len(), 2);
+    }
+
+    #[test]
+    fn test_backward_windows_run_newest_first() {
+        let window = |start, end| ArchiveWindow {
+            start_time: Some(start),
+            end_time: Some(end),
+        };
+        let mut task = DerivativeTask {
+            symbol: "BTCUSDT".to_string(),
+            series: DerivativeSeries::FundingRate,
+            period: TimeFrame::H8,
+            window: vec![window(0, 10), window(10, 20), window(20, 30)],
+            direction: ArchiveDirection::Backward,
+        };
+        let starts = |task: &DerivativeTask| -> Vec<Option<i64>> {
+            execution_order(task).iter().map(|w| w.start_time).collect()
+        };
+
+        // 回溯从紧邻已归档数据的窗口开始，中途失败不会在更早的位置留下空洞
+        assert_eq!(starts(&task), vec![Some(20), Some(10), Some(0)]);
+
+        task.direction = ArchiveDirection::Forward;
+        assert_eq!(starts(&task), vec![Some(0), Some(10), Some(20)]);
+    }
+}
@@ -1,151 +1,249 @@
-use crate::collector::archive::fetch::kline_fetch_process;
-use crate::collector::archive::flush::flush_all;
+use crate::collector::archive::derivatives::{
+    self, build_derivative_tasks, run_derivative_tasks, DerivativeConfig, DerivativeTask,
+};
+use crate::collector::archive::dispatcher::{
+    budget_key, estimate_weight, DispatchConfig, FairQueue,
+};
+use crate::collector::archive::fetch::{build_all_archive_tasks, kline_fetch_process};
+use crate::collector::archive::flush::{flush_all, force_flush_all};
+use crate::collector::archive::types::ArchiveTask;
+use crate::collector::archive::universe::{
+    resolve_archive_universe, ArchiveTarget, UniverseConfig,
+};
 use crate::collector::archive::KlineMessage;
-use crate::global::get_flush_buffer;
+use crate::global::{get_dispatch_stats, get_flush_buffer, get_shutdown};
+use crate::model::market_type::MarketType;
 use crate::model::TimeFrame;
-use std::collections::{HashMap, VecDeque};
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
-use std::time::Duration;
-use tokio::sync::{mpsc, Mutex};
+use tokio::sync::{mpsc, Mutex, OwnedSemaphorePermit, Semaphore};
 use tokio::task;
 use tracing::info;
 
+/// 衍生品序列的调度优先级，与其它低频周期共享最低份额
+const DERIVATIVE_PRIORITY: u8 = 10;
+
 /// 每个任务
-#[derive(Clone)]
 struct ArchiveTaskEntry {
-    symbol: String,
     exchange: String,
-    time_frame: Arc<TimeFrame>,
-    priority: u8, // 支持优先级，值越小优先级越高
+    market_type: MarketType,
+    job: ArchiveJob,
+}
+
+/// 归档任务类型：K线写入 worker pool，衍生品序列直接写入 ClickHouse
+enum ArchiveJob {
+    Klines(Vec<ArchiveTask>),
+    Derivatives(Vec<DerivativeTask>),
 }
 
-/// 主调度器（生成任务 + 轮转调度）
+/// 主调度器（生成任务 + 按权重公平调度）
+///
+/// 同时执行的拉取任务不超过 `max_concurrency`，各交易所与市场在途任务的预估请求权重不超过其限流预算份额；
+/// 各优先级按 `class_share` 分享权重，低优先级周期不会被高优先级饿死
 pub async fn start_fair_task_scheduler() -> Result<(), anyhow::Error> {
+    let config = DispatchConfig::from_env();
+    let stats = get_dispatch_stats();
+    if !stats.begin_round(&config) {
+        info!("Previous archive dispatch round is still running, skip.");
+        return Ok(());
+    }
+
+    // 每次运行重新解析归档范围（交易对状态 + 市值排名 + 包含/排除列表）
+    let targets = match resolve_archive_universe(&UniverseConfig::from_env()).await {
+        Ok(targets) => targets,
+        Err(e) => {
+            stats.end_round();
+            return Err(e);
+        }
+    };
+
+    let queue = build_task_queue(&targets).await;
+    if queue.is_empty() {
+        info!("Archive universe is empty, nothing to dispatch.");
+        stats.end_round();
+        return Ok(());
+    }
+    stats.set_queued(&queue.depths());
+
     let (tx, rx) = mpsc::channel::<KlineMessage>(1000);
-    let symbols = vec!["btcusdt"]; //, "ethusdt", "solusdt"
-    let timeframes = vec![TimeFrame::M1]; //, TimeFrame::M5, TimeFrame::H1
 
     // 启动异步 worker pool
     tokio::spawn(start_worker_pool(rx, 20));
 
-    // 启动调度器定时生成任务（公平轮询 + 优先级支持）
-    let mut round_robin_queue = build_task_queues(&symbols, &timeframes);
-
-    // 任务投递 这种方式每个任务执行一次
-    tokio::spawn(async move {
-        loop {
-            let mut dispatched = false;
-
-            for _ in 0..round_robin_queue.len() {
-                if let Some(task_entry) = next_fair_task(&mut round_robin_queue) {
-                    dispatched = true;
-                    let tx = tx.clone();
-
-                    task::spawn(async move {
-                        let messages = kline_fetch_process(
-                            task_entry.symbol.clone(),
-                            task_entry.exchange.clone(),
-                            task_entry.time_frame.clone(),
-                        )
-                        .await;
-
-                        for msg in messages {
-                            let _ = tx.send(msg).await;
-                        }
-                    });
-
-                    tokio::time::sleep(Duration::from_millis(200)).await;
-                }
-            }
+    tokio::spawn(dispatch(queue, config, tx));
 
-            // 所有任务已调度完成（空）
-            if !dispatched {
-                info!("All archive tasks have been dispatched.");
-                break;
-            }
+    Ok(())
+}
 
-            // 控制每轮调度间隔
-            tokio::time::sleep(Duration::from_secs(2)).await;
+/// 构建公平调度队列：预先生成归档任务，按窗口数预估请求权重
+async fn build_task_queue(targets: &[ArchiveTarget]) -> FairQueue<ArchiveTaskEntry> {
+    let mut queue = FairQueue::new();
+
+    for target in targets {
+        let priority = match target.time_frame {
+            TimeFrame::M1 => 1,
+            TimeFrame::M5 => 2,
+            TimeFrame::H1 => 3,
+            _ => 10,
+        };
+        let tasks = build_all_archive_tasks(
+            &target.symbol,
+            &target.exchange,
+            target.market_type,
+            Arc::new(target.time_frame.clone()),
+            target.listing,
+        )
+        .await;
+        if tasks.is_empty() {
+            continue;
         }
-    });
 
-    Ok(())
+        let weight = estimate_weight(&tasks);
+        queue.push(
+            priority,
+            ArchiveTaskEntry {
+                exchange: target.exchange.clone(),
+                market_type: target.market_type,
+                job: ArchiveJob::Klines(tasks),
+            },
+            weight,
+        );
+    }
+
+    push_derivative_tasks(&mut queue, targets).await;
+
+    queue
 }
 
-/// 构建轮转调度队列（每个任务配置优先级）
-fn build_task_queues(
-    symbols: &[&str],
-    timeframes: &[TimeFrame],
-) -> HashMap<u8, VecDeque<ArchiveTaskEntry>> {
-    let mut map: HashMap<u8, VecDeque<ArchiveTaskEntry>> = HashMap::new();
-
-    for symbol in symbols {
-        for tf in timeframes {
-            let priority = match tf {
-                TimeFrame::M1 => 1,
-                TimeFrame::M5 => 2,
-                TimeFrame::H1 => 3,
-                _ => 10,
-            };
-            map.entry(priority)
-                .or_default()
-                .push_back(ArchiveTaskEntry {
-                    symbol: symbol.to_string(),
+/// 为归档范围内的 Binance U本位交易对规划衍生品序列任务，每个交易对只规划一次（与K线周期无关）
+async fn push_derivative_tasks(queue: &mut FairQueue<ArchiveTaskEntry>, targets: &[ArchiveTarget]) {
+    let config = DerivativeConfig::from_env();
+    if !config.is_enabled() {
+        return;
+    }
+
+    let symbols: BTreeMap<&str, _> = targets
+        .iter()
+        .filter(|t| t.exchange == "binance" && t.market_type == MarketType::UsdM)
+        .map(|t| (t.symbol.as_str(), t.listing))
+        .collect();
+
+    for (symbol, listing) in symbols {
+        for series in &config.series {
+            let tasks = build_derivative_tasks(symbol, *series, &config.period, listing).await;
+            if tasks.is_empty() {
+                continue;
+            }
+
+            let weight = derivatives::estimate_weight(&tasks);
+            queue.push(
+                DERIVATIVE_PRIORITY,
+                ArchiveTaskEntry {
                     exchange: "binance".to_string(),
-                    time_frame: Arc::new(tf.clone()),
-                    priority,
-                });
+                    market_type: MarketType::UsdM,
+                    job: ArchiveJob::Derivatives(tasks),
+                },
+                weight,
+            );
         }
     }
-
-    map
 }
 
-/// 公平调度，按优先级轮转队列分发任务 会重复执行
-// fn next_fair_task(
-//     queues: &mut HashMap<u8, VecDeque<ArchiveTaskEntry>>,
-// ) -> Option<ArchiveTaskEntry> {
-//     let mut keys: Vec<u8> = queues.keys().cloned().collect();
-//     keys.sort();
-//
-//     for k in keys {
-//         if let Some(queue) = queues.get_mut(&k) {
-//             if let Some(task) = queue.pop_front() {
-//                 queue.push_back(task.clone()); // 重新放回队尾形成轮转
-//                 return Some(task);
-//             }
-//         }
-//     }
-//     None
-// }
-
-/// 从多级队列中公平地弹出一个任务 让每个任务只执行一次
-fn next_fair_task(
-    queue_map: &mut HashMap<u8, VecDeque<ArchiveTaskEntry>>,
-) -> Option<ArchiveTaskEntry> {
-    let mut keys: Vec<u8> = queue_map.keys().cloned().collect();
-    keys.sort(); // 从低数字的优先级开始
-
-    for key in keys {
-        if let Some(queue) = queue_map.get_mut(&key) {
-            if let Some(task) = queue.pop_front() {
-                return Some(task);
+/// 调度循环：依次取出公平队列中的任务，等待并发槽位与权重预算后执行
+async fn dispatch(
+    mut queue: FairQueue<ArchiveTaskEntry>,
+    config: DispatchConfig,
+    tx: mpsc::Sender<KlineMessage>,
+) {
+    let shutdown = get_shutdown();
+    let stats = get_dispatch_stats();
+    let slots = Arc::new(Semaphore::new(config.max_concurrency));
+    let mut budgets: HashMap<String, Arc<Semaphore>> = HashMap::new();
+    let mut running = Vec::new();
+
+    while let Some(next) = queue.pop() {
+        let entry = next.item;
+        let key = budget_key(&entry.exchange, entry.market_type);
+        let budget = config.weight_budget(&entry.exchange, entry.market_type);
+        let budget_permits = budgets
+            .entry(key.clone())
+            .or_insert_with(|| {
+                stats.set_budget(&key, budget);
+                Arc::new(Semaphore::new(budget as usize))
+            })
+            .clone();
+        // 超过预算的大任务按预算计，独占该交易所与市场的额度执行
+        let weight = next.weight.clamp(1, budget);
+
+        // 退出中不再投递新任务，已投递的任务继续执行至完成
+        let permits = tokio::select! {
+            permits = acquire(slots.clone(), budget_permits, weight) => permits,
+            _ = shutdown.wait() => {
+                info!("Shutdown requested, stop dispatching archive tasks.");
+                break;
             }
-        }
+        };
+
+        stats.record_dispatch(next.priority, &key, weight, next.enqueued_at.elapsed());
+        stats.set_queued(&queue.depths());
+
+        let tx = tx.clone();
+        let stats = stats.clone();
+        let guard = shutdown.track();
+        running.push(task::spawn(async move {
+            let _guard = guard;
+            let _permits = permits;
+            match entry.job {
+                ArchiveJob::Klines(tasks) => {
+                    let messages = kline_fetch_process(tasks).await;
+                    for msg in messages {
+                        let _ = tx.send(msg).await;
+                    }
+                }
+                ArchiveJob::Derivatives(tasks) => run_derivative_tasks(tasks).await,
+            }
+            stats.record_complete(&key, weight);
+        }));
+    }
+
+    info!("All archive tasks have been dispatched.");
+    for handle in running {
+        let _ = handle.await;
     }
+    stats.set_queued(&queue.depths());
+    stats.end_round();
+}
 
-    None
+/// 先占并发槽位再占权重预算；两类信号量都不会关闭
+async fn acquire(
+    slots: Arc<Semaphore>,
+    budget: Arc<Semaphore>,
+    weight: u32,
+) -> (OwnedSemaphorePermit, OwnedSemaphorePermit) {
+    let slot = slots
+        .acquire_owned()
+        .await
+        .expect("dispatch semaphore closed");
+    let weight = budget
+        .acquire_many_owned(weight)
+        .await
+        .expect("dispatch semaphore closed");
+    (slot, weight)
 }
 
 /// 启动异步 Worker 池，消费 `KlineMessage` 并按方向写入目标（MySQL/ClickHouse）
+///
+/// 所有发送端关闭后执行最后一轮 drain，不论阈值写出缓存中的剩余数据
 pub async fn start_worker_pool(receiver: mpsc::Receiver<KlineMessage>, num_workers: usize) {
     let receiver = Arc::new(Mutex::new(receiver));
 
     for i in 0..num_workers {
         let receiver = Arc::clone(&receiver);
         let buffer = get_flush_buffer(); // Forward 和 Backward 都共用一个 buffer，内部按方向区分
+        let guard = get_shutdown().track();
 
         tokio::spawn(async move {
+            let _guard = guard;
             loop {
                 // 尽量缩小锁粒度
                 let maybe_msg = {
@@ -168,7 +266,7 @@ pub async fn start_worker_pool(receiver: mpsc::Receiver<KlineMessage>, num_worke
             }
 
             // 最后一轮 drain
-            if let Err(e) = flush_all(&*buffer).await {
+            if let Err(e) = force_flush_all(&*buffer).await {
                 tracing::error!("Worker {i} final flush failed: {:?}", e);
             }
         });
@@ -0,0 +1,356 @@
+use crate::collector::archive::types::ArchiveTask;
+use crate::common::utils::get_env_or;
+use crate::global::get_binance_limiter;
+use crate::infra::external::rate_limiter::binance_limiter::BinanceLimiter;
+use crate::infra::external::rate_limiter::request_budget;
+use crate::model::market_type::MarketType;
+use chrono::Utc;
+use serde::Serialize;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 归档拉取每次请求的K线数量，与 `fetch_klines_with_retry` 保持一致
+const KLINES_PER_REQUEST: u32 = 1000;
+
+/// 调度参数
+///
+/// - `ARCHIVE_DISPATCH_CONCURRENCY`：同时执行的拉取任务上限，默认 8
+/// - `ARCHIVE_DISPATCH_BUDGET_SHARE`：调度器占用交易所每分钟限流预算的比例，默认 0.75（其余留给按需回补）
+#[derive(Debug, Clone)]
+pub struct DispatchConfig {
+    pub max_concurrency: usize,
+    pub budget_share: f64,
+}
+
+impl DispatchConfig {
+    pub fn from_env() -> Self {
+        Self {
+            max_concurrency: get_env_or("ARCHIVE_DISPATCH_CONCURRENCY", 8usize).max(1),
+            budget_share: get_env_or("ARCHIVE_DISPATCH_BUDGET_SHARE", 0.75f64).clamp(0.01, 1.0),
+        }
+    }
+
+    /// 同一交易所与市场的在途任务可占用的权重上限
+    pub fn weight_budget(&self, exchange: &str, market_type: MarketType) -> u32 {
+        self.share_of(weight_per_minute(exchange, market_type))
+    }
+
+    fn share_of(&self, per_minute: u32) -> u32 {
+        ((per_minute as f64 * self.budget_share).floor() as u32).max(1)
+    }
+}
+
+/// 每分钟的权重预算：Binance 取限流器当前生效的该市场权重上限，OKX / Bybit 按请求次数（每次权重 1）
+pub fn weight_per_minute(exchange: &str, market_type: MarketType) -> u32 {
+    match exchange {
+        "binance" => get_binance_limiter().weight_limit(market_type),
+        _ => request_budget(exchange).0,
+    }
+}
+
+/// 在途权重预算的键：Binance 各市场分别限流，如 binance:usdm
+pub fn budget_key(exchange: &str, market_type: MarketType) -> String {
+    format!("{}:{}", exchange, market_type)
+}
+
+/// 单次K线请求的权重
+pub fn request_weight(exchange: &str) -> u32 {
+    match exchange {
+        "binance" => BinanceLimiter::weight_for_limit(KLINES_PER_REQUEST),
+        _ => 1,
+    }
+}
+
+/// 按窗口数预估一组归档任务的请求权重，每个窗口一次请求
+pub fn estimate_weight(tasks: &[ArchiveTask]) -> u32 {
+    let Some(first) = tasks.first() else {
+        return 0;
+    };
+    let requests: usize = tasks.iter().map(|t| t.window.len()).sum();
+    (requests as u32).saturating_mul(request_weight(&first.exchange))
+}
+
+/// 各优先级的调度份额：1m > 5m > 1h > 其它
+///
+/// 所有优先级都有积压时，最低优先级至少获得 1/10 的权重份额，不会被饿死
+pub fn class_share(priority: u8) -> u32 {
+    match priority {
+        1 => 4,
+        2 => 3,
+        3 => 2,
+        _ => 1,
+    }
+}
+
+/// 排队中的任务
+#[derive(Debug)]
+pub struct Queued<T> {
+    pub item: T,
+    pub priority: u8,
+    pub weight: u32,
+    pub enqueued_at: Instant,
+}
+
+struct PriorityClass<T> {
+    share: u32,
+    /// 已调度权重 / 份额，越小越优先
+    pass: f64,
+    queue: VecDeque<Queued<T>>,
+}
+
+/// 按权重的公平队列（stride scheduling）
+///
+/// 每次从 `已调度权重 / 份额` 最小的非空优先级弹出任务，相同时数值小的优先级先出；
+/// 因此各优先级按份额分享请求权重，而不是高优先级全部清空后才轮到低优先级
+pub struct FairQueue<T> {
+    classes: BTreeMap<u8, PriorityClass<T>>,
+    /// 最近一次弹出时的 pass，空队列重新入队时以此追平，避免积攒额度后突发
+    virtual_time: f64,
+}
+
+impl<T> Default for FairQueue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> FairQueue<T> {
+    pub fn new() -> Self {
+        Self {
+            classes: BTreeMap::new(),
+            virtual_time: 0.0,
+        }
+    }
+
+    pub fn push(&mut self, priority: u8, item: T, weight: u32) {
+        // 队列整体清空后重新开始，历史调度量不再影响新一批任务
+        if self.is_empty() {
+            for class in self.classes.values_mut() {
+                class.pass = self.virtual_time;
+            }
+        }
+
+        let virtual_time = self.virtual_time;
+        let class = self
+            .classes
+            .entry(priority)
+            .or_insert_with(|| PriorityClass {
+                share: class_share(priority),
+                pass: virtual_time,
+                queue: VecDeque::new(),
+            });
+        if class.queue.is_empty() {
+            class.pass = class.pass.max(virtual_time);
+        }
+        class.queue.push_back(Queued {
+            item,
+            priority,
+            weight,
+            enqueued_at: Instant::now(),
+        });
+    }
+
+    pub fn pop(&mut self) -> Option<Queued<T>> {
+        let (_, class) = self
+            .classes
+            .iter_mut()
+            .filter(|(_, c)| !c.queue.is_empty())
+            .min_by(|(pa, a), (pb, b)| a.pass.total_cmp(&b.pass).then(pa.cmp(pb)))?;
+
+        let next = class.queue.pop_front()?;
+        self.virtual_time = class.pass;
+        class.pass += next.weight.max(1) as f64 / class.share as f64;
+        Some(next)
+    }
+
+    /// 各优先级排队数量
+    pub fn depths(&self) -> BTreeMap<u8, usize> {
+        self.classes
+            .iter()
+            .map(|(p, c)| (*p, c.queue.len()))
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.classes.values().map(|c| c.queue.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// 单个优先级的调度统计
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ClassStats {
+    pub share: u32,
+    pub queued: usize,
+    pub dispatched: u64,
+    pub weight_dispatched: u64,
+    pub avg_wait_ms: u64,
+    pub max_wait_ms: u64,
+    #[serde(skip)]
+    total_wait_ms: u64,
+}
+
+/// 调度器状态快照
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DispatchSnapshot {
+    /// 当前轮次是否仍在调度或执行
+    pub running: bool,
+    /// 本轮开始时间（毫秒）
+    pub round_started_at: Option<i64>,
+    pub max_concurrency: usize,
+    pub in_flight: usize,
+    /// 各交易所与市场（见 `budget_key`）在途任务占用的权重
+    pub in_flight_weight: BTreeMap<String, u32>,
+    /// 各交易所与市场的在途权重上限
+    pub weight_budget: BTreeMap<String, u32>,
+    pub completed: u64,
+    pub classes: BTreeMap<u8, ClassStats>,
+}
+
+/// 调度统计，调度循环与任务完成时更新，供 HTTP 接口读取
+#[derive(Default)]
+pub struct DispatchStats {
+    inner: Mutex<DispatchSnapshot>,
+}
+
+impl DispatchStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 开始新一轮调度；上一轮仍在执行时返回 false
+    pub fn begin_round(&self, config: &DispatchConfig) -> bool {
+        let mut s = self.inner.lock().unwrap();
+        if s.running {
+            return false;
+        }
+        *s = DispatchSnapshot {
+            running: true,
+            round_started_at: Some(Utc::now().timestamp_millis()),
+            max_concurrency: config.max_concurrency,
+            ..Default::default()
+        };
+        true
+    }
+
+    pub fn set_queued(&self, depths: &BTreeMap<u8, usize>) {
+        let mut s = self.inner.lock().unwrap();
+        for class in s.classes.values_mut() {
+            class.queued = 0;
+        }
+        for (priority, queued) in depths {
+            let class = s.classes.entry(*priority).or_default();
+            class.share = class_share(*priority);
+            class.queued = *queued;
+        }
+    }
+
+    pub fn set_budget(&self, key: &str, budget: u32) {
+        let mut s = self.inner.lock().unwrap();
+        s.weight_budget.insert(key.to_string(), budget);
+    }
+
+    pub fn record_dispatch(&self, priority: u8, key: &str, weight: u32, wait: Duration) {
+        let mut s = self.inner.lock().unwrap();
+        s.in_flight += 1;
+        *s.in_flight_weight.entry(key.to_string()).or_default() += weight;
+
+        let wait_ms = wait.as_millis() as u64;
+        let class = s.classes.entry(priority).or_default();
+        class.share = class_share(priority);
+        class.dispatched += 1;
+        class.weight_dispatched += weight as u64;
+        class.total_wait_ms += wait_ms;
+        class.max_wait_ms = class.max_wait_ms.max(wait_ms);
+        class.avg_wait_ms = class.total_wait_ms / class.dispatched;
+    }
+
+    pub fn record_complete(&self, key: &str, weight: u32) {
+        let mut s = self.inner.lock().unwrap();
+        s.in_flight = s.in_flight.saturating_sub(1);
+        s.completed += 1;
+        if let Some(w) = s.in_flight_weight.get_mut(key) {
+            *w = w.saturating_sub(weight);
+        }
+    }
+
+    /// 调度结束且在途任务全部完成
+    pub fn end_round(&self) {
+        self.inner.lock().unwrap().running = false;
+    }
+
+    pub fn snapshot(&self) -> DispatchSnapshot {
+        self.inner.lock().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drain_counts(queue: &mut FairQueue<u32>, pops: usize) -> BTreeMap<u8, usize> {
+        let mut counts = BTreeMap::new();
+        for _ in 0..pops {
+            let next = queue.pop().unwrap();
+            *counts.entry(next.priority).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    #[test]
+    fn test_budget_follows_market_limit() {
+        let config = DispatchConfig {
+            max_concurrency: 8,
+            budget_share: 0.75,
+        };
+        assert_eq!(config.share_of(2400), 1800);
+        assert_eq!(config.share_of(6000), 4500);
+        assert_eq!(config.share_of(0), 1);
+        assert_eq!(weight_per_minute("okx", MarketType::Spot), 600);
+        assert_ne!(
+            budget_key("binance", MarketType::Spot),
+            budget_key("binance", MarketType::UsdM)
+        );
+    }
+
+    #[test]
+    fn test_every_class_gets_minimum_share() {
+        let mut queue = FairQueue::new();
+        for i in 0..1000 {
+            for priority in [1, 2, 3, 10] {
+                queue.push(priority, i, 5);
+            }
+        }
+
+        let counts = drain_counts(&mut queue, 100);
+        assert_eq!(counts[&1], 40);
+        assert_eq!(counts[&2], 30);
+        assert_eq!(counts[&3], 20);
+        assert_eq!(counts[&10], 10);
+        assert_eq!(queue.len(), 3900);
+    }
+
+    #[test]
+    fn test_heavy_tasks_consume_more_share() {
+        let mut queue = FairQueue::new();
+        for i in 0..100 {
+            // 1m 任务请求权重是 5m 的 4 倍，份额仅高出 4:3，5m 的调度次数约为 1m 的 3 倍
+            queue.push(1, i, 20);
+            queue.push(2, i, 5);
+            queue.push(2, i, 5);
+        }
+        let counts = drain_counts(&mut queue, 60);
+        assert!(counts[&2] > counts[&1]);
+
+        // 空队列重新入队不会积攒额度插队
+        while queue.pop().is_some() {}
+        queue.push(3, 0, 5);
+        queue.push(1, 0, 5);
+        assert_eq!(queue.pop().unwrap().priority, 1);
+        assert!(queue.depths().values().all(|d| *d <= 1));
+    }
+}
@@ -0,0 +1,506 @@
+use crate::collector::archive::checkpoint;
+use crate::collector::archive::flush::write_batch;
+use crate::collector::archive::sink::ClickhouseSink;
+use crate::collector::archive::types::{ArchiveDirection, ArchiveTask, ArchiveWindow};
+use crate::collector::archive::validate::KlineValidator;
+use crate::collector::archive::KlineMessage;
+use crate::common::utils::{get_env_list, get_env_or};
+use crate::domain::model::archive_checkpoint::CHECKPOINT_STATUS_FLUSHED;
+use crate::global::get_kline_validator;
+use crate::infra::external::binance::market::KlineSummary;
+use crate::model::market_type::MarketType;
+use crate::model::TimeFrame;
+use chrono::{Datelike, NaiveDate};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// 公开数据包只来自 Binance
+const EXCHANGE: &str = "binance";
+
+/// 超过该值的时间戳视为微秒（现货数据包自 2025 年起使用微秒）
+const MICROS_THRESHOLD: i64 = 10_000_000_000_000;
+
+/// 离线导入配置（来自环境变量）
+///
+/// - `ARCHIVE_DUMP_DIR`：数据包所在目录，递归扫描其中的 `*.zip`，未配置时不导入
+/// - `ARCHIVE_DUMP_MARKET_TYPE`：路径中无法识别市场类型（spot / um / cm）时使用，默认 usdm
+/// - `ARCHIVE_DUMP_SYMBOLS`：只导入这些交易对，默认全部
+#[derive(Debug, Clone)]
+pub struct DumpImportConfig {
+    pub dir: Option<PathBuf>,
+    pub default_market_type: MarketType,
+    pub symbols: Vec<String>,
+}
+
+impl DumpImportConfig {
+    pub fn from_env() -> Self {
+        let dir: String = get_env_or("ARCHIVE_DUMP_DIR", String::new());
+        let default_market_type = get_env_or("ARCHIVE_DUMP_MARKET_TYPE", MarketType::UsdM);
+        Self {
+            dir: (!dir.trim().is_empty()).then(|| PathBuf::from(dir.trim())),
+            default_market_type,
+            symbols: get_env_list("ARCHIVE_DUMP_SYMBOLS")
+                .iter()
+                .map(|s| s.to_uppercase())
+                .collect(),
+        }
+    }
+}
+
+/// 数据包覆盖的周期
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DumpPeriod {
+    Monthly { year: i32, month: u32 },
+    Daily { date: NaiveDate },
+}
+
+impl DumpPeriod {
+    /// 覆盖的时间范围 [起点, 下一周期起点)，毫秒
+    pub fn range(&self) -> Option<(i64, i64)> {
+        let (start, next) = match *self {
+            DumpPeriod::Monthly { year, month } => {
+                let start = NaiveDate::from_ymd_opt(year, month, 1)?;
+                let next = if month == 12 {
+                    NaiveDate::from_ymd_opt(year + 1, 1, 1)?
+                } else {
+                    NaiveDate::from_ymd_opt(year, month + 1, 1)?
+                };
+                (start, next)
+            }
+            DumpPeriod::Daily { date } => (date, date.succ_opt()?),
+        };
+        let millis = |d: NaiveDate| Some(d.and_hms_opt(0, 0, 0)?.and_utc().timestamp_millis());
+        Some((millis(start)?, millis(next)?))
+    }
+
+    fn is_daily(&self) -> bool {
+        matches!(self, DumpPeriod::Daily { .. })
+    }
+
+    /// 所在月份的月包周期
+    fn month(&self) -> DumpPeriod {
+        match *self {
+            DumpPeriod::Daily { date } => DumpPeriod::Monthly {
+                year: date.year(),
+                month: date.month(),
+            },
+            monthly => monthly,
+        }
+    }
+}
+
+/// 一个K线数据包，如 `data/futures/um/monthly/klines/BTCUSDT/1m/BTCUSDT-1m-2024-01.zip`
+#[derive(Debug, Clone, PartialEq)]
+pub struct DumpFile {
+    pub path: PathBuf,
+    pub market_type: MarketType,
+    pub symbol: String,
+    pub time_frame: TimeFrame,
+    pub period: DumpPeriod,
+}
+
+impl DumpFile {
+    /// 由文件名 `SYMBOL-INTERVAL-YYYY-MM[-DD].zip` 解析，市场类型取自路径中的 spot / um / cm 目录
+    pub fn parse(path: &Path, default_market_type: MarketType) -> Option<Self> {
+        let name = path.file_name()?.to_str()?.strip_suffix(".zip")?;
+        let parts: Vec<&str> = name.split('-').collect();
+        let (symbol, interval, date) = match parts.as_slice() {
+            [symbol, interval, year, month] => (
+                symbol,
+                interval,
+                DumpPeriod::Monthly {
+                    year: year.parse().ok()?,
+                    month: month.parse().ok()?,
+                },
+            ),
+            [symbol, interval, year, month, day] => (
+                symbol,
+                interval,
+                DumpPeriod::Daily {
+                    date: NaiveDate::from_ymd_opt(
+                        year.parse().ok()?,
+                        month.parse().ok()?,
+                        day.parse().ok()?,
+                    )?,
+                },
+            ),
+            _ => return None,
+        };
+        // 数据包的月线周期为 1mo
+        let interval = if *interval == "1mo" { "1M" } else { interval };
+        let time_frame = TimeFrame::from_str(interval).ok()?;
+        date.range()?;
+
+        let market_type = path
+            .components()
+            .filter_map(|c| c.as_os_str().to_str())
+            .find_map(|c| match c {
+                "spot" => Some(MarketType::Spot),
+                "um" => Some(MarketType::UsdM),
+                "cm" => Some(MarketType::CoinM),
+                _ => None,
+            })
+            .unwrap_or(default_market_type);
+
+        Some(DumpFile {
+            path: path.to_path_buf(),
+            market_type,
+            symbol: symbol.to_uppercase(),
+            time_frame,
+            period: date,
+        })
+    }
+
+    /// 以数据包覆盖范围作为归档窗口，终点为最后一根K线的收盘时间
+    fn task(&self, direction: ArchiveDirection) -> Option<ArchiveTask> {
+        let (start, next) = self.period.range()?;
+        Some(ArchiveTask {
+            symbol: self.symbol.clone(),
+            exchange: EXCHANGE.to_string(),
+            market_type: self.market_type,
+            tf: Arc::new(self.time_frame.clone()),
+            window: vec![ArchiveWindow {
+                start_time: Some(start),
+                end_time: Some(next - 1),
+            }],
+            direction,
+        })
+    }
+
+    fn checksum_path(&self) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(".CHECKSUM");
+        PathBuf::from(path)
+    }
+}
+
+/// 数据包导入错误
+#[derive(Debug)]
+pub enum DumpError {
+    Io(String),
+    /// 缺少 `.CHECKSUM` 文件
+    MissingChecksum(PathBuf),
+    ChecksumMismatch {
+        expected: String,
+        actual: String,
+    },
+    /// 压缩包损坏或不含 CSV
+    Archive(String),
+    /// CSV 行无法解析，行号从 1 开始
+    Parse {
+        line: usize,
+        message: String,
+    },
+}
+
+impl fmt::Display for DumpError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DumpError::Io(msg) => write!(f, "IO error: {}", msg),
+            DumpError::MissingChecksum(path) => {
+                write!(f, "Missing checksum file: {}", path.display())
+            }
+            DumpError::ChecksumMismatch { expected, actual } => {
+                write!(
+                    f,
+                    "Checksum mismatch: expected {}, got {}",
+                    expected, actual
+                )
+            }
+            DumpError::Archive(msg) => write!(f, "Archive error: {}", msg),
+            DumpError::Parse { line, message } => {
+                write!(f, "Parse error at line {}: {}", line, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for DumpError {}
+
+impl From<io::Error> for DumpError {
+    fn from(error: io::Error) -> Self {
+        DumpError::Io(error.to_string())
+    }
+}
+
+/// 一轮导入的统计
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DumpImportReport {
+    /// 扫描到的数据包
+    pub files: usize,
+    pub imported: usize,
+    /// 已导入过而跳过的数据包
+    pub skipped: usize,
+    pub failed: usize,
+    pub klines: usize,
+}
+
+/// 递归扫描目录中的K线数据包
+///
+/// 同一交易对按月包在前、日包在后排序，已被月包覆盖的日包随后会被跳过
+pub fn scan_dump_dir(dir: &Path, default_market_type: MarketType) -> io::Result<Vec<DumpFile>> {
+    let mut files = vec![];
+    let mut dirs = vec![dir.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        for entry in std::fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                dirs.push(path);
+            } else if let Some(file) = DumpFile::parse(&path, default_market_type) {
+                files.push(file);
+            }
+        }
+    }
+    files.sort_by(|a, b| {
+        (a.market_type.as_str(), &a.symbol, a.time_frame.to_str())
+            .cmp(&(b.market_type.as_str(), &b.symbol, b.time_frame.to_str()))
+            .then(a.period.is_daily().cmp(&b.period.is_daily()))
+            .then(a.period.cmp(&b.period))
+    });
+    Ok(files)
+}
+
+/// 校验数据包的 SHA-256，`.CHECKSUM` 文件格式同 `sha256sum` 输出
+pub fn verify_checksum(file: &DumpFile) -> Result<(), DumpError> {
+    let checksum_path = file.checksum_path();
+    if !checksum_path.exists() {
+        return Err(DumpError::MissingChecksum(checksum_path));
+    }
+    let expected = std::fs::read_to_string(&checksum_path)?
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+
+    let mut hasher = Sha256::new();
+    io::copy(&mut File::open(&file.path)?, &mut hasher)?;
+    let actual: String = hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+
+    if actual == expected {
+        Ok(())
+    } else {
+        Err(DumpError::ChecksumMismatch { expected, actual })
+    }
+}
+
+/// 解压并解析数据包中的 CSV
+pub fn read_dump(file: &DumpFile) -> Result<Vec<KlineSummary>, DumpError> {
+    let mut archive = zip::ZipArchive::new(File::open(&file.path)?)
+        .map_err(|e| DumpError::Archive(e.to_string()))?;
+    let index = (0..archive.len())
+        .find(|i| {
+            archive
+                .name_for_index(*i)
+                .is_some_and(|name| name.ends_with(".csv"))
+        })
+        .ok_or_else(|| DumpError::Archive(format!("No csv in {}", file.path.display())))?;
+    let entry = archive
+        .by_index(index)
+        .map_err(|e| DumpError::Archive(e.to_string()))?;
+    parse_kline_csv(BufReader::new(entry))
+}
+
+/// 解析K线 CSV：列顺序与 REST K线接口一致，可选表头（较新的合约数据包带表头）
+pub fn parse_kline_csv(reader: impl BufRead) -> Result<Vec<KlineSummary>, DumpError> {
+    let mut klines = vec![];
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let fields: Vec<&str> = line.split(',').collect();
+        if index == 0 && fields[0].parse::<i64>().is_err() {
+            continue;
+        }
+        if fields.len() < 11 {
+            return Err(DumpError::Parse {
+                line: index + 1,
+                message: format!("expected at least 11 columns, got {}", fields.len()),
+            });
+        }
+
+        let parse_err = |name: &str, value: &str| DumpError::Parse {
+            line: index + 1,
+            message: format!("invalid {}: {}", name, value),
+        };
+        let int = |i: usize, name: &str| {
+            fields[i]
+                .parse::<i64>()
+                .map_err(|_| parse_err(name, fields[i]))
+        };
+        let float = |i: usize, name: &str| {
+            fields[i]
+                .parse::<f64>()
+                .map_err(|_| parse_err(name, fields[i]))
+        };
+        let millis = |ts: i64| {
+            if ts >= MICROS_THRESHOLD {
+                ts / 1000
+            } else {
+                ts
+            }
+        };
+
+        klines.push(KlineSummary {
+            open_time: millis(int(0, "open_time")?),
+            open: float(1, "open")?,
+            high: float(2, "high")?,
+            low: float(3, "low")?,
+            close: float(4, "close")?,
+            volume: float(5, "volume")?,
+            close_time: millis(int(6, "close_time")?),
+            quote_asset_volume: float(7, "quote_volume")?,
+            number_of_trades: int(8, "count")?,
+            taker_buy_base_asset_volume: float(9, "taker_buy_volume")?,
+            taker_buy_quote_asset_volume: float(10, "taker_buy_quote_volume")?,
+        });
+    }
+    Ok(klines)
+}
+
+/// 导入目录中的全部数据包
+///
+/// 写入 ClickHouse（ReplacingMergeTree，重复导入幂等），成功后将数据包覆盖范围登记为
+/// 两个方向上已完成的窗口：回溯进度随 ClickHouse 中最早的K线前移，
+/// 追溯窗口从数据包末尾开始，REST 归档只需补齐之后的部分
+pub async fn import_dumps(config: &DumpImportConfig) -> anyhow::Result<DumpImportReport> {
+    let Some(dir) = config.dir.clone() else {
+        return Ok(DumpImportReport::default());
+    };
+    let default_market_type = config.default_market_type;
+    let files =
+        tokio::task::spawn_blocking(move || scan_dump_dir(&dir, default_market_type)).await??;
+
+    let validator = get_kline_validator();
+    let mut report = DumpImportReport::default();
+    for file in files {
+        if !config.symbols.is_empty() && !config.symbols.contains(&file.symbol) {
+            continue;
+        }
+        report.files += 1;
+
+        let (Some(backward), Some(forward)) = (
+            file.task(ArchiveDirection::Backward),
+            file.task(ArchiveDirection::Forward),
+        ) else {
+            continue;
+        };
+        if is_imported(&file, &backward) {
+            report.skipped += 1;
+            continue;
+        }
+
+        match import_file(&validator, &file).await {
+            Ok(count) => {
+                checkpoint::record_imported(&backward, count);
+                checkpoint::record_imported(&forward, count);
+                report.imported += 1;
+                report.klines += count;
+                info!("Imported {} klines from {}", count, file.path.display());
+            }
+            Err(e) => {
+                report.failed += 1;
+                warn!(?e, "Failed to import {}", file.path.display());
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// 数据包范围已被完成的窗口覆盖：自身窗口，或日包所在月份的月包窗口
+fn is_imported(file: &DumpFile, task: &ArchiveTask) -> bool {
+    let Some((start, next)) = file.period.range() else {
+        return false;
+    };
+    let month_start = file.period.month().range().map(|(s, _)| s);
+    [Some(start), month_start]
+        .into_iter()
+        .flatten()
+        .any(|window_start| {
+            checkpoint::find(&checkpoint::checkpoint_id(task, window_start)).is_some_and(|row| {
+                row.status == CHECKPOINT_STATUS_FLUSHED && row.window_end >= next - 1
+            })
+        })
+}
+
+async fn import_file(validator: &KlineValidator, file: &DumpFile) -> anyhow::Result<usize> {
+    let blocking = file.clone();
+    let klines = tokio::task::spawn_blocking(move || {
+        verify_checksum(&blocking)?;
+        read_dump(&blocking)
+    })
+    .await??;
+    let count = klines.len();
+
+    let message = KlineMessage {
+        datas: klines,
+        symbol: file.symbol.clone(),
+        exchange: EXCHANGE.to_string(),
+        market_type: file.market_type,
+        time_frame: file.time_frame.to_str().to_string(),
+        archive_direction: ArchiveDirection::Backward,
+        checkpoint_id: None,
+    };
+    write_batch(validator, &ClickhouseSink, vec![message]).await?;
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_dump_file_and_csv() {
+        let file = DumpFile::parse(
+            Path::new("data/futures/um/monthly/klines/BTCUSDT/1m/BTCUSDT-1m-2024-02.zip"),
+            MarketType::Spot,
+        )
+        .unwrap();
+        assert_eq!(file.market_type, MarketType::UsdM);
+        assert_eq!(file.symbol, "BTCUSDT");
+        assert_eq!(file.time_frame, TimeFrame::M1);
+        // 2024 为闰年，二月共 29 天
+        let (start, next) = file.period.range().unwrap();
+        assert_eq!(start, 1_706_745_600_000);
+        assert_eq!(next - start, 29 * 86_400_000);
+
+        let daily = DumpFile::parse(
+            Path::new("dumps/ETHUSDT-1h-2024-12-31.zip"),
+            MarketType::Spot,
+        )
+        .unwrap();
+        assert_eq!(daily.market_type, MarketType::Spot);
+        assert!(daily.period.is_daily());
+        assert!(DumpFile::parse(
+            Path::new("BTCUSDT-1m-2024-01.zip.CHECKSUM"),
+            MarketType::Spot
+        )
+        .is_none());
+
+        // 带表头的合约数据与微秒时间戳的现货数据
+        let csv = "open_time,open,high,low,close,volume,close_time,quote_volume,count,taker_buy_volume,taker_buy_quote_volume,ignore\n\
+                   1706745600000,42000.1,42010,41990,42005,12.5,1706745659999,525000.5,300,6.1,256000.2,0\n\
+                   1706745660000000,42005,42020,42000,42015,3,1706745719999999,126045,80,1.5,63022,0\n";
+        let klines = parse_kline_csv(csv.as_bytes()).unwrap();
+        assert_eq!(klines.len(), 2);
+        assert_eq!(klines[0].number_of_trades, 300);
+        assert_eq!(klines[1].open_time, 1_706_745_660_000);
+        assert_eq!(klines[1].close_time, 1_706_745_719_999);
+
+        let err = parse_kline_csv("1706745600000,abc,1,1,1,1,1706745659999,1,1,1,1,0\n".as_bytes())
+            .unwrap_err();
+        assert!(matches!(err, DumpError::Parse { line: 1, .. }));
+    }
+}
@@ -1,11 +1,17 @@
+pub mod bybit_fetcher;
 pub mod helper;
+pub mod okx_fetcher;
 pub mod progress;
 
+use crate::collector::archive::checkpoint;
+use crate::collector::archive::fetch::bybit_fetcher::BybitFetcher;
 use crate::collector::archive::fetch::helper::{
     create_aligned_windows_with_limit, create_aligned_windows_with_limit_backward,
     is_kline_continuous, should_skip_archiving_due_to_old_data, valid_window_range,
 };
+use crate::collector::archive::fetch::okx_fetcher::OkxFetcher;
 use crate::collector::archive::fetch::progress::ProgressTracker;
+use crate::collector::archive::listing::{self, ListingBounds};
 use crate::collector::archive::types::{ArchiveDirection, ArchiveError, ArchiveTask};
 use crate::collector::archive::KlineMessage;
 use crate::common::utils::get_env_bool;
@@ -13,6 +19,7 @@ use crate::global::get_binance_limiter;
 use crate::infra::external::binance::market::KlineSummary;
 use crate::infra::external::binance::DefaultBinanceExchange;
 use crate::model::cex::kline::MinMaxCloseTime;
+use crate::model::market_type::MarketType;
 use crate::model::TimeFrame;
 use async_trait::async_trait;
 use backoff::{future::retry, ExponentialBackoff};
@@ -26,6 +33,11 @@ use tracing::{error, info, warn};
 
 #[async_trait]
 pub trait KlineFetcher: Send + Sync {
+    /// 交易所是否提供该周期的K线
+    fn supports(&self, _tf: &TimeFrame) -> bool {
+        true
+    }
+
     async fn klines(
         &self,
         symbol: &str,
@@ -36,11 +48,13 @@ pub trait KlineFetcher: Send + Sync {
     ) -> anyhow::Result<Vec<KlineSummary>, anyhow::Error>;
 }
 
-pub struct BinanceFetcher;
+pub struct BinanceFetcher {
+    market_type: MarketType,
+}
 
 impl BinanceFetcher {
-    pub fn new() -> Self {
-        BinanceFetcher
+    pub fn new(market_type: MarketType) -> Self {
+        BinanceFetcher { market_type }
     }
 }
 
@@ -56,28 +70,40 @@ impl KlineFetcher for BinanceFetcher {
     ) -> anyhow::Result<Vec<KlineSummary>> {
         // 阻塞式限流，等待令牌
         get_binance_limiter()
-            .acquire_with_limit(limit.unwrap_or(1000).into())
+            .acquire_with_limit(self.market_type, limit.unwrap_or(1000).into())
             .await;
         //let symbol_with_usdt = format!("{}usdt", symbol);
-        let dbe = DefaultBinanceExchange::default();
+        let dbe = DefaultBinanceExchange::for_market(self.market_type);
 
-        let klines = dbe.get_klines(symbol, tf, limit, start, end).await;
+        let klines = dbe.get_klines(symbol, tf, limit, start, end).await?;
         Ok(klines)
     }
 }
 
-/// 顶层调度：用于定时器、外部调用等
-pub async fn kline_fetch_process(
-    symbol: String,
-    exchange: String,
-    time_frame: Arc<TimeFrame>,
-) -> Vec<KlineMessage> {
-    let tf_str = time_frame.to_str();
+/// 按交易所选择 KlineFetcher
+pub fn kline_fetcher(
+    exchange: &str,
+    market_type: MarketType,
+) -> Result<Box<dyn KlineFetcher>, ArchiveError> {
+    match exchange {
+        "binance" => Ok(Box::new(BinanceFetcher::new(market_type))),
+        "okx" => Ok(Box::new(OkxFetcher::new(market_type))),
+        "bybit" => Ok(Box::new(BybitFetcher::new(market_type))),
+        other => Err(ArchiveError::OtherError(format!(
+            "Unsupported exchange: {}",
+            other
+        ))),
+    }
+}
 
-    // 3. 构建归档任务
-    let tasks = build_all_archive_tasks(&symbol, &exchange, time_frame.clone()).await;
+/// 顶层调度：用于定时器、外部调用等
+pub async fn kline_fetch_process(tasks: Vec<ArchiveTask>) -> Vec<KlineMessage> {
+    let Some(first) = tasks.first() else {
+        return vec![];
+    };
+    let (symbol, exchange, tf_str) = (&first.symbol, &first.exchange, first.tf.to_str());
 
-    // // 4. 执行带重试的归档任务
+    // 执行带重试的归档任务（任务由调度器预先构建，以便按请求权重排队）
     match run_archive_task_with_retry(&tasks).await {
         Ok(messages) => {
             if messages.is_empty() {
@@ -100,19 +126,25 @@ pub async fn kline_fetch_process(
     }
 }
 
-/// 构建双向任务
+/// 构建双向任务，窗口受交易对上市/交割时间约束
 pub async fn build_all_archive_tasks(
     symbol: &str,
     exchange: &str,
+    market_type: MarketType,
     tf: Arc<TimeFrame>,
+    listing: ListingBounds,
 ) -> Vec<ArchiveTask> {
     let mut tasks = vec![];
 
-    if let Some(forward_task) = build_forward_tasks(symbol, exchange, tf.clone()).await {
+    if let Some(forward_task) =
+        build_forward_tasks(symbol, exchange, market_type, tf.clone(), listing).await
+    {
         tasks.push(forward_task);
     }
     if get_env_bool("ENABLE_CLICKHOUSE", true) {
-        if let Some(backward_task) = build_backward_tasks(symbol, exchange, tf.clone()).await {
+        if let Some(backward_task) =
+            build_backward_tasks(symbol, exchange, market_type, tf.clone(), listing).await
+        {
             tasks.push(backward_task);
         }
     }
@@ -121,16 +153,40 @@ pub async fn build_all_archive_tasks(
 }
 
 /// 构建回溯任务（Backward）
+///
+/// 已知上市时间时回溯到上市为止，到达后标记序列完成且不再调度；未知时沿用五年上限
 pub async fn build_backward_tasks(
     symbol: &str,
     exchange: &str,
+    market_type: MarketType,
     tf: Arc<TimeFrame>,
+    listing: ListingBounds,
 ) -> Option<ArchiveTask> {
-    let mima_time =
-        ProgressTracker::get_or_init_progress(symbol, exchange, &tf, ArchiveDirection::Backward)
-            .await;
+    let direction = ArchiveDirection::Backward;
+    if listing::is_complete(exchange, market_type, symbol, &tf, direction).await {
+        return None;
+    }
+    if let Some(task) =
+        checkpoint::resume_task(symbol, exchange, market_type, tf.clone(), direction)
+    {
+        return Some(task);
+    }
+
+    let mima_time = checkpoint::merge_progress(
+        ProgressTracker::get_or_init_progress(symbol, exchange, market_type, &tf, direction).await,
+        checkpoint::window_bounds(symbol, exchange, market_type, &tf, direction),
+        direction,
+    );
 
-    if should_skip_archiving_due_to_old_data(mima_time.min_close_time, &symbol, &exchange, &tf) {
+    if listing.reached_listing(mima_time.min_close_time, &tf) {
+        let onboard_time = listing.onboard_time.unwrap_or_default();
+        listing::mark_complete(exchange, market_type, symbol, &tf, direction, onboard_time).await;
+        return None;
+    }
+
+    if listing.onboard_time.is_none()
+        && should_skip_archiving_due_to_old_data(mima_time.min_close_time, &symbol, &exchange, &tf)
+    {
         info!(
             "Skipping old data: {} - {} - {}",
             symbol,
@@ -146,35 +202,60 @@ pub async fn build_backward_tasks(
     let default_chunk_size_ms = 1000 * period_ms;
     let actual_chunk_size_ms = default_chunk_size_ms.min(backtrack_ms);
 
-    // 注意回溯方向：起点在更早时间，终点在更晚时间
-    let start = mima_time.min_close_time - backtrack_ms;
+    // 注意回溯方向：起点在更早时间，终点在更晚时间；不早于上市时间
+    let start = listing.clamp_start(mima_time.min_close_time - backtrack_ms);
     let end = mima_time.min_close_time;
 
     let windows =
         create_aligned_windows_with_limit_backward(start, end, actual_chunk_size_ms, period_ms);
 
     if windows.is_empty() {
-        None
-    } else {
-        Some(ArchiveTask {
-            symbol: symbol.to_string(),
-            exchange: exchange.to_string(),
-            tf,
-            window: windows,
-            direction: ArchiveDirection::Backward,
-        })
+        return None;
     }
+
+    let task = ArchiveTask {
+        symbol: symbol.to_string(),
+        exchange: exchange.to_string(),
+        market_type,
+        tf,
+        window: windows,
+        direction,
+    };
+    checkpoint::register_task(&task);
+    Some(task)
 }
 
 /// 构建追溯任务（Forward）
+///
+/// 有交割时间的合约追溯到交割为止，到达后标记序列完成且不再调度
 pub async fn build_forward_tasks(
     symbol: &str,
     exchange: &str,
+    market_type: MarketType,
     tf: Arc<TimeFrame>,
+    listing: ListingBounds,
 ) -> Option<ArchiveTask> {
-    let mima_time =
-        ProgressTracker::get_or_init_progress(symbol, exchange, &tf, ArchiveDirection::Forward)
-            .await;
+    let direction = ArchiveDirection::Forward;
+    if listing::is_complete(exchange, market_type, symbol, &tf, direction).await {
+        return None;
+    }
+    if let Some(task) =
+        checkpoint::resume_task(symbol, exchange, market_type, tf.clone(), direction)
+    {
+        return Some(task);
+    }
+
+    let mima_time = checkpoint::merge_progress(
+        ProgressTracker::get_or_init_progress(symbol, exchange, market_type, &tf, direction).await,
+        checkpoint::window_bounds(symbol, exchange, market_type, &tf, direction),
+        direction,
+    );
+
+    if listing.reached_delivery(mima_time.max_close_time) {
+        let delivery_time = listing.delivery_time.unwrap_or_default();
+        listing::mark_complete(exchange, market_type, symbol, &tf, direction, delivery_time).await;
+        return None;
+    }
 
     let period_ms = tf.to_millis();
     let backtrack_count = tf.backtrack_count() as i64;
@@ -184,7 +265,7 @@ pub async fn build_forward_tasks(
     let actual_chunk_size_ms = default_chunk_size_ms.min(backtrack_ms);
 
     let start = mima_time.max_close_time;
-    let end = start + backtrack_ms;
+    let end = listing.clamp_end(start + backtrack_ms);
 
     let windows = create_aligned_windows_with_limit(start, end, actual_chunk_size_ms, period_ms);
 
@@ -192,13 +273,16 @@ pub async fn build_forward_tasks(
         return None;
     }
 
-    Some(ArchiveTask {
+    let task = ArchiveTask {
         symbol: symbol.to_string(),
         exchange: exchange.to_string(),
+        market_type,
         tf,
         window: windows,
-        direction: ArchiveDirection::Forward,
-    })
+        direction,
+    };
+    checkpoint::register_task(&task);
+    Some(task)
 }
 
 /// 封装归档任务重试逻辑
@@ -212,6 +296,10 @@ pub async fn run_archive_task_with_retry(
     loop {
         match execute_archive_messages(tasks).await {
             Ok(messages) => return Ok(messages),
+            Err(e) if !e.is_transient() => {
+                error!(?e, "Archive task failed permanently, not retrying");
+                return Err(e);
+            }
             Err(e) => {
                 retries += 1;
                 error!(
@@ -233,28 +321,52 @@ pub async fn run_archive_task_with_retry(
 pub async fn execute_archive_messages(
     tasks: &[ArchiveTask],
 ) -> Result<Vec<KlineMessage>, ArchiveError> {
-    let fetcher = BinanceFetcher::new();
     let mut messages = Vec::with_capacity(tasks.len() * 2); // 预估容量
 
     for task in tasks {
+        let fetcher = kline_fetcher(&task.exchange, task.market_type)?;
+        if !fetcher.supports(&task.tf) {
+            warn!(
+                "{} does not provide {} klines, skipping {}",
+                task.exchange,
+                task.tf.to_str(),
+                task.symbol
+            );
+            continue;
+        }
+
         let tf_str = task.tf.to_str();
         let tf_ms = task.tf.to_millis();
 
         trace_kv!(info,
              "exchange" => task.exchange,
+             "market_type" => task.market_type,
              "symbol" => task.symbol,
              "windows" => task.window.len(),
         );
 
         for window in task.window.iter().filter_map(valid_window_range) {
-            let klines = fetch_klines_with_retry(
-                &fetcher,
+            let checkpoint_id = checkpoint::checkpoint_id(task, window.start_time.unwrap());
+            let klines = match fetch_klines_with_retry(
+                fetcher.as_ref(),
                 &task.symbol,
                 tf_str,
                 window.start_time.unwrap(),
                 window.end_time.unwrap(),
             )
-            .await?;
+            .await
+            {
+                Ok(klines) => klines,
+                Err(e) => {
+                    // 永久错误（如交易对已下架）直接用尽重试次数，断点续传不再重放该窗口
+                    if e.is_transient() {
+                        checkpoint::mark_failed(&[checkpoint_id], &e.to_string());
+                    } else {
+                        checkpoint::mark_abandoned(&[checkpoint_id], &e.to_string());
+                    }
+                    return Err(e);
+                }
+            };
 
             if klines.is_empty() {
                 info!(
@@ -262,8 +374,11 @@ pub async fn execute_archive_messages(
                     window.start_time.unwrap_or(0),
                     window.end_time.unwrap_or(0)
                 );
+                // 无数据可写，窗口直接完成
+                checkpoint::mark_flushed(&[checkpoint_id]);
                 continue;
             }
+            checkpoint::mark_fetched(&checkpoint_id, klines.len());
 
             if !is_kline_continuous(&klines, tf_ms) {
                 warn!(
@@ -280,8 +395,10 @@ pub async fn execute_archive_messages(
                 datas: sorted,
                 symbol: task.symbol.clone(),
                 exchange: task.exchange.clone(),
+                market_type: task.market_type,
                 time_frame: tf_str.to_string(),
                 archive_direction: task.direction,
+                checkpoint_id: Some(checkpoint_id),
             });
         }
     }
@@ -289,9 +406,9 @@ pub async fn execute_archive_messages(
     Ok(messages)
 }
 
-/// 使用 backoff 拉取 K线
+/// 使用 backoff 拉取 K线，仅重试临时错误
 async fn fetch_klines_with_retry(
-    fetcher: &impl KlineFetcher,
+    fetcher: &dyn KlineFetcher,
     symbol: &str,
     tf_str: &str,
     start: i64,
@@ -308,10 +425,15 @@ async fn fetch_klines_with_retry(
             )
             .await
             .map_err(|e| {
-                warn!(?e, "Failed to fetch Klines, retrying...");
-                backoff::Error::transient(e)
+                let e = ArchiveError::from(e);
+                if e.is_transient() {
+                    warn!(?e, "Failed to fetch Klines, retrying...");
+                    backoff::Error::transient(e)
+                } else {
+                    warn!(?e, "Failed to fetch Klines for {}, giving up", symbol);
+                    backoff::Error::permanent(e)
+                }
             })
     })
     .await
-    .map_err(|e| ArchiveError::DataError(e.to_string()))
 }
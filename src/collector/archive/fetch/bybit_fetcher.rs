@@ -0,0 +1,189 @@
+use crate::collector::archive::fetch::KlineFetcher;
+use crate::global::get_bybit_limiter;
+use crate::infra::external::binance::market::KlineSummary;
+use crate::infra::external::bybit::constant::KLINE_MAX_LIMIT;
+use crate::infra::external::bybit::DefaultBybitExchange;
+use crate::infra::external::rate_limiter::RateBudget;
+use crate::model::market_type::MarketType;
+use crate::model::TimeFrame;
+use async_trait::async_trait;
+use chrono::Utc;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Bybit K线拉取：返回 [start, end] 内最新的一页（倒序），以收缩 end 的方式向更早方向翻页
+pub struct BybitFetcher {
+    client: DefaultBybitExchange<'static>,
+    market_type: MarketType,
+    limiter: Arc<dyn RateBudget>,
+    page_size: u16,
+}
+
+impl BybitFetcher {
+    pub fn new(market_type: MarketType) -> Self {
+        Self::with_client(
+            DefaultBybitExchange::default(),
+            market_type,
+            get_bybit_limiter(),
+        )
+    }
+
+    pub fn with_client(
+        client: DefaultBybitExchange<'static>,
+        market_type: MarketType,
+        limiter: Arc<dyn RateBudget>,
+    ) -> Self {
+        Self {
+            client,
+            market_type,
+            limiter,
+            page_size: KLINE_MAX_LIMIT,
+        }
+    }
+
+    /// 单页条数，默认取接口上限 1000
+    pub fn with_page_size(mut self, page_size: u16) -> Self {
+        self.page_size = page_size.clamp(1, KLINE_MAX_LIMIT);
+        self
+    }
+}
+
+/// 周期映射，Bybit 不支持 8h 与 3d
+pub fn bybit_interval(tf: &TimeFrame) -> Option<&'static str> {
+    match tf {
+        TimeFrame::M1 => Some("1"),
+        TimeFrame::M3 => Some("3"),
+        TimeFrame::M5 => Some("5"),
+        TimeFrame::M15 => Some("15"),
+        TimeFrame::M30 => Some("30"),
+        TimeFrame::H1 => Some("60"),
+        TimeFrame::H2 => Some("120"),
+        TimeFrame::H4 => Some("240"),
+        TimeFrame::H6 => Some("360"),
+        TimeFrame::H8 => None,
+        TimeFrame::H12 => Some("720"),
+        TimeFrame::D1 => Some("D"),
+        TimeFrame::D3 => None,
+        TimeFrame::W1 => Some("W"),
+        TimeFrame::M1L => Some("M"),
+    }
+}
+
+#[async_trait]
+impl KlineFetcher for BybitFetcher {
+    fn supports(&self, tf: &TimeFrame) -> bool {
+        bybit_interval(tf).is_some()
+    }
+
+    async fn klines(
+        &self,
+        symbol: &str,
+        interval: &str,
+        limit: Option<u16>,
+        start: Option<u64>,
+        end: Option<u64>,
+    ) -> anyhow::Result<Vec<KlineSummary>> {
+        let tf = TimeFrame::from_str(interval).map_err(|e| anyhow::anyhow!(e))?;
+        let bybit_tf = bybit_interval(&tf)
+            .ok_or_else(|| anyhow::anyhow!("Bybit does not support interval {}", interval))?;
+        let now = Utc::now().timestamp_millis();
+        let start = start.unwrap_or(0) as i64;
+        let end = end.map(|e| e as i64).unwrap_or(now);
+        let is_inverse = self.market_type == MarketType::CoinM;
+
+        let mut klines = vec![];
+        let mut cursor_end = end;
+
+        loop {
+            self.limiter.acquire().await;
+            let page = self
+                .client
+                .get_klines(
+                    self.market_type,
+                    symbol,
+                    bybit_tf,
+                    start,
+                    cursor_end,
+                    self.page_size,
+                )
+                .await?;
+
+            let Some(oldest) = page.iter().map(|k| k.start_time).min() else {
+                break;
+            };
+
+            // 未收盘的K线不入库
+            klines.extend(
+                page.iter()
+                    .filter(|k| k.start_time >= start && k.start_time <= end)
+                    .map(|k| k.to_kline_summary(tf.close_time(k.start_time), is_inverse))
+                    .filter(|k| k.close_time < now),
+            );
+
+            if oldest <= start || oldest > cursor_end || page.len() < self.page_size as usize {
+                break;
+            }
+            cursor_end = oldest - 1;
+        }
+
+        // 与 Binance 语义一致：从 start 起按时间升序最多返回 limit 根
+        klines.sort_by_key(|k| k.open_time);
+        klines.dedup_by_key(|k| k.open_time);
+        klines.truncate(limit.unwrap_or(1000) as usize);
+        Ok(klines)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::test_utils::{spawn_fixture_server, FixtureRoute};
+    use crate::infra::external::rate_limiter::request_limiter::RequestLimiter;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_bybit_klines_paginate_backwards() {
+        let base_url = spawn_fixture_server(vec![
+            FixtureRoute {
+                matches: "end=1717200299999",
+                body: include_str!(concat!(
+                    env!("CARGO_MANIFEST_DIR"),
+                    "/tests/fixtures/bybit/kline_page1.json"
+                )),
+            },
+            FixtureRoute {
+                matches: "end=1717200119999",
+                body: include_str!(concat!(
+                    env!("CARGO_MANIFEST_DIR"),
+                    "/tests/fixtures/bybit/kline_page2.json"
+                )),
+            },
+        ])
+        .await;
+
+        let fetcher = BybitFetcher::with_client(
+            DefaultBybitExchange::with_base_url(base_url),
+            MarketType::UsdM,
+            Arc::new(RequestLimiter::new(100, Duration::from_secs(1))),
+        )
+        .with_page_size(3);
+
+        let klines = fetcher
+            .klines(
+                "BTCUSDT",
+                "1m",
+                Some(1000),
+                Some(1717200000000),
+                Some(1717200299999),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(klines.len(), 5);
+        assert!(klines.windows(2).all(|w| w[0].open_time < w[1].open_time));
+        assert_eq!(klines[0].open_time, 1717200000000);
+        assert_eq!(klines[4].close_time, 1717200299999);
+        assert_eq!(klines[0].volume, 12.5);
+        assert_eq!(klines[0].quote_asset_volume, 843600.4);
+    }
+}
@@ -0,0 +1,191 @@
+use crate::collector::archive::fetch::KlineFetcher;
+use crate::global::get_okx_limiter;
+use crate::infra::external::binance::market::KlineSummary;
+use crate::infra::external::okx::constant::HISTORY_CANDLES_MAX_LIMIT;
+use crate::infra::external::okx::DefaultOkxExchange;
+use crate::infra::external::rate_limiter::RateBudget;
+use crate::model::market_type::MarketType;
+use crate::model::TimeFrame;
+use async_trait::async_trait;
+use chrono::Utc;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// OKX K线拉取：history-candles 按开盘时间倒序返回，以 `after` 游标向更早方向翻页
+pub struct OkxFetcher {
+    client: DefaultOkxExchange<'static>,
+    market_type: MarketType,
+    limiter: Arc<dyn RateBudget>,
+    page_size: u16,
+}
+
+impl OkxFetcher {
+    pub fn new(market_type: MarketType) -> Self {
+        Self::with_client(
+            DefaultOkxExchange::default(),
+            market_type,
+            get_okx_limiter(),
+        )
+    }
+
+    pub fn with_client(
+        client: DefaultOkxExchange<'static>,
+        market_type: MarketType,
+        limiter: Arc<dyn RateBudget>,
+    ) -> Self {
+        Self {
+            client,
+            market_type,
+            limiter,
+            page_size: HISTORY_CANDLES_MAX_LIMIT,
+        }
+    }
+
+    /// 单页条数，默认取接口上限 100
+    pub fn with_page_size(mut self, page_size: u16) -> Self {
+        self.page_size = page_size.clamp(1, HISTORY_CANDLES_MAX_LIMIT);
+        self
+    }
+}
+
+/// 周期映射，6h 及以上使用 UTC 对齐的周期与 Binance 保持一致；OKX 不支持 8h
+pub fn okx_bar(tf: &TimeFrame) -> Option<&'static str> {
+    match tf {
+        TimeFrame::M1 => Some("1m"),
+        TimeFrame::M3 => Some("3m"),
+        TimeFrame::M5 => Some("5m"),
+        TimeFrame::M15 => Some("15m"),
+        TimeFrame::M30 => Some("30m"),
+        TimeFrame::H1 => Some("1H"),
+        TimeFrame::H2 => Some("2H"),
+        TimeFrame::H4 => Some("4H"),
+        TimeFrame::H6 => Some("6Hutc"),
+        TimeFrame::H8 => None,
+        TimeFrame::H12 => Some("12Hutc"),
+        TimeFrame::D1 => Some("1Dutc"),
+        TimeFrame::D3 => Some("3Dutc"),
+        TimeFrame::W1 => Some("1Wutc"),
+        TimeFrame::M1L => Some("1Mutc"),
+    }
+}
+
+#[async_trait]
+impl KlineFetcher for OkxFetcher {
+    fn supports(&self, tf: &TimeFrame) -> bool {
+        okx_bar(tf).is_some()
+    }
+
+    async fn klines(
+        &self,
+        symbol: &str,
+        interval: &str,
+        limit: Option<u16>,
+        start: Option<u64>,
+        end: Option<u64>,
+    ) -> anyhow::Result<Vec<KlineSummary>> {
+        let tf = TimeFrame::from_str(interval).map_err(|e| anyhow::anyhow!(e))?;
+        let bar = okx_bar(&tf)
+            .ok_or_else(|| anyhow::anyhow!("OKX does not support interval {}", interval))?;
+        let start = start.unwrap_or(0) as i64;
+        let end = end
+            .map(|e| e as i64)
+            .unwrap_or_else(|| Utc::now().timestamp_millis());
+        let is_spot = self.market_type == MarketType::Spot;
+
+        let mut klines = vec![];
+        // after 为开区间：返回开盘时间早于游标的数据
+        let mut cursor = end + 1;
+
+        loop {
+            self.limiter.acquire().await;
+            let page = self
+                .client
+                .get_history_candles(symbol, bar, Some(cursor), self.page_size)
+                .await?;
+
+            let Some(oldest) = page.iter().map(|c| c.ts).min() else {
+                break;
+            };
+
+            klines.extend(
+                page.iter()
+                    .filter(|c| c.is_confirmed() && c.ts >= start && c.ts <= end)
+                    .map(|c| c.to_kline_summary(tf.close_time(c.ts), is_spot)),
+            );
+
+            if oldest <= start || oldest >= cursor || page.len() < self.page_size as usize {
+                break;
+            }
+            cursor = oldest;
+        }
+
+        // 与 Binance 语义一致：从 start 起按时间升序最多返回 limit 根
+        klines.sort_by_key(|k| k.open_time);
+        klines.dedup_by_key(|k| k.open_time);
+        klines.truncate(limit.unwrap_or(1000) as usize);
+        Ok(klines)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::test_utils::{spawn_fixture_server, FixtureRoute};
+    use crate::infra::external::rate_limiter::request_limiter::RequestLimiter;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_okx_klines_paginate_backwards() {
+        let base_url = spawn_fixture_server(vec![
+            FixtureRoute {
+                matches: "after=1717200300000",
+                body: include_str!(concat!(
+                    env!("CARGO_MANIFEST_DIR"),
+                    "/tests/fixtures/okx/history_candles_page1.json"
+                )),
+            },
+            FixtureRoute {
+                matches: "after=1717200120000",
+                body: include_str!(concat!(
+                    env!("CARGO_MANIFEST_DIR"),
+                    "/tests/fixtures/okx/history_candles_page2.json"
+                )),
+            },
+        ])
+        .await;
+
+        let fetcher = OkxFetcher::with_client(
+            DefaultOkxExchange::with_base_url(base_url),
+            MarketType::UsdM,
+            Arc::new(RequestLimiter::new(100, Duration::from_secs(1))),
+        )
+        .with_page_size(3);
+
+        let klines = fetcher
+            .klines(
+                "BTC-USDT-SWAP",
+                "1m",
+                Some(1000),
+                Some(1717200000000),
+                Some(1717200299999),
+            )
+            .await
+            .unwrap();
+
+        let open_times: Vec<i64> = klines.iter().map(|k| k.open_time).collect();
+        assert_eq!(
+            open_times,
+            vec![
+                1717200000000,
+                1717200060000,
+                1717200120000,
+                1717200180000,
+                1717200240000
+            ]
+        );
+        assert_eq!(klines[0].close_time, 1717200059999);
+        // 永续合约的成交量取 volCcy（币数量）
+        assert_eq!(klines[0].volume, 12.5);
+        assert_eq!(klines[0].quote_asset_volume, 843600.4);
+    }
+}
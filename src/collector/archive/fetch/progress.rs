@@ -4,6 +4,7 @@ use crate::domain::repository::market_kline_repository::MarketKlineRepository;
 use crate::domain::service::market_kline_service::MarketKlineService;
 use crate::global::{get_ck_db, get_mysql_pool};
 use crate::model::cex::kline::MinMaxCloseTime;
+use crate::model::market_type::MarketType;
 use crate::model::TimeFrame;
 use chrono::Utc;
 use tracing::{error, info};
@@ -15,6 +16,7 @@ impl ProgressTracker {
     pub async fn get_or_init_progress(
         symbol: &str,
         exchange: &str,
+        market_type: MarketType,
         time_frame: &TimeFrame,
         direction: ArchiveDirection,
     ) -> MinMaxCloseTime {
@@ -26,7 +28,12 @@ impl ProgressTracker {
                         let repo = MarketKlineRepository::new(&mut conn);
                         let mut service = MarketKlineService { repo };
                         match service
-                            .get_mima_time(exchange, symbol, time_frame.to_str())
+                            .get_mima_time(
+                                exchange,
+                                market_type.as_str(),
+                                symbol,
+                                time_frame.to_str(),
+                            )
                             .await
                         {
                             Ok(Some(r)) => Some(MinMaxCloseTime {
@@ -49,7 +56,7 @@ impl ProgressTracker {
 
             ArchiveDirection::Backward => {
                 match get_ck_db()
-                    .get_mima_time(exchange, symbol, time_frame.to_str())
+                    .get_mima_time(exchange, market_type.as_str(), symbol, time_frame.to_str())
                     .await
                 {
                     Ok(Some(r)) => Some(MinMaxCloseTime {
@@ -69,9 +76,10 @@ impl ProgressTracker {
             Some(progress) => progress,
             None => {
                 info!(
-                    "No history progress found. Using fallback time for {} - {} - {}",
+                    "No history progress found. Using fallback time for {} - {} - {} - {}",
                     symbol,
                     exchange,
+                    market_type,
                     time_frame.to_str()
                 );
                 let fallback = get_default_start_time_with_offset(time_frame, 90)
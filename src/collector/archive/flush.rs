@@ -1,18 +1,115 @@
+use crate::collector::archive::checkpoint;
+use crate::collector::archive::dead_letter;
 use crate::collector::archive::kline_buffer::FlushableBuffer;
 use crate::collector::archive::sink::{ClickhouseSink, KlineSink, MysqlSink};
+use crate::collector::archive::validate::{KlineValidator, RejectedKline};
+use crate::collector::archive::KlineMessage;
+use crate::domain::model::kline_quarantine::NewOrUpdateKlineQuarantine;
+use crate::domain::repository::kline_quarantine_repository::KlineQuarantineRepository;
+use crate::domain::service::kline_quarantine_service::KlineQuarantineService;
+use crate::global::{get_kline_hub, get_kline_validator, get_mysql_pool};
+use tracing::warn;
 
 pub async fn flush_all<B: FlushableBuffer>(buffer: &B) -> Result<(), anyhow::Error> {
+    let validator = get_kline_validator();
+
     // Forward -> MySQL
     if buffer.should_flush_forward().await {
-        let data = buffer.drain_forward().await;
-        MysqlSink.write(data).await?;
+        flush_to(&validator, &MysqlSink, buffer.drain_forward().await).await?;
     }
 
     // Backward -> ClickHouse
     if buffer.should_flush_backward().await {
-        let data = buffer.drain_backward().await;
-        ClickhouseSink.write(data).await?;
+        flush_to(&validator, &ClickhouseSink, buffer.drain_backward().await).await?;
     }
 
     Ok(())
 }
+
+/// 不论阈值，立即写出两个方向的全部缓存数据，用于退出前的最后一轮 drain
+pub async fn force_flush_all<B: FlushableBuffer>(buffer: &B) -> Result<(), anyhow::Error> {
+    let validator = get_kline_validator();
+
+    let forward = flush_to(&validator, &MysqlSink, buffer.drain_forward().await).await;
+    let backward = flush_to(&validator, &ClickhouseSink, buffer.drain_backward().await).await;
+
+    forward.and(backward)
+}
+
+/// 写入失败的批次转入死信存储，等待自动重试或人工重放
+pub async fn flush_to(
+    validator: &KlineValidator,
+    sink: &dyn KlineSink,
+    data: Vec<KlineMessage>,
+) -> Result<(), anyhow::Error> {
+    if data.is_empty() {
+        return Ok(());
+    }
+
+    let result = write_batch(validator, sink, data.clone()).await;
+    if let Err(e) = &result {
+        dead_letter::store(sink.name(), e, data).await;
+    }
+    result
+}
+
+/// 校验并写入目标存储，按结果更新消息所属窗口的检查点；写入成功的K线推送给实时订阅者
+pub async fn write_batch(
+    validator: &KlineValidator,
+    sink: &dyn KlineSink,
+    data: Vec<KlineMessage>,
+) -> Result<(), anyhow::Error> {
+    let ids = checkpoint::checkpoint_ids(&data);
+    let hub = get_kline_hub();
+    let result = match validate_and_quarantine(validator, data) {
+        Ok(accepted) => {
+            // 仅在有订阅时保留一份副本，写库成功后推送给实时订阅者
+            let live = hub.has_subscribers().then(|| accepted.clone());
+            let result = sink.write(accepted).await;
+            if let (Ok(()), Some(live)) = (&result, live) {
+                hub.publish(&live);
+            }
+            result
+        }
+        Err(e) => Err(e),
+    };
+
+    match &result {
+        Ok(()) => checkpoint::mark_flushed(&ids),
+        Err(e) => checkpoint::mark_failed(&ids, &e.to_string()),
+    }
+    result
+}
+
+/// 写库前的校验阶段：未通过校验的K线写入隔离表，其余继续写入目标存储
+pub fn validate_and_quarantine(
+    validator: &KlineValidator,
+    data: Vec<KlineMessage>,
+) -> Result<Vec<KlineMessage>, anyhow::Error> {
+    let (accepted, rejected) = validator.validate(data);
+    if !rejected.is_empty() {
+        quarantine(&rejected)?;
+    }
+    Ok(accepted)
+}
+
+fn quarantine(rejected: &[RejectedKline]) -> Result<(), anyhow::Error> {
+    for r in rejected {
+        warn!(
+            "Quarantined {} {} {} {} kline at {}: {} ({})",
+            r.message.exchange,
+            r.message.market_type,
+            r.message.symbol,
+            r.message.time_frame,
+            r.kline.close_time,
+            r.rule.as_str(),
+            r.detail
+        );
+    }
+
+    let rows: Vec<NewOrUpdateKlineQuarantine> = rejected.iter().map(Into::into).collect();
+    let mut conn = get_mysql_pool().get()?;
+    let repo = KlineQuarantineRepository::new(&mut conn);
+    let mut service = KlineQuarantineService { repo };
+    service.save_quarantined(rows)
+}
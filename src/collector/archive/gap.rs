@@ -0,0 +1,388 @@
+use crate::collector::archive::fetch::execute_archive_messages;
+use crate::collector::archive::fetch::helper::create_aligned_windows_with_limit_backward;
+use crate::collector::archive::flush::validate_and_quarantine;
+use crate::collector::archive::sink::{ClickhouseSink, KlineSink, MysqlSink};
+use crate::collector::archive::types::{ArchiveDirection, ArchiveTask};
+use crate::domain::model::kline_gap::{
+    encode_kline_gap_pk, KlineGap, NewOrUpdateKlineGap, GAP_STATUS_FILLED, GAP_STATUS_FOUND,
+    GAP_STATUS_UNFILLABLE,
+};
+use crate::domain::service::kline_gap_service::KlineGapService;
+use crate::domain::service::market_kline_service::MarketKlineService;
+use crate::global::{get_ck_db, get_kline_validator, get_mysql_pool};
+use crate::model::market_type::MarketType;
+use crate::model::TimeFrame;
+use chrono::Utc;
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// 单次扫描的K线根数，避免一次性加载全部历史
+const GAP_SCAN_CHUNK: i64 = 50_000;
+
+/// 单个缺口最大修复次数，超过后标记为无法补齐
+pub const GAP_REPAIR_MAX_ATTEMPTS: u32 = 3;
+
+/// 缺口所在存储：Forward 数据在 MySQL，Backward 数据在 ClickHouse
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GapStore {
+    MySql,
+    ClickHouse,
+}
+
+impl GapStore {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GapStore::MySql => "mysql",
+            GapStore::ClickHouse => "clickhouse",
+        }
+    }
+
+    pub fn from_name(value: &str) -> Option<GapStore> {
+        match value {
+            "mysql" => Some(GapStore::MySql),
+            "clickhouse" => Some(GapStore::ClickHouse),
+            _ => None,
+        }
+    }
+
+    /// 修复数据写回时使用的归档方向
+    pub fn direction(&self) -> ArchiveDirection {
+        match self {
+            GapStore::MySql => ArchiveDirection::Forward,
+            GapStore::ClickHouse => ArchiveDirection::Backward,
+        }
+    }
+}
+
+/// 根据升序的 close_time 序列找出缺失区间，返回 (第一根缺失, 最后一根缺失) 的 close_time
+pub fn find_kline_gaps(close_times: &[i64], tf_ms: i64) -> Vec<(i64, i64)> {
+    close_times
+        .windows(2)
+        .filter(|w| w[1] - w[0] > tf_ms)
+        .map(|w| (w[0] + tf_ms, w[1] - tf_ms))
+        .collect()
+}
+
+/// 扫描指定市场在某个存储中的全部缺口
+pub async fn scan_kline_gaps(
+    exchange: &str,
+    market_type: MarketType,
+    symbol: &str,
+    tf: &TimeFrame,
+    store: GapStore,
+) -> anyhow::Result<Vec<(i64, i64)>> {
+    // 月线周期长度不固定，按固定毫秒数比较会误报
+    if *tf == TimeFrame::M1L {
+        return Ok(vec![]);
+    }
+
+    let period = tf.to_str();
+    let tf_ms = tf.to_millis();
+
+    let Some(mima) = get_min_max_close_time(exchange, market_type, symbol, period, store).await?
+    else {
+        return Ok(vec![]);
+    };
+
+    let chunk_ms = GAP_SCAN_CHUNK * tf_ms;
+    let mut gaps = vec![];
+    let mut prev: Option<i64> = None;
+    let mut cursor = mima.0;
+
+    while cursor <= mima.1 {
+        let chunk_end = (cursor + chunk_ms - 1).min(mima.1);
+        let mut close_times = Vec::with_capacity(GAP_SCAN_CHUNK as usize + 1);
+        close_times.extend(prev);
+        close_times.extend(
+            list_close_times(
+                exchange,
+                market_type,
+                symbol,
+                period,
+                store,
+                cursor,
+                chunk_end,
+            )
+            .await?,
+        );
+
+        gaps.extend(find_kline_gaps(&close_times, tf_ms));
+
+        prev = close_times.last().copied().or(prev);
+        cursor = chunk_end + 1;
+    }
+
+    Ok(gaps)
+}
+
+/// 扫描缺口并持久化，已存在的缺口保留其状态与修复次数
+pub async fn scan_and_record_gaps(
+    exchange: &str,
+    market_type: MarketType,
+    symbol: &str,
+    tf: &TimeFrame,
+    store: GapStore,
+) -> anyhow::Result<usize> {
+    let gaps = scan_kline_gaps(exchange, market_type, symbol, tf, store).await?;
+    if gaps.is_empty() {
+        return Ok(0);
+    }
+
+    let period = tf.to_str();
+    let tf_ms = tf.to_millis();
+    let now = Utc::now().naive_utc();
+
+    let mut conn = get_mysql_pool().get()?;
+    let mut service = KlineGapService::new(&mut conn);
+
+    let mut records = Vec::with_capacity(gaps.len());
+    for (gap_start, gap_end) in &gaps {
+        let id = encode_kline_gap_pk(
+            exchange,
+            market_type.as_str(),
+            symbol,
+            period,
+            store.as_str(),
+            *gap_start,
+        );
+        let missing_count = (gap_end - gap_start) / tf_ms + 1;
+
+        let record = match service.get_by_id(&id)? {
+            Some(existing) => NewOrUpdateKlineGap {
+                gap_end: *gap_end,
+                missing_count,
+                updated_at: now,
+                ..existing.into()
+            },
+            None => NewOrUpdateKlineGap {
+                id,
+                exchange: exchange.to_string(),
+                market_type: market_type.as_str().to_string(),
+                symbol: symbol.to_string(),
+                time_frame: period.to_string(),
+                store: store.as_str().to_string(),
+                gap_start: *gap_start,
+                gap_end: *gap_end,
+                missing_count,
+                filled_count: 0,
+                status: GAP_STATUS_FOUND.to_string(),
+                attempts: 0,
+                detected_at: now,
+                updated_at: now,
+            },
+        };
+        records.push(record);
+    }
+
+    let count = records.len();
+    service.save_kline_gaps(records)?;
+
+    info!(
+        "Recorded {} kline gaps for {} - {} - {} - {} ({})",
+        count,
+        exchange,
+        market_type,
+        symbol,
+        period,
+        store.as_str()
+    );
+    Ok(count)
+}
+
+/// 通过现有 KlineFetcher 重新拉取缺口区间并写回对应存储，返回更新后的缺口记录
+pub async fn repair_kline_gap(gap: &KlineGap) -> anyhow::Result<NewOrUpdateKlineGap> {
+    let store = GapStore::from_name(&gap.store)
+        .ok_or_else(|| anyhow::anyhow!("Unknown gap store: {}", gap.store))?;
+    let tf: TimeFrame = gap
+        .time_frame
+        .parse()
+        .map_err(|e: String| anyhow::anyhow!(e))?;
+    let tf_ms = tf.to_millis();
+    let market_type: MarketType = gap
+        .market_type
+        .parse()
+        .map_err(|e: String| anyhow::anyhow!(e))?;
+
+    // Binance 按 open_time 过滤：首根缺失K线的 open_time 起，到末根缺失K线的下一周期止
+    let windows = create_aligned_windows_with_limit_backward(
+        gap.gap_start,
+        gap.gap_end + 1,
+        1000 * tf_ms,
+        tf_ms,
+    );
+
+    let task = ArchiveTask {
+        symbol: gap.symbol.clone(),
+        exchange: gap.exchange.clone(),
+        market_type,
+        tf: Arc::new(tf),
+        window: windows,
+        direction: store.direction(),
+    };
+
+    // 修复数据同样经过校验，被隔离的K线不计入已补齐
+    let messages = validate_and_quarantine(
+        &get_kline_validator(),
+        execute_archive_messages(&[task]).await?,
+    )?;
+
+    let filled: BTreeSet<i64> = messages
+        .iter()
+        .flat_map(|m| m.datas.iter().map(|k| k.close_time))
+        .filter(|t| *t >= gap.gap_start && *t <= gap.gap_end)
+        .collect();
+
+    if !messages.is_empty() {
+        match store {
+            GapStore::MySql => MysqlSink.write(messages).await?,
+            GapStore::ClickHouse => ClickhouseSink.write(messages).await?,
+        }
+    }
+
+    let attempts = gap.attempts + 1;
+    let filled_count = filled.len() as i64;
+    let status = if filled_count >= gap.missing_count {
+        GAP_STATUS_FILLED
+    } else if filled_count == 0 || attempts >= GAP_REPAIR_MAX_ATTEMPTS {
+        GAP_STATUS_UNFILLABLE
+    } else {
+        GAP_STATUS_FOUND
+    };
+
+    if status == GAP_STATUS_UNFILLABLE {
+        warn!(
+            "Kline gap {} ~ {} for {} - {} - {} - {} has no exchange data ({} / {} filled)",
+            gap.gap_start,
+            gap.gap_end,
+            gap.exchange,
+            gap.market_type,
+            gap.symbol,
+            gap.time_frame,
+            filled_count,
+            gap.missing_count
+        );
+    }
+
+    Ok(NewOrUpdateKlineGap {
+        filled_count,
+        status: status.to_string(),
+        attempts,
+        updated_at: Utc::now().naive_utc(),
+        ..gap.clone().into()
+    })
+}
+
+/// 修复指定市场在某个存储中所有待处理的缺口
+pub async fn repair_open_gaps(
+    exchange: &str,
+    market_type: MarketType,
+    symbol: &str,
+    tf: &TimeFrame,
+    store: GapStore,
+) -> anyhow::Result<()> {
+    let open_gaps = {
+        let mut conn = get_mysql_pool().get()?;
+        let mut service = KlineGapService::new(&mut conn);
+        service.list_open_gaps(
+            exchange,
+            market_type.as_str(),
+            symbol,
+            tf.to_str(),
+            store.as_str(),
+        )?
+    };
+
+    for gap in &open_gaps {
+        let updated = match repair_kline_gap(gap).await {
+            Ok(updated) => updated,
+            Err(e) => {
+                warn!(?e, "Failed to repair kline gap {}", gap.id);
+                continue;
+            }
+        };
+
+        let mut conn = get_mysql_pool().get()?;
+        let mut service = KlineGapService::new(&mut conn);
+        service.save_kline_gaps(vec![updated])?;
+    }
+
+    Ok(())
+}
+
+async fn get_min_max_close_time(
+    exchange: &str,
+    market_type: MarketType,
+    symbol: &str,
+    period: &str,
+    store: GapStore,
+) -> anyhow::Result<Option<(i64, i64)>> {
+    let mima = match store {
+        GapStore::MySql => {
+            let mut conn = get_mysql_pool().get()?;
+            let mut service = MarketKlineService::new(&mut conn);
+            service
+                .get_mima_time(exchange, market_type.as_str(), symbol, period)
+                .await?
+        }
+        GapStore::ClickHouse => {
+            get_ck_db()
+                .get_mima_time(exchange, market_type.as_str(), symbol, period)
+                .await?
+        }
+    };
+
+    Ok(mima.map(|m| (m.min_close_time, m.max_close_time)))
+}
+
+async fn list_close_times(
+    exchange: &str,
+    market_type: MarketType,
+    symbol: &str,
+    period: &str,
+    store: GapStore,
+    start: i64,
+    end: i64,
+) -> anyhow::Result<Vec<i64>> {
+    match store {
+        GapStore::MySql => {
+            let mut conn = get_mysql_pool().get()?;
+            let mut service = MarketKlineService::new(&mut conn);
+            Ok(service.list_close_times(
+                exchange,
+                market_type.as_str(),
+                symbol,
+                period,
+                start,
+                end,
+            )?)
+        }
+        GapStore::ClickHouse => {
+            get_ck_db()
+                .query_close_times(exchange, market_type.as_str(), symbol, period, start, end)
+                .await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_kline_gaps() {
+        let tf_ms = 60_000;
+        let close_times = vec![59_999, 119_999, 299_999, 359_999, 479_999];
+
+        let gaps = find_kline_gaps(&close_times, tf_ms);
+
+        assert_eq!(gaps, vec![(179_999, 239_999), (419_999, 419_999)]);
+    }
+
+    #[test]
+    fn test_find_kline_gaps_continuous() {
+        let close_times = vec![59_999, 119_999, 179_999];
+        assert!(find_kline_gaps(&close_times, 60_000).is_empty());
+        assert!(find_kline_gaps(&[], 60_000).is_empty());
+    }
+}
@@ -0,0 +1,164 @@
+use crate::collector::archive::types::ArchiveDirection;
+use crate::domain::model::market_symbol::MarketSymbol;
+use crate::global::get_kv;
+use crate::model::market_type::MarketType;
+use crate::model::TimeFrame;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+/// 已归档到上市/交割时间的序列，Redis 哈希表，字段为 exchange:market_type:symbol:tf:direction
+const SERIES_COMPLETE_KEY: &str = "archive:series_complete";
+
+/// 交易对上市与交割时间（毫秒），约束归档窗口；交易所未提供时为 None
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ListingBounds {
+    pub onboard_time: Option<i64>,
+    pub delivery_time: Option<i64>,
+}
+
+impl From<&MarketSymbol> for ListingBounds {
+    fn from(s: &MarketSymbol) -> Self {
+        Self::new(s.onboard_date, s.delivery_date)
+    }
+}
+
+impl ListingBounds {
+    /// 非正数视为未知（现货与永续合约通常没有交割时间）
+    pub fn new(onboard_date: i64, delivery_date: i64) -> Self {
+        Self {
+            onboard_time: (onboard_date > 0).then_some(onboard_date),
+            delivery_time: (delivery_date > 0).then_some(delivery_date),
+        }
+    }
+
+    /// 回溯窗口的起点不早于上市时间
+    pub fn clamp_start(&self, start: i64) -> i64 {
+        self.onboard_time.map_or(start, |t| start.max(t))
+    }
+
+    /// 追溯窗口的终点不晚于交割时间
+    pub fn clamp_end(&self, end: i64) -> i64 {
+        self.delivery_time.map_or(end, |t| end.min(t))
+    }
+
+    /// 已归档的最早收盘时间已覆盖上市后的第一根K线
+    pub fn reached_listing(&self, min_close_time: i64, tf: &TimeFrame) -> bool {
+        self.onboard_time
+            .is_some_and(|t| min_close_time <= t + tf.to_millis())
+    }
+
+    /// 已归档的最晚收盘时间已到达交割时间
+    pub fn reached_delivery(&self, max_close_time: i64) -> bool {
+        self.delivery_time.is_some_and(|t| max_close_time >= t)
+    }
+}
+
+/// 序列完成记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeriesCompletion {
+    /// 完成时的边界：回溯为上市时间，追溯为交割时间
+    pub bound: i64,
+    /// 标记完成的时间（毫秒）
+    pub completed_at: i64,
+}
+
+pub fn series_field(
+    exchange: &str,
+    market_type: MarketType,
+    symbol: &str,
+    tf: &TimeFrame,
+    direction: ArchiveDirection,
+) -> String {
+    let direction = match direction {
+        ArchiveDirection::Forward => "forward",
+        ArchiveDirection::Backward => "backward",
+    };
+    format!(
+        "{}:{}:{}:{}:{}",
+        exchange,
+        market_type,
+        symbol,
+        tf.to_str(),
+        direction
+    )
+}
+
+/// 序列在该方向上是否已归档完成；Redis 不可用时按未完成处理
+pub async fn is_complete(
+    exchange: &str,
+    market_type: MarketType,
+    symbol: &str,
+    tf: &TimeFrame,
+    direction: ArchiveDirection,
+) -> bool {
+    is_field_complete(&series_field(exchange, market_type, symbol, tf, direction)).await
+}
+
+/// 按完成标记字段查询，供K线以外的序列（如资金费率）在 `series_field` 后追加序列名使用
+pub async fn is_field_complete(field: &str) -> bool {
+    match get_kv()
+        .hget::<SeriesCompletion>(SERIES_COMPLETE_KEY, field)
+        .await
+    {
+        Ok(completion) => completion.is_some(),
+        Err(e) => {
+            warn!(?e, "Failed to read series completion for {}", field);
+            false
+        }
+    }
+}
+
+/// 标记序列在该方向上归档完成，之后不再为其调度任务
+pub async fn mark_complete(
+    exchange: &str,
+    market_type: MarketType,
+    symbol: &str,
+    tf: &TimeFrame,
+    direction: ArchiveDirection,
+    bound: i64,
+) {
+    mark_field_complete(
+        &series_field(exchange, market_type, symbol, tf, direction),
+        bound,
+    )
+    .await;
+}
+
+/// 按完成标记字段标记序列完成
+pub async fn mark_field_complete(field: &str, bound: i64) {
+    let completion = SeriesCompletion {
+        bound,
+        completed_at: Utc::now().timestamp_millis(),
+    };
+    match get_kv().hset(SERIES_COMPLETE_KEY, field, &completion).await {
+        Ok(()) => info!("Archive series {} complete at {}", field, bound),
+        Err(e) => warn!(?e, "Failed to mark series {} complete", field),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_listing_bounds_clamp_windows() {
+        let onboard = 1_569_398_400_000; // 2019-09-25
+        let bounds = ListingBounds::new(onboard, 0);
+        assert_eq!(bounds.delivery_time, None);
+        assert_eq!(bounds.clamp_start(onboard - 1_000), onboard);
+        assert_eq!(bounds.clamp_start(onboard + 1_000), onboard + 1_000);
+        assert_eq!(bounds.clamp_end(i64::MAX), i64::MAX);
+
+        let tf = TimeFrame::M1;
+        assert!(!bounds.reached_listing(onboard + 2 * 60_000, &tf));
+        assert!(bounds.reached_listing(onboard + 60_000 - 1, &tf));
+        assert!(!ListingBounds::default().reached_listing(0, &tf));
+
+        let delivery = 1_719_561_600_000;
+        let quarterly = ListingBounds::new(onboard, delivery);
+        assert_eq!(quarterly.clamp_end(delivery + 1), delivery);
+        assert!(quarterly.reached_delivery(delivery));
+        assert!(!quarterly.reached_delivery(delivery - 1));
+    }
+}
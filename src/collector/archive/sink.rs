@@ -1,3 +1,4 @@
+use crate::collector::archive::derivatives::DerivativeBatch;
 use crate::collector::archive::{IntoSinkRows, KlineMessage};
 use crate::domain::model::market_kline::NewOrUpdateMarketKline as MysqlKline;
 use crate::domain::repository::market_kline_repository::MarketKlineRepository;
@@ -10,6 +11,9 @@ use async_trait::async_trait;
 
 #[async_trait]
 pub trait KlineSink: Send + Sync {
+    /// 存储名称：mysql / clickhouse
+    fn name(&self) -> &'static str;
+
     async fn write(&self, data: Vec<KlineMessage>) -> Result<(), anyhow::Error>;
 }
 
@@ -17,6 +21,10 @@ pub struct MysqlSink;
 
 #[async_trait]
 impl KlineSink for MysqlSink {
+    fn name(&self) -> &'static str {
+        "mysql"
+    }
+
     async fn write(&self, data: Vec<KlineMessage>) -> Result<(), anyhow::Error> {
         let items: Vec<MysqlKline> = data.iter().flat_map(|m| m.into_sink_rows()).collect();
 
@@ -32,9 +40,35 @@ pub struct ClickhouseSink;
 
 #[async_trait]
 impl KlineSink for ClickhouseSink {
+    fn name(&self) -> &'static str {
+        "clickhouse"
+    }
+
     async fn write(&self, data: Vec<KlineMessage>) -> Result<(), anyhow::Error> {
         let items: Vec<ClickhouseKline> = data.iter().flat_map(|m| m.into_sink_rows()).collect();
 
         get_ck_db().insert_batch(&items).await
     }
 }
+
+impl ClickhouseSink {
+    /// 写入衍生品序列（资金费率、持仓量、多空比、标记/指数价格K线），各序列对应独立的表
+    pub async fn write_derivatives(&self, batch: &DerivativeBatch) -> Result<(), anyhow::Error> {
+        let db = get_ck_db();
+        match batch {
+            DerivativeBatch::FundingRate(rows) => db.insert_batch(rows).await,
+            DerivativeBatch::OpenInterest(rows) => db.insert_batch(rows).await,
+            DerivativeBatch::LongShortRatio(rows) => db.insert_batch(rows).await,
+            DerivativeBatch::PriceKline(rows) => db.insert_batch(rows).await,
+        }
+    }
+}
+
+/// 按名称查找存储，用于死信重放
+pub fn sink_by_name(name: &str) -> Option<Box<dyn KlineSink>> {
+    match name {
+        "mysql" => Some(Box::new(MysqlSink)),
+        "clickhouse" => Some(Box::new(ClickhouseSink)),
+        _ => None,
+    }
+}
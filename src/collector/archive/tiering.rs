@@ -0,0 +1,333 @@
+use crate::common::utils::{get_env_bool, get_env_or};
+use crate::domain::model::market_kline::MarketKline;
+use crate::domain::service::market_kline_service::MarketKlineService;
+use crate::global::{get_ck_db, get_mysql_pool};
+use crate::model::cex::kline::MarketKline as CkMarketKline;
+use chrono::Utc;
+use serde::Serialize;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+use tracing::{info, warn};
+
+const DAY_MS: i64 = 86_400_000;
+
+/// 冷热分层配置（来自环境变量）
+///
+/// - `KLINE_TIERING_ENABLED`：是否定时执行，默认 false
+/// - `KLINE_TIERING_RETENTION_DAYS`：MySQL 保留的天数，更早收盘的K线迁移到 ClickHouse，默认 90
+/// - `KLINE_TIERING_DRY_RUN`：只比对并输出报告，不写入也不删除，默认 false
+/// - `KLINE_TIERING_MAX_DAYS`：单次最多处理的 (序列, 日) 数，默认 500
+#[derive(Debug, Clone)]
+pub struct TieringConfig {
+    pub retention_days: i64,
+    pub dry_run: bool,
+    pub max_days: usize,
+}
+
+impl TieringConfig {
+    pub fn from_env() -> Self {
+        Self {
+            retention_days: get_env_or("KLINE_TIERING_RETENTION_DAYS", 90i64).max(1),
+            dry_run: get_env_bool("KLINE_TIERING_DRY_RUN", false),
+            max_days: get_env_or("KLINE_TIERING_MAX_DAYS", 500usize).max(1),
+        }
+    }
+
+    /// 迁移边界：按 UTC 日对齐，收盘时间早于该时间的K线参与迁移
+    pub fn cutoff(&self, now: i64) -> i64 {
+        now.div_euclid(DAY_MS) * DAY_MS - self.retention_days * DAY_MS
+    }
+}
+
+/// 单个 (序列, 日) 的处理结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TieringStatus {
+    /// 已写入 ClickHouse、校验通过并从 MySQL 删除
+    Moved,
+    /// 试运行：ClickHouse 中已有一致的数据，可直接删除
+    Verified,
+    /// 试运行：ClickHouse 中缺失或不一致，需要复制
+    Pending,
+    /// 写入后校验不一致，MySQL 数据保留
+    Mismatch,
+    Failed,
+}
+
+/// 单个 (序列, 日) 的迁移明细
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TieringDay {
+    pub exchange: String,
+    pub market_type: String,
+    pub symbol: String,
+    pub time_frame: String,
+    /// UTC 日起点（毫秒）
+    pub day: i64,
+    pub mysql_rows: usize,
+    /// ClickHouse 中与 MySQL 同开盘时间的行数
+    pub clickhouse_rows: usize,
+    pub status: TieringStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 一次分层迁移的报告
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TieringReport {
+    pub dry_run: bool,
+    pub cutoff: i64,
+    pub copied: usize,
+    pub deleted: usize,
+    pub mismatched: usize,
+    pub failed: usize,
+    /// 达到单次处理上限，仍有未处理的数据
+    pub truncated: bool,
+    pub days: Vec<TieringDay>,
+}
+
+/// 行数与校验和，用于比对 MySQL 与 ClickHouse 中同一日的数据
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DayDigest {
+    pub rows: usize,
+    pub checksum: u64,
+}
+
+/// 按开盘时间排序后对全部字段求校验和；浮点数按位比较
+pub fn day_digest(rows: &[CkMarketKline]) -> DayDigest {
+    let mut sorted: Vec<&CkMarketKline> = rows.iter().collect();
+    sorted.sort_by_key(|k| k.open_time);
+
+    let mut hasher = DefaultHasher::new();
+    for k in sorted {
+        (k.open_time, k.close_time, k.number_of_trades).hash(&mut hasher);
+        for value in [
+            k.open,
+            k.high,
+            k.low,
+            k.close,
+            k.volume,
+            k.quote_asset_volume,
+            k.taker_buy_base_asset_volume,
+            k.taker_buy_quote_asset_volume,
+        ] {
+            value.to_bits().hash(&mut hasher);
+        }
+    }
+    DayDigest {
+        rows: rows.len(),
+        checksum: hasher.finish(),
+    }
+}
+
+/// ClickHouse 中与 MySQL 同开盘时间的行；同一日内 ClickHouse 独有的K线不参与比对
+fn matching_rows(clickhouse: Vec<CkMarketKline>, mysql: &[CkMarketKline]) -> Vec<CkMarketKline> {
+    let open_times: HashSet<i64> = mysql.iter().map(|k| k.open_time).collect();
+    clickhouse
+        .into_iter()
+        .filter(|k| open_times.contains(&k.open_time))
+        .collect()
+}
+
+/// 将收盘时间早于保留边界的 MySQL K线按 (序列, 日) 迁移到 ClickHouse
+///
+/// 每日数据写入后回读 ClickHouse，行数与校验和一致才从 MySQL 删除；试运行只比对不写入
+pub async fn run_tiering(config: &TieringConfig) -> anyhow::Result<TieringReport> {
+    let cutoff = config.cutoff(Utc::now().timestamp_millis());
+    let mut report = TieringReport {
+        dry_run: config.dry_run,
+        cutoff,
+        ..Default::default()
+    };
+
+    let series = {
+        let mut conn = get_mysql_pool().get()?;
+        MarketKlineService::new(&mut conn).list_series_closed_before(cutoff)?
+    };
+
+    'series: for (exchange, market_type, symbol, time_frame) in series {
+        let mut from = i64::MIN;
+        loop {
+            if report.days.len() >= config.max_days {
+                report.truncated = true;
+                break 'series;
+            }
+
+            let (day, rows) = {
+                let mut conn = get_mysql_pool().get()?;
+                let mut service = MarketKlineService::new(&mut conn);
+                let Some(next) = service.next_open_time_closed_before(
+                    &exchange,
+                    &market_type,
+                    &symbol,
+                    &time_frame,
+                    from,
+                    cutoff,
+                )?
+                else {
+                    break;
+                };
+                let day = next.div_euclid(DAY_MS) * DAY_MS;
+                let rows: Vec<MarketKline> = service
+                    .list_by_open_time(
+                        &exchange,
+                        &market_type,
+                        &symbol,
+                        &time_frame,
+                        day,
+                        day + DAY_MS,
+                    )?
+                    .into_iter()
+                    .filter(|k| k.close_time < cutoff)
+                    .collect();
+                (day, rows)
+            };
+            from = day + DAY_MS;
+
+            let mut entry = TieringDay {
+                exchange: exchange.clone(),
+                market_type: market_type.clone(),
+                symbol: symbol.clone(),
+                time_frame: time_frame.clone(),
+                day,
+                mysql_rows: rows.len(),
+                clickhouse_rows: 0,
+                status: TieringStatus::Failed,
+                error: None,
+            };
+            if let Err(e) = tier_day(config.dry_run, &rows, &mut entry, &mut report).await {
+                warn!(
+                    ?e,
+                    "Tiering failed for {} {} {} {} at {}",
+                    exchange,
+                    market_type,
+                    symbol,
+                    time_frame,
+                    day
+                );
+                entry.status = TieringStatus::Failed;
+                entry.error = Some(e.to_string());
+            }
+            match entry.status {
+                TieringStatus::Mismatch => report.mismatched += 1,
+                TieringStatus::Failed => report.failed += 1,
+                _ => {}
+            }
+            report.days.push(entry);
+        }
+    }
+
+    info!(
+        "Kline tiering{} before {}: {} days, {} copied, {} deleted, {} mismatched, {} failed",
+        if config.dry_run { " (dry run)" } else { "" },
+        cutoff,
+        report.days.len(),
+        report.copied,
+        report.deleted,
+        report.mismatched,
+        report.failed
+    );
+    Ok(report)
+}
+
+async fn tier_day(
+    dry_run: bool,
+    rows: &[MarketKline],
+    entry: &mut TieringDay,
+    report: &mut TieringReport,
+) -> anyhow::Result<()> {
+    let ck_rows: Vec<CkMarketKline> = rows.iter().map(Into::into).collect();
+    let expected = day_digest(&ck_rows);
+
+    if !dry_run {
+        get_ck_db().insert_now(&ck_rows).await?;
+        report.copied += ck_rows.len();
+    }
+
+    let stored = matching_rows(
+        get_ck_db()
+            .query_klines_by_open_time(
+                &entry.exchange,
+                &entry.market_type,
+                &entry.symbol,
+                &entry.time_frame,
+                entry.day,
+                entry.day + DAY_MS,
+            )
+            .await?,
+        &ck_rows,
+    );
+    let actual = day_digest(&stored);
+    entry.clickhouse_rows = actual.rows;
+
+    entry.status = match (dry_run, actual == expected) {
+        (true, true) => TieringStatus::Verified,
+        (true, false) => TieringStatus::Pending,
+        (false, false) => {
+            warn!(
+                "Tiering verification failed for {} {} {} {} at {}: mysql {:?}, clickhouse {:?}",
+                entry.exchange,
+                entry.market_type,
+                entry.symbol,
+                entry.time_frame,
+                entry.day,
+                expected,
+                actual
+            );
+            TieringStatus::Mismatch
+        }
+        (false, true) => {
+            let ids: Vec<String> = rows.iter().map(|k| k.id.clone()).collect();
+            let mut conn = get_mysql_pool().get()?;
+            report.deleted += MarketKlineService::new(&mut conn).delete_by_ids(&ids)?;
+            TieringStatus::Moved
+        }
+    };
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kline(open_time: i64, close: f64) -> CkMarketKline {
+        CkMarketKline {
+            exchange: "binance".to_string(),
+            market_type: "usdm".to_string(),
+            symbol: "BTCUSDT".to_string(),
+            period: "1m".to_string(),
+            open_time,
+            open: 100.0,
+            high: 101.0,
+            low: 99.0,
+            close,
+            volume: 10.0,
+            close_time: open_time + 59_999,
+            quote_asset_volume: 1000.0,
+            number_of_trades: 5,
+            taker_buy_base_asset_volume: 5.0,
+            taker_buy_quote_asset_volume: 500.0,
+        }
+    }
+
+    #[test]
+    fn test_day_digest_and_matching_rows() {
+        let mysql = vec![kline(60_000, 100.5), kline(0, 100.0)];
+        // ClickHouse 中多出的K线不影响比对，顺序不同校验和一致
+        let clickhouse = vec![kline(0, 100.0), kline(60_000, 100.5), kline(120_000, 1.0)];
+        let stored = matching_rows(clickhouse, &mysql);
+        assert_eq!(day_digest(&stored), day_digest(&mysql));
+        assert_eq!(day_digest(&stored).rows, 2);
+
+        let changed = vec![kline(0, 100.0), kline(60_000, 100.500_000_1)];
+        assert_ne!(day_digest(&changed), day_digest(&mysql));
+        assert_ne!(day_digest(&mysql[..1]), day_digest(&mysql));
+
+        let config = TieringConfig {
+            retention_days: 90,
+            dry_run: true,
+            max_days: 10,
+        };
+        assert_eq!(config.cutoff(100 * DAY_MS + 1234), 10 * DAY_MS);
+    }
+}
@@ -1,3 +1,5 @@
+use crate::infra::external::binance::error::BinanceError;
+use crate::model::market_type::MarketType;
 use crate::model::TimeFrame;
 use serde::{Deserialize, Serialize};
 use std::fmt;
@@ -45,9 +47,25 @@ pub enum ArchiveError {
     DatabaseError(String),
     DataError(String),
     TimeoutError(String),
+    RateLimitedError(String),
+    /// 交易对不存在或已下架，重试无意义
+    InvalidSymbolError(String),
     OtherError(String),
 }
 
+impl ArchiveError {
+    /// 网络、超时、限流与数据库错误可重试；数据错误、无效交易对等永久错误直接放弃
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            ArchiveError::NetworkError(_)
+                | ArchiveError::DatabaseError(_)
+                | ArchiveError::TimeoutError(_)
+                | ArchiveError::RateLimitedError(_)
+        )
+    }
+}
+
 // 实现 Display trait 来格式化错误消息
 impl fmt::Display for ArchiveError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -56,11 +74,15 @@ impl fmt::Display for ArchiveError {
             ArchiveError::DatabaseError(msg) => write!(f, "Database Error: {}", msg),
             ArchiveError::DataError(msg) => write!(f, "Data Error: {}", msg),
             ArchiveError::TimeoutError(msg) => write!(f, "Timeout Error: {}", msg),
+            ArchiveError::RateLimitedError(msg) => write!(f, "Rate Limited Error: {}", msg),
+            ArchiveError::InvalidSymbolError(msg) => write!(f, "Invalid Symbol Error: {}", msg),
             ArchiveError::OtherError(msg) => write!(f, "Other Error: {}", msg),
         }
     }
 }
 
+impl std::error::Error for ArchiveError {}
+
 // 实现 From trait 来支持转换其他错误类型为 ArchiveError
 impl From<std::io::Error> for ArchiveError {
     fn from(error: std::io::Error) -> Self {
@@ -74,12 +96,37 @@ impl From<tokio::time::error::Elapsed> for ArchiveError {
     }
 }
 
+impl From<BinanceError> for ArchiveError {
+    fn from(error: BinanceError) -> Self {
+        let msg = error.to_string();
+        match error {
+            BinanceError::Network(_) | BinanceError::Server { .. } => {
+                ArchiveError::NetworkError(msg)
+            }
+            BinanceError::RateLimited { .. } => ArchiveError::RateLimitedError(msg),
+            BinanceError::InvalidSymbol(_) => ArchiveError::InvalidSymbolError(msg),
+            BinanceError::Request { .. } | BinanceError::Decode(_) => ArchiveError::DataError(msg),
+        }
+    }
+}
+
+/// 拉取器返回的错误：Binance 按错误类型分类，其它交易所的错误按网络错误处理（可重试）
+impl From<anyhow::Error> for ArchiveError {
+    fn from(error: anyhow::Error) -> Self {
+        match error.downcast::<BinanceError>() {
+            Ok(e) => e.into(),
+            Err(e) => ArchiveError::NetworkError(e.to_string()),
+        }
+    }
+}
+
 // ========== Input Structures ==========
 
 #[derive(Debug, Clone)]
 pub struct ArchiveTask {
     pub symbol: String,
     pub exchange: String,
+    pub market_type: MarketType,
     pub tf: Arc<TimeFrame>,
     pub window: Vec<ArchiveWindow>,
     pub direction: ArchiveDirection,
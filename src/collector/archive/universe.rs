@@ -0,0 +1,316 @@
+use crate::collector::archive::listing::ListingBounds;
+use crate::common::utils::{get_env_list, get_env_or, normalize_base_asset};
+use crate::domain::model::coin_rank_info::CoinRankInfoFilter;
+use crate::domain::model::market_symbol::{MarketSymbol, MarketSymbolFilter};
+use crate::domain::model::SortOrder;
+use crate::domain::repository::coin_rank_info_repository::CoinRankInfoRepository;
+use crate::domain::repository::market_symbol_repository::MarketSymbolRepository;
+use crate::domain::repository::FilterableRepository;
+use crate::global::get_mysql_pool;
+use crate::model::market_type::MarketType;
+use crate::model::TimeFrame;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::str::FromStr;
+use tracing::{info, warn};
+
+/// 单次查询交易对/排名的最大行数
+const MAX_UNIVERSE_ROWS: i64 = 10_000;
+
+/// 归档目标：(交易所, 市场类型, 交易对, 周期)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ArchiveTarget {
+    pub exchange: String,
+    pub market_type: MarketType,
+    pub symbol: String,
+    pub time_frame: TimeFrame,
+    /// 上市/交割时间，约束回溯与追溯窗口
+    pub listing: ListingBounds,
+}
+
+/// 归档范围配置（来自环境变量）
+///
+/// - `ARCHIVE_EXCHANGES`：交易所列表，如 `binance,okx,bybit`，未配置时回退 `ARCHIVE_EXCHANGE`，默认 binance
+/// - `ARCHIVE_MARKET_TYPES`：市场类型，如 `spot,usdm,coinm`，默认 usdm
+/// - `ARCHIVE_QUOTE_ASSETS`：报价资产过滤，如 `USDT,USDC`（币本位合约均以 USD 计价，不参与过滤）
+/// - `ARCHIVE_TOP_N`：按 CoinGecko 市值排名取前 N，0 表示不过滤
+/// - `ARCHIVE_INCLUDE_SYMBOLS` / `ARCHIVE_EXCLUDE_SYMBOLS`：显式包含/排除的交易对（使用交易所原生格式，如 BTC-USDT-SWAP）
+/// - `ARCHIVE_TIMEFRAMES`：归档周期，如 `1m,5m,1h`
+#[derive(Debug, Clone)]
+pub struct UniverseConfig {
+    pub exchanges: Vec<String>,
+    pub market_types: Vec<MarketType>,
+    pub quote_assets: Vec<String>,
+    pub top_n: u32,
+    pub include_symbols: Vec<String>,
+    pub exclude_symbols: Vec<String>,
+    pub time_frames: Vec<TimeFrame>,
+}
+
+impl UniverseConfig {
+    pub fn from_env() -> Self {
+        let mut quote_assets = upper_all(get_env_list("ARCHIVE_QUOTE_ASSETS"));
+        if quote_assets.is_empty() {
+            quote_assets.push("USDT".to_string());
+        }
+
+        let mut time_frames: Vec<TimeFrame> = get_env_list("ARCHIVE_TIMEFRAMES")
+            .iter()
+            .filter_map(|tf| match TimeFrame::from_str(tf) {
+                Ok(tf) => Some(tf),
+                Err(e) => {
+                    warn!("Ignoring archive time frame: {}", e);
+                    None
+                }
+            })
+            .collect();
+        if time_frames.is_empty() {
+            time_frames.push(TimeFrame::M1);
+        }
+
+        let mut market_types: Vec<MarketType> = get_env_list("ARCHIVE_MARKET_TYPES")
+            .iter()
+            .filter_map(|mt| match MarketType::from_str(mt) {
+                Ok(mt) => Some(mt),
+                Err(e) => {
+                    warn!("Ignoring archive market type: {}", e);
+                    None
+                }
+            })
+            .collect();
+        if market_types.is_empty() {
+            market_types.push(MarketType::UsdM);
+        }
+        market_types.sort();
+        market_types.dedup();
+
+        let mut exchanges: Vec<String> = get_env_list("ARCHIVE_EXCHANGES")
+            .into_iter()
+            .map(|e| e.to_lowercase())
+            .collect();
+        if exchanges.is_empty() {
+            exchanges.push(get_env_or("ARCHIVE_EXCHANGE", "binance".to_string()).to_lowercase());
+        }
+        exchanges.sort();
+        exchanges.dedup();
+
+        Self {
+            exchanges,
+            market_types,
+            quote_assets,
+            top_n: get_env_or("ARCHIVE_TOP_N", 20),
+            include_symbols: upper_all(get_env_list("ARCHIVE_INCLUDE_SYMBOLS")),
+            exclude_symbols: upper_all(get_env_list("ARCHIVE_EXCLUDE_SYMBOLS")),
+            time_frames,
+        }
+    }
+}
+
+/// 每次运行时重新解析归档范围：新上线的合约自动加入，下架的自动移除
+pub async fn resolve_archive_universe(
+    config: &UniverseConfig,
+) -> Result<Vec<ArchiveTarget>, anyhow::Error> {
+    let mut conn = get_mysql_pool().get()?;
+
+    let ranked_assets = if config.top_n > 0 {
+        let mut repo = CoinRankInfoRepository::new(&mut conn);
+        let filter = CoinRankInfoFilter {
+            symbol: None,
+            symbol_like: None,
+            min_rank: None,
+            max_rank: Some(config.top_n),
+            sort_by_rank: Some(SortOrder::Asc),
+            page: None,
+            page_size: None,
+        };
+        let ranks = repo.filter_paginated(&filter, 0, MAX_UNIVERSE_ROWS)?;
+        Some(
+            ranks
+                .into_iter()
+                .map(|r| r.symbol.to_uppercase())
+                .collect::<HashSet<String>>(),
+        )
+    } else {
+        None
+    };
+
+    let mut targets: Vec<ArchiveTarget> = vec![];
+    let markets = config
+        .exchanges
+        .iter()
+        .flat_map(|e| config.market_types.iter().map(move |mt| (e, mt)));
+    for (exchange, market_type) in markets {
+        let trading_symbols = {
+            let mut repo = MarketSymbolRepository::new(&mut conn);
+            let quote_assets = match market_type {
+                MarketType::CoinM => None,
+                _ => Some(config.quote_assets.clone()),
+            };
+            let filter = MarketSymbolFilter {
+                exchange: Some(exchange.clone()),
+                market_type: Some(market_type.as_str().to_string()),
+                symbol: None,
+                status: Some("TRADING".to_string()),
+                quote_assets,
+                page: None,
+                page_size: None,
+            };
+            repo.filter_paginated(&filter, 0, MAX_UNIVERSE_ROWS)?
+        };
+
+        let symbols = select_symbols(&trading_symbols, ranked_assets.as_ref(), config);
+        let listings: HashMap<String, ListingBounds> = trading_symbols
+            .iter()
+            .map(|s| (s.symbol.to_uppercase(), ListingBounds::from(s)))
+            .collect();
+
+        info!(
+            "Resolved {} archive symbols for {} {}",
+            symbols.len(),
+            exchange,
+            market_type
+        );
+
+        targets.extend(symbols.iter().flat_map(|symbol| {
+            let listing = listings
+                .get(&symbol.to_uppercase())
+                .copied()
+                .unwrap_or_default();
+            config.time_frames.iter().map(move |tf| ArchiveTarget {
+                exchange: exchange.clone(),
+                market_type: *market_type,
+                symbol: symbol.clone(),
+                time_frame: tf.clone(),
+                listing,
+            })
+        }));
+    }
+
+    info!(
+        "Resolved archive universe: {} exchanges x {} market types x {} time frames = {} targets",
+        config.exchanges.len(),
+        config.market_types.len(),
+        config.time_frames.len(),
+        targets.len()
+    );
+
+    Ok(targets)
+}
+
+/// 合并交易对状态、市值排名与显式包含/排除列表，返回有序去重的交易对
+///
+/// 显式包含的交易对只在当前交易所/市场确实在交易时加入，避免跨交易所的符号格式误配
+fn select_symbols(
+    trading_symbols: &[MarketSymbol],
+    ranked_assets: Option<&HashSet<String>>,
+    config: &UniverseConfig,
+) -> BTreeSet<String> {
+    let mut selected: BTreeSet<String> = trading_symbols
+        .iter()
+        .filter(|s| match ranked_assets {
+            Some(ranked) => ranked.contains(&normalize_base_asset(&s.base_asset)),
+            None => true,
+        })
+        .map(|s| s.symbol.to_uppercase())
+        .collect();
+
+    selected.extend(
+        config
+            .include_symbols
+            .iter()
+            .filter(|symbol| {
+                trading_symbols
+                    .iter()
+                    .any(|s| s.symbol.eq_ignore_ascii_case(symbol))
+            })
+            .cloned(),
+    );
+
+    for symbol in &config.exclude_symbols {
+        selected.remove(symbol);
+    }
+
+    selected
+}
+
+fn upper_all(values: Vec<String>) -> Vec<String> {
+    values.into_iter().map(|v| v.to_uppercase()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn symbol(symbol: &str, base_asset: &str) -> MarketSymbol {
+        MarketSymbol {
+            id: symbol.to_string(),
+            exchange: "binance".to_string(),
+            market_type: "usdm".to_string(),
+            symbol: symbol.to_string(),
+            pair: symbol.to_string(),
+            contract_type: "PERPETUAL".to_string(),
+            delivery_date: 0,
+            onboard_date: 0,
+            status: "TRADING".to_string(),
+            maint_margin_percent: String::new(),
+            required_margin_percent: String::new(),
+            base_asset: base_asset.to_string(),
+            quote_asset: "USDT".to_string(),
+            margin_asset: "USDT".to_string(),
+            price_precision: 0,
+            quantity_precision: 0,
+            base_asset_precision: 0,
+            quote_precision: 0,
+            underlying_type: "COIN".to_string(),
+            underlying_sub_type: None,
+            trigger_protect: String::new(),
+            liquidation_fee: String::new(),
+            market_take_bound: String::new(),
+            max_move_order_limit: 0,
+            filters: None,
+            order_types: None,
+            time_in_force: None,
+            permission_sets: None,
+        }
+    }
+
+    fn config(include: &[&str], exclude: &[&str]) -> UniverseConfig {
+        UniverseConfig {
+            exchanges: vec!["binance".to_string()],
+            market_types: vec![MarketType::UsdM],
+            quote_assets: vec!["USDT".to_string()],
+            top_n: 3,
+            include_symbols: include.iter().map(|s| s.to_string()).collect(),
+            exclude_symbols: exclude.iter().map(|s| s.to_string()).collect(),
+            time_frames: vec![TimeFrame::M1],
+        }
+    }
+
+    #[test]
+    fn test_select_symbols() {
+        let trading = vec![
+            symbol("BTCUSDT", "BTC"),
+            symbol("1000PEPEUSDT", "1000PEPE"),
+            symbol("10000LADYSUSDT", "10000LADYS"),
+            symbol("DOGEUSDT", "DOGE"),
+            symbol("XRPUSDT", "XRP"),
+        ];
+        let ranked: HashSet<String> = ["BTC", "PEPE", "LADYS", "XRP"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        // 排名过滤 + 显式包含（仅限在交易的）+ 显式排除
+        let selected = select_symbols(
+            &trading,
+            Some(&ranked),
+            &config(&["DOGEUSDT", "ETHUSDT"], &["XRPUSDT"]),
+        );
+        assert_eq!(
+            selected.into_iter().collect::<Vec<_>>(),
+            vec!["10000LADYSUSDT", "1000PEPEUSDT", "BTCUSDT", "DOGEUSDT"]
+        );
+
+        // 不按排名过滤时保留全部在交易的交易对
+        assert_eq!(select_symbols(&trading, None, &config(&[], &[])).len(), 5);
+    }
+}
@@ -0,0 +1,463 @@
+use crate::collector::archive::KlineMessage;
+use crate::common::utils::{get_env_bool, get_env_list};
+use crate::domain::model::kline_quarantine::{
+    encode_kline_quarantine_pk, NewOrUpdateKlineQuarantine,
+};
+use crate::infra::external::binance::market::KlineSummary;
+use crate::model::TimeFrame;
+use chrono::Utc;
+use std::collections::HashMap;
+use std::str::FromStr;
+use tracing::warn;
+
+/// K线数据质量校验规则
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ValidationRule {
+    /// 最高价低于最低价
+    HighBelowLow,
+    /// 开盘价或收盘价不在 [low, high] 区间内
+    PriceOutOfRange,
+    /// 价格非正数或非有限值
+    NonPositivePrice,
+    /// 成交量、成交额、成交笔数为负
+    NegativeVolume,
+    /// open_time/close_time 未按周期对齐
+    MisalignedTime,
+    /// 同一批次中同一 close_time 出现不同数值
+    ///
+    /// 只比较同一次写库批次内的K线，不与已入库的数据比对：已入库的K线会被新数据覆盖（upsert），
+    /// 因为重新拉取时尚未收盘或被交易所修正的K线本就应以最新数据为准
+    ConflictingDuplicate,
+}
+
+impl ValidationRule {
+    pub const ALL: [ValidationRule; 6] = [
+        ValidationRule::HighBelowLow,
+        ValidationRule::PriceOutOfRange,
+        ValidationRule::NonPositivePrice,
+        ValidationRule::NegativeVolume,
+        ValidationRule::MisalignedTime,
+        ValidationRule::ConflictingDuplicate,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ValidationRule::HighBelowLow => "high_below_low",
+            ValidationRule::PriceOutOfRange => "price_out_of_range",
+            ValidationRule::NonPositivePrice => "non_positive_price",
+            ValidationRule::NegativeVolume => "negative_volume",
+            ValidationRule::MisalignedTime => "misaligned_time",
+            ValidationRule::ConflictingDuplicate => "conflicting_duplicate",
+        }
+    }
+}
+
+impl FromStr for ValidationRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ValidationRule::ALL
+            .into_iter()
+            .find(|rule| rule.as_str() == s)
+            .ok_or_else(|| format!("Unsupported validation rule: {}", s))
+    }
+}
+
+/// 被拒绝的K线及其失败规则
+#[derive(Debug, Clone)]
+pub struct RejectedKline {
+    pub message: KlineMessage,
+    pub kline: KlineSummary,
+    pub rule: ValidationRule,
+    pub detail: String,
+}
+
+impl From<&RejectedKline> for NewOrUpdateKlineQuarantine {
+    fn from(r: &RejectedKline) -> Self {
+        let m = &r.message;
+        NewOrUpdateKlineQuarantine {
+            id: encode_kline_quarantine_pk(
+                &m.exchange,
+                m.market_type.as_str(),
+                &m.symbol,
+                &m.time_frame,
+                r.kline.close_time,
+                r.rule.as_str(),
+            ),
+            exchange: m.exchange.clone(),
+            market_type: m.market_type.as_str().to_string(),
+            symbol: m.symbol.clone(),
+            time_frame: m.time_frame.clone(),
+            direction: m.archive_direction.as_str().to_string(),
+            open_time: r.kline.open_time,
+            close_time: r.kline.close_time,
+            rule: r.rule.as_str().to_string(),
+            detail: r.detail.chars().take(255).collect(),
+            payload: serde_json::to_value(&r.kline).unwrap_or_default(),
+            detected_at: Utc::now().naive_utc(),
+        }
+    }
+}
+
+/// 写库前的K线校验器
+///
+/// - `KLINE_VALIDATION_ENABLED`：是否启用校验，默认 true
+/// - `KLINE_VALIDATION_RULES`：启用的规则，如 `high_below_low,negative_volume`，默认全部
+#[derive(Debug, Clone)]
+pub struct KlineValidator {
+    rules: Vec<ValidationRule>,
+}
+
+impl KlineValidator {
+    pub fn new(rules: Vec<ValidationRule>) -> Self {
+        Self { rules }
+    }
+
+    pub fn from_env() -> Self {
+        if !get_env_bool("KLINE_VALIDATION_ENABLED", true) {
+            return Self::new(vec![]);
+        }
+
+        let mut rules: Vec<ValidationRule> = get_env_list("KLINE_VALIDATION_RULES")
+            .iter()
+            .filter_map(|rule| match ValidationRule::from_str(rule) {
+                Ok(rule) => Some(rule),
+                Err(e) => {
+                    warn!("Ignoring kline validation rule: {}", e);
+                    None
+                }
+            })
+            .collect();
+        if rules.is_empty() {
+            rules = ValidationRule::ALL.to_vec();
+        }
+        rules.sort();
+        rules.dedup();
+
+        Self::new(rules)
+    }
+
+    fn enabled(&self, rule: ValidationRule) -> bool {
+        self.rules.contains(&rule)
+    }
+
+    /// 校验一批消息，返回通过的消息与被拒绝的K线
+    ///
+    /// 同一批次内数值完全相同的重复K线只保留一根；数值不同的重复K线全部隔离
+    pub fn validate(&self, messages: Vec<KlineMessage>) -> (Vec<KlineMessage>, Vec<RejectedKline>) {
+        if self.rules.is_empty() {
+            return (messages, vec![]);
+        }
+
+        let mut rejected: Vec<RejectedKline> = vec![];
+        let mut seen: HashMap<(String, String, String, String, i64), KlineSummary> = HashMap::new();
+        let mut conflicts: HashMap<(String, String, String, String, i64), Vec<KlineSummary>> =
+            HashMap::new();
+
+        let mut accepted: Vec<KlineMessage> = messages
+            .into_iter()
+            .map(|mut message| {
+                let time_frame = TimeFrame::from_str(&message.time_frame).ok();
+                let datas = std::mem::take(&mut message.datas);
+                for kline in datas {
+                    if let Some((rule, detail)) = self.check(&kline, time_frame.as_ref()) {
+                        rejected.push(RejectedKline {
+                            message: message.clone(),
+                            kline,
+                            rule,
+                            detail,
+                        });
+                        continue;
+                    }
+
+                    let key = (
+                        message.exchange.clone(),
+                        message.market_type.as_str().to_string(),
+                        message.symbol.clone(),
+                        message.time_frame.clone(),
+                        kline.close_time,
+                    );
+                    match seen.get(&key) {
+                        None => {
+                            seen.insert(key, kline.clone());
+                            message.datas.push(kline);
+                        }
+                        Some(existing) if same_values(existing, &kline) => {}
+                        Some(_) if self.enabled(ValidationRule::ConflictingDuplicate) => {
+                            conflicts.entry(key).or_default().push(kline);
+                        }
+                        Some(_) => message.datas.push(kline),
+                    }
+                }
+                message
+            })
+            .collect();
+
+        // 冲突的 close_time 无法判断哪一根正确，已接受的那根也一并隔离
+        if !conflicts.is_empty() {
+            for message in accepted.iter_mut() {
+                let datas = std::mem::take(&mut message.datas);
+                for kline in datas {
+                    let key = (
+                        message.exchange.clone(),
+                        message.market_type.as_str().to_string(),
+                        message.symbol.clone(),
+                        message.time_frame.clone(),
+                        kline.close_time,
+                    );
+                    match conflicts.remove(&key) {
+                        Some(others) => {
+                            let count = others.len() + 1;
+                            for other in std::iter::once(kline).chain(others) {
+                                rejected.push(RejectedKline {
+                                    message: KlineMessage {
+                                        datas: vec![],
+                                        ..message.clone()
+                                    },
+                                    kline: other,
+                                    rule: ValidationRule::ConflictingDuplicate,
+                                    detail: format!(
+                                        "{} different klines share close_time in one batch",
+                                        count
+                                    ),
+                                });
+                            }
+                        }
+                        None => message.datas.push(kline),
+                    }
+                }
+            }
+        }
+
+        accepted.retain(|m| !m.datas.is_empty());
+        (accepted, rejected)
+    }
+
+    /// 对单根K线依次执行启用的规则，返回第一个失败的规则
+    fn check(
+        &self,
+        k: &KlineSummary,
+        time_frame: Option<&TimeFrame>,
+    ) -> Option<(ValidationRule, String)> {
+        let prices = [k.open, k.high, k.low, k.close];
+        if self.enabled(ValidationRule::NonPositivePrice)
+            && prices.iter().any(|p| !p.is_finite() || *p <= 0.0)
+        {
+            return Some((
+                ValidationRule::NonPositivePrice,
+                format!(
+                    "open={} high={} low={} close={}",
+                    k.open, k.high, k.low, k.close
+                ),
+            ));
+        }
+
+        if self.enabled(ValidationRule::HighBelowLow) && k.high < k.low {
+            return Some((
+                ValidationRule::HighBelowLow,
+                format!("high={} < low={}", k.high, k.low),
+            ));
+        }
+
+        if self.enabled(ValidationRule::PriceOutOfRange) {
+            for (name, price) in [("open", k.open), ("close", k.close)] {
+                if price < k.low || price > k.high {
+                    return Some((
+                        ValidationRule::PriceOutOfRange,
+                        format!("{}={} outside [{}, {}]", name, price, k.low, k.high),
+                    ));
+                }
+            }
+        }
+
+        if self.enabled(ValidationRule::NegativeVolume) {
+            let volumes = [
+                ("volume", k.volume),
+                ("quote_asset_volume", k.quote_asset_volume),
+                ("taker_buy_base_asset_volume", k.taker_buy_base_asset_volume),
+                (
+                    "taker_buy_quote_asset_volume",
+                    k.taker_buy_quote_asset_volume,
+                ),
+                ("number_of_trades", k.number_of_trades as f64),
+            ];
+            if let Some((name, value)) = volumes.iter().find(|(_, v)| !v.is_finite() || *v < 0.0) {
+                return Some((
+                    ValidationRule::NegativeVolume,
+                    format!("{}={}", name, value),
+                ));
+            }
+        }
+
+        if self.enabled(ValidationRule::MisalignedTime) {
+            match time_frame {
+                None => {
+                    return Some((
+                        ValidationRule::MisalignedTime,
+                        "unknown time frame".to_string(),
+                    ))
+                }
+                Some(tf) => {
+                    let aligned_open = tf.bucket_open_time(k.open_time);
+                    let expected_close = tf.close_time(aligned_open);
+                    if aligned_open != k.open_time || expected_close != k.close_time {
+                        return Some((
+                            ValidationRule::MisalignedTime,
+                            format!(
+                                "open_time={} close_time={} expected {}..{} for {}",
+                                k.open_time,
+                                k.close_time,
+                                aligned_open,
+                                expected_close,
+                                tf.to_str()
+                            ),
+                        ));
+                    }
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// 判断两根K线数值是否完全一致
+fn same_values(a: &KlineSummary, b: &KlineSummary) -> bool {
+    a.open_time == b.open_time
+        && a.open == b.open
+        && a.high == b.high
+        && a.low == b.low
+        && a.close == b.close
+        && a.volume == b.volume
+        && a.quote_asset_volume == b.quote_asset_volume
+        && a.number_of_trades == b.number_of_trades
+        && a.taker_buy_base_asset_volume == b.taker_buy_base_asset_volume
+        && a.taker_buy_quote_asset_volume == b.taker_buy_quote_asset_volume
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collector::archive::types::ArchiveDirection;
+    use crate::model::market_type::MarketType;
+
+    const MINUTE: i64 = 60_000;
+
+    fn kline(open_time: i64) -> KlineSummary {
+        KlineSummary {
+            open_time,
+            open: 100.0,
+            high: 110.0,
+            low: 90.0,
+            close: 105.0,
+            volume: 1.0,
+            close_time: open_time + MINUTE - 1,
+            quote_asset_volume: 100.0,
+            number_of_trades: 3,
+            taker_buy_base_asset_volume: 0.5,
+            taker_buy_quote_asset_volume: 50.0,
+        }
+    }
+
+    fn message(datas: Vec<KlineSummary>) -> KlineMessage {
+        KlineMessage {
+            datas,
+            symbol: "BTCUSDT".to_string(),
+            exchange: "binance".to_string(),
+            market_type: MarketType::UsdM,
+            time_frame: "1m".to_string(),
+            archive_direction: ArchiveDirection::Forward,
+            checkpoint_id: None,
+        }
+    }
+
+    #[test]
+    fn test_rules_reject_bad_klines() {
+        let start = 1717200000000;
+        let validator = KlineValidator::new(ValidationRule::ALL.to_vec());
+
+        let good = kline(start);
+        let high_below_low = KlineSummary {
+            high: 80.0,
+            ..kline(start + MINUTE)
+        };
+        let close_out_of_range = KlineSummary {
+            close: 120.0,
+            ..kline(start + 2 * MINUTE)
+        };
+        let negative_volume = KlineSummary {
+            volume: -1.0,
+            ..kline(start + 3 * MINUTE)
+        };
+        let misaligned = KlineSummary {
+            close_time: start + 5 * MINUTE,
+            ..kline(start + 4 * MINUTE)
+        };
+
+        let (accepted, rejected) = validator.validate(vec![message(vec![
+            good,
+            high_below_low,
+            close_out_of_range,
+            negative_volume,
+            misaligned,
+        ])]);
+
+        assert_eq!(accepted.len(), 1);
+        assert_eq!(accepted[0].datas.len(), 1);
+        assert_eq!(accepted[0].datas[0].open_time, start);
+
+        let rules: Vec<ValidationRule> = rejected.iter().map(|r| r.rule).collect();
+        assert_eq!(
+            rules,
+            vec![
+                ValidationRule::HighBelowLow,
+                ValidationRule::PriceOutOfRange,
+                ValidationRule::NegativeVolume,
+                ValidationRule::MisalignedTime,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_duplicates_across_messages() {
+        let start = 1717200000000;
+        let validator = KlineValidator::new(ValidationRule::ALL.to_vec());
+
+        let conflicting = KlineSummary {
+            close: 101.0,
+            ..kline(start + MINUTE)
+        };
+        let (accepted, rejected) = validator.validate(vec![
+            message(vec![kline(start), kline(start + MINUTE)]),
+            message(vec![kline(start), conflicting]),
+        ]);
+
+        // 完全相同的重复K线去重，数值冲突的两根都被隔离
+        let kept: Vec<i64> = accepted
+            .iter()
+            .flat_map(|m| m.datas.iter().map(|k| k.open_time))
+            .collect();
+        assert_eq!(kept, vec![start]);
+        assert_eq!(rejected.len(), 2);
+        assert!(rejected
+            .iter()
+            .all(|r| r.rule == ValidationRule::ConflictingDuplicate));
+
+        let row = NewOrUpdateKlineQuarantine::from(&rejected[0]);
+        assert_eq!(row.rule, "conflicting_duplicate");
+        assert_eq!(row.direction, "forward");
+        assert_eq!(row.close_time, start + 2 * MINUTE - 1);
+    }
+
+    #[test]
+    fn test_disabled_rules_are_skipped() {
+        let validator = KlineValidator::new(vec![ValidationRule::NegativeVolume]);
+        let bad = KlineSummary {
+            high: 80.0,
+            ..kline(1717200000000)
+        };
+        let (accepted, rejected) = validator.validate(vec![message(vec![bad])]);
+        assert_eq!(accepted[0].datas.len(), 1);
+        assert!(rejected.is_empty());
+    }
+}
@@ -0,0 +1,64 @@
+use crate::common::utils::{get_env_list, get_env_or};
+use crate::model::market_type::MarketType;
+use std::str::FromStr;
+use std::time::Duration;
+use tracing::warn;
+
+pub mod book;
+pub mod recorder;
+
+/// 订单簿目前只从 Binance 采集
+pub const EXCHANGE: &str = "binance";
+
+/// 订单簿记录配置（来自环境变量）
+///
+/// - `DEPTH_SYMBOLS`：记录的交易对，如 `BTCUSDT,ETHUSDT`，未配置时不记录
+/// - `DEPTH_MARKET_TYPES`：市场类型，如 `spot,usdm`，默认 usdm
+/// - `DEPTH_LEVELS`：每侧保存的档位数，默认 20
+/// - `DEPTH_SAMPLE_INTERVAL_MS`：采样间隔（按推送事件时间对齐），默认 1000
+/// - `DEPTH_SNAPSHOT_LIMIT`：REST 快照档位数，默认 1000
+/// - `DEPTH_RESNAPSHOT_SECS`：定期重新拉取快照校正本地订单簿的间隔，默认 3600
+#[derive(Debug, Clone)]
+pub struct DepthConfig {
+    pub symbols: Vec<String>,
+    pub market_types: Vec<MarketType>,
+    pub levels: usize,
+    pub sample_interval_ms: i64,
+    pub snapshot_limit: u16,
+    pub resnapshot_interval: Duration,
+    /// 两次快照请求的最小间隔，避免推送持续不连续时频繁拉取快照
+    pub resync_cooldown: Duration,
+}
+
+impl DepthConfig {
+    pub fn from_env() -> Self {
+        let mut market_types: Vec<MarketType> = get_env_list("DEPTH_MARKET_TYPES")
+            .iter()
+            .filter_map(|m| match MarketType::from_str(m) {
+                Ok(m) => Some(m),
+                Err(e) => {
+                    warn!("Ignoring depth market type: {}", e);
+                    None
+                }
+            })
+            .collect();
+        if market_types.is_empty() {
+            market_types = vec![MarketType::UsdM];
+        }
+
+        Self {
+            symbols: get_env_list("DEPTH_SYMBOLS")
+                .iter()
+                .map(|s| s.to_uppercase())
+                .collect(),
+            market_types,
+            levels: get_env_or("DEPTH_LEVELS", 20usize).max(1),
+            sample_interval_ms: get_env_or("DEPTH_SAMPLE_INTERVAL_MS", 1000i64).max(100),
+            snapshot_limit: get_env_or("DEPTH_SNAPSHOT_LIMIT", 1000u16).clamp(5, 1000),
+            resnapshot_interval: Duration::from_secs(
+                get_env_or("DEPTH_RESNAPSHOT_SECS", 3600u64).max(60),
+            ),
+            resync_cooldown: Duration::from_secs(1),
+        }
+    }
+}
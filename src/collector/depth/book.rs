@@ -0,0 +1,289 @@
+use crate::infra::external::binance::market::{
+    deserialize_price_levels, DepthSnapshot, PriceLevel,
+};
+use crate::model::cex::depth::OrderBookRow;
+use crate::model::market_type::MarketType;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// 增量深度推送（depthUpdate），档位数量为该价格的最新挂单量，0 表示撤档
+#[derive(Debug, Clone, Deserialize)]
+pub struct DepthUpdateEvent {
+    /// 事件时间（毫秒）
+    #[serde(rename = "E")]
+    pub event_time: i64,
+
+    #[serde(rename = "s")]
+    pub symbol: String,
+
+    /// 本次推送的第一个更新 ID
+    #[serde(rename = "U")]
+    pub first_update_id: u64,
+
+    /// 本次推送的最后一个更新 ID
+    #[serde(rename = "u")]
+    pub final_update_id: u64,
+
+    /// 上一条推送的最后一个更新 ID，仅合约推送携带
+    #[serde(rename = "pu", default)]
+    pub prev_final_update_id: Option<u64>,
+
+    #[serde(rename = "b", deserialize_with = "deserialize_price_levels")]
+    pub bids: Vec<PriceLevel>,
+
+    #[serde(rename = "a", deserialize_with = "deserialize_price_levels")]
+    pub asks: Vec<PriceLevel>,
+}
+
+/// 推送与本地订单簿不连续，需要重新拉取快照
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SequenceError {
+    /// 快照后的第一条推送未覆盖快照的 lastUpdateId
+    NotAligned {
+        snapshot: u64,
+        first: u64,
+        last: u64,
+    },
+    /// 相邻推送之间缺失更新
+    Gap {
+        expected: u64,
+        first: u64,
+        prev: Option<u64>,
+    },
+}
+
+impl fmt::Display for SequenceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SequenceError::NotAligned {
+                snapshot,
+                first,
+                last,
+            } => write!(
+                f,
+                "First update {}..{} does not cover snapshot {}",
+                first, last, snapshot
+            ),
+            SequenceError::Gap {
+                expected,
+                first,
+                prev,
+            } => write!(
+                f,
+                "Update gap after {}: got U={} pu={:?}",
+                expected, first, prev
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SequenceError {}
+
+/// 推送应用结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApplyOutcome {
+    Applied,
+    /// 推送早于本地订单簿，已忽略
+    Stale,
+}
+
+/// 价格作为有序键：正数 f64 的位模式顺序与数值顺序一致
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct PriceKey(u64);
+
+impl PriceKey {
+    fn new(price: f64) -> Self {
+        PriceKey(price.to_bits())
+    }
+
+    fn price(self) -> f64 {
+        f64::from_bits(self.0)
+    }
+}
+
+/// 由快照 + 增量推送维护的本地订单簿
+///
+/// 连续性校验规则：
+/// - 现货：第一条推送需满足 `U <= lastUpdateId + 1 <= u`，之后每条 `U` 等于上一条 `u + 1`
+/// - 合约：第一条推送需满足 `U <= lastUpdateId <= u`，之后每条 `pu` 等于上一条 `u`
+#[derive(Debug, Clone)]
+pub struct LocalOrderBook {
+    futures: bool,
+    last_update_id: u64,
+    /// 是否已应用快照后的第一条推送
+    aligned: bool,
+    bids: BTreeMap<PriceKey, f64>,
+    asks: BTreeMap<PriceKey, f64>,
+}
+
+impl LocalOrderBook {
+    pub fn from_snapshot(market_type: MarketType, snapshot: &DepthSnapshot) -> Self {
+        let mut book = LocalOrderBook {
+            futures: market_type != MarketType::Spot,
+            last_update_id: snapshot.last_update_id,
+            aligned: false,
+            bids: BTreeMap::new(),
+            asks: BTreeMap::new(),
+        };
+        update_levels(&mut book.bids, &snapshot.bids);
+        update_levels(&mut book.asks, &snapshot.asks);
+        book
+    }
+
+    pub fn last_update_id(&self) -> u64 {
+        self.last_update_id
+    }
+
+    /// 校验连续性后应用一条推送；返回错误时本地订单簿已不可信，调用方应丢弃并重新同步
+    pub fn apply(&mut self, event: &DepthUpdateEvent) -> Result<ApplyOutcome, SequenceError> {
+        let last = self.last_update_id;
+        let (first_id, final_id) = (event.first_update_id, event.final_update_id);
+
+        if !self.aligned {
+            let (stale, covers) = if self.futures {
+                (final_id < last, first_id <= last && final_id >= last)
+            } else {
+                (final_id <= last, first_id <= last + 1 && final_id > last)
+            };
+            if stale {
+                return Ok(ApplyOutcome::Stale);
+            }
+            if !covers {
+                return Err(SequenceError::NotAligned {
+                    snapshot: last,
+                    first: first_id,
+                    last: final_id,
+                });
+            }
+            self.aligned = true;
+        } else {
+            if final_id <= last {
+                return Ok(ApplyOutcome::Stale);
+            }
+            let continuous = if self.futures {
+                event.prev_final_update_id == Some(last)
+            } else {
+                first_id == last + 1
+            };
+            if !continuous {
+                return Err(SequenceError::Gap {
+                    expected: last,
+                    first: first_id,
+                    prev: event.prev_final_update_id,
+                });
+            }
+        }
+
+        update_levels(&mut self.bids, &event.bids);
+        update_levels(&mut self.asks, &event.asks);
+        self.last_update_id = final_id;
+        Ok(ApplyOutcome::Applied)
+    }
+
+    /// 前 `n` 档：买盘价格从高到低，卖盘从低到高
+    pub fn top_levels(&self, n: usize) -> (Vec<PriceLevel>, Vec<PriceLevel>) {
+        let bids = self
+            .bids
+            .iter()
+            .rev()
+            .take(n)
+            .map(|(k, q)| (k.price(), *q))
+            .collect();
+        let asks = self
+            .asks
+            .iter()
+            .take(n)
+            .map(|(k, q)| (k.price(), *q))
+            .collect();
+        (bids, asks)
+    }
+
+    /// 生成前 `levels` 档的存储行
+    pub fn to_row(
+        &self,
+        exchange: &str,
+        market_type: MarketType,
+        symbol: &str,
+        snapshot_time: i64,
+        levels: usize,
+    ) -> OrderBookRow {
+        let (bids, asks) = self.top_levels(levels);
+        OrderBookRow {
+            exchange: exchange.to_string(),
+            market_type: market_type.as_str().to_string(),
+            symbol: symbol.to_string(),
+            snapshot_time,
+            last_update_id: self.last_update_id,
+            bid_prices: bids.iter().map(|l| l.0).collect(),
+            bid_qtys: bids.iter().map(|l| l.1).collect(),
+            ask_prices: asks.iter().map(|l| l.0).collect(),
+            ask_qtys: asks.iter().map(|l| l.1).collect(),
+        }
+    }
+}
+
+fn update_levels(side: &mut BTreeMap<PriceKey, f64>, levels: &[PriceLevel]) {
+    for (price, qty) in levels {
+        if *qty == 0.0 {
+            side.remove(&PriceKey::new(*price));
+        } else {
+            side.insert(PriceKey::new(*price), *qty);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(first: u64, last: u64, bids: Vec<PriceLevel>) -> DepthUpdateEvent {
+        DepthUpdateEvent {
+            event_time: 0,
+            symbol: "BTCUSDT".to_string(),
+            first_update_id: first,
+            final_update_id: last,
+            prev_final_update_id: None,
+            bids,
+            asks: vec![],
+        }
+    }
+
+    #[test]
+    fn test_spot_sequence_checks() {
+        let snapshot = DepthSnapshot {
+            last_update_id: 100,
+            bids: vec![(10.0, 1.0), (9.5, 2.0)],
+            asks: vec![(10.5, 1.0)],
+        };
+        let mut book = LocalOrderBook::from_snapshot(MarketType::Spot, &snapshot);
+
+        assert_eq!(book.apply(&event(95, 100, vec![])), Ok(ApplyOutcome::Stale));
+        // 现货第一条推送需覆盖 lastUpdateId + 1
+        assert_eq!(
+            book.apply(&event(101, 103, vec![(10.0, 0.0), (9.8, 5.0)])),
+            Ok(ApplyOutcome::Applied)
+        );
+        assert_eq!(
+            book.apply(&event(104, 104, vec![])),
+            Ok(ApplyOutcome::Applied)
+        );
+        assert_eq!(book.last_update_id(), 104);
+        assert_eq!(book.top_levels(5).0, vec![(9.8, 5.0), (9.5, 2.0)]);
+
+        assert_eq!(
+            book.apply(&event(106, 107, vec![])),
+            Err(SequenceError::Gap {
+                expected: 104,
+                first: 106,
+                prev: None
+            })
+        );
+
+        let mut late = LocalOrderBook::from_snapshot(MarketType::Spot, &snapshot);
+        assert!(matches!(
+            late.apply(&event(102, 105, vec![])),
+            Err(SequenceError::NotAligned { snapshot: 100, .. })
+        ));
+    }
+}
@@ -0,0 +1,466 @@
+use crate::collector::depth::book::{DepthUpdateEvent, LocalOrderBook};
+use crate::collector::depth::{DepthConfig, EXCHANGE};
+use crate::collector::stream::kline_stream::{
+    CombinedStreamFrame, StreamExit, IDLE_TIMEOUT, MAX_CONNECTION_AGE, MAX_RECONNECT_DELAY,
+    MAX_STREAMS_PER_CONNECTION, RECONNECT_DELAY,
+};
+use crate::global::{get_binance_limiter, get_ck_db, get_shutdown};
+use crate::infra::external::binance::constant::{depth_weight, ws_base_url};
+use crate::infra::external::binance::market::DepthSnapshot;
+use crate::infra::external::binance::DefaultBinanceExchange;
+use crate::model::cex::depth::OrderBookRow;
+use crate::model::market_type::MarketType;
+use async_trait::async_trait;
+use futures::{SinkExt, StreamExt};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tokio::time::{interval, sleep, timeout, MissedTickBehavior};
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{debug, info, warn};
+
+/// 等待快照期间每个交易对最多缓存的推送数，超出时丢弃最早的
+const MAX_PENDING_EVENTS: usize = 1000;
+
+/// 快照写入批量大小与定时写入间隔
+const WRITE_BATCH_SIZE: usize = 500;
+const WRITE_INTERVAL: Duration = Duration::from_secs(2);
+
+/// 订单簿快照来源，测试中可替换为录制数据
+#[async_trait]
+pub trait SnapshotSource: Send + Sync {
+    async fn fetch(
+        &self,
+        market_type: MarketType,
+        symbol: &str,
+        limit: u16,
+    ) -> anyhow::Result<DepthSnapshot>;
+}
+
+/// 通过 REST depth 接口拉取快照，请求前扣减限流权重
+pub struct RestSnapshotSource;
+
+#[async_trait]
+impl SnapshotSource for RestSnapshotSource {
+    async fn fetch(
+        &self,
+        market_type: MarketType,
+        symbol: &str,
+        limit: u16,
+    ) -> anyhow::Result<DepthSnapshot> {
+        get_binance_limiter()
+            .acquire_weight(market_type, depth_weight(market_type, limit))
+            .await;
+        Ok(DefaultBinanceExchange::for_market(market_type)
+            .get_depth(symbol, limit)
+            .await?)
+    }
+}
+
+/// 单个交易对的同步状态
+#[derive(Default)]
+struct SymbolState {
+    /// 已与快照对齐的本地订单簿，None 表示等待（重新）同步
+    book: Option<LocalOrderBook>,
+    /// 等待快照期间收到的推送
+    pending: Vec<DepthUpdateEvent>,
+    last_snapshot_attempt: Option<Instant>,
+    synced_at: Option<Instant>,
+    /// 最近一条推送所在的采样周期起点
+    last_bucket: Option<i64>,
+}
+
+/// 维护同一市场下一组交易对的本地订单簿，并按采样间隔产出前 N 档快照
+pub struct DepthRecorder<S> {
+    market_type: MarketType,
+    config: DepthConfig,
+    source: S,
+    states: HashMap<String, SymbolState>,
+    syncs: u64,
+}
+
+impl<S: SnapshotSource> DepthRecorder<S> {
+    pub fn new(market_type: MarketType, config: DepthConfig, source: S) -> Self {
+        Self {
+            market_type,
+            config,
+            source,
+            states: HashMap::new(),
+            syncs: 0,
+        }
+    }
+
+    /// 成功与快照同步的次数（含首次同步、推送不连续后的重新同步与定期校正）
+    pub fn syncs(&self) -> u64 {
+        self.syncs
+    }
+
+    /// 连接断开后增量推送不再连续，所有订单簿需重新同步
+    pub fn reset(&mut self) {
+        self.states.clear();
+    }
+
+    /// 处理一条推送：推送跨入新的采样周期时，先输出周期起点时的订单簿，再应用推送
+    pub async fn on_event(&mut self, event: DepthUpdateEvent) -> Option<OrderBookRow> {
+        let interval_ms = self.config.sample_interval_ms;
+        let state = self.states.entry(event.symbol.clone()).or_default();
+
+        let bucket = event.event_time.div_euclid(interval_ms) * interval_ms;
+        let row = match (&state.book, state.last_bucket) {
+            (Some(book), Some(last)) if bucket > last => Some(book.to_row(
+                EXCHANGE,
+                self.market_type,
+                &event.symbol,
+                bucket,
+                self.config.levels,
+            )),
+            _ => None,
+        };
+        state.last_bucket = Some(bucket);
+
+        if state
+            .synced_at
+            .is_some_and(|t| t.elapsed() >= self.config.resnapshot_interval)
+        {
+            info!("Refreshing order book snapshot for {}", event.symbol);
+            state.book = None;
+        }
+
+        let symbol = event.symbol.clone();
+        match state.book.as_mut() {
+            Some(book) => {
+                if let Err(e) = book.apply(&event) {
+                    warn!("Order book {} out of sync, resyncing: {}", symbol, e);
+                    state.book = None;
+                    state.pending.clear();
+                    state.pending.push(event);
+                }
+            }
+            None => {
+                if state.pending.len() >= MAX_PENDING_EVENTS {
+                    state.pending.remove(0);
+                }
+                state.pending.push(event);
+            }
+        }
+
+        if state.book.is_none() {
+            let synced =
+                sync_book(&self.source, &self.config, self.market_type, &symbol, state).await;
+            if synced {
+                self.syncs += 1;
+            }
+        }
+        row
+    }
+}
+
+/// 拉取快照并回放缓存的推送；对齐失败时清空缓存，等待下一条推送后重试
+///
+/// 快照在缓存第一条推送之后请求，保证快照不早于缓存中的推送
+async fn sync_book<S: SnapshotSource>(
+    source: &S,
+    config: &DepthConfig,
+    market_type: MarketType,
+    symbol: &str,
+    state: &mut SymbolState,
+) -> bool {
+    if state
+        .last_snapshot_attempt
+        .is_some_and(|t| t.elapsed() < config.resync_cooldown)
+    {
+        return false;
+    }
+    state.last_snapshot_attempt = Some(Instant::now());
+
+    let snapshot = match source
+        .fetch(market_type, symbol, config.snapshot_limit)
+        .await
+    {
+        Ok(snapshot) => snapshot,
+        Err(e) => {
+            warn!(?e, "Failed to fetch depth snapshot for {}", symbol);
+            return false;
+        }
+    };
+
+    let mut book = LocalOrderBook::from_snapshot(market_type, &snapshot);
+    for event in std::mem::take(&mut state.pending) {
+        if let Err(e) = book.apply(&event) {
+            warn!("Depth snapshot for {} not aligned: {}", symbol, e);
+            return false;
+        }
+    }
+
+    debug!(
+        "Order book {} synced at update {}",
+        symbol,
+        book.last_update_id()
+    );
+    state.book = Some(book);
+    state.synced_at = Some(Instant::now());
+    true
+}
+
+/// 构建组合流地址，如 wss://fstream.binance.com/stream?streams=btcusdt@depth@100ms/ethusdt@depth@100ms
+pub fn build_depth_stream_url(base_url: &str, symbols: &[String]) -> String {
+    let streams: Vec<String> = symbols
+        .iter()
+        .map(|s| format!("{}@depth@100ms", s.to_lowercase()))
+        .collect();
+    format!("{}/stream?streams={}", base_url, streams.join("/"))
+}
+
+/// 维持单个连接直到断开或到达 `max_age`，采样得到的快照发送到 `tx`
+pub async fn run_depth_connection<S: SnapshotSource>(
+    url: &str,
+    recorder: &mut DepthRecorder<S>,
+    tx: &mpsc::Sender<OrderBookRow>,
+    max_age: Duration,
+) -> anyhow::Result<StreamExit> {
+    let (ws, _) = connect_async(url).await?;
+    let (mut write, mut read) = ws.split();
+    info!("Depth stream connected: {}", url);
+    recorder.reset();
+
+    let deadline = sleep(max_age);
+    tokio::pin!(deadline);
+
+    loop {
+        tokio::select! {
+            _ = &mut deadline => {
+                let _ = write.send(Message::Close(None)).await;
+                return Ok(StreamExit::Expired);
+            }
+            frame = timeout(IDLE_TIMEOUT, read.next()) => {
+                let frame = frame.map_err(|_| anyhow::anyhow!("Depth stream idle for {:?}", IDLE_TIMEOUT))?;
+                match frame {
+                    None | Some(Ok(Message::Close(_))) => return Ok(StreamExit::Closed),
+                    Some(Err(e)) => return Err(e.into()),
+                    Some(Ok(Message::Ping(payload))) => write.send(Message::Pong(payload)).await?,
+                    Some(Ok(Message::Text(text))) => {
+                        let frame: CombinedStreamFrame<DepthUpdateEvent> = match serde_json::from_str(text.as_str()) {
+                            Ok(frame) => frame,
+                            Err(e) => {
+                                debug!("Ignoring non-depth frame: {} ({})", text, e);
+                                continue;
+                            }
+                        };
+                        if let Some(row) = recorder.on_event(frame.data).await {
+                            if tx.send(row).await.is_err() {
+                                return Ok(StreamExit::Closed);
+                            }
+                        }
+                    }
+                    Some(Ok(_)) => {}
+                }
+            }
+        }
+    }
+}
+
+/// 持续运行一组订阅，断线后指数退避重连，重连后所有订单簿重新同步
+pub async fn run_depth_stream(
+    market_type: MarketType,
+    symbols: Vec<String>,
+    config: DepthConfig,
+    tx: mpsc::Sender<OrderBookRow>,
+) {
+    let url = build_depth_stream_url(ws_base_url(market_type), &symbols);
+    let mut recorder = DepthRecorder::new(market_type, config, RestSnapshotSource);
+    let shutdown = get_shutdown();
+    let mut delay = RECONNECT_DELAY;
+
+    while !tx.is_closed() {
+        let result = tokio::select! {
+            result = run_depth_connection(&url, &mut recorder, &tx, MAX_CONNECTION_AGE) => result,
+            _ = shutdown.wait() => break,
+        };
+        match result {
+            Ok(StreamExit::Expired) => {
+                info!("Depth stream reached max connection age, reconnecting");
+                delay = RECONNECT_DELAY;
+                continue;
+            }
+            Ok(StreamExit::Closed) => warn!("Depth stream closed by server"),
+            Err(e) => warn!(?e, "Depth stream connection failed"),
+        }
+
+        tokio::select! {
+            _ = sleep(delay) => {}
+            _ = shutdown.wait() => break,
+        }
+        delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+    }
+    info!(
+        "Depth stream stopped: {} ({} snapshot syncs)",
+        url,
+        recorder.syncs()
+    );
+}
+
+/// 批量写入 order_book_snapshots，通道关闭后写完剩余数据
+pub async fn run_depth_writer(mut rx: mpsc::Receiver<OrderBookRow>) {
+    let mut buffer: Vec<OrderBookRow> = Vec::with_capacity(WRITE_BATCH_SIZE);
+    let mut ticker = interval(WRITE_INTERVAL);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            row = rx.recv() => {
+                let Some(row) = row else {
+                    break;
+                };
+                buffer.push(row);
+                if buffer.len() >= WRITE_BATCH_SIZE {
+                    flush(&mut buffer).await;
+                }
+            }
+            _ = ticker.tick() => flush(&mut buffer).await,
+        }
+    }
+    flush(&mut buffer).await;
+    info!("Depth writer stopped");
+}
+
+async fn flush(buffer: &mut Vec<OrderBookRow>) {
+    if buffer.is_empty() {
+        return;
+    }
+    let rows = std::mem::take(buffer);
+    if let Err(e) = get_ck_db().insert_batch(&rows).await {
+        warn!(?e, "Failed to write {} order book snapshots", rows.len());
+    }
+}
+
+/// 启动订单簿记录：按 DEPTH_SYMBOLS / DEPTH_MARKET_TYPES 订阅增量深度，所有连接共用一个写入任务
+pub async fn start_depth_recorder() -> anyhow::Result<()> {
+    let config = DepthConfig::from_env();
+    if config.symbols.is_empty() {
+        info!("No depth streams to subscribe.");
+        return Ok(());
+    }
+
+    let (tx, rx) = mpsc::channel::<OrderBookRow>(1000);
+    tokio::spawn(run_depth_writer(rx));
+
+    for market_type in &config.market_types {
+        for chunk in config.symbols.chunks(MAX_STREAMS_PER_CONNECTION) {
+            tokio::spawn(run_depth_stream(
+                *market_type,
+                chunk.to_vec(),
+                config.clone(),
+                tx.clone(),
+            ));
+        }
+    }
+
+    info!(
+        "Started depth recorder for {} symbols on {} markets",
+        config.symbols.len(),
+        config.market_types.len()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+    use std::sync::Mutex;
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::accept_async;
+
+    /// 按顺序返回录制的快照
+    struct ReplaySnapshots(Mutex<VecDeque<DepthSnapshot>>);
+
+    #[async_trait]
+    impl SnapshotSource for ReplaySnapshots {
+        async fn fetch(
+            &self,
+            _market_type: MarketType,
+            _symbol: &str,
+            _limit: u16,
+        ) -> anyhow::Result<DepthSnapshot> {
+            self.0
+                .lock()
+                .unwrap()
+                .pop_front()
+                .ok_or_else(|| anyhow::anyhow!("No more snapshots"))
+        }
+    }
+
+    /// 录制的合约增量推送：订阅回执、早于快照、对齐、连续、跨周期、pu 不连续、重新同步后跨周期
+    const RECORDED_FRAMES: &[&str] = &[
+        r#"{"result":null,"id":1}"#,
+        r#"{"stream":"btcusdt@depth@100ms","data":{"e":"depthUpdate","E":1000,"T":999,"s":"BTCUSDT","U":90,"u":99,"pu":89,"b":[],"a":[]}}"#,
+        r#"{"stream":"btcusdt@depth@100ms","data":{"e":"depthUpdate","E":1200,"T":1199,"s":"BTCUSDT","U":99,"u":105,"pu":99,"b":[["100.0","0"]],"a":[["101.5","3"]]}}"#,
+        r#"{"stream":"btcusdt@depth@100ms","data":{"e":"depthUpdate","E":1500,"T":1499,"s":"BTCUSDT","U":106,"u":110,"pu":105,"b":[["99.5","4"]],"a":[]}}"#,
+        r#"{"stream":"btcusdt@depth@100ms","data":{"e":"depthUpdate","E":2100,"T":2099,"s":"BTCUSDT","U":111,"u":112,"pu":110,"b":[],"a":[]}}"#,
+        r#"{"stream":"btcusdt@depth@100ms","data":{"e":"depthUpdate","E":2200,"T":2199,"s":"BTCUSDT","U":120,"u":125,"pu":118,"b":[],"a":[]}}"#,
+        r#"{"stream":"btcusdt@depth@100ms","data":{"e":"depthUpdate","E":3100,"T":3099,"s":"BTCUSDT","U":128,"u":135,"pu":125,"b":[],"a":[]}}"#,
+    ];
+
+    #[tokio::test]
+    async fn test_depth_stream_resyncs_on_gap() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = accept_async(stream).await.unwrap();
+            for frame in RECORDED_FRAMES {
+                ws.send(Message::text(*frame)).await.unwrap();
+            }
+            ws.close(None).await.unwrap();
+        });
+
+        let snapshots = ReplaySnapshots(Mutex::new(VecDeque::from(vec![
+            DepthSnapshot {
+                last_update_id: 100,
+                bids: vec![(100.0, 1.0), (99.0, 2.0)],
+                asks: vec![(101.0, 1.0), (102.0, 2.0)],
+            },
+            DepthSnapshot {
+                last_update_id: 130,
+                bids: vec![(98.0, 1.0)],
+                asks: vec![(103.0, 1.0)],
+            },
+        ])));
+        let config = DepthConfig {
+            symbols: vec!["BTCUSDT".to_string()],
+            market_types: vec![MarketType::UsdM],
+            levels: 5,
+            sample_interval_ms: 1000,
+            snapshot_limit: 1000,
+            resnapshot_interval: Duration::from_secs(3600),
+            resync_cooldown: Duration::ZERO,
+        };
+        let mut recorder = DepthRecorder::new(MarketType::UsdM, config, snapshots);
+
+        let (tx, mut rx) = mpsc::channel::<OrderBookRow>(10);
+        let url = build_depth_stream_url(&format!("ws://{}", addr), &["BTCUSDT".to_string()]);
+        let exit = run_depth_connection(&url, &mut recorder, &tx, Duration::from_secs(10))
+            .await
+            .unwrap();
+        server.await.unwrap();
+        assert_eq!(exit, StreamExit::Closed);
+        assert_eq!(recorder.syncs(), 2);
+
+        // 2000 周期起点：已应用到 u=110 的订单簿
+        let first = rx.try_recv().unwrap();
+        assert_eq!(first.snapshot_time, 2000);
+        assert_eq!(first.last_update_id, 110);
+        assert_eq!(first.bid_prices, vec![99.5, 99.0]);
+        assert_eq!(first.bid_qtys, vec![4.0, 2.0]);
+        assert_eq!(first.ask_prices, vec![101.0, 101.5, 102.0]);
+
+        // pu 不连续后重新同步，3000 周期起点为第二份快照
+        let second = rx.try_recv().unwrap();
+        assert_eq!(second.snapshot_time, 3000);
+        assert_eq!(second.last_update_id, 130);
+        assert_eq!(second.bid_prices, vec![98.0]);
+        assert!(rx.try_recv().is_err());
+    }
+}
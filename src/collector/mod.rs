@@ -1 +1,5 @@
 pub mod archive;
+pub mod depth;
+pub mod resample;
+pub mod stream;
+pub mod trades;
@@ -0,0 +1,48 @@
+use crate::common::utils::get_env_list;
+use crate::model::TimeFrame;
+use std::str::FromStr;
+use tracing::warn;
+
+pub mod aggregate;
+pub mod history;
+pub mod incremental;
+
+/// 重采样的源周期：所有高周期均由 1m 聚合而来
+pub const SOURCE_TIME_FRAME: TimeFrame = TimeFrame::M1;
+
+/// 重采样配置（来自环境变量）
+///
+/// - `RESAMPLE_TIMEFRAMES`：需要由 1m 派生的目标周期，如 `5m,15m,1h,4h,1d`
+#[derive(Debug, Clone)]
+pub struct ResampleConfig {
+    pub time_frames: Vec<TimeFrame>,
+}
+
+impl ResampleConfig {
+    pub fn from_env() -> Self {
+        let mut time_frames: Vec<TimeFrame> = get_env_list("RESAMPLE_TIMEFRAMES")
+            .iter()
+            .filter_map(|tf| match TimeFrame::from_str(tf) {
+                Ok(tf) => Some(tf),
+                Err(e) => {
+                    warn!("Ignoring resample time frame: {}", e);
+                    None
+                }
+            })
+            .filter(|tf| *tf != SOURCE_TIME_FRAME)
+            .collect();
+        if time_frames.is_empty() {
+            time_frames = vec![
+                TimeFrame::M5,
+                TimeFrame::M15,
+                TimeFrame::H1,
+                TimeFrame::H4,
+                TimeFrame::D1,
+            ];
+        }
+        time_frames.sort();
+        time_frames.dedup();
+
+        Self { time_frames }
+    }
+}
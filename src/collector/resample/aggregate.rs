@@ -0,0 +1,178 @@
+use crate::collector::resample::SOURCE_TIME_FRAME;
+use crate::infra::external::binance::market::KlineSummary;
+use crate::model::TimeFrame;
+
+/// 一根正在聚合的目标周期K线
+#[derive(Debug, Clone)]
+pub struct KlineBucket {
+    /// 目标周期
+    pub time_frame: TimeFrame,
+
+    /// 聚合结果，open_time/close_time 为目标周期的对齐边界
+    pub kline: KlineSummary,
+
+    /// 已合并的 1m K线数量
+    pub source_count: usize,
+
+    /// 最后合并的 1m 开盘时间，用于丢弃重复与乱序数据
+    last_open_time: i64,
+}
+
+impl KlineBucket {
+    /// 以第一根 1m K线开启一个新的目标周期K线
+    pub fn new(time_frame: TimeFrame, first: &KlineSummary) -> Self {
+        let open_time = time_frame.bucket_open_time(first.open_time);
+        let close_time = time_frame.close_time(open_time);
+        Self {
+            time_frame,
+            kline: KlineSummary {
+                open_time,
+                close_time,
+                ..first.clone()
+            },
+            source_count: 1,
+            last_open_time: first.open_time,
+        }
+    }
+
+    /// 是否属于当前目标周期
+    pub fn contains(&self, open_time: i64) -> bool {
+        open_time >= self.kline.open_time && open_time <= self.kline.close_time
+    }
+
+    /// 合并一根 1m K线：开盘价取首根，收盘价取末根，最高/最低取极值，成交量与笔数累加
+    ///
+    /// 不属于当前周期、重复或早于已合并数据的K线返回 false 且不做修改
+    pub fn push(&mut self, k: &KlineSummary) -> bool {
+        if !self.contains(k.open_time) || k.open_time <= self.last_open_time {
+            return false;
+        }
+
+        let agg = &mut self.kline;
+        agg.high = agg.high.max(k.high);
+        agg.low = agg.low.min(k.low);
+        agg.close = k.close;
+        agg.volume += k.volume;
+        agg.quote_asset_volume += k.quote_asset_volume;
+        agg.number_of_trades += k.number_of_trades;
+        agg.taker_buy_base_asset_volume += k.taker_buy_base_asset_volume;
+        agg.taker_buy_quote_asset_volume += k.taker_buy_quote_asset_volume;
+
+        self.source_count += 1;
+        self.last_open_time = k.open_time;
+        true
+    }
+
+    /// 完整周期应包含的 1m K线数量（月线按自然月天数计算）
+    pub fn expected_count(&self) -> usize {
+        ((self.kline.close_time - self.kline.open_time + 1) / SOURCE_TIME_FRAME.to_millis())
+            as usize
+    }
+
+    /// 所有 1m K线均已到齐
+    pub fn is_complete(&self) -> bool {
+        self.source_count >= self.expected_count()
+    }
+
+    /// 最后一根 1m K线已到达（周期已结束，但中间可能缺数据）
+    pub fn is_closed(&self) -> bool {
+        SOURCE_TIME_FRAME.close_time(self.last_open_time) >= self.kline.close_time
+    }
+}
+
+/// 将按开盘时间升序排列的 1m K线聚合为目标周期，返回所有出现过的周期（含不完整周期）
+pub fn resample_klines(time_frame: &TimeFrame, klines: &[KlineSummary]) -> Vec<KlineBucket> {
+    let mut buckets: Vec<KlineBucket> = vec![];
+
+    for k in klines {
+        match buckets.last_mut() {
+            Some(bucket) if bucket.contains(k.open_time) => {
+                bucket.push(k);
+            }
+            Some(bucket) if k.open_time < bucket.kline.open_time => {
+                // 乱序数据直接丢弃，调用方需保证升序
+            }
+            _ => buckets.push(KlineBucket::new(time_frame.clone(), k)),
+        }
+    }
+
+    buckets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINUTE: i64 = 60_000;
+
+    fn minute_kline(open_time: i64, open: f64, close: f64) -> KlineSummary {
+        KlineSummary {
+            open_time,
+            open,
+            high: open.max(close) + 1.0,
+            low: open.min(close) - 1.0,
+            close,
+            volume: 2.0,
+            close_time: open_time + MINUTE - 1,
+            quote_asset_volume: 200.0,
+            number_of_trades: 10,
+            taker_buy_base_asset_volume: 1.0,
+            taker_buy_quote_asset_volume: 100.0,
+        }
+    }
+
+    #[test]
+    fn test_bucket_alignment_matches_exchange() {
+        // 2024-06-05 13:47:00 UTC，周三
+        let ts = 1717595220000;
+        assert_eq!(TimeFrame::M5.bucket_open_time(ts), 1717595100000);
+        assert_eq!(TimeFrame::H4.bucket_open_time(ts), 1717588800000);
+        assert_eq!(TimeFrame::D1.bucket_open_time(ts), 1717545600000);
+        // 周线从 2024-06-03（周一）开始
+        assert_eq!(TimeFrame::W1.bucket_open_time(ts), 1717372800000);
+        // 月线从 2024-06-01 开始，到 2024-06-30 23:59:59.999 结束
+        assert_eq!(TimeFrame::M1L.bucket_open_time(ts), 1717200000000);
+        assert_eq!(TimeFrame::M1L.close_time(1717200000000), 1719791999999);
+    }
+
+    #[test]
+    fn test_resample_ohlcv() {
+        let start = 1717200000000;
+        let klines: Vec<KlineSummary> = (0..7)
+            .map(|i| minute_kline(start + i * MINUTE, 100.0 + i as f64, 101.0 + i as f64))
+            .collect();
+
+        let buckets = resample_klines(&TimeFrame::M5, &klines);
+        assert_eq!(buckets.len(), 2);
+
+        let first = &buckets[0];
+        assert!(first.is_complete());
+        assert_eq!(first.kline.open_time, start);
+        assert_eq!(first.kline.close_time, start + 5 * MINUTE - 1);
+        assert_eq!(first.kline.open, 100.0);
+        assert_eq!(first.kline.close, 105.0);
+        assert_eq!(first.kline.high, 106.0);
+        assert_eq!(first.kline.low, 99.0);
+        assert_eq!(first.kline.volume, 10.0);
+        assert_eq!(first.kline.quote_asset_volume, 1000.0);
+        assert_eq!(first.kline.number_of_trades, 50);
+        assert_eq!(first.kline.taker_buy_base_asset_volume, 5.0);
+        assert_eq!(first.kline.taker_buy_quote_asset_volume, 500.0);
+
+        let second = &buckets[1];
+        assert_eq!(second.source_count, 2);
+        assert!(!second.is_complete());
+        assert!(!second.is_closed());
+    }
+
+    #[test]
+    fn test_duplicate_minutes_are_ignored() {
+        let start = 1717200000000;
+        let mut bucket = KlineBucket::new(TimeFrame::M5, &minute_kline(start, 1.0, 2.0));
+        assert!(bucket.push(&minute_kline(start + MINUTE, 2.0, 3.0)));
+        assert!(!bucket.push(&minute_kline(start + MINUTE, 2.0, 3.0)));
+        assert!(!bucket.push(&minute_kline(start + 5 * MINUTE, 3.0, 4.0)));
+        assert_eq!(bucket.source_count, 2);
+        assert_eq!(bucket.kline.volume, 4.0);
+    }
+}
@@ -0,0 +1,165 @@
+use crate::collector::resample::aggregate::resample_klines;
+use crate::collector::resample::SOURCE_TIME_FRAME;
+use crate::global::get_ck_db;
+use crate::infra::db::types::ClickHouseDatabase;
+use crate::infra::external::binance::market::KlineSummary;
+use crate::model::cex::kline::MarketKline;
+use crate::model::market_type::MarketType;
+use crate::model::TimeFrame;
+use tracing::{debug, info};
+
+/// 单个批次读取的 1m 数据跨度（按目标周期边界向后取整），约 1 万行
+const WINDOW_MILLIS: i64 = 7 * 24 * 60 * 60 * 1000;
+
+/// 由 ClickHouse 中的 1m 历史派生目标周期并写回 market_klines
+///
+/// 只补算已派生区间之外的部分：早于最早派生K线的（向后归档新写入的历史）与晚于最新派生K线的，
+/// 不完整的周期跳过，待 1m 补齐后由下一次运行补算。返回写入的K线数量
+pub async fn resample_history(
+    exchange: &str,
+    market_type: MarketType,
+    symbol: &str,
+    time_frame: &TimeFrame,
+) -> anyhow::Result<usize> {
+    let db = get_ck_db();
+    let Some(source) = db
+        .get_mima_time(
+            exchange,
+            market_type.as_str(),
+            symbol,
+            SOURCE_TIME_FRAME.to_str(),
+        )
+        .await?
+    else {
+        return Ok(0);
+    };
+
+    let source_start = time_frame.bucket_open_time(source.min_close_time);
+    let source_end = source.max_close_time + 1;
+
+    let ranges = match db
+        .get_mima_time(exchange, market_type.as_str(), symbol, time_frame.to_str())
+        .await?
+    {
+        None => vec![(source_start, source_end)],
+        Some(derived) => vec![
+            (
+                source_start,
+                time_frame.bucket_open_time(derived.min_close_time),
+            ),
+            (derived.max_close_time + 1, source_end),
+        ],
+    };
+
+    let mut written = 0;
+    for (start, end) in ranges {
+        if start < end {
+            written +=
+                resample_range(exchange, market_type, symbol, time_frame, start, end).await?;
+        }
+    }
+
+    if written > 0 {
+        info!(
+            "Resampled {} {} klines for {} {} {}",
+            written,
+            time_frame.to_str(),
+            exchange,
+            market_type,
+            symbol
+        );
+    }
+    Ok(written)
+}
+
+/// 对开盘时间 [start, end) 内的 1m 数据重新聚合，按窗口分批读取并写入完整的目标周期K线
+///
+/// 写入依赖 ReplacingMergeTree 去重，重复执行同一区间是安全的
+pub async fn resample_range(
+    exchange: &str,
+    market_type: MarketType,
+    symbol: &str,
+    time_frame: &TimeFrame,
+    start: i64,
+    end: i64,
+) -> anyhow::Result<usize> {
+    let db = get_ck_db();
+    let period = time_frame.to_str();
+
+    let mut written = 0;
+    let mut window_start = time_frame.bucket_open_time(start);
+    while window_start < end {
+        let window_end = next_window_end(time_frame, window_start).min(end);
+
+        let rows = db
+            .query_klines_by_open_time(
+                exchange,
+                market_type.as_str(),
+                symbol,
+                SOURCE_TIME_FRAME.to_str(),
+                window_start,
+                window_end,
+            )
+            .await?;
+        let klines: Vec<KlineSummary> = rows.iter().map(KlineSummary::from).collect();
+
+        let buckets = resample_klines(time_frame, &klines);
+        let (complete, incomplete): (Vec<_>, Vec<_>) =
+            buckets.into_iter().partition(|b| b.is_complete());
+        if !incomplete.is_empty() {
+            debug!(
+                "Skipped {} incomplete {} buckets for {} {} {} in [{}, {})",
+                incomplete.len(),
+                period,
+                exchange,
+                market_type,
+                symbol,
+                window_start,
+                window_end
+            );
+        }
+
+        let items: Vec<MarketKline> = complete
+            .iter()
+            .map(|b| MarketKline::from((&b.kline, exchange, market_type.as_str(), symbol, period)))
+            .collect();
+        db.insert_batch(&items).await?;
+        written += items.len();
+
+        window_start = window_end;
+    }
+
+    Ok(written)
+}
+
+/// 窗口终点对齐到目标周期边界，保证同一周期的 1m 数据在同一批次中
+fn next_window_end(time_frame: &TimeFrame, window_start: i64) -> i64 {
+    let aligned = time_frame.bucket_open_time(window_start + WINDOW_MILLIS);
+    if aligned > window_start {
+        aligned
+    } else {
+        // 目标周期长于窗口（如月线），至少覆盖一个完整周期
+        time_frame.close_time(window_start) + 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window_end_aligned_to_bucket() {
+        let start = 1717200000000; // 2024-06-01 00:00 UTC
+        assert_eq!(
+            next_window_end(&TimeFrame::H1, start),
+            start + WINDOW_MILLIS
+        );
+        // 周线窗口对齐到周一
+        assert_eq!(
+            next_window_end(&TimeFrame::W1, 1717372800000),
+            1717977600000
+        );
+        // 月线窗口覆盖整月
+        assert_eq!(next_window_end(&TimeFrame::M1L, start), 1719792000000);
+    }
+}
@@ -0,0 +1,193 @@
+use crate::collector::archive::types::ArchiveDirection;
+use crate::collector::archive::KlineMessage;
+use crate::collector::resample::aggregate::KlineBucket;
+use crate::collector::resample::SOURCE_TIME_FRAME;
+use crate::model::market_type::MarketType;
+use crate::model::TimeFrame;
+use std::collections::HashMap;
+use tokio::sync::mpsc;
+use tracing::debug;
+
+/// 聚合状态键：(交易所, 市场类型, 交易对, 目标周期)
+type BucketKey = (String, MarketType, String, TimeFrame);
+
+/// 增量重采样：随新到达的已收盘 1m K线滚动聚合，目标周期最后一根 1m 到达时立即产出
+///
+/// 只产出数据完整的周期；因断线等原因缺失的周期留给历史批量任务补算
+pub struct IncrementalResampler {
+    time_frames: Vec<TimeFrame>,
+    buckets: HashMap<BucketKey, KlineBucket>,
+}
+
+impl IncrementalResampler {
+    pub fn new(time_frames: Vec<TimeFrame>) -> Self {
+        Self {
+            time_frames,
+            buckets: HashMap::new(),
+        }
+    }
+
+    /// 处理一条 1m 消息，返回已完成的目标周期消息（每个周期一条）
+    pub fn on_message(&mut self, message: &KlineMessage) -> Vec<KlineMessage> {
+        if message.time_frame != SOURCE_TIME_FRAME.to_str() {
+            return vec![];
+        }
+
+        let mut completed: Vec<KlineMessage> = vec![];
+        for time_frame in &self.time_frames {
+            let key: BucketKey = (
+                message.exchange.clone(),
+                message.market_type,
+                message.symbol.clone(),
+                time_frame.clone(),
+            );
+
+            let mut datas = vec![];
+            for k in &message.datas {
+                let bucket = self
+                    .buckets
+                    .entry(key.clone())
+                    .or_insert_with(|| KlineBucket::new(time_frame.clone(), k));
+
+                if !bucket.contains(k.open_time) {
+                    if k.open_time < bucket.kline.open_time {
+                        debug!("Dropping late 1m kline {} for {:?}", k.open_time, key);
+                        continue;
+                    }
+                    // 新周期开始，上一周期未能补齐则丢弃
+                    debug!(
+                        "Discarding incomplete {} bucket {} ({}/{}) for {:?}",
+                        time_frame.to_str(),
+                        bucket.kline.open_time,
+                        bucket.source_count,
+                        bucket.expected_count(),
+                        key
+                    );
+                    *bucket = KlineBucket::new(time_frame.clone(), k);
+                } else {
+                    bucket.push(k);
+                }
+
+                if bucket.is_closed() {
+                    if bucket.is_complete() {
+                        datas.push(bucket.kline.clone());
+                    }
+                    self.buckets.remove(&key);
+                }
+            }
+
+            if !datas.is_empty() {
+                completed.push(KlineMessage {
+                    datas,
+                    symbol: message.symbol.clone(),
+                    exchange: message.exchange.clone(),
+                    market_type: message.market_type,
+                    time_frame: time_frame.to_str().to_string(),
+                    archive_direction: message.archive_direction,
+                    checkpoint_id: None,
+                });
+            }
+        }
+
+        completed
+    }
+}
+
+/// 重采样中间层：原样转发所有消息，并把 1m 消息派生出的高周期消息一并发给下游
+pub async fn run_resample_stage(
+    mut rx: mpsc::Receiver<KlineMessage>,
+    tx: mpsc::Sender<KlineMessage>,
+    mut resampler: IncrementalResampler,
+) {
+    while let Some(message) = rx.recv().await {
+        let derived = match message.archive_direction {
+            ArchiveDirection::Forward => resampler.on_message(&message),
+            ArchiveDirection::Backward => vec![],
+        };
+
+        if tx.send(message).await.is_err() {
+            break;
+        }
+        for message in derived {
+            if tx.send(message).await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::external::binance::market::KlineSummary;
+
+    const MINUTE: i64 = 60_000;
+
+    fn minute_message(open_time: i64) -> KlineMessage {
+        KlineMessage {
+            datas: vec![KlineSummary {
+                open_time,
+                open: 1.0,
+                high: 2.0,
+                low: 0.5,
+                close: 1.5,
+                volume: 3.0,
+                close_time: open_time + MINUTE - 1,
+                quote_asset_volume: 4.5,
+                number_of_trades: 7,
+                taker_buy_base_asset_volume: 1.0,
+                taker_buy_quote_asset_volume: 1.5,
+            }],
+            symbol: "BTCUSDT".to_string(),
+            exchange: "binance".to_string(),
+            market_type: MarketType::UsdM,
+            time_frame: "1m".to_string(),
+            archive_direction: ArchiveDirection::Forward,
+            checkpoint_id: None,
+        }
+    }
+
+    #[test]
+    fn test_emits_bucket_when_last_minute_arrives() {
+        let start = 1717200000000;
+        let mut resampler = IncrementalResampler::new(vec![TimeFrame::M5, TimeFrame::M15]);
+
+        for i in 0..4 {
+            assert!(resampler
+                .on_message(&minute_message(start + i * MINUTE))
+                .is_empty());
+        }
+
+        let completed = resampler.on_message(&minute_message(start + 4 * MINUTE));
+        assert_eq!(completed.len(), 1);
+        assert_eq!(completed[0].time_frame, "5m");
+        assert_eq!(completed[0].datas[0].open_time, start);
+        assert_eq!(completed[0].datas[0].close_time, start + 5 * MINUTE - 1);
+        assert_eq!(completed[0].datas[0].volume, 15.0);
+        assert_eq!(completed[0].datas[0].number_of_trades, 35);
+    }
+
+    #[test]
+    fn test_incomplete_bucket_is_not_emitted() {
+        let start = 1717200000000;
+        let mut resampler = IncrementalResampler::new(vec![TimeFrame::M5]);
+
+        // 缺少第 2 分钟的K线，该周期不产出，下一个完整周期正常产出
+        for i in [0, 2, 3] {
+            assert!(resampler
+                .on_message(&minute_message(start + i * MINUTE))
+                .is_empty());
+        }
+        assert!(resampler
+            .on_message(&minute_message(start + 4 * MINUTE))
+            .is_empty());
+
+        for i in 5..10 {
+            let completed = resampler.on_message(&minute_message(start + i * MINUTE));
+            if i == 9 {
+                assert_eq!(completed.len(), 1);
+                assert_eq!(completed[0].datas[0].open_time, start + 5 * MINUTE);
+            }
+        }
+    }
+}
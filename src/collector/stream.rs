@@ -0,0 +1,2 @@
+pub mod kline_stream;
+pub mod live;
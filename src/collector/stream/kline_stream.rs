@@ -0,0 +1,452 @@
+use crate::collector::archive::dispatch_worker::start_worker_pool;
+use crate::collector::archive::types::ArchiveDirection;
+use crate::collector::archive::universe::{resolve_archive_universe, UniverseConfig};
+use crate::collector::archive::KlineMessage;
+use crate::collector::resample::incremental::{run_resample_stage, IncrementalResampler};
+use crate::collector::resample::ResampleConfig;
+use crate::common::serde_fun::deserialize_string_to_f64;
+use crate::global::get_shutdown;
+use crate::infra::external::binance::constant::ws_base_url;
+use crate::infra::external::binance::market::KlineSummary;
+use crate::model::market_type::MarketType;
+use crate::model::TimeFrame;
+use futures::{SinkExt, StreamExt};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::{sleep, timeout};
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{debug, info, warn};
+
+/// 币安合约单个连接最多订阅的流数量
+pub const MAX_STREAMS_PER_CONNECTION: usize = 200;
+
+/// 币安会在连接 24 小时后强制断开，这里提前主动重连
+pub const MAX_CONNECTION_AGE: Duration = Duration::from_secs(23 * 3600 + 50 * 60);
+
+/// 超过该时长未收到任何帧（含 ping）视为连接失活
+pub const IDLE_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// 重连退避的初始与最大等待时间
+pub const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+pub const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(60);
+
+/// 组合流推送格式：{"stream":"btcusdt@kline_1m","data":{...}}
+#[derive(Debug, Deserialize)]
+pub struct CombinedStreamFrame<T> {
+    pub stream: String,
+    pub data: T,
+}
+
+/// K线推送事件
+#[derive(Debug, Deserialize)]
+pub struct KlineEvent {
+    /// 事件类型，固定为 "kline"
+    #[serde(rename = "e")]
+    pub event_type: String,
+
+    /// 事件时间（毫秒）
+    #[serde(rename = "E")]
+    pub event_time: i64,
+
+    /// 交易对，如 "BTCUSDT"
+    #[serde(rename = "s")]
+    pub symbol: String,
+
+    /// K线数据
+    #[serde(rename = "k")]
+    pub kline: StreamKline,
+}
+
+/// 推送中的K线字段（价格与成交量以字符串返回）
+#[derive(Debug, Deserialize)]
+pub struct StreamKline {
+    #[serde(rename = "t")]
+    pub open_time: i64,
+
+    #[serde(rename = "T")]
+    pub close_time: i64,
+
+    /// K线周期，如 "1m"
+    #[serde(rename = "i")]
+    pub interval: String,
+
+    #[serde(rename = "o", deserialize_with = "deserialize_string_to_f64")]
+    pub open: f64,
+
+    #[serde(rename = "h", deserialize_with = "deserialize_string_to_f64")]
+    pub high: f64,
+
+    #[serde(rename = "l", deserialize_with = "deserialize_string_to_f64")]
+    pub low: f64,
+
+    #[serde(rename = "c", deserialize_with = "deserialize_string_to_f64")]
+    pub close: f64,
+
+    #[serde(rename = "v", deserialize_with = "deserialize_string_to_f64")]
+    pub volume: f64,
+
+    #[serde(rename = "n")]
+    pub number_of_trades: i64,
+
+    /// 该K线是否已收盘
+    #[serde(rename = "x")]
+    pub is_closed: bool,
+
+    #[serde(rename = "q", deserialize_with = "deserialize_string_to_f64")]
+    pub quote_asset_volume: f64,
+
+    #[serde(rename = "V", deserialize_with = "deserialize_string_to_f64")]
+    pub taker_buy_base_asset_volume: f64,
+
+    #[serde(rename = "Q", deserialize_with = "deserialize_string_to_f64")]
+    pub taker_buy_quote_asset_volume: f64,
+}
+
+impl From<&StreamKline> for KlineSummary {
+    fn from(k: &StreamKline) -> Self {
+        KlineSummary {
+            open_time: k.open_time,
+            open: k.open,
+            high: k.high,
+            low: k.low,
+            close: k.close,
+            volume: k.volume,
+            close_time: k.close_time,
+            quote_asset_volume: k.quote_asset_volume,
+            number_of_trades: k.number_of_trades,
+            taker_buy_base_asset_volume: k.taker_buy_base_asset_volume,
+            taker_buy_quote_asset_volume: k.taker_buy_quote_asset_volume,
+        }
+    }
+}
+
+/// 单个订阅：交易对 + 周期
+#[derive(Debug, Clone)]
+pub struct KlineStreamSubscription {
+    pub symbol: String,
+    pub time_frame: TimeFrame,
+}
+
+impl KlineStreamSubscription {
+    pub fn stream_name(&self) -> String {
+        format!(
+            "{}@kline_{}",
+            self.symbol.to_lowercase(),
+            self.time_frame.to_str()
+        )
+    }
+}
+
+/// 连接结束原因
+#[derive(Debug, PartialEq, Eq)]
+pub enum StreamExit {
+    /// 达到最大连接时长，主动断开
+    Expired,
+    /// 服务端关闭或下游通道已关闭
+    Closed,
+}
+
+/// 构建组合流地址，如 wss://fstream.binance.com/stream?streams=btcusdt@kline_1m/ethusdt@kline_1m
+pub fn build_stream_url(base_url: &str, subscriptions: &[KlineStreamSubscription]) -> String {
+    let streams: Vec<String> = subscriptions.iter().map(|s| s.stream_name()).collect();
+    format!("{}/stream?streams={}", base_url, streams.join("/"))
+}
+
+/// 解析一帧推送，只有已收盘的K线才转换为 Forward 方向的 KlineMessage
+pub fn parse_closed_kline(
+    exchange: &str,
+    market_type: MarketType,
+    text: &str,
+) -> Option<KlineMessage> {
+    let frame: CombinedStreamFrame<KlineEvent> = match serde_json::from_str(text) {
+        Ok(frame) => frame,
+        Err(e) => {
+            debug!("Ignoring non-kline frame: {} ({})", text, e);
+            return None;
+        }
+    };
+
+    let kline = &frame.data.kline;
+    if !kline.is_closed {
+        return None;
+    }
+
+    Some(KlineMessage {
+        datas: vec![kline.into()],
+        symbol: frame.data.symbol.clone(),
+        exchange: exchange.to_string(),
+        market_type,
+        time_frame: kline.interval.clone(),
+        archive_direction: ArchiveDirection::Forward,
+        checkpoint_id: None,
+    })
+}
+
+/// 维持单个连接直到断开或到达 `max_age`，已收盘K线发送到 `tx`
+///
+/// 连接建立后收到过推送帧时将 `received` 置为 true，出错返回时也保留，供重连退避判断连接是否健康
+pub async fn run_stream_connection(
+    url: &str,
+    exchange: &str,
+    market_type: MarketType,
+    tx: &mpsc::Sender<KlineMessage>,
+    max_age: Duration,
+    received: &mut bool,
+) -> anyhow::Result<StreamExit> {
+    let (ws, _) = connect_async(url).await?;
+    let (mut write, mut read) = ws.split();
+    info!("Kline stream connected: {}", url);
+
+    let deadline = sleep(max_age);
+    tokio::pin!(deadline);
+
+    loop {
+        tokio::select! {
+            _ = &mut deadline => {
+                let _ = write.send(Message::Close(None)).await;
+                return Ok(StreamExit::Expired);
+            }
+            frame = timeout(IDLE_TIMEOUT, read.next()) => {
+                let frame = frame.map_err(|_| anyhow::anyhow!("Kline stream idle for {:?}", IDLE_TIMEOUT))?;
+                match frame {
+                    None | Some(Ok(Message::Close(_))) => return Ok(StreamExit::Closed),
+                    Some(Err(e)) => return Err(e.into()),
+                    Some(Ok(Message::Ping(payload))) => write.send(Message::Pong(payload)).await?,
+                    Some(Ok(Message::Text(text))) => {
+                        *received = true;
+                        if let Some(message) = parse_closed_kline(exchange, market_type, text.as_str()) {
+                            if tx.send(message).await.is_err() {
+                                return Ok(StreamExit::Closed);
+                            }
+                        }
+                    }
+                    Some(Ok(_)) => {}
+                }
+            }
+        }
+    }
+}
+
+/// 下次重连前的等待：上一个连接收到过推送说明链路曾恢复正常，从初始间隔重新退避
+fn reconnect_delay(previous: Duration, received: bool) -> Duration {
+    if received {
+        RECONNECT_DELAY
+    } else {
+        previous
+    }
+}
+
+/// 持续运行一组订阅，断线后指数退避重连，24 小时强制断开前主动换新连接
+///
+/// 收到退出信号后断开连接并释放发送端，下游 worker pool 随之执行最后一轮 drain
+pub async fn run_kline_stream(
+    base_url: String,
+    exchange: String,
+    market_type: MarketType,
+    subscriptions: Vec<KlineStreamSubscription>,
+    tx: mpsc::Sender<KlineMessage>,
+) {
+    let url = build_stream_url(&base_url, &subscriptions);
+    let shutdown = get_shutdown();
+    let mut delay = RECONNECT_DELAY;
+
+    while !tx.is_closed() {
+        let mut received = false;
+        let result = tokio::select! {
+            result = run_stream_connection(&url, &exchange, market_type, &tx, MAX_CONNECTION_AGE, &mut received) => result,
+            _ = shutdown.wait() => break,
+        };
+        delay = reconnect_delay(delay, received);
+        match result {
+            Ok(StreamExit::Expired) => {
+                info!("Kline stream reached max connection age, reconnecting");
+                delay = RECONNECT_DELAY;
+                continue;
+            }
+            Ok(StreamExit::Closed) => warn!("Kline stream closed by server"),
+            Err(e) => warn!(?e, "Kline stream connection failed"),
+        }
+
+        tokio::select! {
+            _ = sleep(delay) => {}
+            _ = shutdown.wait() => break,
+        }
+        delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+    }
+    info!("Kline stream stopped: {}", url);
+}
+
+/// 启动实时K线采集：订阅归档范围内所有 Binance 市场，收盘K线（及由 1m 派生的高周期K线）经 worker pool 写入 KlineBuffer
+pub async fn start_kline_stream() -> Result<(), anyhow::Error> {
+    // 不同市场类型使用不同的 WebSocket 域名，按市场分组建立连接
+    let mut subscriptions: BTreeMap<MarketType, Vec<KlineStreamSubscription>> = BTreeMap::new();
+    for target in resolve_archive_universe(&UniverseConfig::from_env()).await? {
+        if target.exchange != "binance" {
+            continue;
+        }
+        subscriptions
+            .entry(target.market_type)
+            .or_default()
+            .push(KlineStreamSubscription {
+                symbol: target.symbol,
+                time_frame: target.time_frame,
+            });
+    }
+
+    let total: usize = subscriptions.values().map(Vec::len).sum();
+    if total == 0 {
+        info!("No kline streams to subscribe.");
+        return Ok(());
+    }
+
+    let (tx, rx) = mpsc::channel::<KlineMessage>(1000);
+    tokio::spawn(start_worker_pool(rx, 2));
+
+    // 收盘的 1m K线同时滚动聚合为高周期，与原始K线一并写入
+    let (stream_tx, stream_rx) = mpsc::channel::<KlineMessage>(1000);
+    let resampler = IncrementalResampler::new(ResampleConfig::from_env().time_frames);
+    tokio::spawn(run_resample_stage(stream_rx, tx, resampler));
+
+    for (market_type, market_subscriptions) in &subscriptions {
+        for chunk in market_subscriptions.chunks(MAX_STREAMS_PER_CONNECTION) {
+            tokio::spawn(run_kline_stream(
+                ws_base_url(*market_type).to_string(),
+                "binance".to_string(),
+                *market_type,
+                chunk.to_vec(),
+                stream_tx.clone(),
+            ));
+        }
+    }
+
+    info!("Started {} kline streams", total);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::accept_async;
+
+    /// 录制的推送帧：一根未收盘、一根已收盘
+    const RECORDED_FRAMES: &[&str] = &[
+        r#"{"stream":"btcusdt@kline_1m","data":{"e":"kline","E":1717200030000,"s":"BTCUSDT","k":{"t":1717200000000,"T":1717200059999,"s":"BTCUSDT","i":"1m","f":1,"L":2,"o":"67500.10","c":"67510.00","h":"67520.00","l":"67490.00","v":"12.345","n":120,"x":false,"q":"833000.1","V":"6.1","Q":"411000.2","B":"0"}}}"#,
+        r#"{"stream":"btcusdt@kline_1m","data":{"e":"kline","E":1717200060001,"s":"BTCUSDT","k":{"t":1717200000000,"T":1717200059999,"s":"BTCUSDT","i":"1m","f":1,"L":3,"o":"67500.10","c":"67515.50","h":"67525.00","l":"67490.00","v":"15.5","n":150,"x":true,"q":"1046000.5","V":"7.2","Q":"486000.7","B":"0"}}}"#,
+    ];
+
+    #[test]
+    fn test_build_stream_url() {
+        let subscriptions = vec![
+            KlineStreamSubscription {
+                symbol: "BTCUSDT".to_string(),
+                time_frame: TimeFrame::M1,
+            },
+            KlineStreamSubscription {
+                symbol: "ETHUSDT".to_string(),
+                time_frame: TimeFrame::H1,
+            },
+        ];
+
+        assert_eq!(
+            build_stream_url("wss://fstream.binance.com", &subscriptions),
+            "wss://fstream.binance.com/stream?streams=btcusdt@kline_1m/ethusdt@kline_1h"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stream_replays_closed_klines() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        // 本地 WebSocket 替身：发送 ping 并等待 pong，然后回放录制帧
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = accept_async(stream).await.unwrap();
+
+            ws.send(Message::Ping("hb".into())).await.unwrap();
+            let pong = ws.next().await.unwrap().unwrap();
+            assert_eq!(pong, Message::Pong("hb".into()));
+
+            for frame in RECORDED_FRAMES {
+                ws.send(Message::text(*frame)).await.unwrap();
+            }
+            ws.close(None).await.unwrap();
+        });
+
+        let (tx, mut rx) = mpsc::channel::<KlineMessage>(10);
+        let url = format!("ws://{}/stream?streams=btcusdt@kline_1m", addr);
+        let mut received = false;
+        let exit = run_stream_connection(
+            &url,
+            "binance",
+            MarketType::UsdM,
+            &tx,
+            Duration::from_secs(10),
+            &mut received,
+        )
+        .await
+        .unwrap();
+        server.await.unwrap();
+
+        assert_eq!(exit, StreamExit::Closed);
+        assert!(received);
+
+        let message = rx.try_recv().unwrap();
+        assert_eq!(message.symbol, "BTCUSDT");
+        assert_eq!(message.market_type, MarketType::UsdM);
+        assert_eq!(message.time_frame, "1m");
+        assert_eq!(message.archive_direction, ArchiveDirection::Forward);
+        assert_eq!(message.datas.len(), 1);
+        assert_eq!(message.datas[0].close_time, 1717200059999);
+        assert_eq!(message.datas[0].close, 67515.5);
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_stream_expires_at_max_age() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = accept_async(stream).await.unwrap();
+            // 保持连接直到客户端主动关闭
+            while let Some(Ok(message)) = ws.next().await {
+                if message.is_close() {
+                    break;
+                }
+            }
+        });
+
+        let (tx, _rx) = mpsc::channel::<KlineMessage>(10);
+        let url = format!("ws://{}/stream?streams=btcusdt@kline_1m", addr);
+        let mut received = false;
+        let exit = run_stream_connection(
+            &url,
+            "binance",
+            MarketType::UsdM,
+            &tx,
+            Duration::from_millis(200),
+            &mut received,
+        )
+        .await
+        .unwrap();
+        server.await.unwrap();
+
+        assert_eq!(exit, StreamExit::Expired);
+        assert!(!received);
+    }
+
+    #[test]
+    fn test_reconnect_delay_resets_after_healthy_connection() {
+        assert_eq!(reconnect_delay(MAX_RECONNECT_DELAY, true), RECONNECT_DELAY);
+        assert_eq!(
+            reconnect_delay(MAX_RECONNECT_DELAY, false),
+            MAX_RECONNECT_DELAY
+        );
+    }
+}
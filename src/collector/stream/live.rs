@@ -0,0 +1,424 @@
+use crate::collector::archive::types::ArchiveDirection;
+use crate::collector::archive::{IntoSinkRows, KlineMessage};
+use crate::common::utils::get_env_or;
+use crate::infra::db::kline_store::KlineSeries;
+use crate::model::cex::kline::MarketKline;
+use crate::model::market_type::MarketType;
+use crate::model::TimeFrame;
+use chrono::Utc;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use tokio::sync::mpsc::error::TrySendError;
+
+/// 实时K线推送配置（来自环境变量）
+///
+/// - `LIVE_KLINE_MAX_CLIENTS`：同时在线的订阅连接数上限，默认 200
+/// - `LIVE_KLINE_MAX_SUBSCRIPTIONS`：单个连接最多订阅的频道数，默认 50
+/// - `LIVE_KLINE_CLIENT_BUFFER`：单个连接待发送事件的队列长度，写满后丢弃新K线并通知客户端，默认 256
+/// - `LIVE_KLINE_SNAPSHOT_SIZE`：订阅时默认返回的最近K线根数，默认 100
+/// - `LIVE_KLINE_MAX_SNAPSHOT`：客户端可请求的快照根数上限，默认 1000
+#[derive(Debug, Clone)]
+pub struct LiveConfig {
+    pub max_clients: usize,
+    pub max_subscriptions: usize,
+    pub client_buffer: usize,
+    pub snapshot_size: usize,
+    pub max_snapshot: usize,
+}
+
+impl LiveConfig {
+    pub fn from_env() -> Self {
+        let max_snapshot = get_env_or("LIVE_KLINE_MAX_SNAPSHOT", 1000usize);
+        Self {
+            max_clients: get_env_or("LIVE_KLINE_MAX_CLIENTS", 200usize).max(1),
+            max_subscriptions: get_env_or("LIVE_KLINE_MAX_SUBSCRIPTIONS", 50usize).max(1),
+            client_buffer: get_env_or("LIVE_KLINE_CLIENT_BUFFER", 256usize).max(1),
+            snapshot_size: get_env_or("LIVE_KLINE_SNAPSHOT_SIZE", 100usize).min(max_snapshot),
+            max_snapshot,
+        }
+    }
+
+    /// 客户端请求的快照根数，未指定时取默认值，超过上限时截断
+    pub fn snapshot_len(&self, requested: Option<usize>) -> usize {
+        requested
+            .unwrap_or(self.snapshot_size)
+            .min(self.max_snapshot)
+    }
+}
+
+/// 订阅频道：交易所 + 市场类型 + 交易对 + 周期，文本形式为 `binance:usdm:BTCUSDT:1m`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LiveChannel {
+    pub exchange: String,
+    pub market_type: MarketType,
+    pub symbol: String,
+    pub time_frame: TimeFrame,
+}
+
+impl LiveChannel {
+    /// 消息所属频道；周期无法识别时返回 None
+    fn of(message: &KlineMessage) -> Option<Self> {
+        Some(Self {
+            exchange: message.exchange.to_lowercase(),
+            market_type: message.market_type,
+            symbol: message.symbol.to_uppercase(),
+            time_frame: TimeFrame::from_str(&message.time_frame).ok()?,
+        })
+    }
+
+    pub fn series(&self) -> KlineSeries {
+        KlineSeries::new(
+            &self.exchange,
+            self.market_type,
+            &self.symbol,
+            self.time_frame.clone(),
+        )
+    }
+}
+
+impl fmt::Display for LiveChannel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}:{}",
+            self.exchange,
+            self.market_type,
+            self.symbol,
+            self.time_frame.to_str()
+        )
+    }
+}
+
+impl FromStr for LiveChannel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.trim().split(':').collect();
+        let [exchange, market_type, symbol, interval] = parts.as_slice() else {
+            return Err(format!(
+                "Invalid channel: {} (expected exchange:market_type:symbol:interval)",
+                s
+            ));
+        };
+        if exchange.is_empty() || symbol.is_empty() {
+            return Err(format!("Invalid channel: {}", s));
+        }
+        Ok(Self {
+            exchange: exchange.to_lowercase(),
+            market_type: MarketType::from_str(market_type)?,
+            symbol: symbol.to_uppercase(),
+            time_frame: TimeFrame::from_str(interval)?,
+        })
+    }
+}
+
+/// 推送给客户端的事件，序列化为 {"type": "kline", ...}
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LiveEvent {
+    Subscribed {
+        channel: String,
+    },
+    Unsubscribed {
+        channel: String,
+    },
+    /// 订阅时的最近K线，按 close_time 升序
+    Snapshot {
+        channel: String,
+        klines: Vec<MarketKline>,
+    },
+    /// 新写入或更新的K线，同一 close_time 的后一条覆盖前一条
+    Kline {
+        channel: String,
+        kline: MarketKline,
+    },
+    /// 客户端消费过慢，自上次通知以来丢弃的K线条数
+    Lagged {
+        dropped: u64,
+    },
+    Error {
+        message: String,
+    },
+}
+
+struct LiveClient {
+    tx: mpsc::Sender<LiveEvent>,
+    channels: HashSet<LiveChannel>,
+    dropped: Arc<AtomicU64>,
+}
+
+/// 实时K线分发中心：写库成功的K线按频道推送给订阅的连接
+///
+/// 每个连接有独立的有界队列，队列写满时丢弃该连接的新事件并计数，不阻塞采集链路
+pub struct KlineHub {
+    config: LiveConfig,
+    next_id: AtomicU64,
+    clients: Mutex<HashMap<u64, LiveClient>>,
+}
+
+impl KlineHub {
+    pub fn new(config: LiveConfig) -> Self {
+        Self {
+            config,
+            next_id: AtomicU64::new(1),
+            clients: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn config(&self) -> &LiveConfig {
+        &self.config
+    }
+
+    pub fn has_subscribers(&self) -> bool {
+        self.clients
+            .lock()
+            .unwrap()
+            .values()
+            .any(|c| !c.channels.is_empty())
+    }
+
+    /// 建立订阅连接，超过连接数上限时返回错误
+    pub fn connect(self: &Arc<Self>) -> Result<LiveSession, String> {
+        let mut clients = self.clients.lock().unwrap();
+        if clients.len() >= self.config.max_clients {
+            return Err(format!(
+                "Too many live kline clients (max {})",
+                self.config.max_clients
+            ));
+        }
+
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = mpsc::channel(self.config.client_buffer);
+        let dropped = Arc::new(AtomicU64::new(0));
+        clients.insert(
+            id,
+            LiveClient {
+                tx,
+                channels: HashSet::new(),
+                dropped: dropped.clone(),
+            },
+        );
+        Ok(LiveSession {
+            id,
+            hub: self.clone(),
+            rx,
+            dropped,
+        })
+    }
+
+    /// 按频道分发一批已写库的K线
+    ///
+    /// 只推送实时数据：回溯（Backward）批次不推送；回补、导入与死信重放写入的历史K线
+    /// 收盘时间早于当前时间一个周期以上，同样跳过
+    pub fn publish(&self, messages: &[KlineMessage]) {
+        self.publish_at(messages, Utc::now().timestamp_millis());
+    }
+
+    fn publish_at(&self, messages: &[KlineMessage], now_ms: i64) {
+        let clients = self.clients.lock().unwrap();
+        if clients.is_empty() {
+            return;
+        }
+
+        for message in messages {
+            if message.archive_direction != ArchiveDirection::Forward {
+                continue;
+            }
+            let Some(channel) = LiveChannel::of(message) else {
+                continue;
+            };
+            let targets: Vec<&LiveClient> = clients
+                .values()
+                .filter(|c| c.channels.contains(&channel))
+                .collect();
+            if targets.is_empty() {
+                continue;
+            }
+
+            let name = channel.to_string();
+            let oldest = now_ms - channel.time_frame.to_millis();
+            let rows: Vec<MarketKline> = message.into_sink_rows();
+            for kline in rows.into_iter().filter(|k| k.close_time >= oldest) {
+                for client in &targets {
+                    let event = LiveEvent::Kline {
+                        channel: name.clone(),
+                        kline: kline.clone(),
+                    };
+                    // 连接关闭后由 LiveSession 的 Drop 注销，这里只统计写满的情况
+                    if let Err(TrySendError::Full(_)) = client.tx.try_send(event) {
+                        client.dropped.fetch_add(1, Ordering::Relaxed);
+                    }
+                }
+            }
+        }
+    }
+
+    fn subscribe(&self, id: u64, channel: LiveChannel) -> Result<bool, String> {
+        let mut clients = self.clients.lock().unwrap();
+        let client = clients
+            .get_mut(&id)
+            .ok_or_else(|| "Live session closed".to_string())?;
+        if client.channels.contains(&channel) {
+            return Ok(false);
+        }
+        if client.channels.len() >= self.config.max_subscriptions {
+            return Err(format!(
+                "Too many subscriptions (max {})",
+                self.config.max_subscriptions
+            ));
+        }
+        Ok(client.channels.insert(channel))
+    }
+
+    fn unsubscribe(&self, id: u64, channel: &LiveChannel) -> bool {
+        self.clients
+            .lock()
+            .unwrap()
+            .get_mut(&id)
+            .is_some_and(|c| c.channels.remove(channel))
+    }
+
+    fn disconnect(&self, id: u64) {
+        self.clients.lock().unwrap().remove(&id);
+    }
+}
+
+/// 单个订阅连接，只接收实时K线；订阅确认与快照由调用方直接发送。drop 时自动注销
+pub struct LiveSession {
+    id: u64,
+    hub: Arc<KlineHub>,
+    rx: mpsc::Receiver<LiveEvent>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl LiveSession {
+    /// 订阅频道，已订阅时返回 false
+    pub fn subscribe(&self, channel: LiveChannel) -> Result<bool, String> {
+        self.hub.subscribe(self.id, channel)
+    }
+
+    pub fn unsubscribe(&self, channel: &LiveChannel) -> bool {
+        self.hub.unsubscribe(self.id, channel)
+    }
+
+    /// 取下一条待发送事件；有丢弃时先返回 Lagged
+    pub async fn recv(&mut self) -> Option<LiveEvent> {
+        let dropped = self.dropped.swap(0, Ordering::Relaxed);
+        if dropped > 0 {
+            return Some(LiveEvent::Lagged { dropped });
+        }
+        self.rx.recv().await
+    }
+}
+
+impl Drop for LiveSession {
+    fn drop(&mut self) {
+        self.hub.disconnect(self.id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::external::binance::market::KlineSummary;
+
+    fn config() -> LiveConfig {
+        LiveConfig {
+            max_clients: 2,
+            max_subscriptions: 1,
+            client_buffer: 2,
+            snapshot_size: 10,
+            max_snapshot: 20,
+        }
+    }
+
+    fn message(symbol: &str, close_times: &[i64]) -> KlineMessage {
+        KlineMessage {
+            datas: close_times
+                .iter()
+                .map(|&close_time| KlineSummary {
+                    open_time: close_time - 59_999,
+                    open: 1.0,
+                    high: 1.0,
+                    low: 1.0,
+                    close: 1.0,
+                    volume: 1.0,
+                    close_time,
+                    quote_asset_volume: 1.0,
+                    number_of_trades: 1,
+                    taker_buy_base_asset_volume: 0.5,
+                    taker_buy_quote_asset_volume: 0.5,
+                })
+                .collect(),
+            symbol: symbol.to_string(),
+            exchange: "binance".to_string(),
+            market_type: MarketType::UsdM,
+            time_frame: "1m".to_string(),
+            archive_direction: ArchiveDirection::Forward,
+            checkpoint_id: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hub_routes_limits_and_lags() {
+        let hub = Arc::new(KlineHub::new(config()));
+        let channel: LiveChannel = "Binance:usdm:btcusdt:1m".parse().unwrap();
+        assert_eq!(channel.to_string(), "binance:usdm:BTCUSDT:1m");
+        assert!("binance:usdm:BTCUSDT".parse::<LiveChannel>().is_err());
+
+        let mut session = hub.connect().unwrap();
+        let _other = hub.connect().unwrap();
+        assert!(hub.connect().is_err());
+        assert!(!hub.has_subscribers());
+
+        assert!(session.subscribe(channel.clone()).unwrap());
+        assert!(!session.subscribe(channel.clone()).unwrap());
+        assert!(session
+            .subscribe("binance:usdm:ETHUSDT:1m".parse().unwrap())
+            .is_err());
+        assert!(hub.has_subscribers());
+
+        // 未订阅的频道不推送；队列长度为 2，第 3 根被丢弃并在下次读取时通知
+        hub.publish_at(
+            &[
+                message("ETHUSDT", &[59_999]),
+                message("BTCUSDT", &[59_999, 119_999, 179_999]),
+            ],
+            60_000,
+        );
+        assert!(matches!(
+            session.recv().await,
+            Some(LiveEvent::Lagged { dropped: 1 })
+        ));
+        match session.recv().await {
+            Some(LiveEvent::Kline { channel, kline }) => {
+                assert_eq!(channel, "binance:usdm:BTCUSDT:1m");
+                assert_eq!(kline.close_time, 59_999);
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+
+        // 回溯批次与一个周期之前收盘的历史K线不推送
+        let mut backward = message("BTCUSDT", &[599_999]);
+        backward.archive_direction = ArchiveDirection::Backward;
+        hub.publish_at(&[backward, message("BTCUSDT", &[539_999])], 600_000);
+        hub.publish_at(&[message("BTCUSDT", &[659_999])], 600_000);
+        match session.recv().await {
+            Some(LiveEvent::Kline { kline, .. }) => assert_eq!(kline.close_time, 119_999),
+            other => panic!("unexpected event: {:?}", other),
+        }
+        match session.recv().await {
+            Some(LiveEvent::Kline { kline, .. }) => assert_eq!(kline.close_time, 659_999),
+            other => panic!("unexpected event: {:?}", other),
+        }
+
+        assert!(session.unsubscribe(&channel));
+        drop(session);
+        assert!(hub.connect().is_ok());
+    }
+}
@@ -0,0 +1,81 @@
+use crate::common::utils::{get_env_list, get_env_or};
+use crate::global::get_ck_db;
+use crate::infra::external::binance::market::AggTrade;
+use crate::model::cex::trade::AggTradeRow;
+use crate::model::market_type::MarketType;
+use std::str::FromStr;
+use tracing::warn;
+
+pub mod bars;
+pub mod consistency;
+pub mod history;
+pub mod stream;
+
+/// 归集成交目前只从 Binance 采集
+pub const EXCHANGE: &str = "binance";
+
+/// 归集成交采集配置（来自环境变量）
+///
+/// - `TRADE_SYMBOLS`：采集的交易对，如 `BTCUSDT,ETHUSDT`，未配置时不采集
+/// - `TRADE_MARKET_TYPES`：市场类型，如 `spot,usdm`，默认 usdm
+/// - `TRADE_HISTORY_DAYS`：REST 回溯的历史天数，默认 3
+/// - `TRADE_MAX_PAGES_PER_RUN`：每个交易对每轮同步最多请求的页数（每页 1000 条），默认 200
+/// - `TRADE_GAP_SCAN_HOURS`：缺口扫描覆盖最近多少小时，默认 24
+#[derive(Debug, Clone)]
+pub struct TradeConfig {
+    pub symbols: Vec<String>,
+    pub market_types: Vec<MarketType>,
+    pub history_days: i64,
+    pub max_pages_per_run: usize,
+    pub gap_scan_hours: i64,
+}
+
+impl TradeConfig {
+    pub fn from_env() -> Self {
+        let mut market_types: Vec<MarketType> = get_env_list("TRADE_MARKET_TYPES")
+            .iter()
+            .filter_map(|m| match MarketType::from_str(m) {
+                Ok(m) => Some(m),
+                Err(e) => {
+                    warn!("Ignoring trade market type: {}", e);
+                    None
+                }
+            })
+            .collect();
+        if market_types.is_empty() {
+            market_types = vec![MarketType::UsdM];
+        }
+
+        Self {
+            symbols: get_env_list("TRADE_SYMBOLS")
+                .iter()
+                .map(|s| s.to_uppercase())
+                .collect(),
+            market_types,
+            history_days: get_env_or("TRADE_HISTORY_DAYS", 3i64).max(0),
+            max_pages_per_run: get_env_or("TRADE_MAX_PAGES_PER_RUN", 200usize).max(1),
+            gap_scan_hours: get_env_or("TRADE_GAP_SCAN_HOURS", 24i64).max(1),
+        }
+    }
+
+    /// 所有 (市场类型, 交易对) 组合
+    pub fn targets(&self) -> Vec<(MarketType, String)> {
+        self.market_types
+            .iter()
+            .flat_map(|m| self.symbols.iter().map(move |s| (*m, s.clone())))
+            .collect()
+    }
+}
+
+/// 转换为 ClickHouse 行并写入 agg_trades
+pub async fn write_agg_trades(
+    market_type: MarketType,
+    symbol: &str,
+    trades: &[AggTrade],
+) -> anyhow::Result<()> {
+    let rows: Vec<AggTradeRow> = trades
+        .iter()
+        .map(|t| (t, EXCHANGE, market_type.as_str(), symbol).into())
+        .collect();
+    get_ck_db().insert_batch(&rows).await
+}
@@ -0,0 +1,211 @@
+use crate::model::cex::trade::AggTradeRow;
+use crate::model::TimeFrame;
+use serde::Serialize;
+use std::str::FromStr;
+
+/// 成交聚合方式
+#[derive(Debug, Clone, PartialEq)]
+pub enum BarSpec {
+    /// 按周期对齐的时间K线，与 market_klines 口径一致
+    Time(TimeFrame),
+    /// 成交量达到阈值时收盘
+    Volume(f64),
+    /// 成交额（价格 × 数量）达到阈值时收盘
+    Dollar(f64),
+}
+
+impl BarSpec {
+    /// 由类型与尺寸解析，如 ("time", "1m")、("volume", "100")、("dollar", "1000000")
+    pub fn parse(kind: &str, size: &str) -> Result<Self, String> {
+        match kind {
+            "time" => TimeFrame::from_str(size).map(BarSpec::Time),
+            "volume" | "dollar" => {
+                let threshold: f64 = size
+                    .parse()
+                    .map_err(|e| format!("Invalid bar size {}: {}", size, e))?;
+                if !(threshold.is_finite() && threshold > 0.0) {
+                    return Err(format!("Bar size must be positive: {}", size));
+                }
+                Ok(if kind == "volume" {
+                    BarSpec::Volume(threshold)
+                } else {
+                    BarSpec::Dollar(threshold)
+                })
+            }
+            _ => Err(format!("Unsupported bar kind: {}", kind)),
+        }
+    }
+}
+
+/// 由归集成交聚合出的K线，主动买入按 `is_buyer_maker == false` 统计
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TradeBar {
+    pub open_time: i64,
+    pub close_time: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+    pub quote_volume: f64,
+    pub trade_count: u64,
+    pub taker_buy_volume: f64,
+    pub taker_buy_quote_volume: f64,
+    pub first_agg_id: u64,
+    pub last_agg_id: u64,
+}
+
+impl TradeBar {
+    fn open(trade: &AggTradeRow, open_time: i64) -> Self {
+        let mut bar = TradeBar {
+            open_time,
+            close_time: trade.trade_time,
+            open: trade.price,
+            high: trade.price,
+            low: trade.price,
+            close: trade.price,
+            volume: 0.0,
+            quote_volume: 0.0,
+            trade_count: 0,
+            taker_buy_volume: 0.0,
+            taker_buy_quote_volume: 0.0,
+            first_agg_id: trade.agg_id,
+            last_agg_id: trade.agg_id,
+        };
+        bar.push(trade);
+        bar
+    }
+
+    fn push(&mut self, trade: &AggTradeRow) {
+        let quote = trade.price * trade.qty;
+        self.high = self.high.max(trade.price);
+        self.low = self.low.min(trade.price);
+        self.close = trade.price;
+        self.volume += trade.qty;
+        self.quote_volume += quote;
+        self.trade_count += trade.trade_count();
+        if !trade.is_buyer_maker {
+            self.taker_buy_volume += trade.qty;
+            self.taker_buy_quote_volume += quote;
+        }
+        self.last_agg_id = trade.agg_id;
+    }
+}
+
+/// 将按 agg_id 升序排列的成交聚合为K线
+///
+/// 时间K线只输出有成交的周期；成交量 / 成交额K线不拆分单笔成交，
+/// 累计达到阈值即收盘，末尾未达到阈值的部分不输出
+pub fn build_bars(trades: &[AggTradeRow], spec: &BarSpec) -> Vec<TradeBar> {
+    let mut bars = Vec::new();
+    let mut current: Option<TradeBar> = None;
+
+    for trade in trades {
+        match spec {
+            BarSpec::Time(tf) => {
+                let open_time = tf.bucket_open_time(trade.trade_time);
+                match current.as_mut() {
+                    Some(bar) if bar.open_time == open_time => bar.push(trade),
+                    _ => {
+                        bars.extend(current.take());
+                        let mut bar = TradeBar::open(trade, open_time);
+                        bar.close_time = tf.close_time(open_time);
+                        current = Some(bar);
+                    }
+                }
+            }
+            BarSpec::Volume(threshold) | BarSpec::Dollar(threshold) => {
+                let bar = match current.as_mut() {
+                    Some(bar) => {
+                        bar.push(trade);
+                        bar.close_time = trade.trade_time;
+                        bar
+                    }
+                    None => current.insert(TradeBar::open(trade, trade.trade_time)),
+                };
+                let filled = match spec {
+                    BarSpec::Volume(_) => bar.volume,
+                    _ => bar.quote_volume,
+                };
+                if filled >= *threshold {
+                    bars.extend(current.take());
+                }
+            }
+        }
+    }
+
+    if matches!(spec, BarSpec::Time(_)) {
+        bars.extend(current);
+    }
+    bars
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trade(
+        agg_id: u64,
+        trade_time: i64,
+        price: f64,
+        qty: f64,
+        is_buyer_maker: bool,
+    ) -> AggTradeRow {
+        AggTradeRow {
+            exchange: "binance".to_string(),
+            market_type: "usdm".to_string(),
+            symbol: "BTCUSDT".to_string(),
+            agg_id,
+            price,
+            qty,
+            first_trade_id: agg_id * 10,
+            last_trade_id: agg_id * 10 + 1,
+            trade_time,
+            is_buyer_maker,
+        }
+    }
+
+    #[test]
+    fn test_build_time_volume_and_dollar_bars() {
+        let trades = vec![
+            trade(1, 60_000, 100.0, 1.0, false),
+            trade(2, 61_000, 102.0, 2.0, true),
+            trade(3, 119_999, 99.0, 1.0, false),
+            trade(4, 180_500, 101.0, 3.0, true),
+        ];
+
+        let time_bars = build_bars(&trades, &BarSpec::parse("time", "1m").unwrap());
+        assert_eq!(time_bars.len(), 2);
+        let first = &time_bars[0];
+        assert_eq!((first.open_time, first.close_time), (60_000, 119_999));
+        assert_eq!(
+            (first.open, first.high, first.low, first.close),
+            (100.0, 102.0, 99.0, 99.0)
+        );
+        assert_eq!(first.volume, 4.0);
+        assert_eq!(first.quote_volume, 403.0);
+        assert_eq!(first.trade_count, 6);
+        assert_eq!(first.taker_buy_volume, 2.0);
+        assert_eq!((first.first_agg_id, first.last_agg_id), (1, 3));
+        assert_eq!(time_bars[1].open_time, 180_000);
+
+        // 第 2 笔成交后累计 3.0 达到阈值，第 3、4 笔累计 4.0 成为第二根
+        let volume_bars = build_bars(&trades, &BarSpec::Volume(3.0));
+        assert_eq!(volume_bars.len(), 2);
+        assert_eq!(
+            (volume_bars[0].open_time, volume_bars[0].close_time),
+            (60_000, 61_000)
+        );
+        assert_eq!(volume_bars[1].volume, 4.0);
+
+        let dollar_bars = build_bars(&trades, &BarSpec::Dollar(500.0));
+        assert_eq!(dollar_bars.len(), 1);
+        assert_eq!(dollar_bars[0].last_agg_id, 4);
+
+        // 累计始终未达到阈值，尾部不输出
+        assert!(build_bars(&trades, &BarSpec::Volume(8.0)).is_empty());
+
+        assert!(BarSpec::parse("dollar", "-1").is_err());
+        assert!(BarSpec::parse("tick", "100").is_err());
+    }
+}
@@ -0,0 +1,279 @@
+use crate::collector::trades::bars::{build_bars, BarSpec, TradeBar};
+use crate::collector::trades::EXCHANGE;
+use crate::global::get_ck_db;
+use crate::infra::db::kline_store::{KlineSeries, KlineStore};
+use crate::infra::db::types::SortOrder;
+use crate::model::cex::kline::MarketKline as CkMarketKline;
+use crate::model::market_type::MarketType;
+use crate::model::TimeFrame;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// 默认相对误差容忍度，成交量等浮点累加允许微小偏差
+pub const DEFAULT_TOLERANCE: f64 = 1e-6;
+
+/// 参与比对的K线字段，可空的字段缺失时跳过比对
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredKline {
+    pub open_time: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+    pub quote_volume: Option<f64>,
+    pub trade_count: Option<u64>,
+    pub taker_buy_volume: Option<f64>,
+    pub taker_buy_quote_volume: Option<f64>,
+}
+
+/// 单个字段不一致
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BarMismatch {
+    pub open_time: i64,
+    pub field: &'static str,
+    pub bar: f64,
+    pub kline: f64,
+}
+
+/// 成交聚合K线与已存储K线的比对结果
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ConsistencyReport {
+    /// 实际比对的区间 [start, end)，已按周期对齐并裁剪到成交覆盖范围
+    pub start: i64,
+    pub end: i64,
+    /// 两边都存在并逐字段比对的K线数
+    pub checked: usize,
+    /// 有成交的K线存在，但由成交聚合不出对应周期
+    pub missing_bars: Vec<i64>,
+    /// 由成交聚合出的周期在K线表中不存在
+    pub missing_klines: Vec<i64>,
+    pub mismatches: Vec<BarMismatch>,
+}
+
+impl ConsistencyReport {
+    pub fn is_consistent(&self) -> bool {
+        self.missing_bars.is_empty() && self.missing_klines.is_empty() && self.mismatches.is_empty()
+    }
+}
+
+impl From<&CkMarketKline> for StoredKline {
+    fn from(k: &CkMarketKline) -> Self {
+        StoredKline {
+            open_time: k.open_time,
+            open: k.open,
+            high: k.high,
+            low: k.low,
+            close: k.close,
+            volume: k.volume,
+            quote_volume: Some(k.quote_asset_volume),
+            trade_count: Some(k.number_of_trades),
+            taker_buy_volume: Some(k.taker_buy_base_asset_volume),
+            taker_buy_quote_volume: Some(k.taker_buy_quote_asset_volume),
+        }
+    }
+}
+
+/// 按开盘时间逐根比对，价格与成交量按相对误差 `tolerance` 判断，成交笔数要求完全一致
+pub fn compare_with_klines(
+    bars: &[TradeBar],
+    klines: &[StoredKline],
+    tolerance: f64,
+) -> ConsistencyReport {
+    let bars: BTreeMap<i64, &TradeBar> = bars.iter().map(|b| (b.open_time, b)).collect();
+    let klines: BTreeMap<i64, &StoredKline> = klines.iter().map(|k| (k.open_time, k)).collect();
+    let mut report = ConsistencyReport::default();
+
+    for (open_time, kline) in &klines {
+        let Some(bar) = bars.get(open_time) else {
+            // 无成交的K线不会有对应的聚合结果
+            if kline.trade_count != Some(0) {
+                report.missing_bars.push(*open_time);
+            }
+            continue;
+        };
+        report.checked += 1;
+
+        let mut check = |field: &'static str, bar_value: f64, kline_value: Option<f64>| {
+            if let Some(kline_value) = kline_value {
+                if !approx_eq(bar_value, kline_value, tolerance) {
+                    report.mismatches.push(BarMismatch {
+                        open_time: *open_time,
+                        field,
+                        bar: bar_value,
+                        kline: kline_value,
+                    });
+                }
+            }
+        };
+        check("open", bar.open, Some(kline.open));
+        check("high", bar.high, Some(kline.high));
+        check("low", bar.low, Some(kline.low));
+        check("close", bar.close, Some(kline.close));
+        check("volume", bar.volume, Some(kline.volume));
+        check("quote_volume", bar.quote_volume, kline.quote_volume);
+        check(
+            "taker_buy_volume",
+            bar.taker_buy_volume,
+            kline.taker_buy_volume,
+        );
+        check(
+            "taker_buy_quote_volume",
+            bar.taker_buy_quote_volume,
+            kline.taker_buy_quote_volume,
+        );
+        check(
+            "trade_count",
+            bar.trade_count as f64,
+            kline.trade_count.map(|n| n as f64),
+        );
+    }
+
+    report.missing_klines = bars
+        .keys()
+        .filter(|open_time| !klines.contains_key(open_time))
+        .copied()
+        .collect();
+    report
+}
+
+fn approx_eq(a: f64, b: f64, tolerance: f64) -> bool {
+    (a - b).abs() <= tolerance * a.abs().max(b.abs()).max(1.0)
+}
+
+/// 由已存储成交聚合 `time_frame` K线，并与 market_klines 比对
+///
+/// 区间按周期对齐后裁剪到成交已覆盖的完整周期，避免首尾不完整的周期误报
+pub async fn check_consistency(
+    market_type: MarketType,
+    symbol: &str,
+    time_frame: &TimeFrame,
+    start: i64,
+    end: i64,
+    tolerance: f64,
+) -> anyhow::Result<ConsistencyReport> {
+    let Some(range) = get_ck_db()
+        .get_agg_trade_range(EXCHANGE, market_type.as_str(), symbol)
+        .await?
+    else {
+        return Ok(ConsistencyReport::default());
+    };
+
+    let start = align_up(time_frame, start.max(range.min_time));
+    let end = time_frame.bucket_open_time(end.min(range.max_time + 1));
+    if start >= end {
+        return Ok(ConsistencyReport {
+            start,
+            end: start,
+            ..Default::default()
+        });
+    }
+
+    let trades = get_ck_db()
+        .query_agg_trades(EXCHANGE, market_type.as_str(), symbol, start, end)
+        .await?;
+    let bars = build_bars(&trades, &BarSpec::Time(time_frame.clone()));
+    let klines = load_klines(market_type, symbol, time_frame, start, end).await?;
+
+    let mut report = compare_with_klines(&bars, &klines, tolerance);
+    report.start = start;
+    report.end = end;
+    Ok(report)
+}
+
+/// 向上对齐到周期开盘时间
+fn align_up(time_frame: &TimeFrame, ts: i64) -> i64 {
+    let open = time_frame.bucket_open_time(ts);
+    if open == ts {
+        ts
+    } else {
+        time_frame.close_time(open) + 1
+    }
+}
+
+/// 读取开盘时间在 [start_open, end_open) 内的K线，跨 MySQL 与 ClickHouse 合并
+async fn load_klines(
+    market_type: MarketType,
+    symbol: &str,
+    time_frame: &TimeFrame,
+    start_open: i64,
+    end_open: i64,
+) -> anyhow::Result<Vec<StoredKline>> {
+    let series = KlineSeries::new(EXCHANGE, market_type, symbol, time_frame.clone());
+    // 周期内收盘时间不晚于下一周期开盘前 1 毫秒
+    let klines = KlineStore::from_env()
+        .range(
+            &series,
+            start_open,
+            end_open - 1,
+            usize::MAX,
+            SortOrder::Asc,
+        )
+        .await?;
+    Ok(klines.iter().map(Into::into).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bar(open_time: i64, close: f64, volume: f64, trade_count: u64) -> TradeBar {
+        TradeBar {
+            open_time,
+            close_time: open_time + 59_999,
+            open: 100.0,
+            high: 101.0,
+            low: 99.0,
+            close,
+            volume,
+            quote_volume: volume * 100.0,
+            trade_count,
+            taker_buy_volume: volume / 2.0,
+            taker_buy_quote_volume: volume * 50.0,
+            first_agg_id: 1,
+            last_agg_id: 2,
+        }
+    }
+
+    fn kline(open_time: i64, close: f64, volume: f64, trade_count: Option<u64>) -> StoredKline {
+        StoredKline {
+            open_time,
+            open: 100.0,
+            high: 101.0,
+            low: 99.0,
+            close,
+            volume,
+            quote_volume: Some(volume * 100.0),
+            trade_count,
+            taker_buy_volume: None,
+            taker_buy_quote_volume: None,
+        }
+    }
+
+    #[test]
+    fn test_compare_with_klines() {
+        let bars = vec![
+            bar(0, 100.5, 10.0, 5),
+            // 浮点累加误差在容忍范围内
+            bar(60_000, 100.0, 3.000_000_000_1, 3),
+            bar(120_000, 100.0, 1.0, 1),
+            bar(240_000, 100.0, 1.0, 1),
+        ];
+        let klines = vec![
+            kline(0, 100.5, 10.0, Some(5)),
+            kline(60_000, 100.0, 3.0, Some(3)),
+            kline(120_000, 100.2, 1.0, Some(2)),
+            kline(180_000, 100.0, 0.0, Some(0)),
+            kline(300_000, 100.0, 1.0, Some(1)),
+        ];
+
+        let report = compare_with_klines(&bars, &klines, DEFAULT_TOLERANCE);
+        assert_eq!(report.checked, 3);
+        assert_eq!(report.missing_bars, vec![300_000]);
+        assert_eq!(report.missing_klines, vec![240_000]);
+        let fields: Vec<&str> = report.mismatches.iter().map(|m| m.field).collect();
+        assert_eq!(fields, vec!["close", "trade_count"]);
+        assert!(report.mismatches.iter().all(|m| m.open_time == 120_000));
+        assert!(!report.is_consistent());
+    }
+}
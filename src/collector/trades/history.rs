@@ -0,0 +1,227 @@
+use crate::collector::trades::{write_agg_trades, TradeConfig, EXCHANGE};
+use crate::global::{get_binance_limiter, get_ck_db};
+use crate::infra::external::binance::constant::agg_trades_weight;
+use crate::infra::external::binance::error::BinanceError;
+use crate::infra::external::binance::market::AggTrade;
+use crate::infra::external::binance::DefaultBinanceExchange;
+use crate::model::market_type::MarketType;
+use backoff::{future::retry, ExponentialBackoff};
+use chrono::Utc;
+use tracing::{info, warn};
+
+/// 每页条数上限
+const PAGE_LIMIT: u16 = 1000;
+
+/// 按时间查询时交易所允许的最大跨度
+const TIME_QUERY_SPAN_MS: i64 = HOUR_MS;
+
+/// 首次同步时向后探测第一条成交的最大小时数
+const MAX_PROBE_HOURS: i64 = 24;
+
+const HOUR_MS: i64 = 3_600_000;
+const DAY_MS: i64 = 86_400_000;
+
+/// 每轮缺口扫描最多处理的缺口数
+const MAX_GAPS_PER_SCAN: u64 = 100;
+
+/// 同步单个交易对：补齐近期 ID 缺口、按 fromId 向后追到最新、向前回溯到配置的历史天数
+///
+/// 每轮请求页数受 `max_pages_per_run` 限制，未完成的部分留到下一轮
+pub async fn sync_agg_trades(
+    market_type: MarketType,
+    symbol: &str,
+    config: &TradeConfig,
+) -> anyhow::Result<usize> {
+    let now = Utc::now().timestamp_millis();
+    let floor = now - config.history_days * DAY_MS;
+    let mut budget = config.max_pages_per_run;
+
+    let range = get_ck_db()
+        .get_agg_trade_range(EXCHANGE, market_type.as_str(), symbol)
+        .await?;
+
+    let Some(range) = range else {
+        // 首次同步：从回溯起点附近的第一条成交开始向后翻页
+        let Some(first_id) = probe_first_id(market_type, symbol, floor, now).await? else {
+            return Ok(0);
+        };
+        return forward_from(market_type, symbol, first_id, None, &mut budget).await;
+    };
+
+    // 先补缺口，实时流断线期间缺失的成交优先于更早的历史
+    let since = now - config.gap_scan_hours * HOUR_MS;
+    let mut written = repair_gaps(market_type, symbol, since, &mut budget).await?;
+    written += forward_from(
+        market_type,
+        symbol,
+        range.max_id as i64 + 1,
+        None,
+        &mut budget,
+    )
+    .await?;
+    if range.min_time > floor {
+        written +=
+            backward_from(market_type, symbol, range.min_id as i64, floor, &mut budget).await?;
+    }
+    Ok(written)
+}
+
+/// 从 `from_id` 起向后翻页写入，直到最新成交、到达 `stop_id`（不含）或页数用尽
+async fn forward_from(
+    market_type: MarketType,
+    symbol: &str,
+    mut from_id: i64,
+    stop_id: Option<i64>,
+    budget: &mut usize,
+) -> anyhow::Result<usize> {
+    let mut written = 0;
+    while *budget > 0 {
+        *budget -= 1;
+        let mut page = fetch_page(market_type, symbol, Some(from_id), None, None).await?;
+        let full_page = page.len() == PAGE_LIMIT as usize;
+
+        let reached_stop = stop_id.is_some_and(|stop| page.iter().any(|t| t.agg_id >= stop));
+        if let Some(stop) = stop_id {
+            page.retain(|t| t.agg_id < stop);
+        }
+        let Some(last) = page.last() else {
+            break;
+        };
+        from_id = last.agg_id + 1;
+
+        write_agg_trades(market_type, symbol, &page).await?;
+        written += page.len();
+
+        if !full_page || reached_stop {
+            break;
+        }
+    }
+    Ok(written)
+}
+
+/// 从 `min_id`（不含）起向前回溯，直到成交时间早于 `floor`、到达首条成交或页数用尽
+async fn backward_from(
+    market_type: MarketType,
+    symbol: &str,
+    mut min_id: i64,
+    floor: i64,
+    budget: &mut usize,
+) -> anyhow::Result<usize> {
+    let mut written = 0;
+    while *budget > 0 && min_id > 0 {
+        *budget -= 1;
+        let from_id = (min_id - PAGE_LIMIT as i64).max(0);
+        let mut page = fetch_page(market_type, symbol, Some(from_id), None, None).await?;
+        page.retain(|t| t.agg_id < min_id);
+        let Some(first) = page.first() else {
+            break;
+        };
+        min_id = first.agg_id;
+        let reached_floor = first.trade_time <= floor;
+
+        write_agg_trades(market_type, symbol, &page).await?;
+        written += page.len();
+
+        if reached_floor || from_id == 0 {
+            break;
+        }
+    }
+    Ok(written)
+}
+
+/// 补齐近期 ID 不连续的区间（通常由实时流断线造成）
+async fn repair_gaps(
+    market_type: MarketType,
+    symbol: &str,
+    since: i64,
+    budget: &mut usize,
+) -> anyhow::Result<usize> {
+    let gaps = get_ck_db()
+        .find_agg_trade_gaps(
+            EXCHANGE,
+            market_type.as_str(),
+            symbol,
+            since,
+            MAX_GAPS_PER_SCAN,
+        )
+        .await?;
+
+    let mut written = 0;
+    for gap in gaps {
+        if *budget == 0 {
+            break;
+        }
+        info!(
+            "Repairing agg trade gap {} ~ {} for {} {}",
+            gap.prev_id, gap.next_id, market_type, symbol
+        );
+        written += forward_from(
+            market_type,
+            symbol,
+            gap.prev_id as i64 + 1,
+            Some(gap.next_id as i64),
+            budget,
+        )
+        .await?;
+    }
+    Ok(written)
+}
+
+/// 查找 `start` 之后的第一条成交 ID：按小时窗口向后探测，仍无成交时从最新成交开始
+async fn probe_first_id(
+    market_type: MarketType,
+    symbol: &str,
+    start: i64,
+    now: i64,
+) -> anyhow::Result<Option<i64>> {
+    let mut window_start = start;
+    for _ in 0..MAX_PROBE_HOURS {
+        if window_start >= now {
+            break;
+        }
+        let window_end = (window_start + TIME_QUERY_SPAN_MS - 1).min(now);
+        let page = fetch_page(
+            market_type,
+            symbol,
+            None,
+            Some(window_start),
+            Some(window_end),
+        )
+        .await?;
+        if let Some(first) = page.first() {
+            return Ok(Some(first.agg_id));
+        }
+        window_start = window_end + 1;
+    }
+
+    let latest = fetch_page(market_type, symbol, None, None, None).await?;
+    Ok(latest.first().map(|t| t.agg_id))
+}
+
+/// 扣减权重后请求一页归集成交，仅重试临时错误
+async fn fetch_page(
+    market_type: MarketType,
+    symbol: &str,
+    from_id: Option<i64>,
+    start_time: Option<i64>,
+    end_time: Option<i64>,
+) -> Result<Vec<AggTrade>, BinanceError> {
+    retry(ExponentialBackoff::default(), || async {
+        get_binance_limiter()
+            .acquire_weight(market_type, agg_trades_weight(market_type))
+            .await;
+        DefaultBinanceExchange::for_market(market_type)
+            .get_agg_trades(symbol, from_id, start_time, end_time, PAGE_LIMIT)
+            .await
+            .map_err(|e| {
+                if e.is_transient() {
+                    warn!(?e, "Failed to fetch agg trades, retrying...");
+                    backoff::Error::transient(e)
+                } else {
+                    warn!(?e, "Failed to fetch agg trades for {}, giving up", symbol);
+                    backoff::Error::permanent(e)
+                }
+            })
+    })
+    .await
+}
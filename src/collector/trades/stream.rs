@@ -0,0 +1,274 @@
+use crate::collector::stream::kline_stream::{
+    CombinedStreamFrame, StreamExit, IDLE_TIMEOUT, MAX_CONNECTION_AGE, MAX_RECONNECT_DELAY,
+    MAX_STREAMS_PER_CONNECTION, RECONNECT_DELAY,
+};
+use crate::collector::trades::{write_agg_trades, TradeConfig};
+use crate::global::get_shutdown;
+use crate::infra::external::binance::constant::ws_base_url;
+use crate::infra::external::binance::market::AggTrade;
+use crate::model::market_type::MarketType;
+use futures::{SinkExt, StreamExt};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::{interval, sleep, timeout, MissedTickBehavior};
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{debug, info, warn};
+
+/// 单个交易对缓冲达到该条数时立即写入
+const FLUSH_SIZE: usize = 1000;
+
+/// 定时写入间隔
+const FLUSH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// 归集成交推送事件，成交字段与 REST 接口相同
+#[derive(Debug, Deserialize)]
+pub struct AggTradeEvent {
+    /// 交易对，如 "BTCUSDT"
+    #[serde(rename = "s")]
+    pub symbol: String,
+
+    #[serde(flatten)]
+    pub trade: AggTrade,
+}
+
+/// 发送给写入任务的单条成交
+#[derive(Debug)]
+pub struct TradeMessage {
+    pub market_type: MarketType,
+    pub symbol: String,
+    pub trade: AggTrade,
+}
+
+/// 构建组合流地址，如 wss://fstream.binance.com/stream?streams=btcusdt@aggTrade/ethusdt@aggTrade
+pub fn build_trade_stream_url(base_url: &str, symbols: &[String]) -> String {
+    let streams: Vec<String> = symbols
+        .iter()
+        .map(|s| format!("{}@aggTrade", s.to_lowercase()))
+        .collect();
+    format!("{}/stream?streams={}", base_url, streams.join("/"))
+}
+
+/// 维持单个连接直到断开或到达 `max_age`，成交逐条发送到 `tx`
+pub async fn run_trade_connection(
+    url: &str,
+    market_type: MarketType,
+    tx: &mpsc::Sender<TradeMessage>,
+    max_age: Duration,
+) -> anyhow::Result<StreamExit> {
+    let (ws, _) = connect_async(url).await?;
+    let (mut write, mut read) = ws.split();
+    info!("Trade stream connected: {}", url);
+
+    let deadline = sleep(max_age);
+    tokio::pin!(deadline);
+
+    loop {
+        tokio::select! {
+            _ = &mut deadline => {
+                let _ = write.send(Message::Close(None)).await;
+                return Ok(StreamExit::Expired);
+            }
+            frame = timeout(IDLE_TIMEOUT, read.next()) => {
+                let frame = frame.map_err(|_| anyhow::anyhow!("Trade stream idle for {:?}", IDLE_TIMEOUT))?;
+                match frame {
+                    None | Some(Ok(Message::Close(_))) => return Ok(StreamExit::Closed),
+                    Some(Err(e)) => return Err(e.into()),
+                    Some(Ok(Message::Ping(payload))) => write.send(Message::Pong(payload)).await?,
+                    Some(Ok(Message::Text(text))) => {
+                        let frame: CombinedStreamFrame<AggTradeEvent> = match serde_json::from_str(text.as_str()) {
+                            Ok(frame) => frame,
+                            Err(e) => {
+                                debug!("Ignoring non-trade frame: {} ({})", text, e);
+                                continue;
+                            }
+                        };
+                        let message = TradeMessage {
+                            market_type,
+                            symbol: frame.data.symbol,
+                            trade: frame.data.trade,
+                        };
+                        if tx.send(message).await.is_err() {
+                            return Ok(StreamExit::Closed);
+                        }
+                    }
+                    Some(Ok(_)) => {}
+                }
+            }
+        }
+    }
+}
+
+/// 持续运行一组订阅，断线后指数退避重连；断线期间缺失的成交由历史同步任务按 ID 缺口补齐
+pub async fn run_trade_stream(
+    market_type: MarketType,
+    symbols: Vec<String>,
+    tx: mpsc::Sender<TradeMessage>,
+) {
+    let url = build_trade_stream_url(ws_base_url(market_type), &symbols);
+    let shutdown = get_shutdown();
+    let mut delay = RECONNECT_DELAY;
+
+    while !tx.is_closed() {
+        let result = tokio::select! {
+            result = run_trade_connection(&url, market_type, &tx, MAX_CONNECTION_AGE) => result,
+            _ = shutdown.wait() => break,
+        };
+        match result {
+            Ok(StreamExit::Expired) => {
+                info!("Trade stream reached max connection age, reconnecting");
+                delay = RECONNECT_DELAY;
+                continue;
+            }
+            Ok(StreamExit::Closed) => warn!("Trade stream closed by server"),
+            Err(e) => warn!(?e, "Trade stream connection failed"),
+        }
+
+        tokio::select! {
+            _ = sleep(delay) => {}
+            _ = shutdown.wait() => break,
+        }
+        delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+    }
+    info!("Trade stream stopped: {}", url);
+}
+
+/// 按交易对缓冲成交，达到 `FLUSH_SIZE` 或每隔 `FLUSH_INTERVAL` 写入 ClickHouse，通道关闭后写完剩余数据
+pub async fn run_trade_writer(mut rx: mpsc::Receiver<TradeMessage>) {
+    let mut buffers: HashMap<(MarketType, String), Vec<AggTrade>> = HashMap::new();
+    let mut last_ids: HashMap<(MarketType, String), i64> = HashMap::new();
+    let mut ticker = interval(FLUSH_INTERVAL);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            message = rx.recv() => {
+                let Some(message) = message else {
+                    break;
+                };
+                let key = (message.market_type, message.symbol);
+                let agg_id = message.trade.agg_id;
+                if let Some(last) = last_ids.insert(key.clone(), agg_id) {
+                    if agg_id > last + 1 {
+                        warn!(
+                            "Agg trade id jump {} -> {} for {} {}, left to history sync",
+                            last, agg_id, key.0, key.1
+                        );
+                    }
+                }
+
+                let buffer = buffers.entry(key.clone()).or_default();
+                buffer.push(message.trade);
+                if buffer.len() >= FLUSH_SIZE {
+                    let trades = std::mem::take(buffer);
+                    flush(&key, &trades).await;
+                }
+            }
+            _ = ticker.tick() => {
+                for (key, buffer) in buffers.iter_mut() {
+                    if !buffer.is_empty() {
+                        let trades = std::mem::take(buffer);
+                        flush(key, &trades).await;
+                    }
+                }
+            }
+        }
+    }
+
+    for (key, trades) in buffers {
+        if !trades.is_empty() {
+            flush(&key, &trades).await;
+        }
+    }
+    info!("Trade writer stopped");
+}
+
+async fn flush((market_type, symbol): &(MarketType, String), trades: &[AggTrade]) {
+    if let Err(e) = write_agg_trades(*market_type, symbol, trades).await {
+        warn!(
+            ?e,
+            "Failed to write {} agg trades for {} {}",
+            trades.len(),
+            market_type,
+            symbol
+        );
+    }
+}
+
+/// 启动实时归集成交采集：按 TRADE_SYMBOLS / TRADE_MARKET_TYPES 订阅，所有连接共用一个写入任务
+pub async fn start_trade_stream() -> anyhow::Result<()> {
+    let config = TradeConfig::from_env();
+    if config.symbols.is_empty() {
+        info!("No trade streams to subscribe.");
+        return Ok(());
+    }
+
+    let (tx, rx) = mpsc::channel::<TradeMessage>(10_000);
+    tokio::spawn(run_trade_writer(rx));
+
+    for market_type in &config.market_types {
+        for chunk in config.symbols.chunks(MAX_STREAMS_PER_CONNECTION) {
+            tokio::spawn(run_trade_stream(*market_type, chunk.to_vec(), tx.clone()));
+        }
+    }
+
+    info!(
+        "Started trade streams for {} symbols on {} markets",
+        config.symbols.len(),
+        config.market_types.len()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::accept_async;
+
+    /// 录制的推送帧：两条成交与一条订阅回执
+    const RECORDED_FRAMES: &[&str] = &[
+        r#"{"result":null,"id":1}"#,
+        r#"{"stream":"btcusdt@aggTrade","data":{"e":"aggTrade","E":1717200000123,"s":"BTCUSDT","a":2150000001,"p":"67500.10","q":"0.015","f":5100000001,"l":5100000003,"T":1717200000120,"m":true}}"#,
+        r#"{"stream":"btcusdt@aggTrade","data":{"e":"aggTrade","E":1717200000150,"s":"BTCUSDT","a":2150000002,"p":"67500.20","q":"0.200","f":5100000004,"l":5100000004,"T":1717200000148,"m":false}}"#,
+    ];
+
+    #[tokio::test]
+    async fn test_trade_stream_replays_agg_trades() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = accept_async(stream).await.unwrap();
+            for frame in RECORDED_FRAMES {
+                ws.send(Message::text(*frame)).await.unwrap();
+            }
+            ws.close(None).await.unwrap();
+        });
+
+        let (tx, mut rx) = mpsc::channel::<TradeMessage>(10);
+        let url = build_trade_stream_url(&format!("ws://{}", addr), &["BTCUSDT".to_string()]);
+        assert!(url.ends_with("/stream?streams=btcusdt@aggTrade"));
+
+        let exit = run_trade_connection(&url, MarketType::UsdM, &tx, Duration::from_secs(10))
+            .await
+            .unwrap();
+        server.await.unwrap();
+        assert_eq!(exit, StreamExit::Closed);
+
+        let first = rx.try_recv().unwrap();
+        assert_eq!(first.symbol, "BTCUSDT");
+        assert_eq!(first.trade.agg_id, 2_150_000_001);
+        assert_eq!(first.trade.price, 67500.1);
+        assert_eq!(first.trade.trade_time, 1_717_200_000_120);
+        assert!(first.trade.is_buyer_maker);
+
+        let second = rx.try_recv().unwrap();
+        assert_eq!(second.trade.qty, 0.2);
+        assert!(!second.trade.is_buyer_maker);
+        assert!(rx.try_recv().is_err());
+    }
+}
@@ -1,4 +1,7 @@
 pub mod serde_fun;
+pub(crate) mod shutdown;
+#[cfg(test)]
+pub(crate) mod test_utils;
 pub(crate) mod utils;
 
 /// 通用批量转换 trait，支持将 `Vec<T>` 转换为 `Vec<U>`，前提是 `U: From<T>`
@@ -48,6 +48,38 @@ where
     }
 }
 
+/// 将字符串或数字形式的数值反序列化为 f64（交易所常以字符串返回价格）
+pub fn deserialize_string_to_f64<'de, D>(deserializer: D) -> Result<f64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = Value::deserialize(deserializer)?;
+    f64::from_json_value(&value, "f64").map_err(serde::de::Error::custom)
+}
+
+/// 将字符串或数字形式的整数反序列化为 i64（部分接口的时间戳以字符串返回）
+pub fn deserialize_string_to_i64<'de, D>(deserializer: D) -> Result<i64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = Value::deserialize(deserializer)?;
+    i64::from_json_value(&value, "i64").map_err(serde::de::Error::custom)
+}
+
+/// 同 `deserialize_string_to_f64`，空字符串或 null 视为缺失（如早期资金费率记录没有标记价格）
+pub fn deserialize_optional_f64<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Value::deserialize(deserializer)? {
+        Value::Null => Ok(None),
+        Value::String(s) if s.trim().is_empty() => Ok(None),
+        value => f64::from_json_value(&value, "f64")
+            .map(Some)
+            .map_err(serde::de::Error::custom),
+    }
+}
+
 const FORMAT: &str = "%Y-%m-%d %H:%M:%S";
 
 pub fn deserialize_datetime_option<'de, D>(
@@ -0,0 +1,145 @@
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::time::timeout;
+use tracing::info;
+
+/// 进程级优雅退出协调器
+///
+/// - `trigger` 广播退出信号：HTTP 服务、定时调度、归档派发、实时流据此停止接收新任务
+/// - `track` 登记进行中的任务，`wait_idle` 在截止时间内等待其全部结束
+#[derive(Debug)]
+pub struct Shutdown {
+    triggered: watch::Sender<bool>,
+    in_flight: watch::Sender<usize>,
+}
+
+/// 进行中任务的登记凭证，drop 时自动注销
+#[derive(Debug)]
+pub struct TaskGuard {
+    in_flight: watch::Sender<usize>,
+}
+
+impl Drop for TaskGuard {
+    fn drop(&mut self) {
+        self.in_flight.send_modify(|n| *n = n.saturating_sub(1));
+    }
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Shutdown {
+    pub fn new() -> Self {
+        Self {
+            triggered: watch::Sender::new(false),
+            in_flight: watch::Sender::new(0),
+        }
+    }
+
+    /// 发出退出信号，重复调用无副作用
+    pub fn trigger(&self) {
+        self.triggered.send_if_modified(|triggered| {
+            let changed = !*triggered;
+            *triggered = true;
+            changed
+        });
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        *self.triggered.borrow()
+    }
+
+    /// 等待退出信号，已触发时立即返回
+    pub async fn wait(&self) {
+        let mut rx = self.triggered.subscribe();
+        let _ = rx.wait_for(|triggered| *triggered).await;
+    }
+
+    /// 登记一个进行中的任务
+    pub fn track(&self) -> TaskGuard {
+        self.in_flight.send_modify(|n| *n += 1);
+        TaskGuard {
+            in_flight: self.in_flight.clone(),
+        }
+    }
+
+    pub fn in_flight(&self) -> usize {
+        *self.in_flight.borrow()
+    }
+
+    /// 在 `deadline` 内等待所有登记的任务结束，超时返回 false
+    pub async fn wait_idle(&self, deadline: Duration) -> bool {
+        let mut rx = self.in_flight.subscribe();
+        let idle = timeout(deadline, rx.wait_for(|n| *n == 0)).await;
+        idle.is_ok()
+    }
+}
+
+/// 等待 Ctrl+C 或 SIGTERM
+pub async fn wait_for_signal() {
+    let ctrl_c = async {
+        let _ = tokio::signal::ctrl_c().await;
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(_) => std::future::pending::<()>().await,
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => info!("Received Ctrl+C, shutting down..."),
+        _ = terminate => info!("Received SIGTERM, shutting down..."),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_wait_idle_tracks_in_flight_tasks() {
+        let shutdown = Shutdown::new();
+        assert!(shutdown.wait_idle(Duration::from_millis(10)).await);
+
+        let guard = shutdown.track();
+        assert_eq!(shutdown.in_flight(), 1);
+        assert!(!shutdown.wait_idle(Duration::from_millis(10)).await);
+
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            drop(guard);
+        });
+        assert!(shutdown.wait_idle(Duration::from_secs(1)).await);
+        assert_eq!(shutdown.in_flight(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_wait_returns_after_trigger() {
+        let shutdown = std::sync::Arc::new(Shutdown::new());
+        assert!(!shutdown.is_triggered());
+
+        let waiter = {
+            let shutdown = shutdown.clone();
+            tokio::spawn(async move { shutdown.wait().await })
+        };
+        shutdown.trigger();
+        shutdown.trigger();
+
+        timeout(Duration::from_secs(1), waiter)
+            .await
+            .expect("wait should return after trigger")
+            .unwrap();
+        assert!(shutdown.is_triggered());
+    }
+}
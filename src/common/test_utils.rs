@@ -0,0 +1,55 @@
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// 录制的 HTTP 响应：请求目标（路径 + 查询串）包含 `matches` 中以 `&` 分隔的全部片段时返回 `body`
+pub struct FixtureRoute {
+    pub matches: &'static str,
+    pub body: &'static str,
+}
+
+/// 启动本地 mock server，按顺序匹配路由回放录制的 JSON，返回 base url
+pub async fn spawn_fixture_server(routes: Vec<FixtureRoute>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        loop {
+            let Ok((mut socket, _)) = listener.accept().await else {
+                break;
+            };
+
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                match socket.read(&mut buf).await {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => request.extend_from_slice(&buf[..n]),
+                }
+            }
+
+            let request = String::from_utf8_lossy(&request);
+            let target = request
+                .lines()
+                .next()
+                .and_then(|line| line.split_whitespace().nth(1))
+                .unwrap_or_default();
+
+            let (status, body) = routes
+                .iter()
+                .find(|r| r.matches.split('&').all(|part| target.contains(part)))
+                .map(|r| ("200 OK", r.body))
+                .unwrap_or(("404 Not Found", r#"{"error":"no fixture"}"#));
+
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            let _ = socket.write_all(response.as_bytes()).await;
+            let _ = socket.shutdown().await;
+        }
+    });
+
+    format!("http://{}", addr)
+}
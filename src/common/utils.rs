@@ -48,6 +48,26 @@ pub fn get_env_bool(key: &str, default: bool) -> bool {
         .unwrap_or(default)
 }
 
+/// 读取逗号分隔的环境变量列表，自动去除空白与空项
+pub fn get_env_list(key: &str) -> Vec<String> {
+    std::env::var(key)
+        .map(|val| {
+            val.split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 读取并解析环境变量，缺失或解析失败时返回默认值
+pub fn get_env_or<T: std::str::FromStr>(key: &str, default: T) -> T {
+    std::env::var(key)
+        .ok()
+        .and_then(|val| val.trim().parse::<T>().ok())
+        .unwrap_or(default)
+}
+
 pub fn round_to_decimals(x: f64, decimals: u32) -> f64 {
     let y = 10i32.pow(decimals) as f64;
     (x * y).round() / y
@@ -72,6 +92,21 @@ pub async fn create_redis_pool(redis_url: &str) -> Result<bb8::Pool<RedisConnect
     Ok(pool)
 }
 
+/// 去掉合约基础资产的倍数前缀并转为大写，如 1000PEPE -> PEPE、10000LADYS -> LADYS、1MBABYDOGE -> BABYDOGE，以便与 CoinGecko 符号匹配
+///
+/// 前缀按长度从长到短匹配，避免 10000LADYS 被 1000 截成 0LADYS
+pub fn normalize_base_asset(base_asset: &str) -> String {
+    let upper = base_asset.to_uppercase();
+    for prefix in ["1000000", "10000", "1000", "1M"] {
+        if let Some(stripped) = upper.strip_prefix(prefix) {
+            if !stripped.is_empty() {
+                return stripped.to_string();
+            }
+        }
+    }
+    upper
+}
+
 pub fn format_opt_decimal(val: &Option<BigDecimal>) -> String {
     val.as_ref()
         .map(ToString::to_string)
@@ -80,6 +115,20 @@ pub fn format_opt_decimal(val: &Option<BigDecimal>) -> String {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_base_asset() {
+        assert_eq!(normalize_base_asset("btc"), "BTC");
+        assert_eq!(normalize_base_asset("1000PEPE"), "PEPE");
+        assert_eq!(normalize_base_asset("10000LADYS"), "LADYS");
+        assert_eq!(normalize_base_asset("1000000MOG"), "MOG");
+        assert_eq!(normalize_base_asset("1MBABYDOGE"), "BABYDOGE");
+        assert_eq!(normalize_base_asset("1mbabydoge"), "BABYDOGE");
+        // 只有前缀时保持原样
+        assert_eq!(normalize_base_asset("1000"), "1000");
+        assert_eq!(normalize_base_asset("1INCH"), "1INCH");
+    }
 
     #[tokio::test]
     async fn test_get_jup_price() {
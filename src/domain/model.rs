@@ -1,9 +1,12 @@
 use diesel::result::Error as DieselError;
 use thiserror::Error;
 
+pub mod archive_checkpoint;
 pub mod coin_category;
 pub mod coin_data_info;
 pub mod coin_rank_info;
+pub mod kline_gap;
+pub mod kline_quarantine;
 pub mod market_kline;
 pub mod market_symbol;
 
@@ -74,6 +77,7 @@ mod tests {
     use crate::infra::external::binance::DefaultBinanceExchange;
     use crate::infra::external::cgecko::coin_rank::CoinRank;
     use crate::infra::external::cgecko::DefaultCoinGecko;
+    use crate::model::market_type::MarketType;
     use bigdecimal::BigDecimal;
     use listen_tracing::trace_kv;
     use listen_tracing::tracing_utils::{fmt_bigdecimal, fmt_json_value, fmt_naive_date};
@@ -138,9 +142,12 @@ mod tests {
     #[tokio::test]
     async fn test_get_symbols() {
         listen_tracing::setup_tracing();
-        let dbe = DefaultBinanceExchange::default();
-        if let Some(symbols) = dbe.get_symbols().await {
-            let market_symbol_list: Vec<NewOrUpdateMarketSymbol> = symbols.convert_vec();
+        let dbe = DefaultBinanceExchange::for_market(MarketType::Spot);
+        if let Ok(symbols) = dbe.get_symbols().await {
+            let market_symbol_list: Vec<NewOrUpdateMarketSymbol> = symbols
+                .into_iter()
+                .map(|s| NewOrUpdateMarketSymbol::from((s, MarketType::Spot)))
+                .collect();
             for market_symbol in &market_symbol_list {
                 trace_kv!(info,
                      "base_asset" => market_symbol.base_asset,
@@ -0,0 +1,127 @@
+use crate::domain::model::SortOrder;
+use base64::Engine;
+use chrono::NaiveDateTime;
+use diesel::{AsChangeset, Identifiable, Insertable, Queryable, Selectable};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// 检查点状态：窗口已规划，尚未拉取
+pub const CHECKPOINT_STATUS_PENDING: &str = "pending";
+/// 检查点状态：已拉取，等待写库
+pub const CHECKPOINT_STATUS_FETCHED: &str = "fetched";
+/// 检查点状态：已写库（或交易所无数据），窗口完成
+pub const CHECKPOINT_STATUS_FLUSHED: &str = "flushed";
+/// 检查点状态：拉取或写库失败，待重试
+pub const CHECKPOINT_STATUS_FAILED: &str = "failed";
+
+/// 归档窗口检查点表模型
+#[derive(Debug, Queryable, Selectable, Serialize, Deserialize, Identifiable, Clone)]
+#[diesel(table_name = crate::schema::archive_checkpoint)]
+pub struct ArchiveCheckpoint {
+    /// 唯一标识符 exchange+market_type+symbol+time_frame+direction+window_start base64编码
+    pub id: String,
+
+    /// 交易所名称，例如 binance
+    pub exchange: String,
+
+    /// 市场类型：spot / usdm / coinm
+    pub market_type: String,
+
+    /// 交易对，例如 BTCUSDT
+    pub symbol: String,
+
+    /// K线周期，例如 1m、5m、1h
+    pub time_frame: String,
+
+    /// 归档方向：forward / backward
+    pub direction: String,
+
+    /// 窗口起点（毫秒）
+    pub window_start: i64,
+
+    /// 窗口终点（毫秒）
+    pub window_end: i64,
+
+    /// 状态：pending / fetched / flushed / failed
+    pub status: String,
+
+    /// 失败次数
+    pub attempts: u32,
+
+    /// 拉取到的K线数量
+    pub kline_count: i64,
+
+    /// 最近一次失败原因
+    pub last_error: Option<String>,
+
+    /// 创建时间
+    pub created_at: NaiveDateTime,
+
+    /// 最后更新时间
+    pub updated_at: NaiveDateTime,
+}
+
+/// 用于创建或更新归档检查点的模型
+#[derive(Debug, Identifiable, Insertable, AsChangeset, Serialize, Deserialize, Clone)]
+#[diesel(table_name = crate::schema::archive_checkpoint)]
+pub struct NewOrUpdateArchiveCheckpoint {
+    pub id: String,
+    pub exchange: String,
+    pub market_type: String,
+    pub symbol: String,
+    pub time_frame: String,
+    pub direction: String,
+    pub window_start: i64,
+    pub window_end: i64,
+    pub status: String,
+    pub attempts: u32,
+    pub kline_count: i64,
+    pub last_error: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+/// 生成组合主键的 Base64 表示
+pub fn encode_archive_checkpoint_pk(
+    exchange: &str,
+    market_type: &str,
+    symbol: &str,
+    time_frame: &str,
+    direction: &str,
+    window_start: i64,
+) -> String {
+    let raw = format!(
+        "{}|{}|{}|{}|{}|{}",
+        exchange, market_type, symbol, time_frame, direction, window_start
+    );
+    base64::encode(raw)
+}
+
+#[derive(Debug, Clone)]
+pub struct ArchiveCheckpointFilter {
+    pub exchange: Option<String>,
+    pub market_type: Option<String>,
+    pub symbol: Option<String>,
+    pub time_frame: Option<String>,
+    pub direction: Option<String>,
+    pub status: Option<String>,
+    pub sort_by_window_start: Option<SortOrder>,
+    pub page: Option<usize>,
+    pub page_size: Option<usize>,
+}
+
+/// 单个归档任务的检查点统计：各状态窗口数量及未完成工作
+#[derive(Debug, Clone, Serialize)]
+pub struct ArchiveCheckpointSummary {
+    pub exchange: String,
+    pub market_type: String,
+    pub symbol: String,
+    pub time_frame: String,
+    pub direction: String,
+    pub statuses: BTreeMap<String, i64>,
+    /// 未完成（非 flushed）的窗口数量
+    pub outstanding: i64,
+    /// 未完成窗口中最早的起点，便于定位缺口
+    pub oldest_outstanding_start: Option<i64>,
+    pub last_updated_at: Option<NaiveDateTime>,
+}
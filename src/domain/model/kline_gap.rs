@@ -0,0 +1,142 @@
+use crate::domain::model::SortOrder;
+use base64::Engine;
+use chrono::NaiveDateTime;
+use diesel::{AsChangeset, Identifiable, Insertable, Queryable, Selectable};
+use serde::{Deserialize, Serialize};
+
+/// 缺口状态：已发现，待修复
+pub const GAP_STATUS_FOUND: &str = "found";
+/// 缺口状态：已补齐
+pub const GAP_STATUS_FILLED: &str = "filled";
+/// 缺口状态：交易所无数据，无法补齐
+pub const GAP_STATUS_UNFILLABLE: &str = "unfillable";
+
+/// K线缺口记录表模型
+#[derive(Debug, Queryable, Selectable, Serialize, Deserialize, Identifiable, Clone)]
+#[diesel(table_name = crate::schema::kline_gap)]
+pub struct KlineGap {
+    /// 唯一标识符 exchange+market_type+symbol+time_frame+store+gap_start base64编码
+    pub id: String,
+
+    /// 交易所名称，例如 binance
+    pub exchange: String,
+
+    /// 市场类型：spot / usdm / coinm
+    pub market_type: String,
+
+    /// 交易对，例如 BTCUSDT
+    pub symbol: String,
+
+    /// K线周期，例如 1m、5m、1h
+    pub time_frame: String,
+
+    /// 存储位置：mysql / clickhouse
+    pub store: String,
+
+    /// 第一根缺失K线的 close_time（毫秒）
+    pub gap_start: i64,
+
+    /// 最后一根缺失K线的 close_time（毫秒）
+    pub gap_end: i64,
+
+    /// 缺失K线数量
+    pub missing_count: i64,
+
+    /// 已补齐K线数量
+    pub filled_count: i64,
+
+    /// 状态：found / filled / unfillable
+    pub status: String,
+
+    /// 修复尝试次数
+    pub attempts: u32,
+
+    /// 发现时间
+    pub detected_at: NaiveDateTime,
+
+    /// 最后更新时间
+    pub updated_at: NaiveDateTime,
+}
+
+/// 用于创建或更新K线缺口记录的模型
+#[derive(Debug, Identifiable, Insertable, AsChangeset, Serialize, Deserialize, Clone)]
+#[diesel(table_name = crate::schema::kline_gap)]
+pub struct NewOrUpdateKlineGap {
+    pub id: String,
+    pub exchange: String,
+    pub market_type: String,
+    pub symbol: String,
+    pub time_frame: String,
+    pub store: String,
+    pub gap_start: i64,
+    pub gap_end: i64,
+    pub missing_count: i64,
+    pub filled_count: i64,
+    pub status: String,
+    pub attempts: u32,
+    pub detected_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl From<KlineGap> for NewOrUpdateKlineGap {
+    fn from(g: KlineGap) -> Self {
+        NewOrUpdateKlineGap {
+            id: g.id,
+            exchange: g.exchange,
+            market_type: g.market_type,
+            symbol: g.symbol,
+            time_frame: g.time_frame,
+            store: g.store,
+            gap_start: g.gap_start,
+            gap_end: g.gap_end,
+            missing_count: g.missing_count,
+            filled_count: g.filled_count,
+            status: g.status,
+            attempts: g.attempts,
+            detected_at: g.detected_at,
+            updated_at: g.updated_at,
+        }
+    }
+}
+
+/// 生成组合主键的 Base64 表示
+pub fn encode_kline_gap_pk(
+    exchange: &str,
+    market_type: &str,
+    symbol: &str,
+    time_frame: &str,
+    store: &str,
+    gap_start: i64,
+) -> String {
+    let raw = format!(
+        "{}|{}|{}|{}|{}|{}",
+        exchange, market_type, symbol, time_frame, store, gap_start
+    );
+    base64::encode(raw)
+}
+
+#[derive(Debug, Clone)]
+pub struct KlineGapFilter {
+    pub exchange: Option<String>,
+    pub market_type: Option<String>,
+    pub symbol: Option<String>,
+    pub time_frame: Option<String>,
+    pub store: Option<String>,
+    pub status: Option<String>,
+    pub sort_by_gap_start: Option<SortOrder>,
+    pub page: Option<usize>,
+    pub page_size: Option<usize>,
+}
+
+/// 单个市场的数据完整度统计
+#[derive(Debug, Clone, Serialize)]
+pub struct KlineGapSummary {
+    pub exchange: String,
+    pub market_type: String,
+    pub symbol: String,
+    pub time_frame: String,
+    pub store: String,
+    pub found: i64,
+    pub filled: i64,
+    pub unfillable: i64,
+}
@@ -0,0 +1,105 @@
+use crate::domain::model::SortOrder;
+use base64::Engine;
+use chrono::NaiveDateTime;
+use diesel::{AsChangeset, Identifiable, Insertable, Queryable, Selectable};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// 未通过数据质量校验的K线隔离表模型
+#[derive(Debug, Queryable, Selectable, Serialize, Deserialize, Identifiable, Clone)]
+#[diesel(table_name = crate::schema::kline_quarantine)]
+pub struct KlineQuarantine {
+    /// 唯一标识符 exchange+market_type+symbol+time_frame+close_time+rule base64编码
+    pub id: String,
+
+    /// 交易所名称，例如 binance
+    pub exchange: String,
+
+    /// 市场类型：spot / usdm / coinm
+    pub market_type: String,
+
+    /// 交易对，例如 BTCUSDT
+    pub symbol: String,
+
+    /// K线周期，例如 1m、5m、1h
+    pub time_frame: String,
+
+    /// 归档方向：forward / backward
+    pub direction: String,
+
+    /// K线开盘时间（毫秒）
+    pub open_time: i64,
+
+    /// K线收盘时间（毫秒）
+    pub close_time: i64,
+
+    /// 未通过的校验规则，例如 high_below_low
+    pub rule: String,
+
+    /// 失败原因描述
+    pub detail: String,
+
+    /// 被拒绝的原始K线
+    pub payload: serde_json::Value,
+
+    /// 发现时间
+    pub detected_at: NaiveDateTime,
+}
+
+/// 用于写入隔离记录的模型
+#[derive(Debug, Identifiable, Insertable, AsChangeset, Serialize, Deserialize, Clone)]
+#[diesel(table_name = crate::schema::kline_quarantine)]
+pub struct NewOrUpdateKlineQuarantine {
+    pub id: String,
+    pub exchange: String,
+    pub market_type: String,
+    pub symbol: String,
+    pub time_frame: String,
+    pub direction: String,
+    pub open_time: i64,
+    pub close_time: i64,
+    pub rule: String,
+    pub detail: String,
+    pub payload: serde_json::Value,
+    pub detected_at: NaiveDateTime,
+}
+
+/// 生成组合主键的 Base64 表示
+pub fn encode_kline_quarantine_pk(
+    exchange: &str,
+    market_type: &str,
+    symbol: &str,
+    time_frame: &str,
+    close_time: i64,
+    rule: &str,
+) -> String {
+    let raw = format!(
+        "{}|{}|{}|{}|{}|{}",
+        exchange, market_type, symbol, time_frame, close_time, rule
+    );
+    base64::encode(raw)
+}
+
+#[derive(Debug, Clone)]
+pub struct KlineQuarantineFilter {
+    pub exchange: Option<String>,
+    pub market_type: Option<String>,
+    pub symbol: Option<String>,
+    pub time_frame: Option<String>,
+    pub rule: Option<String>,
+    pub sort_by_close_time: Option<SortOrder>,
+    pub page: Option<usize>,
+    pub page_size: Option<usize>,
+}
+
+/// 单个市场的数据质量统计：各规则的隔离数量
+#[derive(Debug, Clone, Serialize)]
+pub struct KlineQualitySummary {
+    pub exchange: String,
+    pub market_type: String,
+    pub symbol: String,
+    pub time_frame: String,
+    pub total: i64,
+    pub rules: BTreeMap<String, i64>,
+    pub last_detected_at: Option<NaiveDateTime>,
+}
@@ -8,12 +8,15 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Queryable, Selectable, Serialize, Deserialize, Identifiable, Clone)]
 #[diesel(table_name = crate::schema::market_kline)]
 pub struct MarketKline {
-    // 唯一标识符 exchange+symbol+time_frame+close_time base64编码
+    // 唯一标识符 exchange+market_type+symbol+time_frame+close_time base64编码
     pub id: String,
 
     /// 交易所名称，例如 binance
     pub exchange: String,
 
+    /// 市场类型：spot / usdm / coinm
+    pub market_type: String,
+
     /// 交易对，例如 BTCUSDT
     pub symbol: String,
 
@@ -58,11 +61,14 @@ pub struct MarketKline {
 #[derive(Debug, Identifiable, Insertable, AsChangeset, Serialize, Deserialize, Clone)]
 #[diesel(table_name = crate::schema::market_kline)]
 pub struct NewOrUpdateMarketKline {
-    // 唯一标识符 exchange+symbol+time_frame+close_time base64编码
+    // 唯一标识符 exchange+market_type+symbol+time_frame+close_time base64编码
     pub id: String,
     /// 交易所名称，例如 binance
     pub exchange: String,
 
+    /// 市场类型：spot / usdm / coinm
+    pub market_type: String,
+
     /// 交易对，例如 BTCUSDT
     pub symbol: String,
 
@@ -104,11 +110,14 @@ pub struct NewOrUpdateMarketKline {
 }
 
 // 实现从 KlineSummary 到 NewOrUpdateCoinDataInfo 的转换
-impl From<(&KlineSummary, &str, &str, &str)> for NewOrUpdateMarketKline {
-    fn from((s, exchange, symbol, period): (&KlineSummary, &str, &str, &str)) -> Self {
+impl From<(&KlineSummary, &str, &str, &str, &str)> for NewOrUpdateMarketKline {
+    fn from(
+        (s, exchange, market_type, symbol, period): (&KlineSummary, &str, &str, &str, &str),
+    ) -> Self {
         NewOrUpdateMarketKline {
-            id: encode_market_kline_pk(exchange, symbol, period, s.close_time),
+            id: encode_market_kline_pk(exchange, market_type, symbol, period, s.close_time),
             exchange: exchange.to_string(),
+            market_type: market_type.to_string(),
             symbol: symbol.to_string(),
             time_frame: period.to_string(),
 
@@ -130,12 +139,16 @@ impl From<(&KlineSummary, &str, &str, &str)> for NewOrUpdateMarketKline {
 /// 生成组合主键的 Base64 表示
 pub fn encode_market_kline_pk(
     exchange: &str,
+    market_type: &str,
     symbol: &str,
     time_frame: &str,
     close_time: i64,
 ) -> String {
     // 将各字段用分隔符连接
-    let raw = format!("{}|{}|{}|{}", exchange, symbol, time_frame, close_time);
+    let raw = format!(
+        "{}|{}|{}|{}|{}",
+        exchange, market_type, symbol, time_frame, close_time
+    );
     // Base64 编码
     base64::encode(raw)
 }
@@ -143,9 +156,14 @@ pub fn encode_market_kline_pk(
 #[derive(Debug, Clone)]
 pub struct MarketKlineFilter {
     pub exchange: Option<String>,
+    pub market_type: Option<String>,
     pub symbol: Option<String>,
     pub time_frame: Option<String>,
     pub close_time: Option<i64>,
+    /// close_time 下限（含）
+    pub start_close_time: Option<i64>,
+    /// close_time 上限（含）
+    pub end_close_time: Option<i64>,
     pub sort_by_close_time: Option<SortOrder>,
     pub page: Option<usize>,
     pub page_size: Option<usize>,
@@ -1,5 +1,8 @@
 use crate::common::serde_fun::option_obj_to_value;
 use crate::infra::external::binance::meta::Symbol;
+use crate::infra::external::bybit::meta::BybitInstrument;
+use crate::infra::external::okx::meta::OkxInstrument;
+use crate::model::market_type::MarketType;
 use base64::Engine;
 use diesel::{AsChangeset, Identifiable, Insertable, Queryable, Selectable};
 use serde::{Deserialize, Serialize};
@@ -8,12 +11,15 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Queryable, Selectable, Serialize, Deserialize, Identifiable, Clone)]
 #[diesel(table_name = crate::schema::market_symbol)]
 pub struct MarketSymbol {
-    /// 唯一标识符 exchange+symbol base64编码
+    /// 唯一标识符 exchange+market_type+symbol base64编码
     pub id: String,
 
     /// 交易所，如 Binance、OKX
     pub exchange: String,
 
+    /// 市场类型：spot / usdm / coinm
+    pub market_type: String,
+
     /// 交易对，如 "ONEUSDT"
     pub symbol: String,
 
@@ -95,12 +101,15 @@ pub struct MarketSymbol {
 #[derive(Debug, Identifiable, Insertable, AsChangeset, Serialize, Deserialize, Clone)]
 #[diesel(table_name = crate::schema::market_symbol)]
 pub struct NewOrUpdateMarketSymbol {
-    /// 唯一标识符 exchange+symbol base64编码
+    /// 唯一标识符 exchange+market_type+symbol base64编码
     pub id: String,
 
     /// 交易所，如 Binance、OKX
     pub exchange: String,
 
+    /// 市场类型：spot / usdm / coinm
+    pub market_type: String,
+
     /// 交易对，如 "ONEUSDT"
     pub symbol: String,
 
@@ -182,16 +191,29 @@ pub struct NewOrUpdateMarketSymbol {
     pub permission_sets: Option<serde_json::Value>,
 }
 
-// 实现从 Symbol 到 NewOrUpdateMarketSymbol 的转换
-impl From<Symbol> for NewOrUpdateMarketSymbol {
-    fn from(s: Symbol) -> Self {
+// 实现从 (Symbol, 市场类型) 到 NewOrUpdateMarketSymbol 的转换
+impl From<(Symbol, MarketType)> for NewOrUpdateMarketSymbol {
+    fn from((s, market_type): (Symbol, MarketType)) -> Self {
+        // 现货没有 pair / contractType 字段
+        let pair = if s.pair.is_empty() {
+            s.symbol.clone()
+        } else {
+            s.pair
+        };
+        let contract_type = if s.contract_type.is_empty() && market_type == MarketType::Spot {
+            "SPOT".to_string()
+        } else {
+            s.contract_type
+        };
+
         NewOrUpdateMarketSymbol {
-            id: encode_market_kline_pk("binance", &s.symbol),
+            id: encode_market_kline_pk("binance", market_type.as_str(), &s.symbol),
             exchange: "binance".to_string(),
+            market_type: market_type.as_str().to_string(),
             symbol: s.symbol,
 
-            pair: s.pair,
-            contract_type: s.contract_type,
+            pair,
+            contract_type,
             delivery_date: s.delivery_date,
             onboard_date: s.onboard_date,
             status: s.status,
@@ -213,14 +235,156 @@ impl From<Symbol> for NewOrUpdateMarketSymbol {
             filters: option_obj_to_value(Some(s.filters)),
             quantity_precision: s.quantity_precision,
             max_move_order_limit: s.max_move_order_limit,
-            permission_sets: option_obj_to_value(Some(s.permission_sets)),
+            permission_sets: s.permission_sets,
         }
     }
 }
+// 实现从 (OkxInstrument, 市场类型) 到 NewOrUpdateMarketSymbol 的转换
+impl From<(OkxInstrument, MarketType)> for NewOrUpdateMarketSymbol {
+    fn from((i, market_type): (OkxInstrument, MarketType)) -> Self {
+        let (base_asset, quote_asset) = i.base_quote();
+        let contract_type = match i.inst_type.as_str() {
+            "SWAP" => "PERPETUAL".to_string(),
+            other => other.to_string(),
+        };
+        let margin_asset = if i.settle_ccy.is_empty() {
+            quote_asset.clone()
+        } else {
+            i.settle_ccy.clone()
+        };
+        let pair = if i.inst_family.is_empty() {
+            i.inst_id.clone()
+        } else {
+            i.inst_family.clone()
+        };
+        let filters = serde_json::json!([{
+            "filterType": "OKX_INSTRUMENT",
+            "tickSize": i.tick_sz,
+            "stepSize": i.lot_sz,
+            "minQty": i.min_sz,
+            "ctVal": i.ct_val,
+            "ctValCcy": i.ct_val_ccy,
+        }]);
+
+        NewOrUpdateMarketSymbol {
+            id: encode_market_kline_pk("okx", market_type.as_str(), &i.inst_id),
+            exchange: "okx".to_string(),
+            market_type: market_type.as_str().to_string(),
+            symbol: i.inst_id,
+
+            pair,
+            contract_type,
+            delivery_date: i.exp_time.parse().unwrap_or(0),
+            onboard_date: i.list_time.parse().unwrap_or(0),
+            status: normalize_symbol_status(&i.state),
+            maint_margin_percent: String::new(),
+            required_margin_percent: String::new(),
+            base_asset,
+            base_asset_precision: decimal_places(&i.lot_sz),
+            quote_asset,
+            margin_asset,
+            price_precision: decimal_places(&i.tick_sz),
+            quote_precision: decimal_places(&i.tick_sz),
+            underlying_type: i.uly,
+            underlying_sub_type: None,
+            trigger_protect: String::new(),
+            liquidation_fee: String::new(),
+            market_take_bound: String::new(),
+            order_types: None,
+            time_in_force: None,
+            filters: Some(filters),
+            quantity_precision: decimal_places(&i.lot_sz),
+            max_move_order_limit: 0,
+            permission_sets: None,
+        }
+    }
+}
+
+// 实现从 (BybitInstrument, 市场类型) 到 NewOrUpdateMarketSymbol 的转换
+impl From<(BybitInstrument, MarketType)> for NewOrUpdateMarketSymbol {
+    fn from((i, market_type): (BybitInstrument, MarketType)) -> Self {
+        let contract_type = match i.contract_type.as_str() {
+            "LinearPerpetual" | "InversePerpetual" => "PERPETUAL".to_string(),
+            "LinearFutures" | "InverseFutures" => "DELIVERY".to_string(),
+            "" => "SPOT".to_string(),
+            other => other.to_uppercase(),
+        };
+        let margin_asset = if i.settle_coin.is_empty() {
+            i.quote_coin.clone()
+        } else {
+            i.settle_coin.clone()
+        };
+        let quantity_step = if i.lot_size_filter.qty_step.is_empty() {
+            &i.lot_size_filter.base_precision
+        } else {
+            &i.lot_size_filter.qty_step
+        };
+        let price_precision = i
+            .price_scale
+            .parse()
+            .unwrap_or_else(|_| decimal_places(&i.price_filter.tick_size));
+        let filters = serde_json::json!([{
+            "filterType": "BYBIT_INSTRUMENT",
+            "tickSize": i.price_filter.tick_size,
+            "stepSize": quantity_step,
+            "minQty": i.lot_size_filter.min_order_qty,
+            "maxQty": i.lot_size_filter.max_order_qty,
+        }]);
+
+        NewOrUpdateMarketSymbol {
+            id: encode_market_kline_pk("bybit", market_type.as_str(), &i.symbol),
+            exchange: "bybit".to_string(),
+            market_type: market_type.as_str().to_string(),
+            pair: i.symbol.clone(),
+            symbol: i.symbol,
+
+            contract_type,
+            delivery_date: i.delivery_time.parse().unwrap_or(0),
+            onboard_date: i.launch_time.parse().unwrap_or(0),
+            status: normalize_symbol_status(&i.status),
+            maint_margin_percent: String::new(),
+            required_margin_percent: String::new(),
+            base_asset: i.base_coin,
+            base_asset_precision: decimal_places(&i.lot_size_filter.base_precision),
+            quote_asset: i.quote_coin,
+            margin_asset,
+            price_precision,
+            quote_precision: decimal_places(&i.lot_size_filter.quote_precision),
+            underlying_type: String::new(),
+            underlying_sub_type: None,
+            trigger_protect: String::new(),
+            liquidation_fee: String::new(),
+            market_take_bound: String::new(),
+            order_types: None,
+            time_in_force: None,
+            filters: Some(filters),
+            quantity_precision: decimal_places(quantity_step),
+            max_move_order_limit: 0,
+            permission_sets: None,
+        }
+    }
+}
+
+/// 统一交易状态：OKX 的 live、Bybit 的 Trading 均映射为 Binance 的 TRADING
+fn normalize_symbol_status(status: &str) -> String {
+    match status {
+        "live" | "Trading" => "TRADING".to_string(),
+        other => other.to_uppercase(),
+    }
+}
+
+/// 由步长字符串计算小数位数，如 "0.001" -> 3，"1" -> 0
+fn decimal_places(step: &str) -> u64 {
+    match step.split_once('.') {
+        Some((_, fraction)) => fraction.trim_end_matches('0').len() as u64,
+        None => 0,
+    }
+}
+
 /// 生成组合主键的 Base64 表示
-pub fn encode_market_kline_pk(exchange: &str, symbol: &str) -> String {
+pub fn encode_market_kline_pk(exchange: &str, market_type: &str, symbol: &str) -> String {
     // 将各字段用分隔符连接
-    let raw = format!("{}|{}", exchange, symbol);
+    let raw = format!("{}|{}|{}", exchange, market_type, symbol);
     // Base64 编码
     base64::encode(raw)
 }
@@ -228,7 +392,10 @@ pub fn encode_market_kline_pk(exchange: &str, symbol: &str) -> String {
 #[derive(Debug, Clone)]
 pub struct MarketSymbolFilter {
     pub exchange: Option<String>,
+    pub market_type: Option<String>,
     pub symbol: Option<String>,
+    pub status: Option<String>,
+    pub quote_assets: Option<Vec<String>>,
     pub page: Option<usize>,
     pub page_size: Option<usize>,
 }
@@ -1,8 +1,11 @@
 use crate::domain::model::AppResult;
 
+pub mod archive_checkpoint_repository;
 pub mod coin_category_repository;
 pub mod coin_data_info_repository;
 pub mod coin_rank_info_repository;
+pub mod kline_gap_repository;
+pub mod kline_quarantine_repository;
 pub mod market_kline_repository;
 pub mod market_symbol_repository;
 
@@ -0,0 +1,72 @@
+use crate::domain::model::archive_checkpoint::{
+    ArchiveCheckpoint, ArchiveCheckpointFilter, NewOrUpdateArchiveCheckpoint,
+};
+use crate::domain::model::{AppError, AppResult, SortOrder};
+use crate::domain::repository::Repository;
+use crate::{impl_full_repository, impl_repository_with_filter};
+use diesel::{MysqlConnection, OptionalExtension, QueryDsl, RunQueryDsl, SelectableHelper};
+
+// archive_checkpoint_repository
+pub struct ArchiveCheckpointRepository<'a> {
+    pub conn: &'a mut MysqlConnection,
+}
+
+impl<'a> ArchiveCheckpointRepository<'a> {
+    pub fn new(conn: &'a mut MysqlConnection) -> Self {
+        Self { conn }
+    }
+}
+
+impl_full_repository!(
+    ArchiveCheckpointRepository,  // Repository struct
+    archive_checkpoint,           // Table name from schema.rs
+    ArchiveCheckpoint,            // Model
+    NewOrUpdateArchiveCheckpoint, // Insert model
+    NewOrUpdateArchiveCheckpoint  // Update model
+);
+
+impl_repository_with_filter!(
+    ArchiveCheckpointRepository,
+    archive_checkpoint,
+    ArchiveCheckpoint,
+    ArchiveCheckpointFilter,
+    @filter_var = filter,
+    {
+        use crate::schema::archive_checkpoint::dsl::*;
+        let mut q = archive_checkpoint.into_boxed();
+
+        if let Some(ref exchange_arg) = filter.exchange {
+            q = q.filter(exchange.eq(exchange_arg));
+        }
+
+        if let Some(ref market_type_arg) = filter.market_type {
+            q = q.filter(market_type.eq(market_type_arg));
+        }
+
+        if let Some(ref symbol_arg) = filter.symbol {
+            q = q.filter(symbol.eq(symbol_arg));
+        }
+
+        if let Some(ref time_frame_arg) = filter.time_frame {
+            q = q.filter(time_frame.eq(time_frame_arg));
+        }
+
+        if let Some(ref direction_arg) = filter.direction {
+            q = q.filter(direction.eq(direction_arg));
+        }
+
+        if let Some(ref status_arg) = filter.status {
+            q = q.filter(status.eq(status_arg));
+        }
+
+        if let Some(order) = &filter.sort_by_window_start {
+            q = {
+                match order {
+                    SortOrder::Asc => q.order(window_start.asc()),
+                    SortOrder::Desc => q.order(window_start.desc()),
+                }
+            };
+        }
+        q
+    }
+);
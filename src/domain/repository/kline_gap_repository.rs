@@ -0,0 +1,70 @@
+use crate::domain::model::kline_gap::{KlineGap, KlineGapFilter, NewOrUpdateKlineGap};
+use crate::domain::model::{AppError, AppResult, SortOrder};
+use crate::domain::repository::Repository;
+use crate::{impl_full_repository, impl_repository_with_filter};
+use diesel::{MysqlConnection, OptionalExtension, QueryDsl, RunQueryDsl, SelectableHelper};
+
+// kline_gap_repository
+pub struct KlineGapRepository<'a> {
+    pub conn: &'a mut MysqlConnection,
+}
+
+impl<'a> KlineGapRepository<'a> {
+    pub fn new(conn: &'a mut MysqlConnection) -> Self {
+        Self { conn }
+    }
+}
+
+impl_full_repository!(
+    KlineGapRepository,  // Repository struct
+    kline_gap,           // Table name from schema.rs
+    KlineGap,            // Model
+    NewOrUpdateKlineGap, // Insert model
+    NewOrUpdateKlineGap  // Update model
+);
+
+impl_repository_with_filter!(
+    KlineGapRepository,
+    kline_gap,
+    KlineGap,
+    KlineGapFilter,
+    @filter_var = filter,
+    {
+        use crate::schema::kline_gap::dsl::*;
+        let mut q = kline_gap.into_boxed();
+
+        if let Some(ref exchange_arg) = filter.exchange {
+            q = q.filter(exchange.eq(exchange_arg));
+        }
+
+        if let Some(ref market_type_arg) = filter.market_type {
+            q = q.filter(market_type.eq(market_type_arg));
+        }
+
+        if let Some(ref symbol_arg) = filter.symbol {
+            q = q.filter(symbol.eq(symbol_arg));
+        }
+
+        if let Some(ref time_frame_arg) = filter.time_frame {
+            q = q.filter(time_frame.eq(time_frame_arg));
+        }
+
+        if let Some(ref store_arg) = filter.store {
+            q = q.filter(store.eq(store_arg));
+        }
+
+        if let Some(ref status_arg) = filter.status {
+            q = q.filter(status.eq(status_arg));
+        }
+
+        if let Some(order) = &filter.sort_by_gap_start {
+            q = {
+                match order {
+                    SortOrder::Asc => q.order(gap_start.asc()),
+                    SortOrder::Desc => q.order(gap_start.desc()),
+                }
+            };
+        }
+        q
+    }
+);
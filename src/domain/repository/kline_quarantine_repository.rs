@@ -0,0 +1,68 @@
+use crate::domain::model::kline_quarantine::{
+    KlineQuarantine, KlineQuarantineFilter, NewOrUpdateKlineQuarantine,
+};
+use crate::domain::model::{AppError, AppResult, SortOrder};
+use crate::domain::repository::Repository;
+use crate::{impl_full_repository, impl_repository_with_filter};
+use diesel::{MysqlConnection, OptionalExtension, QueryDsl, RunQueryDsl, SelectableHelper};
+
+// kline_quarantine_repository
+pub struct KlineQuarantineRepository<'a> {
+    pub conn: &'a mut MysqlConnection,
+}
+
+impl<'a> KlineQuarantineRepository<'a> {
+    pub fn new(conn: &'a mut MysqlConnection) -> Self {
+        Self { conn }
+    }
+}
+
+impl_full_repository!(
+    KlineQuarantineRepository,  // Repository struct
+    kline_quarantine,           // Table name from schema.rs
+    KlineQuarantine,            // Model
+    NewOrUpdateKlineQuarantine, // Insert model
+    NewOrUpdateKlineQuarantine  // Update model
+);
+
+impl_repository_with_filter!(
+    KlineQuarantineRepository,
+    kline_quarantine,
+    KlineQuarantine,
+    KlineQuarantineFilter,
+    @filter_var = filter,
+    {
+        use crate::schema::kline_quarantine::dsl::*;
+        let mut q = kline_quarantine.into_boxed();
+
+        if let Some(ref exchange_arg) = filter.exchange {
+            q = q.filter(exchange.eq(exchange_arg));
+        }
+
+        if let Some(ref market_type_arg) = filter.market_type {
+            q = q.filter(market_type.eq(market_type_arg));
+        }
+
+        if let Some(ref symbol_arg) = filter.symbol {
+            q = q.filter(symbol.eq(symbol_arg));
+        }
+
+        if let Some(ref time_frame_arg) = filter.time_frame {
+            q = q.filter(time_frame.eq(time_frame_arg));
+        }
+
+        if let Some(ref rule_arg) = filter.rule {
+            q = q.filter(rule.eq(rule_arg));
+        }
+
+        if let Some(order) = &filter.sort_by_close_time {
+            q = {
+                match order {
+                    SortOrder::Asc => q.order(close_time.asc()),
+                    SortOrder::Desc => q.order(close_time.desc()),
+                }
+            };
+        }
+        q
+    }
+);
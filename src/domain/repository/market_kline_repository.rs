@@ -38,6 +38,10 @@ impl_repository_with_filter!(
             q = q.filter(exchange.eq(exchange_arg));
         }
 
+        if let Some(ref market_type_arg) = filter.market_type {
+            q = q.filter(market_type.eq(market_type_arg));
+        }
+
         if let Some(ref symbol_arg) = filter.symbol {
             q = q.filter(symbol.eq(symbol_arg));
         }
@@ -46,6 +50,14 @@ impl_repository_with_filter!(
             q = q.filter(time_frame.eq(time_frame_arg));
         }
 
+        if let Some(start_arg) = filter.start_close_time {
+            q = q.filter(close_time.ge(start_arg));
+        }
+
+        if let Some(end_arg) = filter.end_close_time {
+            q = q.filter(close_time.le(end_arg));
+        }
+
          if let Some(order) = &filter.sort_by_close_time {
             q = {
                 match order {
@@ -40,9 +40,21 @@ impl_repository_with_filter!(
             q = q.filter(exchange.eq(exchange_arg));
         }
 
+        if let Some(ref market_type_arg) = filter.market_type {
+            q = q.filter(market_type.eq(market_type_arg));
+        }
+
         if let Some(ref symbol_arg) = filter.symbol {
             q = q.filter(symbol.eq(symbol_arg));
         }
+
+        if let Some(ref status_arg) = filter.status {
+            q = q.filter(status.eq(status_arg));
+        }
+
+        if let Some(ref quote_assets_arg) = filter.quote_assets {
+            q = q.filter(quote_asset.eq_any(quote_assets_arg));
+        }
         q
     }
 );
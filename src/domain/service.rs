@@ -1,5 +1,8 @@
+pub mod archive_checkpoint_service;
 pub mod coin_category_service;
 pub mod coin_data_info_service;
 pub mod coin_rank_info_service;
+pub mod kline_gap_service;
+pub mod kline_quarantine_service;
 pub mod market_kline_service;
 pub mod market_symbol_service;
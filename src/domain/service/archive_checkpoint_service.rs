@@ -0,0 +1,270 @@
+use crate::domain::model::archive_checkpoint::{
+    ArchiveCheckpoint, ArchiveCheckpointSummary, NewOrUpdateArchiveCheckpoint,
+    CHECKPOINT_STATUS_FAILED, CHECKPOINT_STATUS_FETCHED, CHECKPOINT_STATUS_FLUSHED,
+};
+use crate::domain::model::AppResult;
+use crate::domain::repository::archive_checkpoint_repository::ArchiveCheckpointRepository;
+use crate::domain::repository::Repository;
+use crate::domain::repository::UpdatableRepository;
+use crate::domain::repository::{FilterableRepository, InsertableRepository};
+use crate::impl_full_service;
+use crate::schema::archive_checkpoint;
+use chrono::{NaiveDateTime, Utc};
+use diesel::{Connection, MysqlConnection, RunQueryDsl};
+use std::collections::BTreeMap;
+use tracing::instrument;
+
+impl_full_service!(
+    ArchiveCheckpointService,
+    ArchiveCheckpointRepository,
+    ArchiveCheckpoint,
+    NewOrUpdateArchiveCheckpoint,
+    NewOrUpdateArchiveCheckpoint
+);
+
+impl<'a> ArchiveCheckpointService<'a> {
+    /// 登记新规划的窗口，已存在的窗口保持原有状态与失败次数
+    #[instrument(name = "register_archive_checkpoints", skip(rows))]
+    pub fn register_windows(
+        &mut self,
+        rows: Vec<NewOrUpdateArchiveCheckpoint>,
+    ) -> anyhow::Result<()> {
+        self.repo.conn.transaction(|conn| {
+            for row in &rows {
+                diesel::insert_or_ignore_into(archive_checkpoint::table)
+                    .values(row)
+                    .execute(conn)?;
+            }
+            Ok(())
+        })
+    }
+
+    /// 查询可续跑的窗口：未写库且失败次数未达上限，按窗口起点升序
+    pub fn list_resumable(
+        &mut self,
+        exchange_val: &str,
+        market_type_val: &str,
+        symbol_val: &str,
+        time_frame_val: &str,
+        direction_val: &str,
+        max_attempts: u32,
+    ) -> AppResult<Vec<ArchiveCheckpoint>> {
+        use crate::schema::archive_checkpoint::dsl::*;
+        use diesel::prelude::*;
+
+        Ok(archive_checkpoint
+            .filter(exchange.eq(exchange_val))
+            .filter(market_type.eq(market_type_val))
+            .filter(symbol.eq(symbol_val))
+            .filter(time_frame.eq(time_frame_val))
+            .filter(direction.eq(direction_val))
+            .filter(status.ne(CHECKPOINT_STATUS_FLUSHED))
+            .filter(attempts.lt(max_attempts))
+            .order(window_start.asc())
+            .select(ArchiveCheckpoint::as_select())
+            .load(self.repo.conn)?)
+    }
+
+    /// 已登记窗口覆盖的范围 (最早起点, 最晚终点)
+    pub fn window_bounds(
+        &mut self,
+        exchange_val: &str,
+        market_type_val: &str,
+        symbol_val: &str,
+        time_frame_val: &str,
+        direction_val: &str,
+    ) -> AppResult<Option<(i64, i64)>> {
+        use crate::schema::archive_checkpoint::dsl::*;
+        use diesel::dsl::{max, min};
+        use diesel::prelude::*;
+
+        let (start, end): (Option<i64>, Option<i64>) = archive_checkpoint
+            .filter(exchange.eq(exchange_val))
+            .filter(market_type.eq(market_type_val))
+            .filter(symbol.eq(symbol_val))
+            .filter(time_frame.eq(time_frame_val))
+            .filter(direction.eq(direction_val))
+            .select((min(window_start), max(window_end)))
+            .first(self.repo.conn)?;
+
+        Ok(start.zip(end))
+    }
+
+    /// 标记窗口已拉取，已写库的窗口不回退状态
+    pub fn mark_fetched(&mut self, id_val: &str, count: i64) -> AppResult<()> {
+        use crate::schema::archive_checkpoint::dsl::*;
+        use diesel::prelude::*;
+
+        diesel::update(
+            archive_checkpoint
+                .filter(id.eq(id_val))
+                .filter(status.ne(CHECKPOINT_STATUS_FLUSHED)),
+        )
+        .set((
+            status.eq(CHECKPOINT_STATUS_FETCHED),
+            kline_count.eq(count),
+            updated_at.eq(Utc::now().naive_utc()),
+        ))
+        .execute(self.repo.conn)?;
+        Ok(())
+    }
+
+    /// 标记窗口已完成写库
+    pub fn mark_flushed(&mut self, ids: &[String]) -> AppResult<()> {
+        use crate::schema::archive_checkpoint::dsl::*;
+        use diesel::prelude::*;
+
+        if ids.is_empty() {
+            return Ok(());
+        }
+        diesel::update(archive_checkpoint.filter(id.eq_any(ids)))
+            .set((
+                status.eq(CHECKPOINT_STATUS_FLUSHED),
+                last_error.eq(None::<String>),
+                updated_at.eq(Utc::now().naive_utc()),
+            ))
+            .execute(self.repo.conn)?;
+        Ok(())
+    }
+
+    /// 标记窗口失败并累加失败次数
+    pub fn mark_failed(&mut self, ids: &[String], error: &str) -> AppResult<()> {
+        use crate::schema::archive_checkpoint::dsl::*;
+        use diesel::prelude::*;
+
+        if ids.is_empty() {
+            return Ok(());
+        }
+        let error: String = error.chars().take(255).collect();
+        diesel::update(archive_checkpoint.filter(id.eq_any(ids)))
+            .set((
+                status.eq(CHECKPOINT_STATUS_FAILED),
+                attempts.eq(attempts + 1),
+                last_error.eq(Some(error)),
+                updated_at.eq(Utc::now().naive_utc()),
+            ))
+            .execute(self.repo.conn)?;
+        Ok(())
+    }
+
+    /// 标记窗口失败且不再重试：失败次数直接置为上限，不会被断点续传选中
+    pub fn mark_abandoned(
+        &mut self,
+        ids: &[String],
+        error: &str,
+        max_attempts: u32,
+    ) -> AppResult<()> {
+        use crate::schema::archive_checkpoint::dsl::*;
+        use diesel::prelude::*;
+
+        if ids.is_empty() {
+            return Ok(());
+        }
+        let error: String = error.chars().take(255).collect();
+        diesel::update(archive_checkpoint.filter(id.eq_any(ids)))
+            .set((
+                status.eq(CHECKPOINT_STATUS_FAILED),
+                attempts.eq(max_attempts),
+                last_error.eq(Some(error)),
+                updated_at.eq(Utc::now().naive_utc()),
+            ))
+            .execute(self.repo.conn)?;
+        Ok(())
+    }
+
+    /// 按归档任务统计各状态窗口数量，可按交易所与交易对过滤
+    pub fn outstanding_summary(
+        &mut self,
+        exchange_val: Option<&str>,
+        symbol_val: Option<&str>,
+    ) -> AppResult<Vec<ArchiveCheckpointSummary>> {
+        use crate::schema::archive_checkpoint::dsl::*;
+        use diesel::dsl::{count_star, max, min};
+        use diesel::prelude::*;
+
+        let mut q = archive_checkpoint.into_boxed();
+        if let Some(exchange_arg) = exchange_val {
+            q = q.filter(exchange.eq(exchange_arg.to_string()));
+        }
+        if let Some(symbol_arg) = symbol_val {
+            q = q.filter(symbol.eq(symbol_arg.to_string()));
+        }
+
+        let rows: Vec<(
+            String,
+            String,
+            String,
+            String,
+            String,
+            String,
+            i64,
+            Option<i64>,
+            Option<NaiveDateTime>,
+        )> = q
+            .group_by((exchange, market_type, symbol, time_frame, direction, status))
+            .select((
+                exchange,
+                market_type,
+                symbol,
+                time_frame,
+                direction,
+                status,
+                count_star(),
+                min(window_start),
+                max(updated_at),
+            ))
+            .load(self.repo.conn)?;
+
+        let mut summaries: BTreeMap<
+            (String, String, String, String, String),
+            ArchiveCheckpointSummary,
+        > = BTreeMap::new();
+
+        for (
+            exchange_val,
+            market_type_val,
+            symbol_val,
+            time_frame_val,
+            direction_val,
+            status_val,
+            count,
+            first_start,
+            last,
+        ) in rows
+        {
+            let key = (
+                exchange_val.clone(),
+                market_type_val.clone(),
+                symbol_val.clone(),
+                time_frame_val.clone(),
+                direction_val.clone(),
+            );
+            let summary = summaries
+                .entry(key)
+                .or_insert_with(|| ArchiveCheckpointSummary {
+                    exchange: exchange_val,
+                    market_type: market_type_val,
+                    symbol: symbol_val,
+                    time_frame: time_frame_val,
+                    direction: direction_val,
+                    statuses: BTreeMap::new(),
+                    outstanding: 0,
+                    oldest_outstanding_start: None,
+                    last_updated_at: None,
+                });
+
+            if status_val != CHECKPOINT_STATUS_FLUSHED {
+                summary.outstanding += count;
+                summary.oldest_outstanding_start =
+                    match (summary.oldest_outstanding_start, first_start) {
+                        (Some(current), Some(start)) => Some(current.min(start)),
+                        (current, start) => current.or(start),
+                    };
+            }
+            summary.last_updated_at = summary.last_updated_at.max(last);
+            *summary.statuses.entry(status_val).or_insert(0) += count;
+        }
+
+        Ok(summaries.into_values().collect())
+    }
+}
@@ -0,0 +1,158 @@
+use crate::domain::model::kline_gap::{
+    KlineGap, KlineGapFilter, KlineGapSummary, NewOrUpdateKlineGap, GAP_STATUS_FILLED,
+    GAP_STATUS_FOUND, GAP_STATUS_UNFILLABLE,
+};
+use crate::domain::model::{AppResult, PageResult};
+use crate::domain::repository::kline_gap_repository::KlineGapRepository;
+use crate::domain::repository::Repository;
+use crate::domain::repository::UpdatableRepository;
+use crate::domain::repository::{FilterableRepository, InsertableRepository};
+use crate::impl_full_service;
+use crate::schema::kline_gap;
+use diesel::{Connection, MysqlConnection, RunQueryDsl};
+use std::collections::BTreeMap;
+use tracing::instrument;
+
+impl_full_service!(
+    KlineGapService,
+    KlineGapRepository,
+    KlineGap,
+    NewOrUpdateKlineGap,
+    NewOrUpdateKlineGap
+);
+
+impl<'a> KlineGapService<'a> {
+    #[instrument(name = "save_kline_gaps")]
+    pub fn save_kline_gaps(&mut self, gaps: Vec<NewOrUpdateKlineGap>) -> anyhow::Result<()> {
+        insert_or_update_kline_gaps(&mut self.repo.conn, gaps)
+    }
+
+    pub fn query_page_with_total(
+        &mut self,
+        filter: KlineGapFilter,
+        page: i64,
+        per_page: i64,
+    ) -> AppResult<PageResult<KlineGap>> {
+        let data = self.repo.filter_paginated(&filter, page, per_page)?;
+        let total = self.repo.count_filtered(&filter)?;
+        Ok(PageResult {
+            data,
+            total,
+            page,
+            per_page,
+        })
+    }
+
+    /// 查询指定市场、存储中待修复的缺口
+    pub fn list_open_gaps(
+        &mut self,
+        exchange_val: &str,
+        market_type_val: &str,
+        symbol_val: &str,
+        time_frame_val: &str,
+        store_val: &str,
+    ) -> AppResult<Vec<KlineGap>> {
+        let filter = KlineGapFilter {
+            exchange: Some(exchange_val.to_string()),
+            market_type: Some(market_type_val.to_string()),
+            symbol: Some(symbol_val.to_string()),
+            time_frame: Some(time_frame_val.to_string()),
+            store: Some(store_val.to_string()),
+            status: Some(GAP_STATUS_FOUND.to_string()),
+            sort_by_gap_start: None,
+            page: None,
+            page_size: None,
+        };
+        self.repo.filter_paginated(&filter, 0, 10_000)
+    }
+
+    /// 按市场统计缺口状态，用于数据完整度报告，可按交易所与交易对过滤
+    pub fn completeness_summary(
+        &mut self,
+        exchange_val: Option<&str>,
+        symbol_val: Option<&str>,
+    ) -> AppResult<Vec<KlineGapSummary>> {
+        use crate::schema::kline_gap::dsl::*;
+        use diesel::dsl::count_star;
+        use diesel::prelude::*;
+
+        let mut q = kline_gap.into_boxed();
+        if let Some(exchange_arg) = exchange_val {
+            q = q.filter(exchange.eq(exchange_arg.to_string()));
+        }
+        if let Some(symbol_arg) = symbol_val {
+            q = q.filter(symbol.eq(symbol_arg.to_string()));
+        }
+
+        let rows: Vec<(String, String, String, String, String, String, i64)> = q
+            .group_by((exchange, market_type, symbol, time_frame, store, status))
+            .select((
+                exchange,
+                market_type,
+                symbol,
+                time_frame,
+                store,
+                status,
+                count_star(),
+            ))
+            .load(self.repo.conn)?;
+
+        let mut summaries: BTreeMap<(String, String, String, String, String), KlineGapSummary> =
+            BTreeMap::new();
+
+        for (
+            exchange_val,
+            market_type_val,
+            symbol_val,
+            time_frame_val,
+            store_val,
+            status_val,
+            count,
+        ) in rows
+        {
+            let key = (
+                exchange_val.clone(),
+                market_type_val.clone(),
+                symbol_val.clone(),
+                time_frame_val.clone(),
+                store_val.clone(),
+            );
+            let summary = summaries.entry(key).or_insert_with(|| KlineGapSummary {
+                exchange: exchange_val,
+                market_type: market_type_val,
+                symbol: symbol_val,
+                time_frame: time_frame_val,
+                store: store_val,
+                found: 0,
+                filled: 0,
+                unfillable: 0,
+            });
+
+            match status_val.as_str() {
+                GAP_STATUS_FOUND => summary.found += count,
+                GAP_STATUS_FILLED => summary.filled += count,
+                GAP_STATUS_UNFILLABLE => summary.unfillable += count,
+                _ => {}
+            }
+        }
+
+        Ok(summaries.into_values().collect())
+    }
+}
+
+fn insert_or_update_kline_gaps(
+    conn: &mut MysqlConnection,
+    gaps: Vec<NewOrUpdateKlineGap>,
+) -> anyhow::Result<()> {
+    conn.transaction(|conn| {
+        for gap in &gaps {
+            diesel::insert_into(kline_gap::table)
+                .values(gap)
+                .on_conflict(diesel::dsl::DuplicatedKeys)
+                .do_update()
+                .set(gap)
+                .execute(conn)?;
+        }
+        Ok(())
+    })
+}
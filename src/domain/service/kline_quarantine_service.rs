@@ -0,0 +1,134 @@
+use crate::domain::model::kline_quarantine::{
+    KlineQualitySummary, KlineQuarantine, KlineQuarantineFilter, NewOrUpdateKlineQuarantine,
+};
+use crate::domain::model::{AppResult, PageResult};
+use crate::domain::repository::kline_quarantine_repository::KlineQuarantineRepository;
+use crate::domain::repository::Repository;
+use crate::domain::repository::UpdatableRepository;
+use crate::domain::repository::{FilterableRepository, InsertableRepository};
+use crate::impl_full_service;
+use crate::schema::kline_quarantine;
+use chrono::NaiveDateTime;
+use diesel::{Connection, MysqlConnection, RunQueryDsl};
+use std::collections::BTreeMap;
+use tracing::instrument;
+
+impl_full_service!(
+    KlineQuarantineService,
+    KlineQuarantineRepository,
+    KlineQuarantine,
+    NewOrUpdateKlineQuarantine,
+    NewOrUpdateKlineQuarantine
+);
+
+impl<'a> KlineQuarantineService<'a> {
+    #[instrument(name = "save_quarantined_klines", skip(rows))]
+    pub fn save_quarantined(
+        &mut self,
+        rows: Vec<NewOrUpdateKlineQuarantine>,
+    ) -> anyhow::Result<()> {
+        insert_or_update_quarantine(&mut self.repo.conn, rows)
+    }
+
+    pub fn query_page_with_total(
+        &mut self,
+        filter: KlineQuarantineFilter,
+        page: i64,
+        per_page: i64,
+    ) -> AppResult<PageResult<KlineQuarantine>> {
+        let data = self.repo.filter_paginated(&filter, page, per_page)?;
+        let total = self.repo.count_filtered(&filter)?;
+        Ok(PageResult {
+            data,
+            total,
+            page,
+            per_page,
+        })
+    }
+
+    /// 按市场、规则统计隔离数量，可按交易所与交易对过滤
+    pub fn quality_summary(
+        &mut self,
+        exchange_val: Option<&str>,
+        symbol_val: Option<&str>,
+    ) -> AppResult<Vec<KlineQualitySummary>> {
+        use crate::schema::kline_quarantine::dsl::*;
+        use diesel::dsl::{count_star, max};
+        use diesel::prelude::*;
+
+        let mut q = kline_quarantine.into_boxed();
+        if let Some(exchange_arg) = exchange_val {
+            q = q.filter(exchange.eq(exchange_arg.to_string()));
+        }
+        if let Some(symbol_arg) = symbol_val {
+            q = q.filter(symbol.eq(symbol_arg.to_string()));
+        }
+
+        let rows: Vec<(
+            String,
+            String,
+            String,
+            String,
+            String,
+            i64,
+            Option<NaiveDateTime>,
+        )> = q
+            .group_by((exchange, market_type, symbol, time_frame, rule))
+            .select((
+                exchange,
+                market_type,
+                symbol,
+                time_frame,
+                rule,
+                count_star(),
+                max(detected_at),
+            ))
+            .load(self.repo.conn)?;
+
+        let mut summaries: BTreeMap<(String, String, String, String), KlineQualitySummary> =
+            BTreeMap::new();
+
+        for (exchange_val, market_type_val, symbol_val, time_frame_val, rule_val, count, last) in
+            rows
+        {
+            let key = (
+                exchange_val.clone(),
+                market_type_val.clone(),
+                symbol_val.clone(),
+                time_frame_val.clone(),
+            );
+            let summary = summaries.entry(key).or_insert_with(|| KlineQualitySummary {
+                exchange: exchange_val,
+                market_type: market_type_val,
+                symbol: symbol_val,
+                time_frame: time_frame_val,
+                total: 0,
+                rules: BTreeMap::new(),
+                last_detected_at: None,
+            });
+
+            summary.total += count;
+            *summary.rules.entry(rule_val).or_insert(0) += count;
+            summary.last_detected_at = summary.last_detected_at.max(last);
+        }
+
+        Ok(summaries.into_values().collect())
+    }
+}
+
+fn insert_or_update_quarantine(
+    conn: &mut MysqlConnection,
+    rows: Vec<NewOrUpdateKlineQuarantine>,
+) -> anyhow::Result<()> {
+    conn.transaction(|conn| {
+        for row in &rows {
+            diesel::insert_into(kline_quarantine::table)
+                .values(row)
+                .on_conflict(diesel::dsl::DuplicatedKeys)
+                .do_update()
+                .set(row)
+                .execute(conn)?;
+        }
+        Ok(())
+    })
+}
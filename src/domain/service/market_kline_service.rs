@@ -1,5 +1,5 @@
 use crate::domain::model::market_kline::{MarketKline, MarketKlineFilter, NewOrUpdateMarketKline};
-use crate::domain::model::{AppResult, PageResult};
+use crate::domain::model::{AppResult, PageResult, SortOrder};
 use crate::domain::repository::market_kline_repository::MarketKlineRepository;
 use crate::domain::repository::Repository;
 use crate::domain::repository::UpdatableRepository;
@@ -47,10 +47,11 @@ impl<'a> MarketKlineService<'a> {
         Ok(())
     }
 
-    /// 查询指定交易所、币对、周期的最早和最晚时间
+    /// 查询指定交易所、市场类型、币对、周期的最早和最晚时间
     pub async fn get_mima_time(
         &mut self,
         exchange_val: &str,
+        market_type_val: &str,
         symbol_val: &str,
         time_frame_val: &str,
     ) -> Result<Option<MinMaxCloseTime>, diesel::result::Error> {
@@ -60,6 +61,7 @@ impl<'a> MarketKlineService<'a> {
 
         let result: Option<(Option<i64>, Option<i64>)> = market_kline
             .filter(exchange.eq(exchange_val))
+            .filter(market_type.eq(market_type_val))
             .filter(symbol.eq(symbol_val))
             .filter(time_frame.eq(time_frame_val))
             .select((min(close_time), max(close_time)))
@@ -84,6 +86,173 @@ impl<'a> MarketKlineService<'a> {
             None => Ok(None),
         }
     }
+
+    /// 查询指定时间范围内（含边界）已存储的 close_time，按升序返回
+    pub fn list_close_times(
+        &mut self,
+        exchange_val: &str,
+        market_type_val: &str,
+        symbol_val: &str,
+        time_frame_val: &str,
+        start: i64,
+        end: i64,
+    ) -> Result<Vec<i64>, diesel::result::Error> {
+        use crate::schema::market_kline::dsl::*;
+        use diesel::prelude::*;
+
+        market_kline
+            .filter(exchange.eq(exchange_val))
+            .filter(market_type.eq(market_type_val))
+            .filter(symbol.eq(symbol_val))
+            .filter(time_frame.eq(time_frame_val))
+            .filter(close_time.between(start, end))
+            .select(close_time)
+            .order(close_time.asc())
+            .load::<i64>(self.repo.conn)
+    }
+
+    /// 统计指定时间范围内（含边界）已存储的K线数量
+    pub fn count_close_times(
+        &mut self,
+        exchange_val: &str,
+        market_type_val: &str,
+        symbol_val: &str,
+        time_frame_val: &str,
+        start: i64,
+        end: i64,
+    ) -> Result<i64, diesel::result::Error> {
+        use crate::schema::market_kline::dsl::*;
+        use diesel::prelude::*;
+
+        market_kline
+            .filter(exchange.eq(exchange_val))
+            .filter(market_type.eq(market_type_val))
+            .filter(symbol.eq(symbol_val))
+            .filter(time_frame.eq(time_frame_val))
+            .filter(close_time.between(start, end))
+            .count()
+            .get_result::<i64>(self.repo.conn)
+    }
+
+    /// 查询开盘时间在 [start_open, end_open) 内的K线，按开盘时间升序返回
+    pub fn list_by_open_time(
+        &mut self,
+        exchange_val: &str,
+        market_type_val: &str,
+        symbol_val: &str,
+        time_frame_val: &str,
+        start_open: i64,
+        end_open: i64,
+    ) -> Result<Vec<MarketKline>, diesel::result::Error> {
+        use crate::schema::market_kline::dsl::*;
+        use diesel::prelude::*;
+
+        market_kline
+            .filter(exchange.eq(exchange_val))
+            .filter(market_type.eq(market_type_val))
+            .filter(symbol.eq(symbol_val))
+            .filter(time_frame.eq(time_frame_val))
+            .filter(open_time.ge(start_open))
+            .filter(open_time.lt(end_open))
+            .order(open_time.asc())
+            .select(MarketKline::as_select())
+            .load::<MarketKline>(self.repo.conn)
+    }
+
+    /// 按收盘时间查询 [start, end]（含边界）内的K线，排序取 `filter.sort_by_close_time`（默认升序），最多 `limit` 条
+    ///
+    /// 仅使用过滤条件中的 exchange / market_type / symbol / time_frame
+    pub fn list_by_close_time(
+        &mut self,
+        filter: &MarketKlineFilter,
+        start: i64,
+        end: i64,
+        limit: i64,
+    ) -> Result<Vec<MarketKline>, diesel::result::Error> {
+        use crate::schema::market_kline::dsl::*;
+        use diesel::prelude::*;
+
+        let mut q = market_kline
+            .filter(close_time.between(start, end))
+            .into_boxed();
+        if let Some(ref v) = filter.exchange {
+            q = q.filter(exchange.eq(v));
+        }
+        if let Some(ref v) = filter.market_type {
+            q = q.filter(market_type.eq(v));
+        }
+        if let Some(ref v) = filter.symbol {
+            q = q.filter(symbol.eq(v));
+        }
+        if let Some(ref v) = filter.time_frame {
+            q = q.filter(time_frame.eq(v));
+        }
+        q = match filter.sort_by_close_time {
+            Some(SortOrder::Desc) => q.order(close_time.desc()),
+            _ => q.order(close_time.asc()),
+        };
+
+        q.limit(limit)
+            .select(MarketKline::as_select())
+            .load::<MarketKline>(self.repo.conn)
+    }
+
+    /// 存在收盘时间早于 `before` 的K线的序列 (exchange, market_type, symbol, time_frame)
+    pub fn list_series_closed_before(
+        &mut self,
+        before: i64,
+    ) -> Result<Vec<(String, String, String, String)>, diesel::result::Error> {
+        use crate::schema::market_kline::dsl::*;
+        use diesel::prelude::*;
+
+        market_kline
+            .filter(close_time.lt(before))
+            .select((exchange, market_type, symbol, time_frame))
+            .distinct()
+            .load(self.repo.conn)
+    }
+
+    /// 开盘时间不早于 `from` 且收盘时间早于 `before` 的最早开盘时间
+    pub fn next_open_time_closed_before(
+        &mut self,
+        exchange_val: &str,
+        market_type_val: &str,
+        symbol_val: &str,
+        time_frame_val: &str,
+        from: i64,
+        before: i64,
+    ) -> Result<Option<i64>, diesel::result::Error> {
+        use crate::schema::market_kline::dsl::*;
+        use diesel::dsl::min;
+        use diesel::prelude::*;
+
+        market_kline
+            .filter(exchange.eq(exchange_val))
+            .filter(market_type.eq(market_type_val))
+            .filter(symbol.eq(symbol_val))
+            .filter(time_frame.eq(time_frame_val))
+            .filter(open_time.ge(from))
+            .filter(close_time.lt(before))
+            .select(min(open_time))
+            .first::<Option<i64>>(self.repo.conn)
+    }
+
+    /// 按主键批量删除，返回删除行数
+    pub fn delete_by_ids(&mut self, ids: &[String]) -> Result<usize, diesel::result::Error> {
+        use crate::schema::market_kline::dsl::*;
+        use diesel::prelude::*;
+
+        if ids.is_empty() {
+            return Ok(0);
+        }
+        self.repo.conn.transaction(|conn| {
+            let mut deleted = 0;
+            for chunk in ids.chunks(1000) {
+                deleted += diesel::delete(market_kline.filter(id.eq_any(chunk))).execute(conn)?;
+            }
+            Ok(deleted)
+        })
+    }
 }
 
 fn insert_or_update_market_klines(
@@ -1,4 +1,3 @@
-use crate::common::VecConvert;
 use crate::domain::model::market_symbol::{
     MarketSymbol, MarketSymbolFilter, NewOrUpdateMarketSymbol,
 };
@@ -9,9 +8,12 @@ use crate::domain::repository::UpdatableRepository;
 use crate::domain::repository::{FilterableRepository, InsertableRepository};
 use crate::impl_full_service;
 use crate::infra::external::binance::DefaultBinanceExchange;
+use crate::infra::external::bybit::DefaultBybitExchange;
+use crate::infra::external::okx::DefaultOkxExchange;
+use crate::model::market_type::MarketType;
 use crate::schema::market_symbol;
 use diesel::{Connection, IntoSql, MysqlConnection, RunQueryDsl};
-use tracing::instrument;
+use tracing::{instrument, warn};
 
 impl_full_service!(
     MarketSymbolService,
@@ -24,7 +26,9 @@ impl_full_service!(
 impl<'a> MarketSymbolService<'a> {
     #[instrument(name = "save_exchange_symbol_info")]
     pub async fn save_exchange_symbol_info(&mut self) -> anyhow::Result<()> {
-        let list = fetch_exchange_symbol_data().await;
+        let mut list = fetch_exchange_symbol_data().await;
+        list.extend(fetch_okx_symbol_data().await);
+        list.extend(fetch_bybit_symbol_data().await);
         insert_or_update_market_symbols(&mut self.repo.conn, list)?;
         Ok(())
     }
@@ -46,15 +50,56 @@ impl<'a> MarketSymbolService<'a> {
     }
 }
 
-/// 从 交易所(币安) 获取币种数据
+/// 从 交易所(币安) 获取现货、U本位、币本位的币种数据
 async fn fetch_exchange_symbol_data() -> Vec<NewOrUpdateMarketSymbol> {
-    let dbe = DefaultBinanceExchange::default();
-    if let Some(symbols) = dbe.get_symbols().await {
-        symbols.convert_vec()
-    } else {
-        // 处理 None 的情况
-        Vec::new()
+    let mut list = Vec::new();
+    for market_type in MarketType::ALL {
+        let dbe = DefaultBinanceExchange::for_market(market_type);
+        // 单个市场失败不影响其它市场
+        match dbe.get_symbols().await {
+            Ok(symbols) => list.extend(
+                symbols
+                    .into_iter()
+                    .map(|s| NewOrUpdateMarketSymbol::from((s, market_type))),
+            ),
+            Err(e) => warn!(?e, "Failed to fetch Binance {} symbols", market_type),
+        }
+    }
+    list
+}
+
+/// 从 OKX 获取现货与永续合约交易产品
+async fn fetch_okx_symbol_data() -> Vec<NewOrUpdateMarketSymbol> {
+    let okx = DefaultOkxExchange::default();
+    let mut list = Vec::new();
+    for market_type in MarketType::ALL {
+        match okx.get_instruments(market_type).await {
+            Ok(instruments) => list.extend(
+                instruments
+                    .into_iter()
+                    .map(|i| NewOrUpdateMarketSymbol::from((i, market_type))),
+            ),
+            Err(e) => warn!(?e, "Failed to fetch OKX {} instruments", market_type),
+        }
+    }
+    list
+}
+
+/// 从 Bybit 获取 spot / linear / inverse 交易对
+async fn fetch_bybit_symbol_data() -> Vec<NewOrUpdateMarketSymbol> {
+    let bybit = DefaultBybitExchange::default();
+    let mut list = Vec::new();
+    for market_type in MarketType::ALL {
+        match bybit.get_instruments(market_type).await {
+            Ok(instruments) => list.extend(
+                instruments
+                    .into_iter()
+                    .map(|i| NewOrUpdateMarketSymbol::from((i, market_type))),
+            ),
+            Err(e) => warn!(?e, "Failed to fetch Bybit {} instruments", market_type),
+        }
     }
+    list
 }
 
 fn insert_or_update_market_symbols(
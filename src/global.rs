@@ -1,10 +1,16 @@
+use crate::collector::archive::backfill::BackfillManager;
+use crate::collector::archive::dispatcher::DispatchStats;
 use crate::collector::archive::kline_buffer::KlineBuffer;
+use crate::collector::archive::validate::KlineValidator;
+use crate::collector::stream::live::{KlineHub, LiveConfig};
+use crate::common::shutdown::Shutdown;
 use crate::common::utils::{get_env_bool, make_db, make_kv_store, must_get_env};
 use crate::infra::cache::flush_controller::FlushController;
 use crate::infra::cache::kv_store::RedisKVStore;
 use crate::infra::db::ckdb::ClickhouseDb;
 use crate::infra::db::mysql::{make_mysql_pool, MySqlPool};
 use crate::infra::external::rate_limiter::binance_limiter::BinanceLimiter;
+use crate::infra::external::rate_limiter::{LimiterBackend, RateBudget};
 use once_cell::sync::OnceCell;
 use std::sync::Arc;
 
@@ -14,7 +20,14 @@ pub static KV_STORE: OnceCell<Arc<RedisKVStore>> = OnceCell::new();
 pub static MYSQL_POOL: OnceCell<Arc<MySqlPool>> = OnceCell::new();
 pub static FLUSH_CONTROLLER: OnceCell<Arc<FlushController>> = OnceCell::new();
 pub static FLUSH_BUFFER: OnceCell<Arc<KlineBuffer>> = OnceCell::new();
+pub static KLINE_VALIDATOR: OnceCell<Arc<KlineValidator>> = OnceCell::new();
 pub static BINANCE_LIMITER: OnceCell<Arc<BinanceLimiter>> = OnceCell::new();
+pub static OKX_LIMITER: OnceCell<Arc<dyn RateBudget>> = OnceCell::new();
+pub static BYBIT_LIMITER: OnceCell<Arc<dyn RateBudget>> = OnceCell::new();
+pub static SHUTDOWN: OnceCell<Arc<Shutdown>> = OnceCell::new();
+pub static BACKFILL_MANAGER: OnceCell<Arc<BackfillManager>> = OnceCell::new();
+pub static DISPATCH_STATS: OnceCell<Arc<DispatchStats>> = OnceCell::new();
+pub static KLINE_HUB: OnceCell<Arc<KlineHub>> = OnceCell::new();
 
 pub async fn init_global_services() {
     // 控制 ClickHouse 初始化
@@ -38,7 +51,15 @@ pub async fn init_global_services() {
 
     let flush_buffer = Arc::new(KlineBuffer::new());
 
-    let binance_limiter = Arc::new(BinanceLimiter::new());
+    // 限流预算：RATE_LIMITER_BACKEND=redis 时同一出口 IP 上的多个实例共享
+    let limiter_backend = LimiterBackend::from_env(redis_store.clone());
+    let binance_limiter = Arc::new(BinanceLimiter::with_backend(&limiter_backend));
+    // OKX 行情接口：20 次 / 2 秒（按 IP）
+    let okx_limiter =
+        limiter_backend.limiter("okx:history-candles", 20, std::time::Duration::from_secs(2));
+    // Bybit 行情接口：600 次 / 5 秒（按 IP），留出一半余量
+    let bybit_limiter =
+        limiter_backend.limiter("bybit:kline", 300, std::time::Duration::from_secs(5));
 
     // let _ = set_ck_db(ck_db);
     let _ = set_kv_store(redis_store).unwrap();
@@ -46,6 +67,8 @@ pub async fn init_global_services() {
     let _ = set_flush_controller(flush_controller);
     let _ = set_flush_buffer(flush_buffer);
     let _ = set_binance_limiter(binance_limiter);
+    let _ = set_okx_limiter(okx_limiter);
+    let _ = set_bybit_limiter(bybit_limiter);
 }
 
 pub fn set_ck_db(instance: Arc<ClickhouseDb>) -> Result<(), Arc<ClickhouseDb>> {
@@ -100,6 +123,13 @@ pub fn get_flush_buffer() -> Arc<KlineBuffer> {
         .clone()
 }
 
+/// Getter kline_validator，首次访问时按环境变量创建，所有写库路径共用
+pub fn get_kline_validator() -> Arc<KlineValidator> {
+    KLINE_VALIDATOR
+        .get_or_init(|| Arc::new(KlineValidator::from_env()))
+        .clone()
+}
+
 /// Setter binance_limiter
 pub fn set_binance_limiter(instance: Arc<BinanceLimiter>) -> Result<(), Arc<BinanceLimiter>> {
     BINANCE_LIMITER.set(instance)
@@ -112,3 +142,55 @@ pub fn get_binance_limiter() -> Arc<BinanceLimiter> {
         .expect("BinanceLimiter not initialized")
         .clone()
 }
+
+/// Setter okx_limiter
+pub fn set_okx_limiter(instance: Arc<dyn RateBudget>) -> Result<(), Arc<dyn RateBudget>> {
+    OKX_LIMITER.set(instance)
+}
+
+/// Getter okx_limiter
+pub fn get_okx_limiter() -> Arc<dyn RateBudget> {
+    OKX_LIMITER
+        .get()
+        .expect("OkxLimiter not initialized")
+        .clone()
+}
+
+/// Setter bybit_limiter
+pub fn set_bybit_limiter(instance: Arc<dyn RateBudget>) -> Result<(), Arc<dyn RateBudget>> {
+    BYBIT_LIMITER.set(instance)
+}
+
+/// Getter bybit_limiter
+pub fn get_bybit_limiter() -> Arc<dyn RateBudget> {
+    BYBIT_LIMITER
+        .get()
+        .expect("BybitLimiter not initialized")
+        .clone()
+}
+
+/// Getter shutdown，首次访问时创建，无需在 init_global_services 中初始化
+pub fn get_shutdown() -> Arc<Shutdown> {
+    SHUTDOWN.get_or_init(|| Arc::new(Shutdown::new())).clone()
+}
+
+/// Getter backfill_manager，首次访问时创建
+pub fn get_backfill_manager() -> Arc<BackfillManager> {
+    BACKFILL_MANAGER
+        .get_or_init(|| Arc::new(BackfillManager::new()))
+        .clone()
+}
+
+/// Getter dispatch_stats，首次访问时创建
+pub fn get_dispatch_stats() -> Arc<DispatchStats> {
+    DISPATCH_STATS
+        .get_or_init(|| Arc::new(DispatchStats::new()))
+        .clone()
+}
+
+/// Getter kline_hub，首次访问时按环境变量创建
+pub fn get_kline_hub() -> Arc<KlineHub> {
+    KLINE_HUB
+        .get_or_init(|| Arc::new(KlineHub::new(LiveConfig::from_env())))
+        .clone()
+}
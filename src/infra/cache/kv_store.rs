@@ -1,5 +1,6 @@
 use anyhow::{Context, Result};
-use bb8_redis::{bb8, redis::cmd, RedisConnectionManager};
+use bb8_redis::redis::{cmd, FromRedisValue, Script};
+use bb8_redis::{bb8, RedisConnectionManager};
 use serde::{de::DeserializeOwned, Serialize};
 use tracing::{debug, info};
 
@@ -68,6 +69,122 @@ impl RedisKVStore {
         Ok(exists)
     }
 
+    /// 执行 Lua 脚本（EVALSHA，脚本未缓存时自动回退为 EVAL）
+    pub async fn eval_script<T: FromRedisValue>(
+        &self,
+        script: &Script,
+        keys: &[&str],
+        args: &[i64],
+    ) -> Result<T> {
+        let mut conn = self
+            .pool
+            .get()
+            .await
+            .context("Failed to get Redis connection")?;
+
+        let mut invocation = script.prepare_invoke();
+        for key in keys {
+            invocation.key(*key);
+        }
+        for arg in args {
+            invocation.arg(*arg);
+        }
+        invocation
+            .invoke_async(&mut *conn)
+            .await
+            .with_context(|| format!("Failed to execute script for keys: {:?}", keys))
+    }
+
+    /// HSET：以 JSON 写入哈希表字段
+    pub async fn hset<T: Serialize + Send + Sync>(
+        &self,
+        key: &str,
+        field: &str,
+        value: &T,
+    ) -> Result<()> {
+        let mut conn = self
+            .pool
+            .get()
+            .await
+            .context("Failed to get Redis connection")?;
+        let json_str = serde_json::to_string(value)?;
+        let _: i64 = cmd("HSET")
+            .arg(key)
+            .arg(field)
+            .arg(json_str)
+            .query_async(&mut *conn)
+            .await
+            .with_context(|| format!("Failed to HSET {} {}", key, field))?;
+        Ok(())
+    }
+
+    /// HGET：读取哈希表字段并反序列化
+    pub async fn hget<T: DeserializeOwned + Send>(
+        &self,
+        key: &str,
+        field: &str,
+    ) -> Result<Option<T>> {
+        let mut conn = self
+            .pool
+            .get()
+            .await
+            .context("Failed to get Redis connection")?;
+        let value: Option<String> = cmd("HGET")
+            .arg(key)
+            .arg(field)
+            .query_async(&mut *conn)
+            .await
+            .with_context(|| format!("Failed to HGET {} {}", key, field))?;
+
+        match value {
+            Some(json_str) => serde_json::from_str(&json_str)
+                .with_context(|| format!("Failed to deserialize {} {}", key, field))
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// HVALS：读取哈希表全部值，无法反序列化的条目跳过
+    pub async fn hvals<T: DeserializeOwned + Send>(&self, key: &str) -> Result<Vec<T>> {
+        let mut conn = self
+            .pool
+            .get()
+            .await
+            .context("Failed to get Redis connection")?;
+        let raw: Vec<String> = cmd("HVALS")
+            .arg(key)
+            .query_async(&mut *conn)
+            .await
+            .with_context(|| format!("Failed to HVALS {}", key))?;
+
+        Ok(raw
+            .into_iter()
+            .filter_map(|s| match serde_json::from_str::<T>(&s) {
+                Ok(value) => Some(value),
+                Err(e) => {
+                    tracing::warn!("Failed to deserialize entry from key {}: {}", key, e);
+                    None
+                }
+            })
+            .collect())
+    }
+
+    /// HDEL：删除哈希表字段，返回是否存在
+    pub async fn hdel(&self, key: &str, field: &str) -> Result<bool> {
+        let mut conn = self
+            .pool
+            .get()
+            .await
+            .context("Failed to get Redis connection")?;
+        let removed: i64 = cmd("HDEL")
+            .arg(key)
+            .arg(field)
+            .query_async(&mut *conn)
+            .await
+            .with_context(|| format!("Failed to HDEL {} {}", key, field))?;
+        Ok(removed > 0)
+    }
+
     fn make_price_key(&self, mint: &str) -> String {
         format!("solana:price:{}", mint)
     }
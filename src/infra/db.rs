@@ -1,3 +1,4 @@
 pub mod ckdb;
+pub mod kline_store;
 pub mod mysql;
 pub mod types;
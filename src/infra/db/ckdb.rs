@@ -2,7 +2,12 @@ use crate::infra::db::types::{
     AnyInserter, ClickHouseDatabase, PageParams, PageResult, Paginatable, RowCount, SortOrder,
     TableRecord,
 };
-use crate::model::cex::kline::{MarketKline, MinMaxCloseTime};
+use crate::model::cex::depth::OrderBookRow;
+use crate::model::cex::derivatives::{
+    FundingRateRow, LongShortRatioRow, OpenInterestRow, PriceKlineRow, SeriesLocator,
+};
+use crate::model::cex::kline::{CloseTimeRow, MarketKline, MinMaxCloseTime};
+use crate::model::cex::trade::{AggTradeGap, AggTradeRange, AggTradeRow};
 use crate::model::dex::price::PriceUpdate;
 use anyhow::{Context, Result};
 use clickhouse::inserter::Inserter;
@@ -72,6 +77,7 @@ impl ClickHouseDatabase for ClickhouseDb {
                 r#"
             CREATE TABLE IF NOT EXISTS market_klines (
                 exchange String,
+                market_type String DEFAULT 'usdm',
                 symbol String,
                 period String,
                 open_time UInt64,
@@ -88,7 +94,114 @@ impl ClickHouseDatabase for ClickhouseDb {
                 updated_at DateTime DEFAULT now(),
                 PRIMARY KEY (exchange, symbol, period, close_time)
             ) ENGINE = ReplacingMergeTree(updated_at)
-            ORDER BY (exchange, symbol, period, close_time)
+            ORDER BY (exchange, symbol, period, close_time, market_type)
+        "#,
+            ),
+            // U本位合约资金费率历史
+            (
+                "funding_rates",
+                r#"
+            CREATE TABLE IF NOT EXISTS funding_rates (
+                exchange String,
+                symbol String,
+                funding_time Int64,
+                funding_rate Float64,
+                mark_price Nullable(Float64),
+                updated_at DateTime DEFAULT now()
+            ) ENGINE = ReplacingMergeTree(updated_at)
+            ORDER BY (exchange, symbol, funding_time)
+        "#,
+            ),
+            // U本位合约持仓量历史
+            (
+                "open_interest_hist",
+                r#"
+            CREATE TABLE IF NOT EXISTS open_interest_hist (
+                exchange String,
+                symbol String,
+                period String,
+                timestamp Int64,
+                sum_open_interest Float64,
+                sum_open_interest_value Float64,
+                updated_at DateTime DEFAULT now()
+            ) ENGINE = ReplacingMergeTree(updated_at)
+            ORDER BY (exchange, symbol, period, timestamp)
+        "#,
+            ),
+            // U本位合约多空比
+            (
+                "long_short_ratios",
+                r#"
+            CREATE TABLE IF NOT EXISTS long_short_ratios (
+                exchange String,
+                symbol String,
+                ratio_type String,
+                period String,
+                timestamp Int64,
+                long_short_ratio Float64,
+                long_account Float64,
+                short_account Float64,
+                updated_at DateTime DEFAULT now()
+            ) ENGINE = ReplacingMergeTree(updated_at)
+            ORDER BY (exchange, symbol, ratio_type, period, timestamp)
+        "#,
+            ),
+            // U本位合约标记价格 / 指数价格K线
+            (
+                "price_klines",
+                r#"
+            CREATE TABLE IF NOT EXISTS price_klines (
+                exchange String,
+                symbol String,
+                price_type String,
+                period String,
+                open_time Int64,
+                open Float64,
+                high Float64,
+                low Float64,
+                close Float64,
+                close_time Int64,
+                updated_at DateTime DEFAULT now()
+            ) ENGINE = ReplacingMergeTree(updated_at)
+            ORDER BY (exchange, symbol, price_type, period, close_time)
+        "#,
+            ),
+            // 归集成交：按月分区，ID 与时间列使用差分编码压缩
+            (
+                "agg_trades",
+                r#"
+            CREATE TABLE IF NOT EXISTS agg_trades (
+                exchange LowCardinality(String),
+                market_type LowCardinality(String),
+                symbol LowCardinality(String),
+                agg_id UInt64 CODEC(Delta, ZSTD(1)),
+                price Float64 CODEC(Gorilla, ZSTD(1)),
+                qty Float64 CODEC(Gorilla, ZSTD(1)),
+                first_trade_id UInt64 CODEC(Delta, ZSTD(1)),
+                last_trade_id UInt64 CODEC(Delta, ZSTD(1)),
+                trade_time Int64 CODEC(DoubleDelta, ZSTD(1)),
+                is_buyer_maker Bool
+            ) ENGINE = ReplacingMergeTree()
+            PARTITION BY toYYYYMM(toDateTime(intDiv(trade_time, 1000)))
+            ORDER BY (exchange, market_type, symbol, agg_id)
+        "#,
+            ),
+            (
+                "order_book_snapshots",
+                r#"
+            CREATE TABLE IF NOT EXISTS order_book_snapshots (
+                exchange LowCardinality(String),
+                market_type LowCardinality(String),
+                symbol LowCardinality(String),
+                snapshot_time Int64 CODEC(DoubleDelta, ZSTD(1)),
+                last_update_id UInt64 CODEC(Delta, ZSTD(1)),
+                bid_prices Array(Float64) CODEC(ZSTD(3)),
+                bid_qtys Array(Float64) CODEC(ZSTD(3)),
+                ask_prices Array(Float64) CODEC(ZSTD(3)),
+                ask_qtys Array(Float64) CODEC(ZSTD(3))
+            ) ENGINE = ReplacingMergeTree()
+            PARTITION BY toYYYYMMDD(toDateTime(intDiv(snapshot_time, 1000)))
+            ORDER BY (exchange, market_type, symbol, snapshot_time)
         "#,
             ),
         ];
@@ -105,6 +218,8 @@ impl ClickHouseDatabase for ClickhouseDb {
             .into_iter()
             .collect::<Result<()>>()?;
 
+        self.migrate_market_klines_market_type().await?;
+
         // Initialize inserter and set initialized flag
 
         let price_ins = Arc::new(RwLock::new(self.create_inserter::<PriceUpdate>()?));
@@ -118,6 +233,42 @@ impl ClickHouseDatabase for ClickhouseDb {
             "market_klines".to_string(),
             AnyInserter::MarketKline(kline_ins),
         );
+        self.inserters.insert(
+            "funding_rates".to_string(),
+            AnyInserter::FundingRate(Arc::new(RwLock::new(
+                self.create_inserter::<FundingRateRow>()?,
+            ))),
+        );
+        self.inserters.insert(
+            "open_interest_hist".to_string(),
+            AnyInserter::OpenInterest(Arc::new(RwLock::new(
+                self.create_inserter::<OpenInterestRow>()?,
+            ))),
+        );
+        self.inserters.insert(
+            "long_short_ratios".to_string(),
+            AnyInserter::LongShortRatio(Arc::new(RwLock::new(
+                self.create_inserter::<LongShortRatioRow>()?,
+            ))),
+        );
+        self.inserters.insert(
+            "price_klines".to_string(),
+            AnyInserter::PriceKline(Arc::new(RwLock::new(
+                self.create_inserter::<PriceKlineRow>()?,
+            ))),
+        );
+        self.inserters.insert(
+            "agg_trades".to_string(),
+            AnyInserter::AggTrade(Arc::new(RwLock::new(
+                self.create_inserter::<AggTradeRow>()?,
+            ))),
+        );
+        self.inserters.insert(
+            "order_book_snapshots".to_string(),
+            AnyInserter::OrderBook(Arc::new(RwLock::new(
+                self.create_inserter::<OrderBookRow>()?,
+            ))),
+        );
 
         self.is_initialized = true;
 
@@ -212,6 +363,90 @@ impl ClickHouseDatabase for ClickhouseDb {
 }
 
 impl ClickhouseDb {
+    /// 退出前提交所有 inserter 中未提交的数据并结束当前 INSERT
+    pub async fn close_inserters(&self) -> Result<()> {
+        for (table, inserter) in &self.inserters {
+            let stats = match inserter {
+                AnyInserter::PriceUpdate(ins) => ins.write().await.force_commit().await,
+                AnyInserter::MarketKline(ins) => ins.write().await.force_commit().await,
+                AnyInserter::FundingRate(ins) => ins.write().await.force_commit().await,
+                AnyInserter::OpenInterest(ins) => ins.write().await.force_commit().await,
+                AnyInserter::LongShortRatio(ins) => ins.write().await.force_commit().await,
+                AnyInserter::PriceKline(ins) => ins.write().await.force_commit().await,
+                AnyInserter::AggTrade(ins) => ins.write().await.force_commit().await,
+                AnyInserter::OrderBook(ins) => ins.write().await.force_commit().await,
+            }
+            .context(format!("Failed to close inserter for {}", table))?;
+            info!(
+                "Closed {} inserter, committed {} rows ({} bytes)",
+                table, stats.rows, stats.bytes
+            );
+        }
+        Ok(())
+    }
+
+    /// 不经过 inserter 缓冲，单独执行一次 INSERT 并等待完成，返回后数据即可查询
+    ///
+    /// 用于写入后需要立即回读校验的场景（如冷热分层迁移）
+    pub async fn insert_now<T>(&self, data: &[T]) -> Result<()>
+    where
+        T: TableRecord + Row + Serialize,
+    {
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        let mut insert = self
+            .client
+            .insert::<T>(T::TABLE_NAME)
+            .context(format!("failed to prepare insert for {}", T::TABLE_NAME))?;
+        for item in data {
+            insert
+                .write(item)
+                .await
+                .context(format!("Insert into {} failed", T::TABLE_NAME))?;
+        }
+        insert
+            .end()
+            .await
+            .context(format!("Failed to finish insert into {}", T::TABLE_NAME))?;
+        Ok(())
+    }
+
+    /// 旧表缺少 market_type 列时补齐：历史数据均为 U本位合约，排序键只能在末尾追加新列
+    async fn migrate_market_klines_market_type(&self) -> Result<()> {
+        let count = self
+            .client
+            .query(
+                r#"
+            SELECT count(*) AS count FROM system.columns
+            WHERE database = currentDatabase() AND table = 'market_klines' AND name = 'market_type'
+        "#,
+            )
+            .fetch_one::<RowCount>()
+            .await
+            .context("Failed to inspect market_klines columns")?
+            .count;
+
+        if count > 0 {
+            return Ok(());
+        }
+
+        info!("Adding market_type column to market_klines");
+        self.client
+            .query(
+                r#"
+            ALTER TABLE market_klines
+                ADD COLUMN market_type String DEFAULT 'usdm' AFTER exchange,
+                MODIFY ORDER BY (exchange, symbol, period, close_time, market_type)
+        "#,
+            )
+            .execute()
+            .await
+            .context("Failed to add market_type to market_klines")?;
+        Ok(())
+    }
+
     fn create_inserter<T: TableRecord>(&self) -> Result<Inserter<T>> {
         Ok(self
             .client
@@ -223,10 +458,11 @@ impl ClickhouseDb {
             .with_period(Some(Duration::from_secs(15))))
     }
 
-    /// 查询指定交易所、币对、周期的最早和最晚时间
+    /// 查询指定交易所、市场类型、币对、周期的最早和最晚时间
     pub async fn get_mima_time(
         &self,
         exchange: &str,
+        market_type: &str,
         symbol: &str,
         period: &str,
     ) -> Result<Option<MinMaxCloseTime>> {
@@ -235,13 +471,14 @@ impl ClickhouseDb {
                 min(close_time) AS min_close_time,
                 max(close_time) AS max_close_time
             FROM market_klines
-            WHERE exchange = ? AND symbol = ? AND period = ?
+            WHERE exchange = ? AND market_type = ? AND symbol = ? AND period = ?
         "#;
 
         let mut rows = self
             .client
             .query(query)
             .bind(exchange)
+            .bind(market_type)
             .bind(symbol)
             .bind(period)
             .fetch_all::<MinMaxCloseTime>()
@@ -259,11 +496,344 @@ impl ClickhouseDb {
             Ok(None)
         }
     }
-    /// 查询指定交易所、币对、周期、时间范围内的k线数据
+
+    /// 查询衍生品序列已归档的最早和最晚时间，字段沿用 `MinMaxCloseTime`
+    pub async fn get_series_time_range(
+        &self,
+        exchange: &str,
+        symbol: &str,
+        locator: &SeriesLocator,
+    ) -> Result<Option<MinMaxCloseTime>> {
+        // 表名与列名来自代码内的常量，过滤值通过绑定参数传入
+        let mut query = format!(
+            "SELECT min({col}) AS min_close_time, max({col}) AS max_close_time FROM {table} \
+             WHERE exchange = ? AND symbol = ?",
+            col = locator.time_column,
+            table = locator.table,
+        );
+        for (column, _) in &locator.filters {
+            query.push_str(&format!(" AND {} = ?", column));
+        }
+
+        let mut q = self.client.query(&query).bind(exchange).bind(symbol);
+        for (_, value) in &locator.filters {
+            q = q.bind(value.as_str());
+        }
+        let mut rows = q
+            .fetch_all::<MinMaxCloseTime>()
+            .await
+            .context(format!("Failed to fetch time range from {}", locator.table))?;
+
+        match rows.pop() {
+            Some(r) if r.min_close_time != 0 || r.max_close_time != 0 => Ok(Some(r)),
+            _ => Ok(None),
+        }
+    }
+
+    /// 查询已归档归集成交的 ID 与时间范围
+    pub async fn get_agg_trade_range(
+        &self,
+        exchange: &str,
+        market_type: &str,
+        symbol: &str,
+    ) -> Result<Option<AggTradeRange>> {
+        let query = r#"
+            SELECT
+                min(agg_id) AS min_id,
+                max(agg_id) AS max_id,
+                min(trade_time) AS min_time,
+                max(trade_time) AS max_time
+            FROM agg_trades
+            WHERE exchange = ? AND market_type = ? AND symbol = ?
+        "#;
+
+        let mut rows = self
+            .client
+            .query(query)
+            .bind(exchange)
+            .bind(market_type)
+            .bind(symbol)
+            .fetch_all::<AggTradeRange>()
+            .await
+            .context("Failed to fetch agg_trades range")?;
+
+        match rows.pop() {
+            Some(r) if r.max_time != 0 => Ok(Some(r)),
+            _ => Ok(None),
+        }
+    }
+
+    /// 查找成交时间不早于 `since` 的归集成交中 ID 不连续的位置，最多返回 `limit` 个
+    pub async fn find_agg_trade_gaps(
+        &self,
+        exchange: &str,
+        market_type: &str,
+        symbol: &str,
+        since: i64,
+        limit: u64,
+    ) -> Result<Vec<AggTradeGap>> {
+        let query = r#"
+            SELECT prev_id, next_id FROM (
+                SELECT
+                    lagInFrame(agg_id) OVER (ORDER BY agg_id ASC) AS prev_id,
+                    agg_id AS next_id
+                FROM (
+                    SELECT DISTINCT agg_id FROM agg_trades
+                    WHERE exchange = ? AND market_type = ? AND symbol = ? AND trade_time >= ?
+                )
+            )
+            WHERE prev_id > 0 AND next_id > prev_id + 1
+            ORDER BY prev_id ASC
+            LIMIT ?
+        "#;
+
+        self.client
+            .query(query)
+            .bind(exchange)
+            .bind(market_type)
+            .bind(symbol)
+            .bind(since)
+            .bind(limit)
+            .fetch_all::<AggTradeGap>()
+            .await
+            .context("Failed to scan agg_trades gaps")
+    }
+
+    /// 按成交时间查询 [start, end) 内的归集成交，去重后按 ID 升序返回
+    pub async fn query_agg_trades(
+        &self,
+        exchange: &str,
+        market_type: &str,
+        symbol: &str,
+        start: i64,
+        end: i64,
+    ) -> Result<Vec<AggTradeRow>> {
+        let query = r#"
+            SELECT
+                exchange,
+                market_type,
+                symbol,
+                agg_id,
+                price,
+                qty,
+                first_trade_id,
+                last_trade_id,
+                trade_time,
+                is_buyer_maker
+            FROM agg_trades FINAL
+            WHERE exchange = ? AND market_type = ? AND symbol = ?
+              AND trade_time >= ? AND trade_time < ?
+            ORDER BY agg_id ASC
+        "#;
+
+        self.client
+            .query(query)
+            .bind(exchange)
+            .bind(market_type)
+            .bind(symbol)
+            .bind(start)
+            .bind(end)
+            .fetch_all::<AggTradeRow>()
+            .await
+            .with_context(|| {
+                format!(
+                    "Failed to query agg_trades: exchange={}, market_type={}, symbol={}, range=[{}, {})",
+                    exchange, market_type, symbol, start, end
+                )
+            })
+    }
+
+    /// 查询指定时间范围内（含边界）已存储的 close_time，去重后按升序返回
+    pub async fn query_close_times(
+        &self,
+        exchange: &str,
+        market_type: &str,
+        symbol: &str,
+        period: &str,
+        start: i64,
+        end: i64,
+    ) -> Result<Vec<i64>> {
+        let query = r#"
+            SELECT DISTINCT close_time
+            FROM market_klines
+            WHERE exchange = ? AND market_type = ? AND symbol = ? AND period = ?
+              AND close_time BETWEEN ? AND ?
+            ORDER BY close_time ASC
+        "#;
+
+        let rows = self
+            .client
+            .query(query)
+            .bind(exchange)
+            .bind(market_type)
+            .bind(symbol)
+            .bind(period)
+            .bind(start)
+            .bind(end)
+            .fetch_all::<CloseTimeRow>()
+            .await
+            .context("Failed to fetch close_time list")?;
+
+        Ok(rows.into_iter().map(|r| r.close_time).collect())
+    }
+
+    /// 统计指定时间范围内（含边界）去重后的K线数量
+    pub async fn count_close_times(
+        &self,
+        exchange: &str,
+        market_type: &str,
+        symbol: &str,
+        period: &str,
+        start: i64,
+        end: i64,
+    ) -> Result<u64> {
+        let query = r#"
+            SELECT uniqExact(close_time) AS count
+            FROM market_klines FINAL
+            WHERE exchange = ? AND market_type = ? AND symbol = ? AND period = ?
+              AND close_time BETWEEN ? AND ?
+        "#;
+
+        Ok(self
+            .client
+            .query(query)
+            .bind(exchange)
+            .bind(market_type)
+            .bind(symbol)
+            .bind(period)
+            .bind(start)
+            .bind(end)
+            .fetch_one::<RowCount>()
+            .await
+            .context("Failed to count close_time")?
+            .count)
+    }
+
+    /// 按开盘时间查询 [start_open, end_open) 内的K线，去重后按开盘时间升序返回
+    pub async fn query_klines_by_open_time(
+        &self,
+        exchange: &str,
+        market_type: &str,
+        symbol: &str,
+        period: &str,
+        start_open: i64,
+        end_open: i64,
+    ) -> Result<Vec<MarketKline>> {
+        let query = r#"
+            SELECT
+                exchange,
+                market_type,
+                symbol,
+                period,
+                open_time,
+                open,
+                high,
+                low,
+                close,
+                volume,
+                close_time,
+                quote_asset_volume,
+                number_of_trades,
+                taker_buy_base_asset_volume,
+                taker_buy_quote_asset_volume
+            FROM market_klines FINAL
+            WHERE exchange = ? AND market_type = ? AND symbol = ? AND period = ?
+              AND open_time >= ? AND open_time < ?
+            ORDER BY open_time ASC
+        "#;
+
+        self.client
+            .query(query)
+            .bind(exchange)
+            .bind(market_type)
+            .bind(symbol)
+            .bind(period)
+            .bind(start_open)
+            .bind(end_open)
+            .fetch_all::<MarketKline>()
+            .await
+            .with_context(|| {
+                format!(
+                    "Failed to query market_klines by open_time: exchange={}, market_type={}, symbol={}, period={}, range=[{}, {})",
+                    exchange, market_type, symbol, period, start_open, end_open
+                )
+            })
+    }
+
+    /// 按收盘时间查询去重后的K线（FINAL），时间范围含边界，不返回总数
+    pub async fn query_klines_final(
+        &self,
+        exchange: &str,
+        market_type: &str,
+        symbol: &str,
+        period: &str,
+        params: &PageParams,
+    ) -> Result<Vec<MarketKline>> {
+        let mut query = String::from(
+            r#"
+            SELECT
+                exchange,
+                market_type,
+                symbol,
+                period,
+                open_time,
+                open,
+                high,
+                low,
+                close,
+                volume,
+                close_time,
+                quote_asset_volume,
+                number_of_trades,
+                taker_buy_base_asset_volume,
+                taker_buy_quote_asset_volume
+            FROM market_klines FINAL
+            WHERE exchange = ? AND market_type = ? AND symbol = ? AND period = ?
+        "#,
+        );
+        if params.start_time.is_some() {
+            query.push_str(" AND close_time >= ?");
+        }
+        if params.end_time.is_some() {
+            query.push_str(" AND close_time <= ?");
+        }
+        let order = match params.sort_order {
+            SortOrder::Asc => "ASC",
+            SortOrder::Desc => "DESC",
+        };
+        query.push_str(&format!(" ORDER BY close_time {} LIMIT ? OFFSET ?", order));
+
+        let mut q = self
+            .client
+            .query(&query)
+            .bind(exchange)
+            .bind(market_type)
+            .bind(symbol)
+            .bind(period);
+        if let Some(start) = params.start_time {
+            q = q.bind(start);
+        }
+        if let Some(end) = params.end_time {
+            q = q.bind(end);
+        }
+        q.bind(params.limit as u64)
+            .bind(params.offset as u64)
+            .fetch_all::<MarketKline>()
+            .await
+            .with_context(|| {
+                format!(
+                    "Failed to query market_klines: exchange={}, market_type={}, symbol={}, period={}",
+                    exchange, market_type, symbol, period
+                )
+            })
+    }
+
+    /// 查询指定交易所、市场类型、币对、周期、时间范围内的k线数据
     /// 时间范围可选，默认查询最近1000条数据
     pub async fn query_market_klines(
         &self,
         exchange: &str,
+        market_type: &str,
         symbol: &str,
         period: &str,
         start_time: Option<u64>,
@@ -274,6 +844,7 @@ impl ClickhouseDb {
             r#"
         SELECT
             exchange,
+            market_type,
             symbol,
             period,
             open_time,
@@ -289,7 +860,7 @@ impl ClickhouseDb {
             taker_buy_quote_asset_volume,
             updated_at
         FROM market_klines
-        WHERE exchange = ? AND symbol = ? AND period = ?
+        WHERE exchange = ? AND market_type = ? AND symbol = ? AND period = ?
     "#,
         );
 
@@ -298,6 +869,7 @@ impl ClickhouseDb {
             .client
             .query(&sql)
             .bind(exchange)
+            .bind(market_type)
             .bind(symbol)
             .bind(period);
 
@@ -308,6 +880,7 @@ impl ClickhouseDb {
                 .client
                 .query(&sql)
                 .bind(exchange)
+                .bind(market_type)
                 .bind(symbol)
                 .bind(period)
                 .bind(start)
@@ -320,6 +893,7 @@ impl ClickhouseDb {
             .client
             .query(&sql)
             .bind(exchange)
+            .bind(market_type)
             .bind(symbol)
             .bind(period);
 
@@ -335,8 +909,8 @@ impl ClickhouseDb {
             .await
             .with_context(|| {
                 format!(
-                    "Failed to query market_klines: exchange={}, symbol={}, period={}, start={:?}, end={:?}",
-                    exchange, symbol, period, start_time, end_time
+                    "Failed to query market_klines: exchange={}, market_type={}, symbol={}, period={}, start={:?}, end={:?}",
+                    exchange, market_type, symbol, period, start_time, end_time
                 )
             })
     }
@@ -344,35 +918,35 @@ impl ClickhouseDb {
 
 #[async_trait::async_trait]
 impl Paginatable<MarketKline> for ClickhouseDb {
+    /// 去重（FINAL）后按 close_time 分页，时间范围含边界；总数与分页使用相同的条件
     async fn get_paginated(
         &self,
         exchange: &str,
+        market_type: &str,
         symbol: &str,
         period: &str,
         params: &PageParams,
     ) -> anyhow::Result<PageResult<MarketKline>> {
-        let mut query = String::from(
-            r#"
-            SELECT * FROM market_klines
-            WHERE exchange = ? AND symbol = ? AND period = ?
-        "#,
-        );
-
-        if let Some(start) = params.start_time {
-            query.push_str(" AND close_time >= ?");
+        let mut condition =
+            String::from("exchange = ? AND market_type = ? AND symbol = ? AND period = ?");
+        if params.start_time.is_some() {
+            condition.push_str(" AND close_time >= ?");
         }
-        if let Some(end) = params.end_time {
-            query.push_str(" AND close_time <= ?");
+        if params.end_time.is_some() {
+            condition.push_str(" AND close_time <= ?");
         }
 
         let order = match params.sort_order {
             SortOrder::Asc => "ASC",
             SortOrder::Desc => "DESC",
         };
-        query.push_str(&format!(" ORDER BY close_time {} LIMIT ? OFFSET ?", order));
+        let query = format!(
+            "SELECT * FROM market_klines FINAL WHERE {} ORDER BY close_time {} LIMIT ? OFFSET ?",
+            condition, order
+        );
 
         let mut q = self.client.query(&query);
-        q = q.bind(exchange).bind(symbol).bind(period);
+        q = q.bind(exchange).bind(market_type).bind(symbol).bind(period);
 
         if let Some(start) = params.start_time {
             q = q.bind(start);
@@ -386,13 +960,16 @@ impl Paginatable<MarketKline> for ClickhouseDb {
         let items = q.fetch_all::<MarketKline>().await?;
 
         // 获取总条数
-        let mut count_q = self.client.query(
-            r#"
-            SELECT count(*) AS count FROM market_klines
-            WHERE exchange = ? AND symbol = ? AND period = ?
-        "#,
+        let count_query = format!(
+            "SELECT count(*) AS count FROM market_klines FINAL WHERE {}",
+            condition
         );
-        count_q = count_q.bind(exchange).bind(symbol).bind(period);
+        let mut count_q = self.client.query(&count_query);
+        count_q = count_q
+            .bind(exchange)
+            .bind(market_type)
+            .bind(symbol)
+            .bind(period);
 
         if let Some(start) = params.start_time {
             count_q = count_q.bind(start);
@@ -401,11 +978,11 @@ impl Paginatable<MarketKline> for ClickhouseDb {
             count_q = count_q.bind(end);
         }
 
-        let count: usize = count_q
+        let count = count_q
             .fetch_one::<RowCount>()
             .await
-            .map(|r| r.count as usize)
-            .unwrap_or(0);
+            .context("Failed to count market_klines")?
+            .count as usize;
 
         Ok(PageResult {
             total: count,
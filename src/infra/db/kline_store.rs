@@ -0,0 +1,547 @@
+use crate::common::utils::get_env_bool;
+use crate::domain::model::market_kline::MarketKlineFilter;
+use crate::domain::model::SortOrder as DomainSortOrder;
+use crate::domain::service::market_kline_service::MarketKlineService;
+use crate::global::{get_ck_db, get_mysql_pool};
+use crate::infra::db::types::{PageParams, SortOrder};
+use crate::model::cex::kline::{MarketKline, MinMaxCloseTime};
+use crate::model::market_type::MarketType;
+use crate::model::TimeFrame;
+use anyhow::Context;
+use async_trait::async_trait;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// 一个K线序列
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct KlineSeries {
+    pub exchange: String,
+    pub market_type: MarketType,
+    pub symbol: String,
+    pub time_frame: TimeFrame,
+}
+
+impl KlineSeries {
+    pub fn new(
+        exchange: &str,
+        market_type: MarketType,
+        symbol: &str,
+        time_frame: TimeFrame,
+    ) -> Self {
+        Self {
+            exchange: exchange.to_string(),
+            market_type,
+            symbol: symbol.to_string(),
+            time_frame,
+        }
+    }
+}
+
+/// 单个存储上的K线读取，时间范围均按 close_time 且含边界
+#[async_trait]
+pub trait KlineReader: Send + Sync {
+    /// 存储名称：mysql / clickhouse
+    fn name(&self) -> &'static str;
+
+    /// 按 `order` 排序返回范围内最多 `limit` 根K线
+    async fn query(
+        &self,
+        series: &KlineSeries,
+        start: i64,
+        end: i64,
+        limit: usize,
+        order: SortOrder,
+    ) -> anyhow::Result<Vec<MarketKline>>;
+
+    /// 范围内已存储的 close_time 数量（去重）
+    async fn count(&self, series: &KlineSeries, start: i64, end: i64) -> anyhow::Result<usize>;
+
+    /// 范围内已存储的 close_time
+    async fn close_times(
+        &self,
+        series: &KlineSeries,
+        start: i64,
+        end: i64,
+    ) -> anyhow::Result<Vec<i64>>;
+
+    async fn time_range(&self, series: &KlineSeries) -> anyhow::Result<Option<MinMaxCloseTime>>;
+}
+
+/// 追溯数据（Forward）所在的 MySQL
+pub struct MysqlKlineReader;
+
+#[async_trait]
+impl KlineReader for MysqlKlineReader {
+    fn name(&self) -> &'static str {
+        "mysql"
+    }
+
+    async fn query(
+        &self,
+        series: &KlineSeries,
+        start: i64,
+        end: i64,
+        limit: usize,
+        order: SortOrder,
+    ) -> anyhow::Result<Vec<MarketKline>> {
+        let filter = MarketKlineFilter {
+            exchange: Some(series.exchange.clone()),
+            market_type: Some(series.market_type.as_str().to_string()),
+            symbol: Some(series.symbol.clone()),
+            time_frame: Some(series.time_frame.to_str().to_string()),
+            close_time: None,
+            start_close_time: None,
+            end_close_time: None,
+            sort_by_close_time: Some(match order {
+                SortOrder::Asc => DomainSortOrder::Asc,
+                SortOrder::Desc => DomainSortOrder::Desc,
+            }),
+            page: None,
+            page_size: None,
+        };
+        let mut conn = get_mysql_pool().get()?;
+        let rows = MarketKlineService::new(&mut conn).list_by_close_time(
+            &filter,
+            start,
+            end,
+            limit.min(i64::MAX as usize) as i64,
+        )?;
+        Ok(rows.iter().map(Into::into).collect())
+    }
+
+    async fn count(&self, series: &KlineSeries, start: i64, end: i64) -> anyhow::Result<usize> {
+        let mut conn = get_mysql_pool().get()?;
+        let count = MarketKlineService::new(&mut conn).count_close_times(
+            &series.exchange,
+            series.market_type.as_str(),
+            &series.symbol,
+            series.time_frame.to_str(),
+            start,
+            end,
+        )?;
+        Ok(count as usize)
+    }
+
+    async fn close_times(
+        &self,
+        series: &KlineSeries,
+        start: i64,
+        end: i64,
+    ) -> anyhow::Result<Vec<i64>> {
+        let mut conn = get_mysql_pool().get()?;
+        Ok(MarketKlineService::new(&mut conn).list_close_times(
+            &series.exchange,
+            series.market_type.as_str(),
+            &series.symbol,
+            series.time_frame.to_str(),
+            start,
+            end,
+        )?)
+    }
+
+    async fn time_range(&self, series: &KlineSeries) -> anyhow::Result<Option<MinMaxCloseTime>> {
+        let mut conn = get_mysql_pool().get()?;
+        Ok(MarketKlineService::new(&mut conn)
+            .get_mima_time(
+                &series.exchange,
+                series.market_type.as_str(),
+                &series.symbol,
+                series.time_frame.to_str(),
+            )
+            .await?)
+    }
+}
+
+/// 回溯数据（Backward）所在的 ClickHouse
+pub struct ClickhouseKlineReader;
+
+#[async_trait]
+impl KlineReader for ClickhouseKlineReader {
+    fn name(&self) -> &'static str {
+        "clickhouse"
+    }
+
+    async fn query(
+        &self,
+        series: &KlineSeries,
+        start: i64,
+        end: i64,
+        limit: usize,
+        order: SortOrder,
+    ) -> anyhow::Result<Vec<MarketKline>> {
+        let params = PageParams {
+            limit,
+            offset: 0,
+            sort_order: order,
+            start_time: Some(start.max(0) as u64),
+            end_time: Some(end.max(0) as u64),
+        };
+        get_ck_db()
+            .query_klines_final(
+                &series.exchange,
+                series.market_type.as_str(),
+                &series.symbol,
+                series.time_frame.to_str(),
+                &params,
+            )
+            .await
+    }
+
+    async fn count(&self, series: &KlineSeries, start: i64, end: i64) -> anyhow::Result<usize> {
+        let count = get_ck_db()
+            .count_close_times(
+                &series.exchange,
+                series.market_type.as_str(),
+                &series.symbol,
+                series.time_frame.to_str(),
+                start,
+                end,
+            )
+            .await?;
+        Ok(count as usize)
+    }
+
+    async fn close_times(
+        &self,
+        series: &KlineSeries,
+        start: i64,
+        end: i64,
+    ) -> anyhow::Result<Vec<i64>> {
+        get_ck_db()
+            .query_close_times(
+                &series.exchange,
+                series.market_type.as_str(),
+                &series.symbol,
+                series.time_frame.to_str(),
+                start,
+                end,
+            )
+            .await
+    }
+
+    async fn time_range(&self, series: &KlineSeries) -> anyhow::Result<Option<MinMaxCloseTime>> {
+        get_ck_db()
+            .get_mima_time(
+                &series.exchange,
+                series.market_type.as_str(),
+                &series.symbol,
+                series.time_frame.to_str(),
+            )
+            .await
+    }
+}
+
+/// 跨 MySQL 与 ClickHouse 的统一K线读取
+///
+/// 同一范围同时查询各存储，按 close_time 合并去重后返回单一有序序列；
+/// 同一 close_time 以优先级更高的存储为准（MySQL 为实时写入，优先于 ClickHouse）
+pub struct KlineStore {
+    /// 按优先级从低到高排列
+    readers: Vec<Box<dyn KlineReader>>,
+}
+
+impl KlineStore {
+    pub fn new(readers: Vec<Box<dyn KlineReader>>) -> Self {
+        Self { readers }
+    }
+
+    /// `ENABLE_CLICKHOUSE=false` 时只读取 MySQL
+    pub fn from_env() -> Self {
+        let mut readers: Vec<Box<dyn KlineReader>> = vec![];
+        if get_env_bool("ENABLE_CLICKHOUSE", true) {
+            readers.push(Box::new(ClickhouseKlineReader));
+        }
+        readers.push(Box::new(MysqlKlineReader));
+        Self::new(readers)
+    }
+
+    /// 查询 [start, end] 内的K线，按 `order` 排序取前 `limit` 根
+    ///
+    /// 各存储各取前 `limit` 根即可覆盖合并后的前 `limit` 根
+    pub async fn range(
+        &self,
+        series: &KlineSeries,
+        start: i64,
+        end: i64,
+        limit: usize,
+        order: SortOrder,
+    ) -> anyhow::Result<Vec<MarketKline>> {
+        if start > end || limit == 0 {
+            return Ok(vec![]);
+        }
+        let mut batches = Vec::with_capacity(self.readers.len());
+        for reader in &self.readers {
+            batches.push(
+                reader
+                    .query(series, start, end, limit, order.clone())
+                    .await
+                    .with_context(|| format!("Failed to query {} klines", reader.name()))?,
+            );
+        }
+        Ok(merge_klines(batches, limit, order))
+    }
+
+    /// 最近 `n` 根K线，按 close_time 升序返回
+    pub async fn latest(&self, series: &KlineSeries, n: usize) -> anyhow::Result<Vec<MarketKline>> {
+        let mut klines = self.range(series, 0, i64::MAX, n, SortOrder::Desc).await?;
+        klines.reverse();
+        Ok(klines)
+    }
+
+    /// [start, end] 内去重后的K线数量
+    ///
+    /// 计数下推到各存储；只有多个存储的时间范围相互重叠的区间才读取 close_time 去重，
+    /// 重叠区间外的部分分别计数后相加，不做减法，计数期间有新写入或分层迁移时结果也不会下溢
+    pub async fn count(&self, series: &KlineSeries, start: i64, end: i64) -> anyhow::Result<usize> {
+        if start > end {
+            return Ok(0);
+        }
+        let mut ranges = Vec::with_capacity(self.readers.len());
+        for reader in &self.readers {
+            ranges.push(
+                reader
+                    .time_range(series)
+                    .await
+                    .with_context(|| format!("Failed to query {} kline range", reader.name()))?,
+            );
+        }
+
+        // 无重叠时各存储直接计数；有重叠时重叠区间外的两段分别计数
+        let shared = shared_range(&ranges, start, end);
+        let segments = match shared {
+            None => vec![(start, end)],
+            Some((lo, hi)) => [(start, lo - 1), (hi + 1, end)]
+                .into_iter()
+                .filter(|(s, e)| s <= e)
+                .collect(),
+        };
+        let mut total = 0;
+        for reader in &self.readers {
+            for &(s, e) in &segments {
+                total += reader
+                    .count(series, s, e)
+                    .await
+                    .with_context(|| format!("Failed to count {} klines", reader.name()))?;
+            }
+        }
+
+        if let Some((lo, hi)) = shared {
+            let mut close_times = BTreeSet::new();
+            for reader in &self.readers {
+                close_times.extend(
+                    reader
+                        .close_times(series, lo, hi)
+                        .await
+                        .with_context(|| format!("Failed to list {} close times", reader.name()))?,
+                );
+            }
+            total += close_times.len();
+        }
+        Ok(total)
+    }
+
+    /// 各存储合并后的最早与最晚 close_time
+    pub async fn time_range(
+        &self,
+        series: &KlineSeries,
+    ) -> anyhow::Result<Option<MinMaxCloseTime>> {
+        let mut merged: Option<MinMaxCloseTime> = None;
+        for reader in &self.readers {
+            let range = reader
+                .time_range(series)
+                .await
+                .with_context(|| format!("Failed to query {} kline range", reader.name()))?;
+            if let Some(r) = range {
+                merged = Some(match merged {
+                    Some(m) => MinMaxCloseTime {
+                        min_close_time: m.min_close_time.min(r.min_close_time),
+                        max_close_time: m.max_close_time.max(r.max_close_time),
+                    },
+                    None => r,
+                });
+            }
+        }
+        Ok(merged)
+    }
+}
+
+/// 至少两个存储的时间范围在 [start, end] 内重叠时，返回覆盖所有两两重叠部分的区间
+fn shared_range(ranges: &[Option<MinMaxCloseTime>], start: i64, end: i64) -> Option<(i64, i64)> {
+    let ranges: Vec<(i64, i64)> = ranges
+        .iter()
+        .flatten()
+        .map(|r| (r.min_close_time.max(start), r.max_close_time.min(end)))
+        .filter(|(lo, hi)| lo <= hi)
+        .collect();
+
+    let mut shared: Option<(i64, i64)> = None;
+    for (i, a) in ranges.iter().enumerate() {
+        for b in &ranges[i + 1..] {
+            let (lo, hi) = (a.0.max(b.0), a.1.min(b.1));
+            if lo <= hi {
+                shared = Some(shared.map_or((lo, hi), |(l, h)| (l.min(lo), h.max(hi))));
+            }
+        }
+    }
+    shared
+}
+
+/// 按 close_time 合并去重，后出现的批次覆盖先出现的，排序后取前 `limit` 根
+pub fn merge_klines(
+    batches: Vec<Vec<MarketKline>>,
+    limit: usize,
+    order: SortOrder,
+) -> Vec<MarketKline> {
+    let mut merged: BTreeMap<i64, MarketKline> = BTreeMap::new();
+    for kline in batches.into_iter().flatten() {
+        merged.insert(kline.close_time, kline);
+    }
+    match order {
+        SortOrder::Asc => merged.into_values().take(limit).collect(),
+        SortOrder::Desc => merged.into_values().rev().take(limit).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kline(close_time: i64, close: f64) -> MarketKline {
+        MarketKline {
+            exchange: "binance".to_string(),
+            market_type: "usdm".to_string(),
+            symbol: "BTCUSDT".to_string(),
+            period: "1m".to_string(),
+            open_time: close_time - 59_999,
+            open: 1.0,
+            high: 1.0,
+            low: 1.0,
+            close,
+            volume: 1.0,
+            close_time,
+            quote_asset_volume: 1.0,
+            number_of_trades: 1,
+            taker_buy_base_asset_volume: 1.0,
+            taker_buy_quote_asset_volume: 1.0,
+        }
+    }
+
+    /// 内存中的存储，模拟按范围、排序与条数截取
+    struct MemoryReader(Vec<MarketKline>);
+
+    #[async_trait]
+    impl KlineReader for MemoryReader {
+        fn name(&self) -> &'static str {
+            "memory"
+        }
+
+        async fn query(
+            &self,
+            _series: &KlineSeries,
+            start: i64,
+            end: i64,
+            limit: usize,
+            order: SortOrder,
+        ) -> anyhow::Result<Vec<MarketKline>> {
+            let mut rows: Vec<MarketKline> = self
+                .0
+                .iter()
+                .filter(|k| k.close_time >= start && k.close_time <= end)
+                .cloned()
+                .collect();
+            rows.sort_by_key(|k| k.close_time);
+            if matches!(order, SortOrder::Desc) {
+                rows.reverse();
+            }
+            rows.truncate(limit);
+            Ok(rows)
+        }
+
+        async fn count(&self, series: &KlineSeries, start: i64, end: i64) -> anyhow::Result<usize> {
+            Ok(self.close_times(series, start, end).await?.len())
+        }
+
+        async fn close_times(
+            &self,
+            series: &KlineSeries,
+            start: i64,
+            end: i64,
+        ) -> anyhow::Result<Vec<i64>> {
+            let rows = self
+                .query(series, start, end, usize::MAX, SortOrder::Asc)
+                .await?;
+            Ok(rows.iter().map(|k| k.close_time).collect())
+        }
+
+        async fn time_range(
+            &self,
+            _series: &KlineSeries,
+        ) -> anyhow::Result<Option<MinMaxCloseTime>> {
+            let times = self.0.iter().map(|k| k.close_time);
+            Ok(times
+                .clone()
+                .min()
+                .zip(times.max())
+                .map(|(min, max)| MinMaxCloseTime {
+                    min_close_time: min,
+                    max_close_time: max,
+                }))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_store_merges_and_prefers_later_readers() {
+        // 冷数据 1..=4，热数据 4..=6，close_time 4 两边都有
+        let cold = MemoryReader((1..=4).map(|t| kline(t, 1.0)).collect());
+        let hot = MemoryReader((4..=6).map(|t| kline(t, 2.0)).collect());
+        let store = KlineStore::new(vec![Box::new(cold), Box::new(hot)]);
+        let series = KlineSeries::new("binance", MarketType::UsdM, "BTCUSDT", TimeFrame::M1);
+
+        let all = store
+            .range(&series, 0, 10, 100, SortOrder::Asc)
+            .await
+            .unwrap();
+        let times: Vec<i64> = all.iter().map(|k| k.close_time).collect();
+        assert_eq!(times, vec![1, 2, 3, 4, 5, 6]);
+        assert_eq!(all[3].close, 2.0);
+
+        let page = store
+            .range(&series, 2, 10, 3, SortOrder::Asc)
+            .await
+            .unwrap();
+        let times: Vec<i64> = page.iter().map(|k| k.close_time).collect();
+        assert_eq!(times, vec![2, 3, 4]);
+
+        let latest = store.latest(&series, 2).await.unwrap();
+        let times: Vec<i64> = latest.iter().map(|k| k.close_time).collect();
+        assert_eq!(times, vec![5, 6]);
+
+        assert_eq!(store.count(&series, 3, 5).await.unwrap(), 3);
+        assert_eq!(store.count(&series, 0, 10).await.unwrap(), 6);
+        assert_eq!(store.count(&series, 5, 10).await.unwrap(), 2);
+        let range = store.time_range(&series).await.unwrap().unwrap();
+        assert_eq!((range.min_close_time, range.max_close_time), (1, 6));
+    }
+
+    #[test]
+    fn test_shared_range_bounds_overlap() {
+        let range = |min, max| {
+            Some(MinMaxCloseTime {
+                min_close_time: min,
+                max_close_time: max,
+            })
+        };
+
+        assert_eq!(
+            shared_range(&[range(1, 4), range(4, 6)], 0, 10),
+            Some((4, 4))
+        );
+        // 查询范围外的重叠不需要修正
+        assert_eq!(shared_range(&[range(1, 4), range(4, 6)], 5, 10), None);
+        assert_eq!(shared_range(&[range(1, 3), range(5, 6)], 0, 10), None);
+        assert_eq!(shared_range(&[range(1, 3), None], 0, 10), None);
+        assert_eq!(
+            shared_range(&[range(1, 8), range(3, 5)], 4, 10),
+            Some((4, 5))
+        );
+    }
+}
@@ -1,4 +1,9 @@
+use crate::model::cex::depth::OrderBookRow;
+use crate::model::cex::derivatives::{
+    FundingRateRow, LongShortRatioRow, OpenInterestRow, PriceKlineRow,
+};
 use crate::model::cex::kline::MarketKline;
+use crate::model::cex::trade::AggTradeRow;
 use crate::model::dex::price::PriceUpdate;
 use clickhouse::inserter::Inserter;
 use clickhouse::Row;
@@ -37,6 +42,12 @@ pub trait ClickHouseDatabase {
 pub enum AnyInserter {
     PriceUpdate(Arc<RwLock<Inserter<PriceUpdate>>>),
     MarketKline(Arc<RwLock<Inserter<MarketKline>>>),
+    FundingRate(Arc<RwLock<Inserter<FundingRateRow>>>),
+    OpenInterest(Arc<RwLock<Inserter<OpenInterestRow>>>),
+    LongShortRatio(Arc<RwLock<Inserter<LongShortRatioRow>>>),
+    PriceKline(Arc<RwLock<Inserter<PriceKlineRow>>>),
+    AggTrade(Arc<RwLock<Inserter<AggTradeRow>>>),
+    OrderBook(Arc<RwLock<Inserter<OrderBookRow>>>),
     // 其他表类型可继续添加
 }
 
@@ -45,6 +56,7 @@ pub trait Paginatable<T> {
     async fn get_paginated(
         &self,
         exchange: &str,
+        market_type: &str,
         symbol: &str,
         period: &str,
         params: &PageParams,
@@ -4,7 +4,9 @@ use reqwest::StatusCode;
 use thiserror::Error;
 
 pub mod binance;
+pub mod bybit;
 pub mod cgecko;
+pub mod okx;
 pub mod rate_limiter;
 
 /// Parser for third domain responses
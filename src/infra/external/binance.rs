@@ -1,8 +1,19 @@
-use crate::infra::external::binance::market::{FetchKlineSummaryRequest, KlineSummary};
+use crate::global::BINANCE_LIMITER;
+use crate::infra::external::binance::error::BinanceError;
+use crate::infra::external::binance::futures::{
+    BinancePriceKlineResponse, FetchFuturesDataRequest, FundingRate, LongShortRatio,
+    LongShortRatioKind, OpenInterestHist, PriceKline, PriceKlineKind,
+};
+use crate::infra::external::binance::market::{
+    AggTrade, DepthSnapshot, FetchAggTradesRequest, FetchDepthRequest, FetchKlineSummaryRequest,
+    KlineSummary,
+};
 use crate::infra::external::binance::meta::{
     BinanceExchangeInfo, FetchExchangeInfoRequest, Symbol,
 };
+use crate::infra::external::rate_limiter::binance_limiter::retry_after;
 use crate::infra::external::CommonExternalParser;
+use crate::model::market_type::MarketType;
 use barter_integration::error::SocketError;
 use barter_integration::protocol::http::private::Signer;
 use barter_integration::protocol::http::rest::client::RestClient;
@@ -11,9 +22,10 @@ use barter_integration::protocol::http::{BuildStrategy, HttpParser};
 use reqwest::RequestBuilder;
 use std::collections::BTreeMap;
 use std::fmt::Debug;
-use tracing::error;
 
-mod constant;
+pub mod constant;
+pub mod error;
+pub mod futures;
 pub mod market;
 pub mod meta;
 pub struct BinanceSigner;
@@ -36,14 +48,27 @@ where
     Parser: HttpParser,
 {
     rest_client: RestClient<'a, Strategy, Parser>,
+    market_type: MarketType,
 }
 
 pub type DefaultBinanceExchange<'a> = BinanceExchange<'a, BinanceSigner, CommonExternalParser>;
 
 impl<'a> Default for DefaultBinanceExchange<'a> {
     fn default() -> Self {
+        Self::for_market(MarketType::UsdM)
+    }
+}
+
+impl<'a> DefaultBinanceExchange<'a> {
+    /// 按市场类型（现货 / U本位 / 币本位）创建客户端
+    pub fn for_market(market_type: MarketType) -> Self {
         Self {
-            rest_client: RestClient::new(constant::BASE_URL, BinanceSigner, CommonExternalParser),
+            rest_client: RestClient::new(
+                constant::base_url(market_type),
+                BinanceSigner,
+                CommonExternalParser,
+            ),
+            market_type,
         }
     }
 }
@@ -54,36 +79,70 @@ where
     Parser: HttpParser,
     <Parser as HttpParser>::OutputError: Debug,
 {
-    pub fn new(strategy: Strategy, parser: Parser) -> Self
+    pub fn new(strategy: Strategy, parser: Parser, market_type: MarketType) -> Self
     where
         Strategy: BuildStrategy,
         Parser: HttpParser,
     {
         Self {
-            rest_client: RestClient::new(constant::BASE_URL, strategy, parser),
+            rest_client: RestClient::new(constant::base_url(market_type), strategy, parser),
+            market_type,
         }
     }
 
-    pub async fn get_exchange_info(&self) -> Option<BinanceExchangeInfo> {
-        let fetch_request = FetchExchangeInfoRequest;
+    pub fn market_type(&self) -> MarketType {
+        self.market_type
+    }
 
-        match self.rest_client.execute(fetch_request).await {
-            Ok((response, _)) => Some(response.0),
-            Err(err) => {
-                error!("Failed to fetch coin data: {:?}", err);
-                None
-            }
+    /// 与 `RestClient::execute` 相同，但在解析前将响应状态与已用权重头交给限流器，并返回分类后的错误
+    ///
+    /// 熔断期间（429/418 后）先等待封禁解除，未初始化全局限流器时（如单元测试）直接发出请求
+    async fn execute_tracked<Request>(
+        &self,
+        request: Request,
+    ) -> Result<Request::Response, BinanceError>
+    where
+        Request: RestRequest,
+    {
+        let limiter = BINANCE_LIMITER.get();
+        if let Some(limiter) = limiter {
+            limiter.wait_until_open().await;
+        }
+
+        let request = self.rest_client.build(request)?;
+        let response = self.rest_client.http_client.execute(request).await?;
+
+        let status = response.status();
+        let retry_after = retry_after(response.headers());
+        if let Some(limiter) = limiter {
+            limiter.observe(self.market_type, status, response.headers());
+        }
+
+        let payload = response.bytes().await?;
+        if !status.is_success() {
+            return Err(BinanceError::from_response(status, &payload, retry_after));
         }
+        serde_json::from_slice::<Request::Response>(&payload)
+            .map_err(|e| BinanceError::Decode(e.to_string()))
     }
 
-    pub async fn get_symbols(&self) -> Option<Vec<Symbol>> {
-        let exchange_info = self.get_exchange_info().await;
+    pub async fn get_exchange_info(&self) -> Result<BinanceExchangeInfo, BinanceError> {
+        let fetch_request = FetchExchangeInfoRequest {
+            path: constant::exchange_info_path(self.market_type),
+        };
 
-        if exchange_info.is_some() {
-            exchange_info.map(|exchange_info| exchange_info.symbols)?
-        } else {
-            None
+        let info = self.execute_tracked(fetch_request).await?.0;
+        if let (Some(limiter), Some(rate_limits)) =
+            (BINANCE_LIMITER.get(), info.rate_limits.as_deref())
+        {
+            limiter.sync_rate_limits(self.market_type, rate_limits);
         }
+        Ok(info)
+    }
+
+    pub async fn get_symbols(&self) -> Result<Vec<Symbol>, BinanceError> {
+        let exchange_info = self.get_exchange_info().await?;
+        Ok(exchange_info.symbols.unwrap_or_default())
     }
 
     pub async fn get_klines<S1, S2, S3, S4, S5>(
@@ -93,7 +152,7 @@ where
         limit: S3,
         start_time: S4,
         end_time: S5,
-    ) -> Vec<KlineSummary>
+    ) -> Result<Vec<KlineSummary>, BinanceError>
     where
         S1: Into<String>,
         S2: Into<String>,
@@ -118,16 +177,153 @@ where
         }
 
         let fetch_klines_request = FetchKlineSummaryRequest {
+            path: constant::klines_path(self.market_type),
             query_params: parameters,
         };
-        match self.rest_client.execute(fetch_klines_request).await {
-            Ok((response, _)) => response.0,
-            Err(err) => {
-                error!("Failed to fetch coin data: {:?}", err);
-                Vec::new()
-            }
+        Ok(self.execute_tracked(fetch_klines_request).await?.0)
+    }
+
+    /// 归集成交列表：指定 `from_id` 时从该 ID 起按 ID 升序返回，否则按时间范围查询（跨度不超过 1 小时）
+    pub async fn get_agg_trades(
+        &self,
+        symbol: &str,
+        from_id: Option<i64>,
+        start_time: Option<i64>,
+        end_time: Option<i64>,
+        limit: u16,
+    ) -> Result<Vec<AggTrade>, BinanceError> {
+        let mut parameters: BTreeMap<String, String> = BTreeMap::new();
+        parameters.insert("symbol".into(), symbol.into());
+        parameters.insert("limit".into(), limit.to_string());
+        if let Some(id) = from_id {
+            parameters.insert("fromId".into(), id.to_string());
+        }
+        if let Some(st) = start_time {
+            parameters.insert("startTime".into(), st.to_string());
         }
+        if let Some(et) = end_time {
+            parameters.insert("endTime".into(), et.to_string());
+        }
+
+        let request = FetchAggTradesRequest {
+            path: constant::agg_trades_path(self.market_type),
+            query_params: parameters,
+        };
+        self.execute_tracked(request).await
+    }
+
+    /// 订单簿快照，`limit` 为每侧档位数
+    pub async fn get_depth(&self, symbol: &str, limit: u16) -> Result<DepthSnapshot, BinanceError> {
+        let mut parameters: BTreeMap<String, String> = BTreeMap::new();
+        parameters.insert("symbol".into(), symbol.into());
+        parameters.insert("limit".into(), limit.to_string());
+
+        let request = FetchDepthRequest {
+            path: constant::depth_path(self.market_type),
+            query_params: parameters,
+        };
+        self.execute_tracked(request).await
+    }
+
+    /// 资金费率历史（仅 U本位合约）
+    pub async fn get_funding_rates(
+        &self,
+        symbol: &str,
+        limit: u16,
+        start_time: i64,
+        end_time: i64,
+    ) -> Result<Vec<FundingRate>, BinanceError> {
+        let parameters = time_range_params("symbol", symbol, None, limit, start_time, end_time);
+        let request = FetchFuturesDataRequest::new(constant::FUNDING_RATE, parameters);
+        self.execute_tracked(request).await
+    }
+
+    /// 合约持仓量历史（仅 U本位合约，交易所只保留最近 30 天）
+    pub async fn get_open_interest_hist(
+        &self,
+        symbol: &str,
+        period: &str,
+        limit: u16,
+        start_time: i64,
+        end_time: i64,
+    ) -> Result<Vec<OpenInterestHist>, BinanceError> {
+        let parameters = time_range_params(
+            "symbol",
+            symbol,
+            Some(("period", period)),
+            limit,
+            start_time,
+            end_time,
+        );
+        let request = FetchFuturesDataRequest::new(constant::OPEN_INTEREST_HIST, parameters);
+        self.execute_tracked(request).await
+    }
+
+    /// 多空比（仅 U本位合约，交易所只保留最近 30 天）
+    pub async fn get_long_short_ratio(
+        &self,
+        kind: LongShortRatioKind,
+        symbol: &str,
+        period: &str,
+        limit: u16,
+        start_time: i64,
+        end_time: i64,
+    ) -> Result<Vec<LongShortRatio>, BinanceError> {
+        let parameters = time_range_params(
+            "symbol",
+            symbol,
+            Some(("period", period)),
+            limit,
+            start_time,
+            end_time,
+        );
+        let request = FetchFuturesDataRequest::new(kind.path(), parameters);
+        self.execute_tracked(request).await
+    }
+
+    /// 标记价格 / 指数价格K线（仅 U本位合约）
+    pub async fn get_price_klines(
+        &self,
+        kind: PriceKlineKind,
+        symbol: &str,
+        interval: &str,
+        limit: u16,
+        start_time: i64,
+        end_time: i64,
+    ) -> Result<Vec<PriceKline>, BinanceError> {
+        let (key, value) = kind.symbol_param(symbol);
+        let parameters = time_range_params(
+            key,
+            &value,
+            Some(("interval", interval)),
+            limit,
+            start_time,
+            end_time,
+        );
+        let request =
+            FetchFuturesDataRequest::<BinancePriceKlineResponse>::new(kind.path(), parameters);
+        Ok(self.execute_tracked(request).await?.0)
+    }
+}
+
+/// 合约数据接口的公共查询参数：交易对、周期、条数与时间范围
+fn time_range_params(
+    symbol_key: &str,
+    symbol: &str,
+    period: Option<(&str, &str)>,
+    limit: u16,
+    start_time: i64,
+    end_time: i64,
+) -> BTreeMap<String, String> {
+    let mut parameters: BTreeMap<String, String> = BTreeMap::new();
+    parameters.insert(symbol_key.into(), symbol.into());
+    if let Some((key, value)) = period {
+        parameters.insert(key.into(), value.into());
     }
+    parameters.insert("limit".into(), limit.to_string());
+    parameters.insert("startTime".into(), start_time.to_string());
+    parameters.insert("endTime".into(), end_time.to_string());
+    parameters
 }
 
 #[cfg(test)]
@@ -145,10 +341,10 @@ mod tests {
         let dbe = DefaultBinanceExchange::default();
         let exchange_info = dbe.get_exchange_info().await;
         match exchange_info {
-            None => {
-                debug!("Empty exchange info");
+            Err(e) => {
+                debug!(?e, "Failed to fetch exchange info");
             }
-            Some(exchange_info) => {
+            Ok(exchange_info) => {
                 trace_kv!(info,
                      "server_time" => exchange_info.server_time,
                      "timezone" => exchange_info.timezone,
@@ -164,10 +360,10 @@ mod tests {
 
         let symbols = dbe.get_symbols().await;
         match symbols {
-            None => {
-                debug!("Empty exchange info");
+            Err(e) => {
+                debug!(?e, "Failed to fetch symbols");
             }
-            Some(symbols) => {
+            Ok(symbols) => {
                 for symbol in &symbols {
                     trace_kv!(info,
                      "symbol" => symbol.symbol,
@@ -186,7 +382,10 @@ mod tests {
         let symbol = "btcusdt";
         let interval = "5m";
         let limit = 1;
-        let klines = dbe.get_klines(symbol, interval, limit, None, None).await;
+        let klines = dbe
+            .get_klines(symbol, interval, limit, None, None)
+            .await
+            .unwrap_or_default();
 
         for kline in &klines {
             trace_kv!(info,
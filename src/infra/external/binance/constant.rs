@@ -1,6 +1,11 @@
+use crate::model::market_type::MarketType;
+
 /// CoinMarketCap API. All requests should target domain
 pub const BASE_URL: &str = "https://fapi.binance.com";
 
+/// USD-M futures WebSocket market streams, combined streams are served under `/stream?streams=`
+pub const WS_BASE_URL: &str = "wss://fstream.binance.com";
+
 /// https://docs.coingecko.com/v3.0.1/reference/coins-markets
 /// This endpoint allows you to query all the supported coins with price, market cap, volume and market related data
 pub const EXCHANGE_INFO: &str = "/fapi/v1/exchangeInfo";
@@ -8,3 +13,146 @@ pub const EXCHANGE_INFO: &str = "/fapi/v1/exchangeInfo";
 /// https://docs.coingecko.com/v3.0.1/reference/coins-markets
 /// This endpoint allows you to query all the supported coins with price, market cap, volume and market related data
 pub const KLINES: &str = "/fapi/v1/klines";
+
+/// https://developers.binance.com/docs/derivatives/usds-margined-futures/market-data/rest-api/Get-Funding-Rate-History
+pub const FUNDING_RATE: &str = "/fapi/v1/fundingRate";
+
+/// https://developers.binance.com/docs/derivatives/usds-margined-futures/market-data/rest-api/Mark-Price-Kline-Candlestick-Data
+pub const MARK_PRICE_KLINES: &str = "/fapi/v1/markPriceKlines";
+
+/// https://developers.binance.com/docs/derivatives/usds-margined-futures/market-data/rest-api/Index-Price-Kline-Candlestick-Data
+pub const INDEX_PRICE_KLINES: &str = "/fapi/v1/indexPriceKlines";
+
+/// https://developers.binance.com/docs/derivatives/usds-margined-futures/market-data/rest-api/Open-Interest-Statistics
+pub const OPEN_INTEREST_HIST: &str = "/futures/data/openInterestHist";
+
+/// https://developers.binance.com/docs/derivatives/usds-margined-futures/market-data/rest-api/Long-Short-Ratio
+pub const GLOBAL_LONG_SHORT_ACCOUNT_RATIO: &str = "/futures/data/globalLongShortAccountRatio";
+
+/// https://developers.binance.com/docs/derivatives/usds-margined-futures/market-data/rest-api/Top-Long-Short-Account-Ratio
+pub const TOP_LONG_SHORT_ACCOUNT_RATIO: &str = "/futures/data/topLongShortAccountRatio";
+
+/// https://developers.binance.com/docs/derivatives/usds-margined-futures/market-data/rest-api/Top-Trader-Long-Short-Ratio
+pub const TOP_LONG_SHORT_POSITION_RATIO: &str = "/futures/data/topLongShortPositionRatio";
+
+/// https://developers.binance.com/docs/derivatives/usds-margined-futures/market-data/rest-api/Compressed-Aggregate-Trades-List
+pub const AGG_TRADES: &str = "/fapi/v1/aggTrades";
+
+/// https://developers.binance.com/docs/derivatives/usds-margined-futures/market-data/rest-api/Order-Book
+pub const DEPTH: &str = "/fapi/v1/depth";
+
+/// Spot REST API domain
+pub const SPOT_BASE_URL: &str = "https://api.binance.com";
+
+/// Spot WebSocket market streams
+pub const SPOT_WS_BASE_URL: &str = "wss://stream.binance.com:9443";
+
+/// https://developers.binance.com/docs/binance-spot-api-docs/rest-api/general-endpoints#exchange-information
+pub const SPOT_EXCHANGE_INFO: &str = "/api/v3/exchangeInfo";
+
+/// https://developers.binance.com/docs/binance-spot-api-docs/rest-api/market-data-endpoints#klinecandlestick-data
+pub const SPOT_KLINES: &str = "/api/v3/klines";
+
+/// https://developers.binance.com/docs/binance-spot-api-docs/rest-api/market-data-endpoints#compressedaggregate-trades-list
+pub const SPOT_AGG_TRADES: &str = "/api/v3/aggTrades";
+
+/// https://developers.binance.com/docs/binance-spot-api-docs/rest-api/market-data-endpoints#order-book
+pub const SPOT_DEPTH: &str = "/api/v3/depth";
+
+/// COIN-M futures REST API domain
+pub const COINM_BASE_URL: &str = "https://dapi.binance.com";
+
+/// COIN-M futures WebSocket market streams
+pub const COINM_WS_BASE_URL: &str = "wss://dstream.binance.com";
+
+/// https://developers.binance.com/docs/derivatives/coin-margined-futures/market-data/Exchange-Information
+pub const COINM_EXCHANGE_INFO: &str = "/dapi/v1/exchangeInfo";
+
+/// https://developers.binance.com/docs/derivatives/coin-margined-futures/market-data/Kline-Candlestick-Data
+pub const COINM_KLINES: &str = "/dapi/v1/klines";
+
+/// https://developers.binance.com/docs/derivatives/coin-margined-futures/market-data/Compressed-Aggregate-Trades-List
+pub const COINM_AGG_TRADES: &str = "/dapi/v1/aggTrades";
+
+/// https://developers.binance.com/docs/derivatives/coin-margined-futures/market-data/Order-Book
+pub const COINM_DEPTH: &str = "/dapi/v1/depth";
+
+/// 按市场类型选择 REST 域名
+pub fn base_url(market_type: MarketType) -> &'static str {
+    match market_type {
+        MarketType::Spot => SPOT_BASE_URL,
+        MarketType::UsdM => BASE_URL,
+        MarketType::CoinM => COINM_BASE_URL,
+    }
+}
+
+/// 按市场类型选择 WebSocket 域名
+pub fn ws_base_url(market_type: MarketType) -> &'static str {
+    match market_type {
+        MarketType::Spot => SPOT_WS_BASE_URL,
+        MarketType::UsdM => WS_BASE_URL,
+        MarketType::CoinM => COINM_WS_BASE_URL,
+    }
+}
+
+/// 按市场类型选择 exchangeInfo 路径
+pub fn exchange_info_path(market_type: MarketType) -> &'static str {
+    match market_type {
+        MarketType::Spot => SPOT_EXCHANGE_INFO,
+        MarketType::UsdM => EXCHANGE_INFO,
+        MarketType::CoinM => COINM_EXCHANGE_INFO,
+    }
+}
+
+/// 按市场类型选择 klines 路径
+pub fn klines_path(market_type: MarketType) -> &'static str {
+    match market_type {
+        MarketType::Spot => SPOT_KLINES,
+        MarketType::UsdM => KLINES,
+        MarketType::CoinM => COINM_KLINES,
+    }
+}
+
+/// 按市场类型选择 aggTrades 路径
+pub fn agg_trades_path(market_type: MarketType) -> &'static str {
+    match market_type {
+        MarketType::Spot => SPOT_AGG_TRADES,
+        MarketType::UsdM => AGG_TRADES,
+        MarketType::CoinM => COINM_AGG_TRADES,
+    }
+}
+
+/// aggTrades 单次请求权重：现货 4，合约 20
+pub fn agg_trades_weight(market_type: MarketType) -> u32 {
+    match market_type {
+        MarketType::Spot => 4,
+        MarketType::UsdM | MarketType::CoinM => 20,
+    }
+}
+
+/// 按市场类型选择 depth 路径
+pub fn depth_path(market_type: MarketType) -> &'static str {
+    match market_type {
+        MarketType::Spot => SPOT_DEPTH,
+        MarketType::UsdM => DEPTH,
+        MarketType::CoinM => COINM_DEPTH,
+    }
+}
+
+/// depth 单次请求权重，随档位数递增
+pub fn depth_weight(market_type: MarketType, limit: u16) -> u32 {
+    match market_type {
+        MarketType::Spot => match limit {
+            0..=100 => 5,
+            101..=500 => 25,
+            501..=1000 => 50,
+            _ => 250,
+        },
+        MarketType::UsdM | MarketType::CoinM => match limit {
+            0..=50 => 2,
+            51..=100 => 5,
+            101..=500 => 10,
+            _ => 20,
+        },
+    }
+}
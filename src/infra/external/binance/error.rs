@@ -0,0 +1,142 @@
+use barter_integration::error::SocketError;
+use reqwest::StatusCode;
+use serde::Deserialize;
+use thiserror::Error;
+
+/// Binance 错误码：无效交易对（已下架或不存在）
+const INVALID_SYMBOL: i64 = -1121;
+
+/// Binance REST 接口错误，区分可重试的临时故障与不应重试的永久错误
+#[derive(Debug, Error)]
+pub enum BinanceError {
+    #[error("network error: {0}")]
+    Network(String),
+
+    #[error("rate limited with status {status}, retry after {retry_after:?}s")]
+    RateLimited {
+        status: u16,
+        retry_after: Option<i64>,
+    },
+
+    #[error("invalid symbol: {0}")]
+    InvalidSymbol(String),
+
+    #[error("server error {status}: {message}")]
+    Server { status: u16, message: String },
+
+    #[error("request rejected {status} (code {code:?}): {message}")]
+    Request {
+        status: u16,
+        code: Option<i64>,
+        message: String,
+    },
+
+    #[error("decode error: {0}")]
+    Decode(String),
+}
+
+/// 错误响应体，如 {"code":-1121,"msg":"Invalid symbol."}
+#[derive(Debug, Deserialize)]
+struct ApiErrorBody {
+    code: Option<i64>,
+    msg: Option<String>,
+}
+
+impl BinanceError {
+    /// 按状态码与错误码分类非 2xx 响应
+    pub fn from_response(status: StatusCode, body: &[u8], retry_after: Option<i64>) -> Self {
+        let api_error = serde_json::from_slice::<ApiErrorBody>(body).ok();
+        let code = api_error.as_ref().and_then(|e| e.code);
+        let message = api_error
+            .and_then(|e| e.msg)
+            .unwrap_or_else(|| String::from_utf8_lossy(body).into_owned());
+
+        if status == StatusCode::TOO_MANY_REQUESTS || status.as_u16() == 418 {
+            BinanceError::RateLimited {
+                status: status.as_u16(),
+                retry_after,
+            }
+        } else if status.is_server_error() {
+            BinanceError::Server {
+                status: status.as_u16(),
+                message,
+            }
+        } else if code == Some(INVALID_SYMBOL) {
+            BinanceError::InvalidSymbol(message)
+        } else {
+            BinanceError::Request {
+                status: status.as_u16(),
+                code,
+                message,
+            }
+        }
+    }
+
+    /// 网络、限流与服务端错误可重试；无效交易对、参数错误与解析错误重试也不会成功
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            BinanceError::Network(_)
+                | BinanceError::RateLimited { .. }
+                | BinanceError::Server { .. }
+        )
+    }
+}
+
+impl From<SocketError> for BinanceError {
+    fn from(error: SocketError) -> Self {
+        BinanceError::Network(error.to_string())
+    }
+}
+
+impl From<reqwest::Error> for BinanceError {
+    fn from(error: reqwest::Error) -> Self {
+        BinanceError::Network(error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_error_responses() {
+        let invalid = BinanceError::from_response(
+            StatusCode::BAD_REQUEST,
+            br#"{"code":-1121,"msg":"Invalid symbol."}"#,
+            None,
+        );
+        assert!(matches!(invalid, BinanceError::InvalidSymbol(ref m) if m == "Invalid symbol."));
+        assert!(!invalid.is_transient());
+
+        let interval = BinanceError::from_response(
+            StatusCode::BAD_REQUEST,
+            br#"{"code":-1120,"msg":"Invalid interval."}"#,
+            None,
+        );
+        assert!(matches!(
+            interval,
+            BinanceError::Request {
+                code: Some(-1120),
+                ..
+            }
+        ));
+        assert!(!interval.is_transient());
+
+        let banned = BinanceError::from_response(StatusCode::IM_A_TEAPOT, b"", Some(120));
+        assert!(matches!(
+            banned,
+            BinanceError::RateLimited {
+                status: 418,
+                retry_after: Some(120)
+            }
+        ));
+        assert!(banned.is_transient());
+
+        let gateway = BinanceError::from_response(StatusCode::BAD_GATEWAY, b"<html>", None);
+        assert!(
+            matches!(gateway, BinanceError::Server { status: 502, ref message } if message == "<html>")
+        );
+        assert!(gateway.is_transient());
+    }
+}
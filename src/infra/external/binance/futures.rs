@@ -0,0 +1,248 @@
+use crate::common::serde_fun::{
+    deserialize_optional_f64, deserialize_string_to_f64, deserialize_string_to_i64, parse_field,
+    ParseError,
+};
+use crate::infra::external::binance::constant;
+use barter_integration::protocol::http::rest::RestRequest;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::marker::PhantomData;
+
+/// 资金费率历史
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FundingRate {
+    pub symbol: String,
+
+    /// 资金费结算时间（毫秒）
+    pub funding_time: i64,
+
+    #[serde(deserialize_with = "deserialize_string_to_f64")]
+    pub funding_rate: f64,
+
+    /// 结算时的标记价格，早期记录为空字符串
+    #[serde(default, deserialize_with = "deserialize_optional_f64")]
+    pub mark_price: Option<f64>,
+}
+
+/// 合约持仓量历史
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenInterestHist {
+    pub symbol: String,
+
+    /// 持仓总数量（张 / 币）
+    #[serde(deserialize_with = "deserialize_string_to_f64")]
+    pub sum_open_interest: f64,
+
+    /// 持仓总价值（USDT）
+    #[serde(deserialize_with = "deserialize_string_to_f64")]
+    pub sum_open_interest_value: f64,
+
+    /// 统计时间（毫秒）
+    #[serde(deserialize_with = "deserialize_string_to_i64")]
+    pub timestamp: i64,
+}
+
+/// 多空比（全市场账户 / 大户账户 / 大户持仓，三个接口返回结构相同）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LongShortRatio {
+    pub symbol: String,
+
+    #[serde(deserialize_with = "deserialize_string_to_f64")]
+    pub long_short_ratio: f64,
+
+    /// 多头占比（账户数或持仓量）
+    #[serde(deserialize_with = "deserialize_string_to_f64")]
+    pub long_account: f64,
+
+    /// 空头占比（账户数或持仓量）
+    #[serde(deserialize_with = "deserialize_string_to_f64")]
+    pub short_account: f64,
+
+    /// 统计时间（毫秒）
+    #[serde(deserialize_with = "deserialize_string_to_i64")]
+    pub timestamp: i64,
+}
+
+/// 标记价格 / 指数价格K线，没有成交量相关字段
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceKline {
+    pub open_time: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub close_time: i64,
+}
+
+impl TryFrom<&Vec<Value>> for PriceKline {
+    type Error = ParseError;
+
+    fn try_from(row: &Vec<Value>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            open_time: parse_field(row, 0, "open_time")?,
+            open: parse_field(row, 1, "open")?,
+            high: parse_field(row, 2, "high")?,
+            low: parse_field(row, 3, "low")?,
+            close: parse_field(row, 4, "close")?,
+            close_time: parse_field(row, 6, "close_time")?,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct BinancePriceKlineResponse(pub Vec<PriceKline>);
+impl<'de> Deserialize<'de> for BinancePriceKlineResponse {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let raw: Vec<Vec<Value>> = Vec::deserialize(deserializer)?;
+        let klines = raw
+            .into_iter()
+            .map(|row| PriceKline::try_from(&row).map_err(serde::de::Error::custom))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(BinancePriceKlineResponse(klines))
+    }
+}
+
+/// 价格K线类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PriceKlineKind {
+    Mark,
+    Index,
+}
+
+impl PriceKlineKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PriceKlineKind::Mark => "mark",
+            PriceKlineKind::Index => "index",
+        }
+    }
+
+    pub fn path(&self) -> &'static str {
+        match self {
+            PriceKlineKind::Mark => constant::MARK_PRICE_KLINES,
+            PriceKlineKind::Index => constant::INDEX_PRICE_KLINES,
+        }
+    }
+
+    /// 指数价格按标的对查询（交割合约 BTCUSDT_240628 对应 BTCUSDT），标记价格按合约查询
+    pub fn symbol_param(&self, symbol: &str) -> (&'static str, String) {
+        match self {
+            PriceKlineKind::Mark => ("symbol", symbol.to_string()),
+            PriceKlineKind::Index => {
+                let pair = symbol.split('_').next().unwrap_or(symbol);
+                ("pair", pair.to_string())
+            }
+        }
+    }
+}
+
+/// 多空比类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LongShortRatioKind {
+    /// 全市场多空账户数比
+    GlobalAccount,
+    /// 大户账户数多空比
+    TopAccount,
+    /// 大户持仓量多空比
+    TopPosition,
+}
+
+impl LongShortRatioKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LongShortRatioKind::GlobalAccount => "global_account",
+            LongShortRatioKind::TopAccount => "top_account",
+            LongShortRatioKind::TopPosition => "top_position",
+        }
+    }
+
+    pub fn path(&self) -> &'static str {
+        match self {
+            LongShortRatioKind::GlobalAccount => constant::GLOBAL_LONG_SHORT_ACCOUNT_RATIO,
+            LongShortRatioKind::TopAccount => constant::TOP_LONG_SHORT_ACCOUNT_RATIO,
+            LongShortRatioKind::TopPosition => constant::TOP_LONG_SHORT_POSITION_RATIO,
+        }
+    }
+}
+
+/// U本位合约数据接口的通用 GET 请求，响应按 `Response` 解析
+pub struct FetchFuturesDataRequest<Response> {
+    pub(crate) path: &'static str,
+    pub(crate) query_params: BTreeMap<String, String>,
+    pub(crate) _response: PhantomData<fn() -> Response>,
+}
+
+impl<Response> FetchFuturesDataRequest<Response> {
+    pub fn new(path: &'static str, query_params: BTreeMap<String, String>) -> Self {
+        Self {
+            path,
+            query_params,
+            _response: PhantomData,
+        }
+    }
+}
+
+impl<Response> RestRequest for FetchFuturesDataRequest<Response>
+where
+    Response: DeserializeOwned,
+{
+    type Response = Response;
+    type QueryParams = BTreeMap<String, String>;
+    type Body = ();
+
+    fn path(&self) -> Cow<'static, str> {
+        Cow::Borrowed(self.path)
+    }
+
+    fn method() -> reqwest::Method {
+        reqwest::Method::GET
+    }
+
+    fn query_params(&self) -> Option<&Self::QueryParams> {
+        Some(&self.query_params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_futures_data_responses() {
+        let funding: Vec<FundingRate> = serde_json::from_str(
+            r#"[{"symbol":"BTCUSDT","fundingTime":1698768000000,"fundingRate":"0.00010000","markPrice":"34651.40000000"},
+                {"symbol":"BTCUSDT","fundingTime":1569398400000,"fundingRate":"0.00010000","markPrice":""}]"#,
+        )
+        .unwrap();
+        assert_eq!(funding[0].mark_price, Some(34651.4));
+        assert_eq!(funding[1].mark_price, None);
+
+        // 时间戳可能以字符串或数字返回
+        let ratios: Vec<LongShortRatio> = serde_json::from_str(
+            r#"[{"symbol":"BTCUSDT","longShortRatio":"1.8105","longAccount":"0.6442","shortAccount":"0.3558","timestamp":"1583139600000"},
+                {"symbol":"BTCUSDT","longShortRatio":"1.8105","longAccount":"0.6442","shortAccount":"0.3558","timestamp":1583139900000}]"#,
+        )
+        .unwrap();
+        assert_eq!(ratios[0].timestamp, 1_583_139_600_000);
+        assert_eq!(ratios[1].timestamp, 1_583_139_900_000);
+
+        let klines: BinancePriceKlineResponse = serde_json::from_str(
+            r#"[[1591256400000,"9653.69440000","9653.69640000","9651.38600000","9651.55200000","0",1591256459999,"0",60,"0","0","0"]]"#,
+        )
+        .unwrap();
+        assert_eq!(klines.0[0].close_time, 1_591_256_459_999);
+        assert_eq!(
+            PriceKlineKind::Index.symbol_param("BTCUSDT_240628").1,
+            "BTCUSDT"
+        );
+    }
+}
@@ -1,5 +1,4 @@
-use crate::common::serde_fun::{parse_field, ParseError};
-use crate::infra::external::binance::constant;
+use crate::common::serde_fun::{deserialize_string_to_f64, parse_field, ParseError};
 use barter_integration::protocol::http::rest::RestRequest;
 use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::Value;
@@ -64,6 +63,7 @@ impl TryFrom<&Vec<Value>> for KlineSummary {
 }
 
 pub struct FetchKlineSummaryRequest {
+    pub(crate) path: &'static str,
     pub(crate) query_params: BTreeMap<String, String>,
 }
 
@@ -73,7 +73,7 @@ impl RestRequest for FetchKlineSummaryRequest {
     type Body = ();
 
     fn path(&self) -> Cow<'static, str> {
-        Cow::Borrowed(constant::KLINES)
+        Cow::Borrowed(self.path)
     }
 
     fn method() -> reqwest::Method {
@@ -100,3 +100,118 @@ impl<'de> Deserialize<'de> for BinanceKlineSummaryResponse {
         Ok(BinanceKlineSummaryResponse(klines))
     }
 }
+
+/// 归集成交：同一价格、同一方向、同一时刻的多笔成交合并为一条
+///
+/// REST 与 WebSocket 推送使用相同的字段缩写
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct AggTrade {
+    /// 归集成交 ID，同一交易对内连续递增
+    #[serde(rename = "a")]
+    pub agg_id: i64,
+
+    /// 成交价
+    #[serde(rename = "p", deserialize_with = "deserialize_string_to_f64")]
+    pub price: f64,
+
+    /// 成交量（以基础资产计，币本位合约为张数）
+    #[serde(rename = "q", deserialize_with = "deserialize_string_to_f64")]
+    pub qty: f64,
+
+    /// 被归集的首个原始成交 ID
+    #[serde(rename = "f")]
+    pub first_trade_id: i64,
+
+    /// 被归集的末个原始成交 ID
+    #[serde(rename = "l")]
+    pub last_trade_id: i64,
+
+    /// 成交时间（毫秒）
+    #[serde(rename = "T")]
+    pub trade_time: i64,
+
+    /// 买方是否为挂单方，true 表示主动卖出
+    #[serde(rename = "m")]
+    pub is_buyer_maker: bool,
+}
+
+/// 价格档位 (价格, 数量)，接口以 [["价格", "数量"], ...] 字符串数组返回
+pub type PriceLevel = (f64, f64);
+
+/// 反序列化 [["价格", "数量"], ...] 形式的档位列表
+pub fn deserialize_price_levels<'de, D>(deserializer: D) -> Result<Vec<PriceLevel>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let raw: Vec<Vec<Value>> = Vec::deserialize(deserializer)?;
+    raw.iter()
+        .map(|level| {
+            Ok((
+                parse_field(level, 0, "price")?,
+                parse_field(level, 1, "qty")?,
+            ))
+        })
+        .collect::<Result<Vec<_>, ParseError>>()
+        .map_err(serde::de::Error::custom)
+}
+
+/// 订单簿快照，`last_update_id` 用于与增量深度推送对齐
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct DepthSnapshot {
+    #[serde(rename = "lastUpdateId")]
+    pub last_update_id: u64,
+
+    /// 买盘，价格从高到低
+    #[serde(deserialize_with = "deserialize_price_levels")]
+    pub bids: Vec<PriceLevel>,
+
+    /// 卖盘，价格从低到高
+    #[serde(deserialize_with = "deserialize_price_levels")]
+    pub asks: Vec<PriceLevel>,
+}
+
+pub struct FetchDepthRequest {
+    pub(crate) path: &'static str,
+    pub(crate) query_params: BTreeMap<String, String>,
+}
+
+impl RestRequest for FetchDepthRequest {
+    type Response = DepthSnapshot;
+    type QueryParams = BTreeMap<String, String>;
+    type Body = ();
+
+    fn path(&self) -> Cow<'static, str> {
+        Cow::Borrowed(self.path)
+    }
+
+    fn method() -> reqwest::Method {
+        reqwest::Method::GET
+    }
+
+    fn query_params(&self) -> Option<&Self::QueryParams> {
+        Some(&self.query_params)
+    }
+}
+
+pub struct FetchAggTradesRequest {
+    pub(crate) path: &'static str,
+    pub(crate) query_params: BTreeMap<String, String>,
+}
+
+impl RestRequest for FetchAggTradesRequest {
+    type Response = Vec<AggTrade>;
+    type QueryParams = BTreeMap<String, String>;
+    type Body = ();
+
+    fn path(&self) -> Cow<'static, str> {
+        Cow::Borrowed(self.path)
+    }
+
+    fn method() -> reqwest::Method {
+        reqwest::Method::GET
+    }
+
+    fn query_params(&self) -> Option<&Self::QueryParams> {
+        Some(&self.query_params)
+    }
+}
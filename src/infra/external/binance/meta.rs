@@ -1,4 +1,3 @@
-use crate::infra::external::binance::constant;
 use barter_integration::protocol::http::rest::RestRequest;
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
@@ -26,9 +25,9 @@ pub struct BinanceExchangeInfo {
     pub exchange_filters: Option<Vec<String>>,
 }
 
-/// 合约市场信息结构（适用于 Binance Futures）
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+/// 交易对信息结构（兼容现货、U本位与币本位合约，缺失字段取默认值）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
 pub struct Symbol {
     /// 交易对，如 "ONEUSDT"
     pub symbol: String,
@@ -45,7 +44,8 @@ pub struct Symbol {
     /// 上线日期（毫秒时间戳）
     pub onboard_date: i64,
 
-    /// 状态，如 "TRADING"
+    /// 状态，如 "TRADING"（币本位合约字段名为 contractStatus）
+    #[serde(alias = "contractStatus")]
     pub status: String,
 
     /// 维持保证金百分比，字符串表示浮点数
@@ -102,8 +102,8 @@ pub struct Symbol {
     /// 支持的 TIF 策略，如 GTC、IOC
     pub time_in_force: Option<Vec<String>>,
 
-    /// 权限集，例如 ["GRID", "COPY"]
-    pub permission_sets: Option<Vec<String>>,
+    /// 权限集，合约为 ["GRID", "COPY"]，现货为二维数组 [["SPOT", "MARGIN"]]
+    pub permission_sets: Option<serde_json::Value>,
 }
 
 /// 合约市场过滤器定义
@@ -155,7 +155,9 @@ pub struct RateLimit {
     pub limit: Option<u32>,
 }
 
-pub struct FetchExchangeInfoRequest;
+pub struct FetchExchangeInfoRequest {
+    pub(crate) path: &'static str,
+}
 
 impl RestRequest for FetchExchangeInfoRequest {
     type Response = ExchangeInfoResponse;
@@ -163,7 +165,7 @@ impl RestRequest for FetchExchangeInfoRequest {
     type Body = ();
 
     fn path(&self) -> Cow<'static, str> {
-        Cow::Borrowed(constant::EXCHANGE_INFO)
+        Cow::Borrowed(self.path)
     }
 
     fn method() -> reqwest::Method {
@@ -173,3 +175,37 @@ impl RestRequest for FetchExchangeInfoRequest {
 
 #[derive(Debug, Deserialize)]
 pub struct ExchangeInfoResponse(pub BinanceExchangeInfo);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_spot_and_coinm_symbols() {
+        let spot = r#"{"timezone":"UTC","serverTime":1717200000000,"rateLimits":[],"exchangeFilters":[],
+            "symbols":[{"symbol":"BTCUSDT","status":"TRADING","baseAsset":"BTC","baseAssetPrecision":8,
+            "quoteAsset":"USDT","quotePrecision":8,"orderTypes":["LIMIT","MARKET"],
+            "filters":[{"filterType":"PRICE_FILTER","minPrice":"0.01","maxPrice":"1000000.00","tickSize":"0.01"},
+            {"filterType":"MAX_NUM_ORDERS","maxNumOrders":200}],
+            "permissionSets":[["SPOT","MARGIN"]]}]}"#;
+        let info: BinanceExchangeInfo = serde_json::from_str(spot).unwrap();
+        let symbol = &info.symbols.unwrap()[0];
+        assert_eq!(symbol.symbol, "BTCUSDT");
+        assert_eq!(symbol.status, "TRADING");
+        assert!(symbol.contract_type.is_empty());
+
+        let coinm = r#"{"timezone":"UTC","serverTime":1717200000000,"rateLimits":[],"exchangeFilters":[],
+            "symbols":[{"symbol":"BTCUSD_PERP","pair":"BTCUSD","contractType":"PERPETUAL",
+            "deliveryDate":4133404800000,"onboardDate":1597042800000,"contractStatus":"TRADING",
+            "contractSize":100,"marginAsset":"BTC","maintMarginPercent":"2.5000","requiredMarginPercent":"5.0000",
+            "baseAsset":"BTC","quoteAsset":"USD","pricePrecision":1,"quantityPrecision":0,
+            "baseAssetPrecision":8,"quotePrecision":8,"equalQtyPrecision":4,"triggerProtect":"0.0500",
+            "underlyingType":"COIN","underlyingSubType":[],"filters":[],"orderTypes":["LIMIT"],
+            "timeInForce":["GTC"],"liquidationFee":"0.015000","marketTakeBound":"0.05"}]}"#;
+        let info: BinanceExchangeInfo = serde_json::from_str(coinm).unwrap();
+        let symbol = &info.symbols.unwrap()[0];
+        assert_eq!(symbol.symbol, "BTCUSD_PERP");
+        assert_eq!(symbol.status, "TRADING");
+        assert_eq!(symbol.quote_asset, "USD");
+    }
+}
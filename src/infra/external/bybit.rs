@@ -0,0 +1,179 @@
+use crate::infra::external::bybit::market::{BybitKline, FetchKlineRequest};
+use crate::infra::external::bybit::meta::{category, BybitInstrument, FetchInstrumentsInfoRequest};
+use crate::infra::external::CommonExternalParser;
+use crate::model::market_type::MarketType;
+use barter_integration::error::SocketError;
+use barter_integration::protocol::http::rest::client::RestClient;
+use barter_integration::protocol::http::rest::RestRequest;
+use barter_integration::protocol::http::{BuildStrategy, HttpParser};
+use reqwest::RequestBuilder;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+
+pub mod constant;
+pub mod market;
+pub mod meta;
+
+/// 公共行情接口无需签名
+pub struct BybitSigner;
+impl BuildStrategy for BybitSigner {
+    fn build<Request>(
+        &self,
+        _request: Request,
+        builder: RequestBuilder,
+    ) -> Result<reqwest::Request, SocketError>
+    where
+        Request: RestRequest,
+    {
+        builder.build().map_err(SocketError::from)
+    }
+}
+
+pub struct BybitExchange<'a, Strategy, Parser>
+where
+    Strategy: BuildStrategy,
+    Parser: HttpParser,
+{
+    rest_client: RestClient<'a, Strategy, Parser>,
+}
+
+pub type DefaultBybitExchange<'a> = BybitExchange<'a, BybitSigner, CommonExternalParser>;
+
+impl<'a> Default for DefaultBybitExchange<'a> {
+    fn default() -> Self {
+        Self::with_base_url(constant::BASE_URL)
+    }
+}
+
+impl<'a> DefaultBybitExchange<'a> {
+    /// 指定 REST 域名（测试时指向本地 mock server）
+    pub fn with_base_url<Url: Into<Cow<'a, str>>>(base_url: Url) -> Self {
+        Self {
+            rest_client: RestClient::new(base_url, BybitSigner, CommonExternalParser),
+        }
+    }
+}
+
+impl<'a, Strategy, Parser> BybitExchange<'a, Strategy, Parser>
+where
+    Strategy: BuildStrategy,
+    Parser: HttpParser,
+    <Parser as HttpParser>::OutputError: Debug,
+{
+    pub fn new(strategy: Strategy, parser: Parser) -> Self {
+        Self {
+            rest_client: RestClient::new(constant::BASE_URL, strategy, parser),
+        }
+    }
+
+    /// 查询指定市场类型的全部交易对，按 cursor 翻页直到取完
+    pub async fn get_instruments(
+        &self,
+        market_type: MarketType,
+    ) -> anyhow::Result<Vec<BybitInstrument>> {
+        let mut instruments = vec![];
+        let mut cursor = String::new();
+
+        loop {
+            let mut parameters: BTreeMap<String, String> = BTreeMap::new();
+            parameters.insert("category".into(), category(market_type).into());
+            parameters.insert(
+                "limit".into(),
+                format!("{}", constant::INSTRUMENTS_MAX_LIMIT),
+            );
+            if !cursor.is_empty() {
+                parameters.insert("cursor".into(), cursor.clone());
+            }
+
+            let request = FetchInstrumentsInfoRequest {
+                query_params: parameters,
+            };
+            let (response, _) = self
+                .rest_client
+                .execute(request)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to fetch Bybit instruments: {:?}", e))?;
+
+            let result = response.into_result()?;
+            instruments.extend(result.list);
+
+            if result.next_page_cursor.is_empty() || result.next_page_cursor == cursor {
+                break;
+            }
+            cursor = result.next_page_cursor;
+        }
+
+        Ok(instruments)
+    }
+
+    /// 查询 [start, end] 内（按开盘时间，含边界）最新的至多 `limit` 根K线，按时间倒序
+    pub async fn get_klines(
+        &self,
+        market_type: MarketType,
+        symbol: &str,
+        interval: &str,
+        start: i64,
+        end: i64,
+        limit: u16,
+    ) -> anyhow::Result<Vec<BybitKline>> {
+        let mut parameters: BTreeMap<String, String> = BTreeMap::new();
+        parameters.insert("category".into(), category(market_type).into());
+        parameters.insert("symbol".into(), symbol.into());
+        parameters.insert("interval".into(), interval.into());
+        parameters.insert("start".into(), format!("{}", start));
+        parameters.insert("end".into(), format!("{}", end));
+        parameters.insert("limit".into(), format!("{}", limit));
+
+        let request = FetchKlineRequest {
+            query_params: parameters,
+        };
+        let (response, _) = self
+            .rest_client
+            .execute(request)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch Bybit klines: {:?}", e))?;
+
+        Ok(response.into_result()?.list)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::test_utils::{spawn_fixture_server, FixtureRoute};
+    use crate::domain::model::market_symbol::NewOrUpdateMarketSymbol;
+
+    #[tokio::test]
+    async fn test_get_instruments_follows_cursor() {
+        let base_url = spawn_fixture_server(vec![
+            FixtureRoute {
+                matches: "category=linear&cursor=",
+                body: include_str!(concat!(
+                    env!("CARGO_MANIFEST_DIR"),
+                    "/tests/fixtures/bybit/instruments_linear_page2.json"
+                )),
+            },
+            FixtureRoute {
+                matches: "category=linear",
+                body: include_str!(concat!(
+                    env!("CARGO_MANIFEST_DIR"),
+                    "/tests/fixtures/bybit/instruments_linear_page1.json"
+                )),
+            },
+        ])
+        .await;
+        let bybit = DefaultBybitExchange::with_base_url(base_url);
+
+        let instruments = bybit.get_instruments(MarketType::UsdM).await.unwrap();
+        let symbols: Vec<&str> = instruments.iter().map(|i| i.symbol.as_str()).collect();
+        assert_eq!(symbols, vec!["BTCUSDT", "ETHUSDT"]);
+
+        let symbol = NewOrUpdateMarketSymbol::from((instruments[0].clone(), MarketType::UsdM));
+        assert_eq!(symbol.exchange, "bybit");
+        assert_eq!(symbol.market_type, "usdm");
+        assert_eq!(symbol.base_asset, "BTC");
+        assert_eq!(symbol.quote_asset, "USDT");
+        assert_eq!(symbol.status, "TRADING");
+    }
+}
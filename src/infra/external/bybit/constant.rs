@@ -0,0 +1,16 @@
+/// Bybit REST API domain
+pub const BASE_URL: &str = "https://api.bybit.com";
+
+/// https://bybit-exchange.github.io/docs/v5/market/instrument
+/// Query for the instrument specification of online trading pairs, paginated by `cursor`
+pub const INSTRUMENTS_INFO: &str = "/v5/market/instruments-info";
+
+/// https://bybit-exchange.github.io/docs/v5/market/kline
+/// Query for historical klines, returns at most 1000 rows per request in reverse order
+pub const KLINE: &str = "/v5/market/kline";
+
+/// kline 单次请求最大返回条数
+pub const KLINE_MAX_LIMIT: u16 = 1000;
+
+/// instruments-info 单次请求最大返回条数
+pub const INSTRUMENTS_MAX_LIMIT: u16 = 1000;
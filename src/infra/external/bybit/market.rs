@@ -0,0 +1,123 @@
+use crate::common::serde_fun::{parse_field, ParseError};
+use crate::infra::external::binance::market::KlineSummary;
+use crate::infra::external::bybit::constant;
+use barter_integration::protocol::http::rest::RestRequest;
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+/// Bybit K线：[startTime, open, high, low, close, volume, turnover]
+#[derive(Debug, Clone)]
+pub struct BybitKline {
+    pub start_time: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    /// 成交量：正向合约/现货为基础币，反向合约为张数（USD）
+    pub volume: f64,
+    /// 成交额：正向合约/现货为计价币，反向合约为基础币
+    pub turnover: f64,
+}
+
+impl BybitKline {
+    /// 转换为统一的 KlineSummary，Bybit 不提供成交笔数与主动买入量，置 0
+    pub fn to_kline_summary(&self, close_time: i64, is_inverse: bool) -> KlineSummary {
+        let (volume, quote_asset_volume) = if is_inverse {
+            (self.turnover, self.volume)
+        } else {
+            (self.volume, self.turnover)
+        };
+        KlineSummary {
+            open_time: self.start_time,
+            open: self.open,
+            high: self.high,
+            low: self.low,
+            close: self.close,
+            volume,
+            close_time,
+            quote_asset_volume,
+            number_of_trades: 0,
+            taker_buy_base_asset_volume: 0.0,
+            taker_buy_quote_asset_volume: 0.0,
+        }
+    }
+}
+
+impl TryFrom<&Vec<Value>> for BybitKline {
+    type Error = ParseError;
+
+    fn try_from(row: &Vec<Value>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            start_time: parse_field(row, 0, "start_time")?,
+            open: parse_field(row, 1, "open")?,
+            high: parse_field(row, 2, "high")?,
+            low: parse_field(row, 3, "low")?,
+            close: parse_field(row, 4, "close")?,
+            volume: parse_field(row, 5, "volume")?,
+            turnover: parse_field(row, 6, "turnover")?,
+        })
+    }
+}
+
+/// Bybit 统一响应包装：retCode 为 0 表示成功
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BybitResponse<T> {
+    pub ret_code: i64,
+    #[serde(default)]
+    pub ret_msg: String,
+    pub result: Option<T>,
+}
+
+impl<T> BybitResponse<T> {
+    pub fn into_result(self) -> anyhow::Result<T> {
+        match (self.ret_code, self.result) {
+            (0, Some(result)) => Ok(result),
+            (code, _) => Err(anyhow::anyhow!("Bybit error {}: {}", code, self.ret_msg)),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BybitKlineResult {
+    #[serde(default)]
+    pub symbol: String,
+    #[serde(default)]
+    pub category: String,
+    #[serde(default, deserialize_with = "deserialize_kline_rows")]
+    pub list: Vec<BybitKline>,
+}
+
+fn deserialize_kline_rows<'de, D>(deserializer: D) -> Result<Vec<BybitKline>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let raw: Vec<Vec<Value>> = Vec::deserialize(deserializer)?;
+    raw.iter()
+        .map(|row| BybitKline::try_from(row).map_err(serde::de::Error::custom))
+        .collect()
+}
+
+pub struct FetchKlineRequest {
+    pub(crate) query_params: BTreeMap<String, String>,
+}
+
+impl RestRequest for FetchKlineRequest {
+    type Response = BybitResponse<BybitKlineResult>;
+    type QueryParams = BTreeMap<String, String>;
+    type Body = ();
+
+    fn path(&self) -> Cow<'static, str> {
+        Cow::Borrowed(constant::KLINE)
+    }
+
+    fn method() -> reqwest::Method {
+        reqwest::Method::GET
+    }
+
+    fn query_params(&self) -> Option<&Self::QueryParams> {
+        Some(&self.query_params)
+    }
+}
@@ -0,0 +1,107 @@
+use crate::infra::external::bybit::constant;
+use crate::infra::external::bybit::market::BybitResponse;
+use crate::model::market_type::MarketType;
+use barter_integration::protocol::http::rest::RestRequest;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+/// Bybit 交易对信息（兼容 spot / linear / inverse），缺失字段取默认值
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BybitInstrument {
+    /// 交易对，如 BTCUSDT
+    pub symbol: String,
+
+    /// 合约类型：LinearPerpetual、LinearFutures、InversePerpetual、InverseFutures，现货为空
+    pub contract_type: String,
+
+    /// 状态：Trading、PreLaunch、Delivering、Closed
+    pub status: String,
+
+    /// 基础币种
+    pub base_coin: String,
+
+    /// 计价币种
+    pub quote_coin: String,
+
+    /// 结算币种，现货为空
+    pub settle_coin: String,
+
+    /// 上线时间（毫秒时间戳字符串）
+    pub launch_time: String,
+
+    /// 交割时间（毫秒时间戳字符串），永续为 "0"
+    pub delivery_time: String,
+
+    /// 价格精度（小数位）
+    pub price_scale: String,
+
+    /// 价格过滤器
+    pub price_filter: BybitPriceFilter,
+
+    /// 数量过滤器
+    pub lot_size_filter: BybitLotSizeFilter,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BybitPriceFilter {
+    pub tick_size: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BybitLotSizeFilter {
+    /// 合约下单数量步长
+    pub qty_step: String,
+    /// 现货基础币精度
+    pub base_precision: String,
+    /// 现货计价币精度
+    pub quote_precision: String,
+    pub min_order_qty: String,
+    pub max_order_qty: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BybitInstrumentsResult {
+    #[serde(default)]
+    pub category: String,
+    #[serde(default)]
+    pub list: Vec<BybitInstrument>,
+    /// 下一页游标，为空表示没有更多数据
+    #[serde(default)]
+    pub next_page_cursor: String,
+}
+
+/// 各市场类型对应的 category 查询参数
+pub fn category(market_type: MarketType) -> &'static str {
+    match market_type {
+        MarketType::Spot => "spot",
+        MarketType::UsdM => "linear",
+        MarketType::CoinM => "inverse",
+    }
+}
+
+pub struct FetchInstrumentsInfoRequest {
+    pub(crate) query_params: BTreeMap<String, String>,
+}
+
+impl RestRequest for FetchInstrumentsInfoRequest {
+    type Response = BybitResponse<BybitInstrumentsResult>;
+    type QueryParams = BTreeMap<String, String>;
+    type Body = ();
+
+    fn path(&self) -> Cow<'static, str> {
+        Cow::Borrowed(constant::INSTRUMENTS_INFO)
+    }
+
+    fn method() -> reqwest::Method {
+        reqwest::Method::GET
+    }
+
+    fn query_params(&self) -> Option<&Self::QueryParams> {
+        Some(&self.query_params)
+    }
+}
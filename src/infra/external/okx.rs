@@ -0,0 +1,156 @@
+use crate::infra::external::okx::market::{FetchHistoryCandlesRequest, OkxCandle};
+use crate::infra::external::okx::meta::{inst_type, FetchInstrumentsRequest, OkxInstrument};
+use crate::infra::external::CommonExternalParser;
+use crate::model::market_type::MarketType;
+use barter_integration::error::SocketError;
+use barter_integration::protocol::http::rest::client::RestClient;
+use barter_integration::protocol::http::rest::RestRequest;
+use barter_integration::protocol::http::{BuildStrategy, HttpParser};
+use reqwest::RequestBuilder;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+
+pub mod constant;
+pub mod market;
+pub mod meta;
+
+/// 公共行情接口无需签名
+pub struct OkxSigner;
+impl BuildStrategy for OkxSigner {
+    fn build<Request>(
+        &self,
+        _request: Request,
+        builder: RequestBuilder,
+    ) -> Result<reqwest::Request, SocketError>
+    where
+        Request: RestRequest,
+    {
+        builder.build().map_err(SocketError::from)
+    }
+}
+
+pub struct OkxExchange<'a, Strategy, Parser>
+where
+    Strategy: BuildStrategy,
+    Parser: HttpParser,
+{
+    rest_client: RestClient<'a, Strategy, Parser>,
+}
+
+pub type DefaultOkxExchange<'a> = OkxExchange<'a, OkxSigner, CommonExternalParser>;
+
+impl<'a> Default for DefaultOkxExchange<'a> {
+    fn default() -> Self {
+        Self::with_base_url(constant::BASE_URL)
+    }
+}
+
+impl<'a> DefaultOkxExchange<'a> {
+    /// 指定 REST 域名（测试时指向本地 mock server）
+    pub fn with_base_url<Url: Into<Cow<'a, str>>>(base_url: Url) -> Self {
+        Self {
+            rest_client: RestClient::new(base_url, OkxSigner, CommonExternalParser),
+        }
+    }
+}
+
+impl<'a, Strategy, Parser> OkxExchange<'a, Strategy, Parser>
+where
+    Strategy: BuildStrategy,
+    Parser: HttpParser,
+    <Parser as HttpParser>::OutputError: Debug,
+{
+    pub fn new(strategy: Strategy, parser: Parser) -> Self {
+        Self {
+            rest_client: RestClient::new(constant::BASE_URL, strategy, parser),
+        }
+    }
+
+    /// 查询指定市场类型的全部交易产品
+    pub async fn get_instruments(
+        &self,
+        market_type: MarketType,
+    ) -> anyhow::Result<Vec<OkxInstrument>> {
+        let mut parameters: BTreeMap<String, String> = BTreeMap::new();
+        parameters.insert("instType".into(), inst_type(market_type).into());
+
+        let request = FetchInstrumentsRequest {
+            query_params: parameters,
+        };
+        let (response, _) = self
+            .rest_client
+            .execute(request)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch OKX instruments: {:?}", e))?;
+
+        Ok(response
+            .into_data()?
+            .into_iter()
+            .filter(|i| i.market_type() == Some(market_type))
+            .collect())
+    }
+
+    /// 查询历史K线，`after` 为分页游标：返回开盘时间早于该值的数据，按时间倒序
+    pub async fn get_history_candles(
+        &self,
+        inst_id: &str,
+        bar: &str,
+        after: Option<i64>,
+        limit: u16,
+    ) -> anyhow::Result<Vec<OkxCandle>> {
+        let mut parameters: BTreeMap<String, String> = BTreeMap::new();
+        parameters.insert("instId".into(), inst_id.into());
+        parameters.insert("bar".into(), bar.into());
+        parameters.insert("limit".into(), format!("{}", limit));
+        if let Some(after) = after {
+            parameters.insert("after".into(), format!("{}", after));
+        }
+
+        let request = FetchHistoryCandlesRequest {
+            query_params: parameters,
+        };
+        let (response, _) = self
+            .rest_client
+            .execute(request)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch OKX candles: {:?}", e))?;
+
+        response.0.into_data()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::test_utils::{spawn_fixture_server, FixtureRoute};
+    use crate::domain::model::market_symbol::NewOrUpdateMarketSymbol;
+
+    #[tokio::test]
+    async fn test_get_instruments_maps_market_symbol() {
+        let base_url = spawn_fixture_server(vec![FixtureRoute {
+            matches: "instType=SWAP",
+            body: include_str!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/fixtures/okx/instruments_swap.json"
+            )),
+        }])
+        .await;
+        let okx = DefaultOkxExchange::with_base_url(base_url);
+
+        // 同为 SWAP，按 ctType 区分 U 本位与币本位
+        let instruments = okx.get_instruments(MarketType::UsdM).await.unwrap();
+        assert_eq!(instruments.len(), 1);
+        let symbol = NewOrUpdateMarketSymbol::from((instruments[0].clone(), MarketType::UsdM));
+        assert_eq!(symbol.exchange, "okx");
+        assert_eq!(symbol.market_type, "usdm");
+        assert_eq!(symbol.symbol, "BTC-USDT-SWAP");
+        assert_eq!(symbol.base_asset, "BTC");
+        assert_eq!(symbol.quote_asset, "USDT");
+        assert_eq!(symbol.status, "TRADING");
+
+        let instruments = okx.get_instruments(MarketType::CoinM).await.unwrap();
+        assert_eq!(instruments.len(), 1);
+        assert_eq!(instruments[0].inst_id, "BTC-USD-SWAP");
+    }
+}
@@ -0,0 +1,13 @@
+/// OKX REST API domain
+pub const BASE_URL: &str = "https://www.okx.com";
+
+/// https://www.okx.com/docs-v5/en/#public-data-rest-api-get-instruments
+/// Retrieve a list of instruments with open contracts, 20 requests per 2 seconds
+pub const INSTRUMENTS: &str = "/api/v5/public/instruments";
+
+/// https://www.okx.com/docs-v5/en/#order-book-trading-market-data-get-candlesticks-history
+/// Retrieve history candlestick charts from recent years, 20 requests per 2 seconds, at most 100 rows per request
+pub const HISTORY_CANDLES: &str = "/api/v5/market/history-candles";
+
+/// history-candles 单次请求最大返回条数
+pub const HISTORY_CANDLES_MAX_LIMIT: u16 = 100;
@@ -0,0 +1,131 @@
+use crate::common::serde_fun::{parse_field, ParseError};
+use crate::infra::external::binance::market::KlineSummary;
+use crate::infra::external::okx::constant;
+use barter_integration::protocol::http::rest::RestRequest;
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+/// OKX K线：[ts, o, h, l, c, vol, volCcy, volCcyQuote, confirm]，ts 为开盘时间
+#[derive(Debug, Clone)]
+pub struct OkxCandle {
+    pub ts: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    /// 交易量：现货为基础币，合约为张数
+    pub vol: f64,
+    /// 交易量（币）：现货为计价币，合约为基础币
+    pub vol_ccy: f64,
+    /// 交易量（计价币）
+    pub vol_ccy_quote: f64,
+    /// "1" 表示已收盘
+    pub confirm: String,
+}
+
+impl OkxCandle {
+    pub fn is_confirmed(&self) -> bool {
+        self.confirm == "1"
+    }
+
+    /// 转换为统一的 KlineSummary，OKX 不提供成交笔数与主动买入量，置 0
+    pub fn to_kline_summary(&self, close_time: i64, is_spot: bool) -> KlineSummary {
+        let volume = if is_spot { self.vol } else { self.vol_ccy };
+        KlineSummary {
+            open_time: self.ts,
+            open: self.open,
+            high: self.high,
+            low: self.low,
+            close: self.close,
+            volume,
+            close_time,
+            quote_asset_volume: self.vol_ccy_quote,
+            number_of_trades: 0,
+            taker_buy_base_asset_volume: 0.0,
+            taker_buy_quote_asset_volume: 0.0,
+        }
+    }
+}
+
+impl TryFrom<&Vec<Value>> for OkxCandle {
+    type Error = ParseError;
+
+    fn try_from(row: &Vec<Value>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            ts: parse_field(row, 0, "ts")?,
+            open: parse_field(row, 1, "open")?,
+            high: parse_field(row, 2, "high")?,
+            low: parse_field(row, 3, "low")?,
+            close: parse_field(row, 4, "close")?,
+            vol: parse_field(row, 5, "vol")?,
+            vol_ccy: parse_field(row, 6, "vol_ccy")?,
+            vol_ccy_quote: parse_field(row, 7, "vol_ccy_quote")?,
+            confirm: parse_field(row, 8, "confirm")?,
+        })
+    }
+}
+
+/// OKX 统一响应包装：code 为 "0" 表示成功
+#[derive(Debug, Deserialize)]
+pub struct OkxResponse<T> {
+    pub code: String,
+    #[serde(default)]
+    pub msg: String,
+    #[serde(default = "Vec::new")]
+    pub data: Vec<T>,
+}
+
+impl<T> OkxResponse<T> {
+    pub fn into_data(self) -> anyhow::Result<Vec<T>> {
+        if self.code == "0" {
+            Ok(self.data)
+        } else {
+            Err(anyhow::anyhow!("OKX error {}: {}", self.code, self.msg))
+        }
+    }
+}
+
+pub struct FetchHistoryCandlesRequest {
+    pub(crate) query_params: BTreeMap<String, String>,
+}
+
+impl RestRequest for FetchHistoryCandlesRequest {
+    type Response = OkxCandlesResponse;
+    type QueryParams = BTreeMap<String, String>;
+    type Body = ();
+
+    fn path(&self) -> Cow<'static, str> {
+        Cow::Borrowed(constant::HISTORY_CANDLES)
+    }
+
+    fn method() -> reqwest::Method {
+        reqwest::Method::GET
+    }
+
+    fn query_params(&self) -> Option<&Self::QueryParams> {
+        Some(&self.query_params)
+    }
+}
+
+#[derive(Debug)]
+pub struct OkxCandlesResponse(pub OkxResponse<OkxCandle>);
+impl<'de> Deserialize<'de> for OkxCandlesResponse {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let raw: OkxResponse<Vec<Value>> = OkxResponse::deserialize(deserializer)?;
+        let data = raw
+            .data
+            .iter()
+            .map(|row| OkxCandle::try_from(row).map_err(serde::de::Error::custom))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(OkxCandlesResponse(OkxResponse {
+            code: raw.code,
+            msg: raw.msg,
+            data,
+        }))
+    }
+}
@@ -0,0 +1,119 @@
+use crate::infra::external::okx::constant;
+use crate::infra::external::okx::market::OkxResponse;
+use crate::model::market_type::MarketType;
+use barter_integration::protocol::http::rest::RestRequest;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+/// OKX 交易产品信息（现货 / 永续合约），缺失字段取默认值
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct OkxInstrument {
+    /// 产品类型，如 SPOT、SWAP
+    pub inst_type: String,
+
+    /// 产品ID，如 BTC-USDT、BTC-USDT-SWAP
+    pub inst_id: String,
+
+    /// 标的指数，仅适用于合约，如 BTC-USDT
+    pub uly: String,
+
+    /// 交易品种，仅适用于合约，如 BTC-USDT
+    pub inst_family: String,
+
+    /// 交易货币币种，仅适用于现货，如 BTC
+    pub base_ccy: String,
+
+    /// 计价货币币种，仅适用于现货，如 USDT
+    pub quote_ccy: String,
+
+    /// 盈亏结算和保证金币种，仅适用于合约
+    pub settle_ccy: String,
+
+    /// 合约面值
+    pub ct_val: String,
+
+    /// 合约面值计价币种
+    pub ct_val_ccy: String,
+
+    /// linear：正向合约，inverse：反向合约
+    pub ct_type: String,
+
+    /// 上线时间（毫秒时间戳字符串）
+    pub list_time: String,
+
+    /// 交割/下线时间（毫秒时间戳字符串）
+    pub exp_time: String,
+
+    /// 产品状态：live、suspend、preopen
+    pub state: String,
+
+    /// 下单价格精度，如 0.1
+    pub tick_sz: String,
+
+    /// 下单数量精度
+    pub lot_sz: String,
+
+    /// 最小下单数量
+    pub min_sz: String,
+}
+
+impl OkxInstrument {
+    /// 按产品类型与合约类型映射市场类型，交割合约等暂不支持
+    pub fn market_type(&self) -> Option<MarketType> {
+        match (self.inst_type.as_str(), self.ct_type.as_str()) {
+            ("SPOT", _) => Some(MarketType::Spot),
+            ("SWAP", "linear") => Some(MarketType::UsdM),
+            ("SWAP", "inverse") => Some(MarketType::CoinM),
+            _ => None,
+        }
+    }
+
+    /// 合约没有 baseCcy/quoteCcy 字段，从 instFamily（如 BTC-USDT）拆分
+    pub fn base_quote(&self) -> (String, String) {
+        if !self.base_ccy.is_empty() {
+            return (self.base_ccy.clone(), self.quote_ccy.clone());
+        }
+        let family = if self.inst_family.is_empty() {
+            &self.uly
+        } else {
+            &self.inst_family
+        };
+        let mut parts = family.split('-');
+        (
+            parts.next().unwrap_or_default().to_string(),
+            parts.next().unwrap_or_default().to_string(),
+        )
+    }
+}
+
+/// 各市场类型对应的 instType 查询参数
+pub fn inst_type(market_type: MarketType) -> &'static str {
+    match market_type {
+        MarketType::Spot => "SPOT",
+        MarketType::UsdM | MarketType::CoinM => "SWAP",
+    }
+}
+
+pub struct FetchInstrumentsRequest {
+    pub(crate) query_params: BTreeMap<String, String>,
+}
+
+impl RestRequest for FetchInstrumentsRequest {
+    type Response = OkxResponse<OkxInstrument>;
+    type QueryParams = BTreeMap<String, String>;
+    type Body = ();
+
+    fn path(&self) -> Cow<'static, str> {
+        Cow::Borrowed(constant::INSTRUMENTS)
+    }
+
+    fn method() -> reqwest::Method {
+        reqwest::Method::GET
+    }
+
+    fn query_params(&self) -> Option<&Self::QueryParams> {
+        Some(&self.query_params)
+    }
+}
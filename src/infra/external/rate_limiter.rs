@@ -1 +1,67 @@
+use crate::common::utils::get_env_or;
+use crate::infra::cache::kv_store::RedisKVStore;
+use crate::infra::external::bybit::constant::KLINE_MAX_LIMIT;
+use crate::infra::external::okx::constant::HISTORY_CANDLES_MAX_LIMIT;
+use crate::infra::external::rate_limiter::redis_limiter::RedisTokenBucket;
+use crate::infra::external::rate_limiter::request_limiter::RequestLimiter;
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+
 pub mod binance_limiter;
+pub mod redis_limiter;
+pub mod request_limiter;
+
+/// 限流预算：进程内令牌桶（governor）或同一出口 IP 的多个实例共享的 Redis 令牌桶
+#[async_trait]
+pub trait RateBudget: Send + Sync {
+    /// 异步等待 `n` 个令牌，超过桶容量时按容量计
+    async fn acquire_n(&self, n: u32);
+
+    /// 非阻塞尝试获取 `n` 个令牌
+    async fn try_acquire_n(&self, n: u32) -> bool;
+
+    /// 异步等待一个请求令牌
+    async fn acquire(&self) {
+        self.acquire_n(1).await;
+    }
+}
+
+/// 交易所拉取K线的限流预算：(每分钟请求数, 单次请求K线数)
+///
+/// Binance 权重 2400/分钟，1000 根K线的请求权重为 5；OKX 20 次/2 秒；Bybit 按全局限流器的 300 次/5 秒
+pub fn request_budget(exchange: &str) -> (u32, u32) {
+    match exchange {
+        "okx" => (600, HISTORY_CANDLES_MAX_LIMIT as u32),
+        "bybit" => (3600, KLINE_MAX_LIMIT as u32),
+        _ => (480, 1000),
+    }
+}
+
+/// 限流器后端
+///
+/// - `RATE_LIMITER_BACKEND`：memory（默认，仅限本进程）/ redis（各实例按交易所与接口共享同一预算）
+#[derive(Clone)]
+pub enum LimiterBackend {
+    Memory,
+    Redis(Arc<RedisKVStore>),
+}
+
+impl LimiterBackend {
+    pub fn from_env(kv: Arc<RedisKVStore>) -> Self {
+        match get_env_or("RATE_LIMITER_BACKEND", "memory".to_string()).as_str() {
+            "redis" => LimiterBackend::Redis(kv),
+            _ => LimiterBackend::Memory,
+        }
+    }
+
+    /// 每 `period` 内最多 `requests` 个令牌；`key` 区分交易所与接口，如 binance:usdm
+    pub fn limiter(&self, key: &str, requests: u32, period: Duration) -> Arc<dyn RateBudget> {
+        match self {
+            LimiterBackend::Memory => Arc::new(RequestLimiter::new(requests, period)),
+            LimiterBackend::Redis(kv) => {
+                Arc::new(RedisTokenBucket::new(kv.clone(), key, requests, period))
+            }
+        }
+    }
+}
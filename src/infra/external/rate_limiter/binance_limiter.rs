@@ -1,27 +1,220 @@
-use governor::{
-    clock::DefaultClock,
-    state::{InMemoryState, NotKeyed},
-    Jitter, Quota, RateLimiter,
-};
+use crate::infra::external::binance::meta::RateLimit;
+use crate::infra::external::rate_limiter::{LimiterBackend, RateBudget};
+use crate::model::market_type::MarketType;
+use chrono::Utc;
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use std::collections::HashMap;
+use std::sync::{Mutex, RwLock};
 use std::{num::NonZeroU32, sync::Arc, time::Duration};
+use tracing::{info, warn};
 
-type InnerLimiter = RateLimiter<NotKeyed, InMemoryState, DefaultClock>;
+/// 已用权重达到上限的该比例后暂停到下一分钟，为共享同一 IP 的其它进程留出余量
+const WEIGHT_SAFETY_RATIO: f64 = 0.9;
 
+/// 429/418 未携带 Retry-After 时的默认封禁时长
+const DEFAULT_RETRY_AFTER_SECS: i64 = 60;
+
+const MINUTE_MS: i64 = 60_000;
+
+/// 不计入 IP 权重、按接口单独限流的 U本位合约数据接口
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EndpointQuota {
+    /// /fapi/v1/fundingRate：与 fundingInfo 共享 500 次 / 5 分钟
+    FundingRate,
+    /// /futures/data/*（持仓量、多空比）：1000 次 / 5 分钟
+    FuturesData,
+}
+
+impl EndpointQuota {
+    pub const ALL: [EndpointQuota; 2] = [EndpointQuota::FundingRate, EndpointQuota::FuturesData];
+
+    fn key(&self) -> &'static str {
+        match self {
+            EndpointQuota::FundingRate => "binance:usdm:funding-rate",
+            EndpointQuota::FuturesData => "binance:usdm:futures-data",
+        }
+    }
+
+    /// 每 5 分钟的请求次数上限
+    pub fn requests_per_5min(&self) -> u32 {
+        match self {
+            EndpointQuota::FundingRate => 500,
+            EndpointQuota::FuturesData => 1000,
+        }
+    }
+}
+
+/// 当前分钟内交易所统计的已用权重
+#[derive(Debug, Clone, Copy)]
+struct UsedWeight {
+    minute: i64,
+    used: u32,
+}
+
+/// 按交易所实际计数自适应的权重状态
+///
+/// 现货、U本位、币本位的权重分别计数；429/418 的封禁按 IP 生效，熔断对所有 Binance 请求生效
+#[derive(Debug, Default)]
+struct WeightState {
+    used: HashMap<MarketType, UsedWeight>,
+    limits: HashMap<MarketType, u32>,
+    banned_until_ms: i64,
+}
+
+impl WeightState {
+    fn limit(&self, market_type: MarketType) -> u32 {
+        self.limits
+            .get(&market_type)
+            .copied()
+            .unwrap_or(BinanceLimiter::QUOTA_PER_MINUTE)
+    }
+
+    /// 发出权重为 `weight` 的请求前需等待的毫秒数，0 表示可立即发出
+    fn wait_ms(&self, market_type: MarketType, weight: u32, now_ms: i64) -> i64 {
+        if self.banned_until_ms > now_ms {
+            return self.banned_until_ms - now_ms;
+        }
+
+        let minute = now_ms / MINUTE_MS;
+        match self.used.get(&market_type) {
+            Some(w) if w.minute == minute => {
+                let cap = (self.limit(market_type) as f64 * WEIGHT_SAFETY_RATIO) as u32;
+                if w.used + weight > cap {
+                    (minute + 1) * MINUTE_MS - now_ms
+                } else {
+                    0
+                }
+            }
+            _ => 0,
+        }
+    }
+
+    /// 本地预占权重，响应头到达前并发请求也能看到
+    fn reserve(&mut self, market_type: MarketType, weight: u32, now_ms: i64) {
+        let minute = now_ms / MINUTE_MS;
+        let entry = self
+            .used
+            .entry(market_type)
+            .or_insert(UsedWeight { minute, used: 0 });
+        if entry.minute != minute {
+            *entry = UsedWeight { minute, used: 0 };
+        }
+        entry.used += weight;
+    }
+
+    /// 以交易所返回的已用权重为准；同一分钟内响应可能乱序到达，取较大值
+    fn record_used(&mut self, market_type: MarketType, used: u32, now_ms: i64) {
+        let minute = now_ms / MINUTE_MS;
+        let entry = self
+            .used
+            .entry(market_type)
+            .or_insert(UsedWeight { minute, used });
+        if entry.minute != minute {
+            *entry = UsedWeight { minute, used };
+        } else {
+            entry.used = entry.used.max(used);
+        }
+    }
+
+    fn trip(&mut self, until_ms: i64) {
+        self.banned_until_ms = self.banned_until_ms.max(until_ms);
+    }
+}
+
+/// 响应头中的分钟已用权重，如 `X-MBX-USED-WEIGHT-1M`
+pub fn used_weight(headers: &HeaderMap) -> Option<u32> {
+    ["x-mbx-used-weight-1m", "x-mbx-used-weight"]
+        .iter()
+        .find_map(|name| headers.get(*name))
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse().ok())
+}
+
+/// 响应头中的 `Retry-After`（秒）
+pub fn retry_after(headers: &HeaderMap) -> Option<i64> {
+    headers
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse().ok())
+}
+
+/// exchangeInfo 中按分钟计的 REQUEST_WEIGHT 上限
+pub fn minute_weight_limit(rate_limits: &[RateLimit]) -> Option<u32> {
+    rate_limits.iter().find_map(|r| {
+        let is_weight = r.rate_limit_type.as_deref() == Some("REQUEST_WEIGHT");
+        let per_minute = r.interval.as_deref() == Some("MINUTE") && r.interval_num == Some(1);
+        if is_weight && per_minute {
+            r.limit
+        } else {
+            None
+        }
+    })
+}
+
+/// Binance 权重限流：现货、U本位、币本位各自一份每分钟权重预算
+///
+/// 预算可为进程内令牌桶，也可为多实例共享的 Redis 令牌桶（键 `binance:{market_type}`）；
+/// exchangeInfo 同步到新的权重上限后按新上限重建对应市场的令牌桶
 #[derive(Clone)]
 pub struct BinanceLimiter {
-    limiter: Arc<InnerLimiter>,
+    backend: LimiterBackend,
+    budgets: Arc<RwLock<HashMap<MarketType, Arc<dyn RateBudget>>>>,
+    endpoints: HashMap<EndpointQuota, Arc<dyn RateBudget>>,
+    state: Arc<Mutex<WeightState>>,
+}
+
+impl Default for BinanceLimiter {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl BinanceLimiter {
-    /// 创建新的 BinanceLimiter 实例，默认配额 2400 次/分钟（即权重总和）
+    /// 每分钟权重配额
+    pub const QUOTA_PER_MINUTE: u32 = 2400;
+
+    /// 创建新的 BinanceLimiter 实例，默认配额 2400 次/分钟（即权重总和），仅限本进程
     pub fn new() -> Self {
-        let quota = Quota::per_minute(NonZeroU32::new(2400).unwrap());
-        let limiter = RateLimiter::direct(quota);
+        Self::with_backend(&LimiterBackend::Memory)
+    }
+
+    /// 按后端创建各市场的权重预算
+    pub fn with_backend(backend: &LimiterBackend) -> Self {
+        let budgets = MarketType::ALL
+            .iter()
+            .map(|m| (*m, Self::weight_budget(backend, *m, Self::QUOTA_PER_MINUTE)))
+            .collect();
+        let endpoints = EndpointQuota::ALL
+            .iter()
+            .map(|q| {
+                let budget =
+                    backend.limiter(q.key(), q.requests_per_5min(), Duration::from_secs(300));
+                (*q, budget)
+            })
+            .collect();
         Self {
-            limiter: Arc::new(limiter),
+            backend: backend.clone(),
+            budgets: Arc::new(RwLock::new(budgets)),
+            endpoints,
+            state: Arc::new(Mutex::new(WeightState::default())),
         }
     }
 
+    /// 每分钟 `limit` 权重的令牌桶；Redis 后端按键共享，容量随脚本参数生效
+    fn weight_budget(
+        backend: &LimiterBackend,
+        market_type: MarketType,
+        limit: u32,
+    ) -> Arc<dyn RateBudget> {
+        let key = format!("binance:{}", market_type);
+        backend.limiter(&key, limit, Duration::from_secs(60))
+    }
+
+    fn budget(&self, market_type: MarketType) -> Arc<dyn RateBudget> {
+        self.budgets.read().unwrap()[&market_type].clone()
+    }
+
     /// 根据 limit 动态计算请求权重
     fn calc_weight(limit: u32) -> NonZeroU32 {
         match limit {
@@ -32,29 +225,221 @@ impl BinanceLimiter {
         }
     }
 
+    /// K线请求的权重，供调度器按权重预估任务开销
+    pub fn weight_for_limit(limit: u32) -> u32 {
+        Self::calc_weight(limit).get()
+    }
+
     /// 异步等待获取权重对应的令牌（含抖动）
-    pub async fn acquire_with_limit(&self, limit: u32) {
-        let weight = Self::calc_weight(limit);
-        let jitter = Jitter::up_to(Duration::from_millis(30));
-        let _ = self.limiter.until_n_ready_with_jitter(weight, jitter).await;
+    ///
+    /// 先等待熔断解除以及交易所统计的本分钟已用权重回落，再从（可能跨实例共享的）预算中扣减
+    pub async fn acquire_with_limit(&self, market_type: MarketType, limit: u32) {
+        self.acquire_weight(market_type, Self::calc_weight(limit).get())
+            .await;
+    }
+
+    /// 按固定权重等待令牌，用于权重与 limit 无关的接口（如 aggTrades）
+    pub async fn acquire_weight(&self, market_type: MarketType, weight: u32) {
+        let weight = weight.max(1);
+        loop {
+            let wait_ms = {
+                let mut state = self.state.lock().unwrap();
+                let now_ms = Utc::now().timestamp_millis();
+                let wait_ms = state.wait_ms(market_type, weight, now_ms);
+                if wait_ms == 0 {
+                    state.reserve(market_type, weight, now_ms);
+                }
+                wait_ms
+            };
+            if wait_ms == 0 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(wait_ms as u64)).await;
+        }
+
+        self.budget(market_type).acquire_n(weight).await;
     }
 
     /// 非阻塞尝试获取权重对应的令牌，返回是否成功
-    pub fn try_acquire_with_limit(&self, limit: u32) -> bool {
+    pub async fn try_acquire_with_limit(&self, market_type: MarketType, limit: u32) -> bool {
         let weight = match limit {
             1..=1000 => Self::calc_weight(limit),
             _ => return false,
         };
-        self.limiter.check_n(weight).is_ok()
+        let blocked = {
+            let state = self.state.lock().unwrap();
+            state.wait_ms(market_type, weight.get(), Utc::now().timestamp_millis()) > 0
+        };
+        if blocked || !self.budget(market_type).try_acquire_n(weight.get()).await {
+            return false;
+        }
+
+        let mut state = self.state.lock().unwrap();
+        state.reserve(market_type, weight.get(), Utc::now().timestamp_millis());
+        true
     }
 
     /// 传统的单次请求令牌异步等待（相当于权重1）
-    pub async fn acquire(&self) {
-        self.acquire_with_limit(1).await;
+    pub async fn acquire(&self, market_type: MarketType) {
+        self.acquire_with_limit(market_type, 1).await;
     }
 
     /// 传统的单次请求非阻塞尝试（相当于权重1）
-    pub fn try_acquire(&self) -> bool {
-        self.try_acquire_with_limit(1)
+    pub async fn try_acquire(&self, market_type: MarketType) -> bool {
+        self.try_acquire_with_limit(market_type, 1).await
+    }
+
+    /// 按接口单独限流的请求：等待熔断解除后扣减该接口的次数预算
+    pub async fn acquire_endpoint(&self, quota: EndpointQuota) {
+        self.wait_until_open().await;
+        self.endpoints[&quota].acquire().await;
+    }
+
+    /// 熔断期间等待，封禁解除后返回；用于不经过权重令牌的请求（如 exchangeInfo）
+    pub async fn wait_until_open(&self) {
+        loop {
+            let remaining = {
+                let state = self.state.lock().unwrap();
+                state.banned_until_ms - Utc::now().timestamp_millis()
+            };
+            if remaining <= 0 {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(remaining as u64)).await;
+        }
+    }
+
+    /// 当前生效的每分钟权重上限，exchangeInfo 同步后以交易所返回为准
+    pub fn weight_limit(&self, market_type: MarketType) -> u32 {
+        self.state.lock().unwrap().limit(market_type)
+    }
+
+    /// 熔断是否生效中
+    pub fn is_tripped(&self) -> bool {
+        self.state.lock().unwrap().banned_until_ms > Utc::now().timestamp_millis()
+    }
+
+    /// 根据响应同步已用权重；429/418 按 Retry-After 触发熔断，暂停所有 Binance 请求
+    pub fn observe(&self, market_type: MarketType, status: StatusCode, headers: &HeaderMap) {
+        let now_ms = Utc::now().timestamp_millis();
+        let mut state = self.state.lock().unwrap();
+
+        if let Some(used) = used_weight(headers) {
+            state.record_used(market_type, used, now_ms);
+        }
+
+        if status == StatusCode::TOO_MANY_REQUESTS || status.as_u16() == 418 {
+            let secs = retry_after(headers).unwrap_or(DEFAULT_RETRY_AFTER_SECS);
+            state.trip(now_ms + secs * 1000);
+            warn!(
+                "Binance {} returned {}, pausing all Binance requests for {}s",
+                market_type, status, secs
+            );
+        }
+    }
+
+    /// 使用 exchangeInfo 中的 REQUEST_WEIGHT 限额替换默认配额，并按新限额重建该市场的令牌桶
+    pub fn sync_rate_limits(&self, market_type: MarketType, rate_limits: &[RateLimit]) {
+        let Some(limit) = minute_weight_limit(rate_limits) else {
+            return;
+        };
+        let previous = self.state.lock().unwrap().limits.insert(market_type, limit);
+        if previous.unwrap_or(Self::QUOTA_PER_MINUTE) != limit {
+            self.budgets.write().unwrap().insert(
+                market_type,
+                Self::weight_budget(&self.backend, market_type, limit),
+            );
+        }
+        if previous != Some(limit) {
+            info!(
+                "Binance {} request weight limit: {}/min",
+                market_type, limit
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_weight_state_follows_exchange_counts() {
+        let mut state = WeightState::default();
+        let now = 10 * MINUTE_MS + 15_000;
+        assert_eq!(state.wait_ms(MarketType::UsdM, 5, now), 0);
+
+        // 共享 IP 的其它进程已用掉大部分权重，等到下一分钟
+        state.record_used(MarketType::UsdM, 2158, now);
+        assert_eq!(state.wait_ms(MarketType::UsdM, 5, now), 45_000);
+        assert_eq!(state.wait_ms(MarketType::Spot, 5, now), 0);
+        assert_eq!(state.wait_ms(MarketType::UsdM, 5, 11 * MINUTE_MS), 0);
+
+        // exchangeInfo 返回的更高限额生效
+        state.limits.insert(MarketType::UsdM, 6000);
+        assert_eq!(state.wait_ms(MarketType::UsdM, 5, now), 0);
+
+        // 乱序到达的旧响应不会回退计数，本地预占累加
+        state.record_used(MarketType::UsdM, 100, now);
+        state.reserve(MarketType::UsdM, 5, now);
+        assert_eq!(state.used[&MarketType::UsdM].used, 2163);
+    }
+
+    #[test]
+    fn test_ban_pauses_all_markets() {
+        let mut state = WeightState::default();
+        let now = 1_000_000;
+        state.trip(now + 120_000);
+        state.trip(now + 30_000);
+        assert_eq!(state.wait_ms(MarketType::Spot, 1, now), 120_000);
+        assert_eq!(state.wait_ms(MarketType::CoinM, 1, now + 120_000), 0);
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-mbx-used-weight-1m", HeaderValue::from_static("1234"));
+        headers.insert("retry-after", HeaderValue::from_static("77"));
+        assert_eq!(used_weight(&headers), Some(1234));
+        assert_eq!(retry_after(&headers), Some(77));
+
+        let limits = vec![
+            RateLimit {
+                rate_limit_type: Some("ORDERS".into()),
+                interval: Some("MINUTE".into()),
+                interval_num: Some(1),
+                limit: Some(1200),
+            },
+            RateLimit {
+                rate_limit_type: Some("REQUEST_WEIGHT".into()),
+                interval: Some("MINUTE".into()),
+                interval_num: Some(1),
+                limit: Some(2400),
+            },
+        ];
+        assert_eq!(minute_weight_limit(&limits), Some(2400));
+    }
+
+    #[tokio::test]
+    async fn test_synced_limit_resizes_budget() {
+        let limiter = BinanceLimiter::new();
+        let spot = limiter.budget(MarketType::Spot);
+        let limits = vec![RateLimit {
+            rate_limit_type: Some("REQUEST_WEIGHT".into()),
+            interval: Some("MINUTE".into()),
+            interval_num: Some(1),
+            limit: Some(6000),
+        }];
+        limiter.sync_rate_limits(MarketType::UsdM, &limits);
+        assert_eq!(limiter.weight_limit(MarketType::UsdM), 6000);
+        assert_eq!(limiter.weight_limit(MarketType::Spot), 2400);
+
+        // U本位令牌桶按 6000 重建，其它市场不受影响
+        let usdm = limiter.budget(MarketType::UsdM);
+        assert!(usdm.try_acquire_n(2400).await);
+        assert!(usdm.try_acquire_n(2400).await);
+        assert!(Arc::ptr_eq(&spot, &limiter.budget(MarketType::Spot)));
+
+        // 限额未变化时不重建，已扣减的令牌不会被重置
+        limiter.sync_rate_limits(MarketType::UsdM, &limits);
+        assert!(Arc::ptr_eq(&usdm, &limiter.budget(MarketType::UsdM)));
     }
 }
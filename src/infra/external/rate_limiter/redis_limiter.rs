@@ -0,0 +1,226 @@
+use crate::infra::cache::kv_store::RedisKVStore;
+use crate::infra::external::rate_limiter::request_limiter::RequestLimiter;
+use crate::infra::external::rate_limiter::RateBudget;
+use async_trait::async_trait;
+use bb8_redis::redis::Script;
+use once_cell::sync::Lazy;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// 原子令牌桶：按 Redis 服务器时间补充令牌，足够时扣减并返回 0，否则返回需等待的毫秒数
+///
+/// KEYS[1] 桶；ARGV: 容量、补满周期（毫秒）、本次令牌数。`test_script_against_redis` 对真实 Redis 执行脚本（需 `REDIS_URL`）
+static TOKEN_BUCKET: Lazy<Script> = Lazy::new(|| {
+    Script::new(
+        r#"
+local capacity = tonumber(ARGV[1])
+local period_ms = tonumber(ARGV[2])
+local requested = tonumber(ARGV[3])
+
+local t = redis.call('TIME')
+local now = tonumber(t[1]) * 1000 + math.floor(tonumber(t[2]) / 1000)
+
+local state = redis.call('HMGET', KEYS[1], 'tokens', 'ts')
+local tokens = tonumber(state[1])
+local ts = tonumber(state[2])
+if tokens == nil or ts == nil then
+    tokens = capacity
+    ts = now
+end
+
+local rate = capacity / period_ms
+tokens = math.min(capacity, tokens + math.max(0, now - ts) * rate)
+
+local wait = 0
+if tokens >= requested then
+    tokens = tokens - requested
+else
+    wait = math.ceil((requested - tokens) / rate)
+end
+
+redis.call('HSET', KEYS[1], 'tokens', tostring(tokens), 'ts', now)
+redis.call('PEXPIRE', KEYS[1], period_ms * 2)
+return wait
+"#,
+    )
+});
+
+/// 多实例共享的 Redis 令牌桶
+///
+/// 同一出口 IP 上的所有进程从同一个键扣减预算；Redis 不可用时退回进程内令牌桶，避免阻塞拉取
+pub struct RedisTokenBucket {
+    kv: Arc<RedisKVStore>,
+    key: String,
+    capacity: u32,
+    period: Duration,
+    fallback: RequestLimiter,
+    /// 是否已退回进程内令牌桶，仅在切换与恢复时记录日志
+    degraded: AtomicBool,
+}
+
+impl RedisTokenBucket {
+    pub fn new(kv: Arc<RedisKVStore>, key: &str, capacity: u32, period: Duration) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            kv,
+            key: format!("ratelimit:{}", key),
+            capacity,
+            period,
+            fallback: RequestLimiter::new(capacity, period),
+            degraded: AtomicBool::new(false),
+        }
+    }
+
+    /// 尝试扣减 `n` 个令牌，返回需等待的毫秒数，0 表示已扣减
+    async fn take(&self, n: u32) -> anyhow::Result<u64> {
+        let args = [
+            self.capacity as i64,
+            self.period.as_millis().max(1) as i64,
+            n.clamp(1, self.capacity) as i64,
+        ];
+        let wait: i64 = match self
+            .kv
+            .eval_script(&TOKEN_BUCKET, &[&self.key], &args)
+            .await
+        {
+            Ok(wait) => wait,
+            Err(e) => {
+                if !self.degraded.swap(true, Ordering::Relaxed) {
+                    warn!(
+                        ?e,
+                        "Redis rate limiter {} unavailable, switching to local limiter", self.key
+                    );
+                }
+                return Err(e);
+            }
+        };
+        if self.degraded.swap(false, Ordering::Relaxed) {
+            info!("Redis rate limiter {} recovered", self.key);
+        }
+        Ok(wait.max(0) as u64)
+    }
+}
+
+#[async_trait]
+impl RateBudget for RedisTokenBucket {
+    async fn acquire_n(&self, n: u32) {
+        loop {
+            match self.take(n).await {
+                Ok(0) => return,
+                Ok(wait_ms) => tokio::time::sleep(Duration::from_millis(wait_ms)).await,
+                Err(_) => {
+                    self.fallback.acquire_n(n).await;
+                    return;
+                }
+            }
+        }
+    }
+
+    async fn try_acquire_n(&self, n: u32) -> bool {
+        match self.take(n).await {
+            Ok(wait_ms) => wait_ms == 0,
+            Err(_) => self.fallback.try_acquire_n(n).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 令牌桶状态
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct BucketState {
+        tokens: f64,
+        ts: i64,
+    }
+
+    /// 按 `TOKEN_BUCKET` 脚本的算法推算补充与扣减，返回新状态与需等待的毫秒数（0 表示已扣减）
+    ///
+    /// 只是算法的参考实现，脚本本身由 `test_script_against_redis` 验证
+    fn bucket_step(
+        state: Option<BucketState>,
+        capacity: u32,
+        period_ms: i64,
+        requested: u32,
+        now: i64,
+    ) -> (BucketState, u64) {
+        let capacity = capacity as f64;
+        let requested = requested as f64;
+        let BucketState { tokens, ts } = state.unwrap_or(BucketState {
+            tokens: capacity,
+            ts: now,
+        });
+
+        let rate = capacity / period_ms as f64;
+        let mut tokens = capacity.min(tokens + (now - ts).max(0) as f64 * rate);
+
+        let mut wait = 0;
+        if tokens >= requested {
+            tokens -= requested;
+        } else {
+            wait = ((requested - tokens) / rate).ceil() as u64;
+        }
+        (BucketState { tokens, ts: now }, wait)
+    }
+
+    #[test]
+    fn test_bucket_refill_and_wait() {
+        // 2400 个令牌 / 60 秒，即每毫秒补充 0.04 个
+        let (state, wait) = bucket_step(None, 2400, 60_000, 5, 1_000);
+        assert_eq!(wait, 0);
+        assert_eq!(
+            state,
+            BucketState {
+                tokens: 2395.0,
+                ts: 1_000
+            }
+        );
+
+        // 桶空时按缺口等待：缺 5 个令牌需 125 毫秒
+        let empty = BucketState {
+            tokens: 0.0,
+            ts: 1_000,
+        };
+        let (state, wait) = bucket_step(Some(empty), 2400, 60_000, 5, 1_000);
+        assert_eq!(wait, 125);
+        assert_eq!(state.tokens, 0.0);
+
+        // 等待后补足并扣减
+        let (state, wait) = bucket_step(Some(state), 2400, 60_000, 5, 1_125);
+        assert_eq!(wait, 0);
+        assert!(state.tokens.abs() < 1e-9);
+
+        // 长时间空闲不会超过容量，时钟回拨不扣减令牌
+        let (state, _) = bucket_step(Some(empty), 2400, 60_000, 0, 10_000_000);
+        assert_eq!(state.tokens, 2400.0);
+        let (state, wait) = bucket_step(Some(state), 2400, 60_000, 1, 9_000_000);
+        assert_eq!(wait, 0);
+        assert_eq!(state.tokens, 2399.0);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Redis (REDIS_URL, or LOCAL for localhost)"]
+    async fn test_script_against_redis() {
+        let kv = crate::common::utils::make_kv_store().await.unwrap();
+        let key = format!("test:{}", chrono::Utc::now().timestamp_nanos_opt().unwrap());
+        // 10 个令牌 / 秒，即每 100 毫秒补充 1 个
+        let bucket = RedisTokenBucket::new(kv, &key, 10, Duration::from_secs(1));
+
+        // 新桶为满，一次取完
+        assert_eq!(bucket.take(10).await.unwrap(), 0);
+        // 桶空后按缺口等待，最多 5 个令牌 × 100 毫秒
+        let wait = bucket.take(5).await.unwrap();
+        assert!(wait > 0 && wait <= 500, "wait {}", wait);
+        assert!(!bucket.try_acquire_n(5).await);
+
+        // 等待后补足，超过容量的请求按容量计
+        tokio::time::sleep(Duration::from_millis(wait + 20)).await;
+        assert_eq!(bucket.take(5).await.unwrap(), 0);
+        tokio::time::sleep(Duration::from_millis(1_100)).await;
+        assert_eq!(bucket.take(100).await.unwrap(), 0);
+        assert!(bucket.take(1).await.unwrap() > 0);
+    }
+}
@@ -0,0 +1,70 @@
+use crate::infra::external::rate_limiter::RateBudget;
+use async_trait::async_trait;
+use governor::{
+    clock::DefaultClock,
+    state::{InMemoryState, NotKeyed},
+    Jitter, Quota, RateLimiter,
+};
+use std::{num::NonZeroU32, sync::Arc, time::Duration};
+
+type InnerLimiter = RateLimiter<NotKeyed, InMemoryState, DefaultClock>;
+
+/// 进程内令牌桶，按请求次数或权重计数
+#[derive(Clone)]
+pub struct RequestLimiter {
+    limiter: Arc<InnerLimiter>,
+    burst: NonZeroU32,
+}
+
+impl RequestLimiter {
+    /// 每 `period` 内最多 `requests` 次请求，令牌匀速恢复
+    pub fn new(requests: u32, period: Duration) -> Self {
+        let burst = NonZeroU32::new(requests.max(1)).unwrap();
+        let quota = Quota::with_period(period / burst.get())
+            .expect("rate limit period must be non-zero")
+            .allow_burst(burst);
+        Self {
+            limiter: Arc::new(RateLimiter::direct(quota)),
+            burst,
+        }
+    }
+
+    fn tokens(&self, n: u32) -> NonZeroU32 {
+        NonZeroU32::new(n.clamp(1, self.burst.get())).unwrap()
+    }
+}
+
+#[async_trait]
+impl RateBudget for RequestLimiter {
+    /// 异步等待令牌（含抖动）
+    async fn acquire_n(&self, n: u32) {
+        let jitter = Jitter::up_to(Duration::from_millis(30));
+        let _ = self
+            .limiter
+            .until_n_ready_with_jitter(self.tokens(n), jitter)
+            .await;
+    }
+
+    async fn try_acquire_n(&self, n: u32) -> bool {
+        self.limiter
+            .check_n(self.tokens(n))
+            .is_ok_and(|r| r.is_ok())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_oversized_request_is_capped_to_burst() {
+        let limiter = RequestLimiter::new(10, Duration::from_millis(100));
+        assert!(limiter.try_acquire_n(4).await);
+
+        // 超过容量的请求按容量计，等待补满即可，不会永久等待
+        tokio::time::timeout(Duration::from_secs(1), limiter.acquire_n(100))
+            .await
+            .unwrap();
+        assert!(!limiter.try_acquire_n(1).await);
+    }
+}
@@ -1,6 +1,20 @@
 use crate::impl_table_record;
+use crate::model::cex::depth::OrderBookRow;
+use crate::model::cex::derivatives::{
+    FundingRateRow, LongShortRatioRow, OpenInterestRow, PriceKlineRow,
+};
 use crate::model::cex::kline::MarketKline;
+use crate::model::cex::trade::AggTradeRow;
 
+pub mod depth;
+pub mod derivatives;
 pub mod kline;
+pub mod trade;
 
 impl_table_record!(MarketKline, MarketKline, "market_klines");
+impl_table_record!(FundingRateRow, FundingRate, "funding_rates");
+impl_table_record!(OpenInterestRow, OpenInterest, "open_interest_hist");
+impl_table_record!(LongShortRatioRow, LongShortRatio, "long_short_ratios");
+impl_table_record!(PriceKlineRow, PriceKline, "price_klines");
+impl_table_record!(AggTradeRow, AggTrade, "agg_trades");
+impl_table_record!(OrderBookRow, OrderBook, "order_book_snapshots");
@@ -0,0 +1,18 @@
+use clickhouse::Row;
+use serde::{Deserialize, Serialize};
+
+/// 订单簿前 N 档快照，买卖盘按档位顺序存为并列数组（买盘价格从高到低，卖盘从低到高）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Row)]
+pub struct OrderBookRow {
+    pub exchange: String,
+    pub market_type: String,
+    pub symbol: String,
+    /// 采样时刻（毫秒），为采样周期的起点
+    pub snapshot_time: i64,
+    /// 采样时本地订单簿已应用到的更新 ID
+    pub last_update_id: u64,
+    pub bid_prices: Vec<f64>,
+    pub bid_qtys: Vec<f64>,
+    pub ask_prices: Vec<f64>,
+    pub ask_qtys: Vec<f64>,
+}
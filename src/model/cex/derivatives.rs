@@ -0,0 +1,122 @@
+use crate::infra::external::binance::futures::{
+    FundingRate, LongShortRatio, OpenInterestHist, PriceKline,
+};
+use clickhouse::Row;
+use serde::{Deserialize, Serialize};
+
+/// 资金费率历史
+#[derive(Debug, Clone, Serialize, Deserialize, Row)]
+pub struct FundingRateRow {
+    pub exchange: String,
+    pub symbol: String,
+    pub funding_time: i64,
+    pub funding_rate: f64,
+    pub mark_price: Option<f64>,
+}
+
+/// 合约持仓量历史
+#[derive(Debug, Clone, Serialize, Deserialize, Row)]
+pub struct OpenInterestRow {
+    pub exchange: String,
+    pub symbol: String,
+    pub period: String,
+    pub timestamp: i64,
+    pub sum_open_interest: f64,
+    pub sum_open_interest_value: f64,
+}
+
+/// 多空比，ratio_type 区分全市场账户 / 大户账户 / 大户持仓
+#[derive(Debug, Clone, Serialize, Deserialize, Row)]
+pub struct LongShortRatioRow {
+    pub exchange: String,
+    pub symbol: String,
+    pub ratio_type: String,
+    pub period: String,
+    pub timestamp: i64,
+    pub long_short_ratio: f64,
+    pub long_account: f64,
+    pub short_account: f64,
+}
+
+/// 标记价格 / 指数价格K线，price_type 为 mark / index
+#[derive(Debug, Clone, Serialize, Deserialize, Row)]
+pub struct PriceKlineRow {
+    pub exchange: String,
+    pub symbol: String,
+    pub price_type: String,
+    pub period: String,
+    pub open_time: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub close_time: i64,
+}
+
+/// 序列在 ClickHouse 中的位置：表、时间列与区分子序列的过滤条件，用于查询归档进度
+#[derive(Debug, Clone)]
+pub struct SeriesLocator {
+    pub table: &'static str,
+    pub time_column: &'static str,
+    pub filters: Vec<(&'static str, String)>,
+}
+
+impl From<(&FundingRate, &str)> for FundingRateRow {
+    fn from((r, exchange): (&FundingRate, &str)) -> Self {
+        FundingRateRow {
+            exchange: exchange.to_string(),
+            symbol: r.symbol.clone(),
+            funding_time: r.funding_time,
+            funding_rate: r.funding_rate,
+            mark_price: r.mark_price,
+        }
+    }
+}
+
+impl From<(&OpenInterestHist, &str, &str)> for OpenInterestRow {
+    fn from((r, exchange, period): (&OpenInterestHist, &str, &str)) -> Self {
+        OpenInterestRow {
+            exchange: exchange.to_string(),
+            symbol: r.symbol.clone(),
+            period: period.to_string(),
+            timestamp: r.timestamp,
+            sum_open_interest: r.sum_open_interest,
+            sum_open_interest_value: r.sum_open_interest_value,
+        }
+    }
+}
+
+impl From<(&LongShortRatio, &str, &str, &str)> for LongShortRatioRow {
+    fn from((r, exchange, ratio_type, period): (&LongShortRatio, &str, &str, &str)) -> Self {
+        LongShortRatioRow {
+            exchange: exchange.to_string(),
+            symbol: r.symbol.clone(),
+            ratio_type: ratio_type.to_string(),
+            period: period.to_string(),
+            timestamp: r.timestamp,
+            long_short_ratio: r.long_short_ratio,
+            long_account: r.long_account,
+            short_account: r.short_account,
+        }
+    }
+}
+
+/// 指数价格K线按标的对返回，symbol 仍记为请求的合约，便于与其它序列关联
+impl From<(&PriceKline, &str, &str, &str, &str)> for PriceKlineRow {
+    fn from(
+        (k, exchange, symbol, price_type, period): (&PriceKline, &str, &str, &str, &str),
+    ) -> Self {
+        PriceKlineRow {
+            exchange: exchange.to_string(),
+            symbol: symbol.to_string(),
+            price_type: price_type.to_string(),
+            period: period.to_string(),
+            open_time: k.open_time,
+            open: k.open,
+            high: k.high,
+            low: k.low,
+            close: k.close,
+            close_time: k.close_time,
+        }
+    }
+}
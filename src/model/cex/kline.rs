@@ -1,3 +1,4 @@
+use crate::domain::model::market_kline::MarketKline as MysqlMarketKline;
 use crate::infra::external::binance::market::KlineSummary;
 use clickhouse::Row;
 use serde::{Deserialize, Serialize};
@@ -5,6 +6,7 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Clone, Serialize, Deserialize, Row)]
 pub struct MarketKline {
     pub exchange: String,
+    pub market_type: String,
     pub symbol: String,
     pub period: String,
 
@@ -28,11 +30,19 @@ pub struct MinMaxCloseTime {
     pub max_close_time: i64,
 }
 
+#[derive(Debug, Deserialize, Row)]
+pub struct CloseTimeRow {
+    pub close_time: i64,
+}
+
 /// convert KlineSummary to MarketKline
-impl From<(&KlineSummary, &str, &str, &str)> for MarketKline {
-    fn from((s, exchange, symbol, period): (&KlineSummary, &str, &str, &str)) -> Self {
+impl From<(&KlineSummary, &str, &str, &str, &str)> for MarketKline {
+    fn from(
+        (s, exchange, market_type, symbol, period): (&KlineSummary, &str, &str, &str, &str),
+    ) -> Self {
         MarketKline {
             exchange: exchange.to_string(),
+            market_type: market_type.to_string(),
             symbol: symbol.to_string(),
             period: period.to_string(),
 
@@ -51,3 +61,47 @@ impl From<(&KlineSummary, &str, &str, &str)> for MarketKline {
         }
     }
 }
+
+/// convert MySQL MarketKline to MarketKline, missing optional fields default to 0
+impl From<&MysqlMarketKline> for MarketKline {
+    fn from(k: &MysqlMarketKline) -> Self {
+        MarketKline {
+            exchange: k.exchange.clone(),
+            market_type: k.market_type.clone(),
+            symbol: k.symbol.clone(),
+            period: k.time_frame.clone(),
+
+            open_time: k.open_time,
+            open: k.open,
+            high: k.high,
+            low: k.low,
+            close: k.close,
+            volume: k.volume,
+            close_time: k.close_time,
+
+            quote_asset_volume: k.quote_asset_volume.unwrap_or_default(),
+            number_of_trades: k.number_of_trades.unwrap_or_default(),
+            taker_buy_base_asset_volume: k.taker_buy_base_asset_volume.unwrap_or_default(),
+            taker_buy_quote_asset_volume: k.taker_buy_quote_asset_volume.unwrap_or_default(),
+        }
+    }
+}
+
+/// convert MarketKline back to KlineSummary
+impl From<&MarketKline> for KlineSummary {
+    fn from(k: &MarketKline) -> Self {
+        KlineSummary {
+            open_time: k.open_time,
+            open: k.open,
+            high: k.high,
+            low: k.low,
+            close: k.close,
+            volume: k.volume,
+            close_time: k.close_time,
+            quote_asset_volume: k.quote_asset_volume,
+            number_of_trades: k.number_of_trades as i64,
+            taker_buy_base_asset_volume: k.taker_buy_base_asset_volume,
+            taker_buy_quote_asset_volume: k.taker_buy_quote_asset_volume,
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use crate::infra::external::binance::market::AggTrade;
+use clickhouse::Row;
+use serde::{Deserialize, Serialize};
+
+/// 归集成交，按 (exchange, market_type, symbol, agg_id) 去重
+#[derive(Debug, Clone, Serialize, Deserialize, Row)]
+pub struct AggTradeRow {
+    pub exchange: String,
+    pub market_type: String,
+    pub symbol: String,
+    pub agg_id: u64,
+    pub price: f64,
+    pub qty: f64,
+    pub first_trade_id: u64,
+    pub last_trade_id: u64,
+    pub trade_time: i64,
+    pub is_buyer_maker: bool,
+}
+
+impl AggTradeRow {
+    /// 归集的原始成交笔数，与K线的 number_of_trades 口径一致
+    pub fn trade_count(&self) -> u64 {
+        self.last_trade_id.saturating_sub(self.first_trade_id) + 1
+    }
+}
+
+/// 已归档的归集成交 ID 与时间范围
+#[derive(Debug, Clone, Deserialize, Row)]
+pub struct AggTradeRange {
+    pub min_id: u64,
+    pub max_id: u64,
+    pub min_time: i64,
+    pub max_time: i64,
+}
+
+/// 归集成交 ID 的缺口：(prev_id, next_id) 之间的 ID 缺失
+#[derive(Debug, Clone, Deserialize, Row)]
+pub struct AggTradeGap {
+    pub prev_id: u64,
+    pub next_id: u64,
+}
+
+impl From<(&AggTrade, &str, &str, &str)> for AggTradeRow {
+    fn from((t, exchange, market_type, symbol): (&AggTrade, &str, &str, &str)) -> Self {
+        AggTradeRow {
+            exchange: exchange.to_string(),
+            market_type: market_type.to_string(),
+            symbol: symbol.to_string(),
+            agg_id: t.agg_id as u64,
+            price: t.price,
+            qty: t.qty,
+            first_trade_id: t.first_trade_id as u64,
+            last_trade_id: t.last_trade_id as u64,
+            trade_time: t.trade_time,
+            is_buyer_maker: t.is_buyer_maker,
+        }
+    }
+}
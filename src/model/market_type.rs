@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// 市场类型：现货、U本位合约、币本位合约
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MarketType {
+    Spot,
+    UsdM,
+    CoinM,
+}
+
+impl Default for MarketType {
+    fn default() -> Self {
+        MarketType::UsdM
+    }
+}
+
+impl MarketType {
+    pub const ALL: [MarketType; 3] = [MarketType::Spot, MarketType::UsdM, MarketType::CoinM];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MarketType::Spot => "spot",
+            MarketType::UsdM => "usdm",
+            MarketType::CoinM => "coinm",
+        }
+    }
+}
+
+impl fmt::Display for MarketType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for MarketType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "spot" => Ok(MarketType::Spot),
+            "usdm" => Ok(MarketType::UsdM),
+            "coinm" => Ok(MarketType::CoinM),
+            _ => Err(format!("Unsupported market type: {}", s)),
+        }
+    }
+}
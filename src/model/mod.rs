@@ -1,9 +1,11 @@
-use chrono::Duration;
+use chrono::{DateTime, Datelike, Duration, Months};
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 
 pub mod cex;
 pub mod constant;
 pub mod dex;
+pub mod market_type;
 
 pub static DEFAULT_TIMEFRAMES: &[TimeFrame] = &[
     TimeFrame::M1,
@@ -60,6 +62,38 @@ impl TimeFrame {
         }
     }
 
+    // 根据开盘时间计算收盘时间（毫秒），月线按自然月计算
+    pub fn close_time(&self, open_time: i64) -> i64 {
+        if *self == TimeFrame::M1L {
+            if let Some(next) = DateTime::from_timestamp_millis(open_time)
+                .and_then(|t| t.with_day(1))
+                .and_then(|t| t.checked_add_months(Months::new(1)))
+            {
+                return next.timestamp_millis() - 1;
+            }
+        }
+        open_time + self.to_millis() - 1
+    }
+
+    // 计算时间戳所在K线的开盘时间（毫秒），与交易所对齐规则一致：
+    // 日内周期与日线按 UTC 纪元对齐，周线从周一开始，月线按自然月
+    pub fn bucket_open_time(&self, ts: i64) -> i64 {
+        match self {
+            TimeFrame::W1 => {
+                // 1970-01-01 为周四，1970-01-05 为第一个周一
+                let monday = Duration::days(4).num_milliseconds();
+                let week = self.to_millis();
+                (ts - monday).div_euclid(week) * week + monday
+            }
+            TimeFrame::M1L => DateTime::from_timestamp_millis(ts)
+                .and_then(|t| t.with_day(1))
+                .and_then(|t| t.date_naive().and_hms_opt(0, 0, 0))
+                .map(|t| t.and_utc().timestamp_millis())
+                .unwrap_or(ts),
+            _ => ts.div_euclid(self.to_millis()) * self.to_millis(),
+        }
+    }
+
     // period
     pub fn to_period(&self) -> i64 {
         match self {
@@ -102,3 +136,29 @@ impl TimeFrame {
         }
     }
 }
+
+impl FromStr for TimeFrame {
+    type Err = String;
+
+    // 解析交易所周期字符串，如 "1m"、"4h"、"1M"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1m" => Ok(TimeFrame::M1),
+            "3m" => Ok(TimeFrame::M3),
+            "5m" => Ok(TimeFrame::M5),
+            "15m" => Ok(TimeFrame::M15),
+            "30m" => Ok(TimeFrame::M30),
+            "1h" => Ok(TimeFrame::H1),
+            "2h" => Ok(TimeFrame::H2),
+            "4h" => Ok(TimeFrame::H4),
+            "6h" => Ok(TimeFrame::H6),
+            "8h" => Ok(TimeFrame::H8),
+            "12h" => Ok(TimeFrame::H12),
+            "1d" => Ok(TimeFrame::D1),
+            "3d" => Ok(TimeFrame::D3),
+            "1w" => Ok(TimeFrame::W1),
+            "1M" => Ok(TimeFrame::M1L),
+            _ => Err(format!("Unsupported time frame: {}", s)),
+        }
+    }
+}
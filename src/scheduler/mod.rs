@@ -4,6 +4,7 @@ use std::time::Duration;
 use tokio::{select, time};
 use tracing::{error, info};
 
+use crate::global::get_shutdown;
 use crate::scheduler::tasks::{get_all_tasks, ScheduledTask};
 
 pub struct Scheduler;
@@ -24,11 +25,19 @@ impl Scheduler {
     }
 }
 
-/// 单个任务的调度执行循环
+/// 单个任务的调度执行循环，收到退出信号后不再触发新一轮执行
 async fn run_periodic_task(task: ScheduledTask) {
+    let shutdown = get_shutdown();
     let mut interval = time::interval(task.interval);
     loop {
-        interval.tick().await;
+        select! {
+            _ = interval.tick() => {}
+            _ = shutdown.wait() => {
+                info!(task = task.name, "Scheduler stopped");
+                return;
+            }
+        }
+        let _guard = shutdown.track();
 
         let task_name = task.name;
         let task_fn = task.task_fn;
@@ -1,6 +1,12 @@
+pub mod dead_letter;
+pub mod dump_import;
 pub mod fetch_cgecko;
+pub mod gap_repair;
 pub mod history_data;
 pub mod notify_info;
+pub mod resample;
+pub mod tiering;
+pub mod trades;
 
 use std::time::Duration;
 
@@ -66,7 +72,42 @@ pub fn get_all_tasks() -> Vec<ScheduledTask> {
             Duration::from_secs(3600),
             history_data::exchange_history_data
         ),
-        // todo 定期将最新数据合并到clickhouse mysql只保留近三个月数据
+        // K线缺口扫描与修复
+        task!(
+            "scan_and_repair_kline_gaps",
+            Duration::from_secs(86400),
+            gap_repair::scan_and_repair_kline_gaps
+        ),
+        // 由 1m 历史派生高周期K线
+        task!(
+            "resample_kline_history",
+            Duration::from_secs(3600),
+            resample::resample_kline_history
+        ),
+        // 写库失败批次的自动重放
+        task!(
+            "retry_dead_letters",
+            Duration::from_secs(60),
+            dead_letter::retry_dead_letters
+        ),
+        // 归集成交历史同步与缺口补齐
+        task!(
+            "sync_agg_trade_history",
+            Duration::from_secs(300),
+            trades::sync_agg_trade_history
+        ),
+        // 离线导入公开数据包，REST 归档只补齐数据包之后的部分
+        task!(
+            "import_binance_dumps",
+            Duration::from_secs(3600),
+            dump_import::import_binance_dumps
+        ),
+        // 冷热分层：超过保留期的K线迁移到 ClickHouse，MySQL 只保留近期数据
+        task!(
+            "tier_market_klines",
+            Duration::from_secs(86400),
+            tiering::tier_market_klines
+        ),
         // todo 定期数据清洗
     ]
 }
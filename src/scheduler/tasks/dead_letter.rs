@@ -0,0 +1,14 @@
+use crate::collector::archive::dead_letter::retry_due;
+use tracing::{info, warn};
+
+/// 异步任务：按退避时间自动重放到期的写库死信
+pub async fn retry_dead_letters() -> Result<(), anyhow::Error> {
+    let (replayed, failed) = retry_due().await?;
+    if replayed > 0 {
+        info!("Replayed {} dead-lettered kline batches", replayed);
+    }
+    if failed > 0 {
+        warn!("{} dead-lettered kline batches still failing", failed);
+    }
+    Ok(())
+}
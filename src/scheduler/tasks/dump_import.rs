@@ -0,0 +1,25 @@
+use crate::collector::archive::dump_import::{import_dumps, DumpImportConfig};
+use crate::common::utils::get_env_bool;
+use tracing::info;
+
+/// 异步任务：导入 ARCHIVE_DUMP_DIR 中新增的 Binance 公开数据包
+pub async fn import_binance_dumps() -> Result<(), anyhow::Error> {
+    if !get_env_bool("ENABLE_CLICKHOUSE", true) {
+        info!("Dump import skipped (ENABLE_CLICKHOUSE=false)");
+        return Ok(());
+    }
+
+    let config = DumpImportConfig::from_env();
+    if config.dir.is_none() {
+        return Ok(());
+    }
+
+    let report = import_dumps(&config).await?;
+    if report.imported > 0 || report.failed > 0 {
+        info!(
+            "Dump import: {} imported ({} klines), {} skipped, {} failed",
+            report.imported, report.klines, report.skipped, report.failed
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use crate::collector::archive::gap::{repair_open_gaps, scan_and_record_gaps, GapStore};
+use crate::collector::archive::universe::{resolve_archive_universe, UniverseConfig};
+use crate::common::utils::get_env_bool;
+use tracing::warn;
+
+/// 异步任务：扫描归档范围内所有市场的K线缺口，并通过 KlineFetcher 定向补齐
+pub async fn scan_and_repair_kline_gaps() -> Result<(), anyhow::Error> {
+    let targets = resolve_archive_universe(&UniverseConfig::from_env()).await?;
+
+    let mut stores = vec![GapStore::MySql];
+    if get_env_bool("ENABLE_CLICKHOUSE", true) {
+        stores.push(GapStore::ClickHouse);
+    }
+
+    for target in &targets {
+        for store in &stores {
+            if let Err(e) = scan_and_record_gaps(
+                &target.exchange,
+                target.market_type,
+                &target.symbol,
+                &target.time_frame,
+                *store,
+            )
+            .await
+            {
+                warn!(?e, "Gap scan failed for {:?} ({})", target, store.as_str());
+                continue;
+            }
+
+            if let Err(e) = repair_open_gaps(
+                &target.exchange,
+                target.market_type,
+                &target.symbol,
+                &target.time_frame,
+                *store,
+            )
+            .await
+            {
+                warn!(
+                    ?e,
+                    "Gap repair failed for {:?} ({})",
+                    target,
+                    store.as_str()
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use crate::collector::archive::universe::{resolve_archive_universe, UniverseConfig};
+use crate::collector::resample::history::resample_history;
+use crate::collector::resample::{ResampleConfig, SOURCE_TIME_FRAME};
+use crate::common::utils::get_env_bool;
+use std::collections::BTreeSet;
+use tracing::{info, warn};
+
+/// 异步任务：由 ClickHouse 中归档的 1m 历史派生配置的高周期K线
+pub async fn resample_kline_history() -> Result<(), anyhow::Error> {
+    if !get_env_bool("ENABLE_CLICKHOUSE", true) {
+        info!("Kline resampling skipped (ENABLE_CLICKHOUSE=false)");
+        return Ok(());
+    }
+
+    let config = ResampleConfig::from_env();
+    let markets: BTreeSet<_> = resolve_archive_universe(&UniverseConfig::from_env())
+        .await?
+        .into_iter()
+        .filter(|t| t.time_frame == SOURCE_TIME_FRAME)
+        .map(|t| (t.exchange, t.market_type, t.symbol))
+        .collect();
+
+    for (exchange, market_type, symbol) in &markets {
+        for time_frame in &config.time_frames {
+            if let Err(e) = resample_history(exchange, *market_type, symbol, time_frame).await {
+                warn!(
+                    ?e,
+                    "Resampling {} failed for {} {} {}",
+                    time_frame.to_str(),
+                    exchange,
+                    market_type,
+                    symbol
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
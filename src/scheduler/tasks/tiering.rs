@@ -0,0 +1,12 @@
+use crate::collector::archive::tiering::{run_tiering, TieringConfig};
+use crate::common::utils::get_env_bool;
+
+/// 异步任务：将超过保留期的 MySQL K线迁移到 ClickHouse，校验一致后从 MySQL 删除
+pub async fn tier_market_klines() -> Result<(), anyhow::Error> {
+    if !get_env_bool("KLINE_TIERING_ENABLED", false) || !get_env_bool("ENABLE_CLICKHOUSE", true) {
+        return Ok(());
+    }
+
+    run_tiering(&TieringConfig::from_env()).await?;
+    Ok(())
+}
@@ -0,0 +1,28 @@
+use crate::collector::trades::history::sync_agg_trades;
+use crate::collector::trades::TradeConfig;
+use crate::common::utils::get_env_bool;
+use tracing::{info, warn};
+
+/// 异步任务：同步 TRADE_SYMBOLS 中各交易对的归集成交历史并补齐 ID 缺口
+pub async fn sync_agg_trade_history() -> Result<(), anyhow::Error> {
+    if !get_env_bool("ENABLE_CLICKHOUSE", true) {
+        info!("Agg trade sync skipped (ENABLE_CLICKHOUSE=false)");
+        return Ok(());
+    }
+
+    let config = TradeConfig::from_env();
+    for (market_type, symbol) in config.targets() {
+        match sync_agg_trades(market_type, &symbol, &config).await {
+            Ok(written) if written > 0 => {
+                info!(
+                    "Synced {} agg trades for {} {}",
+                    written, market_type, symbol
+                )
+            }
+            Ok(_) => {}
+            Err(e) => warn!(?e, "Agg trade sync failed for {} {}", market_type, symbol),
+        }
+    }
+
+    Ok(())
+}
@@ -1,5 +1,32 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    archive_checkpoint (id) {
+        #[max_length = 250]
+        id -> Varchar,
+        #[max_length = 64]
+        exchange -> Varchar,
+        #[max_length = 16]
+        market_type -> Varchar,
+        #[max_length = 64]
+        symbol -> Varchar,
+        #[max_length = 16]
+        time_frame -> Varchar,
+        #[max_length = 16]
+        direction -> Varchar,
+        window_start -> Bigint,
+        window_end -> Bigint,
+        #[max_length = 16]
+        status -> Varchar,
+        attempts -> Unsigned<Integer>,
+        kline_count -> Bigint,
+        #[max_length = 255]
+        last_error -> Nullable<Varchar>,
+        created_at -> Datetime,
+        updated_at -> Datetime,
+    }
+}
+
 diesel::table! {
     coin_categories (id) {
         #[max_length = 64]
@@ -87,12 +114,65 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    kline_gap (id) {
+        #[max_length = 250]
+        id -> Varchar,
+        #[max_length = 64]
+        exchange -> Varchar,
+        #[max_length = 16]
+        market_type -> Varchar,
+        #[max_length = 64]
+        symbol -> Varchar,
+        #[max_length = 16]
+        time_frame -> Varchar,
+        #[max_length = 16]
+        store -> Varchar,
+        gap_start -> Bigint,
+        gap_end -> Bigint,
+        missing_count -> Bigint,
+        filled_count -> Bigint,
+        #[max_length = 16]
+        status -> Varchar,
+        attempts -> Unsigned<Integer>,
+        detected_at -> Datetime,
+        updated_at -> Datetime,
+    }
+}
+
+diesel::table! {
+    kline_quarantine (id) {
+        #[max_length = 250]
+        id -> Varchar,
+        #[max_length = 64]
+        exchange -> Varchar,
+        #[max_length = 16]
+        market_type -> Varchar,
+        #[max_length = 64]
+        symbol -> Varchar,
+        #[max_length = 16]
+        time_frame -> Varchar,
+        #[max_length = 16]
+        direction -> Varchar,
+        open_time -> Bigint,
+        close_time -> Bigint,
+        #[max_length = 32]
+        rule -> Varchar,
+        #[max_length = 255]
+        detail -> Varchar,
+        payload -> Json,
+        detected_at -> Datetime,
+    }
+}
+
 diesel::table! {
     market_kline (id) {
         #[max_length = 250]
         id -> Varchar,
         #[max_length = 64]
         exchange -> Varchar,
+        #[max_length = 16]
+        market_type -> Varchar,
         #[max_length = 64]
         symbol -> Varchar,
         #[max_length = 16]
@@ -117,6 +197,8 @@ diesel::table! {
         id -> Varchar,
         #[max_length = 50]
         exchange -> Varchar,
+        #[max_length = 16]
+        market_type -> Varchar,
         #[max_length = 50]
         symbol -> Varchar,
         #[max_length = 50]
@@ -159,9 +241,12 @@ diesel::table! {
 }
 
 diesel::allow_tables_to_appear_in_same_query!(
+    archive_checkpoint,
     coin_categories,
     coin_data_info,
     coin_rank_info,
+    kline_gap,
+    kline_quarantine,
     market_kline,
     market_symbol,
 );
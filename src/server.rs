@@ -1,11 +1,18 @@
-use crate::global::init_global_services;
+use crate::collector::archive::flush::force_flush_all;
+use crate::collector::depth::recorder::start_depth_recorder;
+use crate::collector::stream::kline_stream::start_kline_stream;
+use crate::collector::trades::stream::start_trade_stream;
+use crate::common::shutdown::wait_for_signal;
+use crate::common::utils::{get_env_bool, get_env_or};
+use crate::global::{get_ck_db, get_flush_buffer, get_shutdown, init_global_services};
 use crate::scheduler::Scheduler;
 use listen_tracing::{LogCache, LogEntry};
 use std::env;
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::broadcast;
-use tracing::info;
+use tracing::{error, info, warn};
 use warp::Filter;
 
 mod response;
@@ -45,6 +52,33 @@ pub async fn start() {
         scheduler.run().await;
     });
 
+    // 实时K线采集（WebSocket）
+    if get_env_bool("ENABLE_KLINE_STREAM", false) {
+        tokio::spawn(async move {
+            if let Err(e) = start_kline_stream().await {
+                tracing::error!(?e, "Failed to start kline stream");
+            }
+        });
+    }
+
+    // 实时归集成交采集（WebSocket）
+    if get_env_bool("ENABLE_TRADE_STREAM", false) {
+        tokio::spawn(async move {
+            if let Err(e) = start_trade_stream().await {
+                tracing::error!(?e, "Failed to start trade stream");
+            }
+        });
+    }
+
+    // 订单簿深度记录（REST 快照 + WebSocket 增量）
+    if get_env_bool("ENABLE_DEPTH_RECORDER", false) {
+        tokio::spawn(async move {
+            if let Err(e) = start_depth_recorder().await {
+                tracing::error!(?e, "Failed to start depth recorder");
+            }
+        });
+    }
+
     let bind_address: SocketAddr = "127.0.0.1:10099".parse().unwrap();
 
     // init app
@@ -55,9 +89,52 @@ pub async fn start() {
 
     let routes = routes::routes(app_state).with(warp::log(APPLICATION_NAME));
 
-    warp::serve(routes).run(bind_address).await;
+    // SIGTERM / Ctrl+C 触发统一退出信号
+    tokio::spawn(async {
+        wait_for_signal().await;
+        get_shutdown().trigger();
+    });
+
+    let shutdown = get_shutdown();
+    let (addr, server) = warp::serve(routes)
+        .bind_with_graceful_shutdown(bind_address, async move { shutdown.wait().await });
+    info!("You can access the server at {}", addr);
+    server.await;
+
+    drain_on_shutdown().await;
+}
+
+/// 退出流程：等待进行中的任务在截止时间内结束，强制写出 KlineBuffer，关闭 ClickHouse inserter
+///
+/// - `SHUTDOWN_TIMEOUT_SECS`：等待进行中任务的最长时间，默认 30 秒
+async fn drain_on_shutdown() {
+    let shutdown = get_shutdown();
+    let deadline = Duration::from_secs(get_env_or("SHUTDOWN_TIMEOUT_SECS", 30));
+
+    info!(
+        "Waiting up to {:?} for {} in-flight tasks...",
+        deadline,
+        shutdown.in_flight()
+    );
+    if !shutdown.wait_idle(deadline).await {
+        warn!(
+            "{} tasks still running after {:?}, flushing buffered klines anyway",
+            shutdown.in_flight(),
+            deadline
+        );
+    }
+
+    if let Err(e) = force_flush_all(&*get_flush_buffer()).await {
+        error!(?e, "Failed to flush kline buffer on shutdown");
+    }
+
+    if get_env_bool("ENABLE_CLICKHOUSE", true) {
+        if let Err(e) = get_ck_db().close_inserters().await {
+            error!(?e, "Failed to close ClickHouse inserters");
+        }
+    }
 
-    info!("You can access the server at {}", bind_address);
+    info!("Shutdown complete");
 }
 
 fn get_absolute_path(file_name: &str) -> String {
@@ -1,3 +1,6 @@
+use crate::domain::model::{AppResult, PageResult, SortOrder};
+use serde::Serialize;
+use warp::http::StatusCode;
 use warp::Reply;
 
 pub type CusResponse = Result<warp::reply::Json, warp::Rejection>;
@@ -13,3 +16,77 @@ impl Reply for ErrorResponse {
 }
 
 impl warp::reject::Reject for ErrorResponse {}
+
+/// 以 JSON 返回错误信息，如 {"error": "..."}
+pub fn error_reply(
+    status: warp::http::StatusCode,
+    message: impl ToString,
+) -> warp::reply::Response {
+    warp::reply::with_status(
+        warp::reply::json(&serde_json::json!({ "error": message.to_string() })),
+        status,
+    )
+    .into_response()
+}
+
+/// 页码从 1 开始，每页默认 100 条，最多 1000 条；返回 (页码, 每页条数)
+pub fn page_params(page: Option<i64>, page_size: Option<i64>) -> (i64, i64) {
+    (
+        page.unwrap_or(1).max(1),
+        page_size.unwrap_or(100).clamp(1, 1000),
+    )
+}
+
+/// 解析 asc / desc（不区分大小写），未传时返回 None
+pub fn parse_sort_order(order: Option<&str>) -> Result<Option<SortOrder>, String> {
+    match order.map(str::to_ascii_lowercase).as_deref() {
+        None => Ok(None),
+        Some("asc") => Ok(Some(SortOrder::Asc)),
+        Some("desc") => Ok(Some(SortOrder::Desc)),
+        Some(other) => Err(format!(
+            "Unsupported order: {} (expected asc or desc)",
+            other
+        )),
+    }
+}
+
+/// 以分页结果应答，页码改回从 1 开始
+pub fn page_reply<T: Serialize>(
+    result: AppResult<PageResult<T>>,
+    page: i64,
+) -> warp::reply::Response {
+    match result {
+        Ok(mut result) => {
+            result.page = page;
+            warp::reply::json(&result).into_response()
+        }
+        Err(e) => error_reply(StatusCode::INTERNAL_SERVER_ERROR, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_params() {
+        assert_eq!(page_params(None, None), (1, 100));
+        assert_eq!(page_params(Some(0), Some(0)), (1, 1));
+        assert_eq!(page_params(Some(-3), Some(5000)), (1, 1000));
+        assert_eq!(page_params(Some(7), Some(50)), (7, 50));
+    }
+
+    #[test]
+    fn test_parse_sort_order() {
+        assert!(matches!(parse_sort_order(None), Ok(None)));
+        assert!(matches!(
+            parse_sort_order(Some("asc")),
+            Ok(Some(SortOrder::Asc))
+        ));
+        assert!(matches!(
+            parse_sort_order(Some("DESC")),
+            Ok(Some(SortOrder::Desc))
+        ));
+        assert!(parse_sort_order(Some("random")).is_err());
+    }
+}
@@ -1,5 +1,30 @@
 use super::AppState;
+use crate::server::routes::handlers::archive_handlers::{
+    checkpoint_summary, dispatcher_stats, run_kline_tiering, CheckpointSummaryQuery, TieringQuery,
+};
+use crate::server::routes::handlers::backfill_handlers::{
+    control_backfill, get_backfill, list_backfills, start_backfill,
+};
+use crate::server::routes::handlers::data_quality_handlers::{
+    list_quarantine, quality_summary, QualitySummaryQuery, QuarantineQuery,
+};
+use crate::server::routes::handlers::dead_letter_handlers::{
+    discard_dead_letter, get_dead_letter, list_dead_letters, replay_all_dead_letters,
+    replay_dead_letter,
+};
+use crate::server::routes::handlers::gap_handlers::{
+    gap_summary, list_gaps, GapQuery, GapSummaryQuery,
+};
+use crate::server::routes::handlers::kline_handlers::{query_klines, KlineQuery};
+use crate::server::routes::handlers::live_handlers::{sse_klines, ws_klines, LiveKlineQuery};
 use crate::server::routes::handlers::log_handlers::{query_logs, sse_logs, with_cache, with_tx};
+use crate::server::routes::handlers::market_data_handlers::{
+    list_coin_categories, list_coin_data, list_coin_ranks, list_symbols, symbol_overview,
+    CoinCategoryQuery, CoinDataQuery, CoinRankQuery, SymbolQuery,
+};
+use crate::server::routes::handlers::trade_handlers::{
+    trade_bars, trade_consistency, TradeBarsQuery, TradeConsistencyQuery,
+};
 use listen_tracing::LogQuery;
 use warp::{self, Filter};
 
@@ -32,6 +57,135 @@ pub fn routes(
             ),
         )
         .and_then(query_logs);
+    // 数据质量：校验隔离汇总与明细
+    let quality_summary = api
+        .and(warp::path!("data-quality" / "summary"))
+        .and(warp::get())
+        .and(warp::query::<QualitySummaryQuery>())
+        .and_then(quality_summary);
+    let quarantine = api
+        .and(warp::path!("data-quality" / "quarantine"))
+        .and(warp::get())
+        .and(warp::query::<QuarantineQuery>())
+        .and_then(list_quarantine);
+    // 归档检查点：各任务窗口进度与未完成工作
+    let checkpoints = api
+        .and(warp::path!("archive" / "checkpoints"))
+        .and(warp::get())
+        .and(warp::query::<CheckpointSummaryQuery>())
+        .and_then(checkpoint_summary);
+    let dispatcher = api
+        .and(warp::path!("archive" / "dispatcher"))
+        .and(warp::get())
+        .and_then(dispatcher_stats);
+    // 数据完整度：缺口明细与按市场汇总
+    let gaps = api
+        .and(warp::path!("archive" / "gaps"))
+        .and(warp::get())
+        .and(warp::query::<GapQuery>())
+        .and_then(list_gaps);
+    let gaps_summary = api
+        .and(warp::path!("archive" / "gaps" / "summary"))
+        .and(warp::get())
+        .and(warp::query::<GapSummaryQuery>())
+        .and_then(gap_summary);
+    // 冷热分层：手动执行或试运行
+    let tiering = api
+        .and(warp::path!("archive" / "tiering"))
+        .and(warp::post())
+        .and(warp::query::<TieringQuery>())
+        .and_then(run_kline_tiering);
+    // 按需回补：发起、查询、暂停/恢复/取消
+    let backfill_start = api
+        .and(warp::path!("backfill"))
+        .and(warp::post())
+        .and(warp::body::json())
+        .and_then(start_backfill);
+    let backfill_list = api
+        .and(warp::path!("backfill"))
+        .and(warp::get())
+        .and_then(list_backfills);
+    let backfill_get = api
+        .and(warp::path!("backfill" / String))
+        .and(warp::get())
+        .and_then(get_backfill);
+    let backfill_control = api
+        .and(warp::path!("backfill" / String / String))
+        .and(warp::post())
+        .and_then(control_backfill);
+    // 死信：写库失败批次的查看、重放与丢弃
+    let dead_letters = api
+        .and(warp::path!("dead-letters"))
+        .and(warp::get())
+        .and_then(list_dead_letters);
+    let dead_letters_replay_all = api
+        .and(warp::path!("dead-letters" / "replay"))
+        .and(warp::post())
+        .and_then(replay_all_dead_letters);
+    let dead_letter = api
+        .and(warp::path!("dead-letters" / String))
+        .and(warp::get())
+        .and_then(get_dead_letter);
+    let dead_letter_replay = api
+        .and(warp::path!("dead-letters" / String / "replay"))
+        .and(warp::post())
+        .and_then(replay_dead_letter);
+    let dead_letter_discard = api
+        .and(warp::path!("dead-letters" / String))
+        .and(warp::delete())
+        .and_then(discard_dead_letter);
+    // 归集成交：聚合K线与K线一致性检查
+    let trade_bars = api
+        .and(warp::path!("trades" / "bars"))
+        .and(warp::get())
+        .and(warp::query::<TradeBarsQuery>())
+        .and_then(trade_bars);
+    let trade_consistency = api
+        .and(warp::path!("trades" / "consistency"))
+        .and(warp::get())
+        .and(warp::query::<TradeConsistencyQuery>())
+        .and_then(trade_consistency);
+    // K线查询：合并 MySQL 与 ClickHouse，按 close_time 游标分页
+    let klines = api
+        .and(warp::path!("klines"))
+        .and(warp::get())
+        .and(warp::query::<KlineQuery>())
+        .and_then(query_klines);
+    // 实时K线推送：SSE 按查询参数订阅，WebSocket 通过指令增减订阅
+    let klines_live = api
+        .and(warp::path!("klines" / "live"))
+        .and(warp::get())
+        .and(warp::query::<LiveKlineQuery>())
+        .and_then(sse_klines);
+    let klines_ws = api
+        .and(warp::path!("klines" / "ws"))
+        .and(warp::ws())
+        .and_then(ws_klines);
+    // 交易对与 CoinGecko 元数据
+    let symbols = api
+        .and(warp::path!("symbols"))
+        .and(warp::get())
+        .and(warp::query::<SymbolQuery>())
+        .and_then(list_symbols);
+    let symbol_overview = api
+        .and(warp::path!("symbols" / String / String / String))
+        .and(warp::get())
+        .and_then(symbol_overview);
+    let coin_ranks = api
+        .and(warp::path!("coins" / "ranks"))
+        .and(warp::get())
+        .and(warp::query::<CoinRankQuery>())
+        .and_then(list_coin_ranks);
+    let coin_categories = api
+        .and(warp::path!("coins" / "categories"))
+        .and(warp::get())
+        .and(warp::query::<CoinCategoryQuery>())
+        .and_then(list_coin_categories);
+    let coins = api
+        .and(warp::path!("coins"))
+        .and(warp::get())
+        .and(warp::query::<CoinDataQuery>())
+        .and_then(list_coin_data);
     // 日志服务路由
     // let log_routes = api.and(warp::path("logs")
     //     .and(
@@ -56,6 +210,32 @@ pub fn routes(
         .or(version)
         .or(sysinfo)
         .or(health)
+        .or(quality_summary)
+        .or(quarantine)
+        .or(checkpoints)
+        .or(dispatcher)
+        .or(gaps)
+        .or(gaps_summary)
+        .or(tiering)
+        .or(backfill_start)
+        .or(backfill_list)
+        .or(backfill_get)
+        .or(backfill_control)
+        .or(dead_letters)
+        .or(dead_letters_replay_all)
+        .or(dead_letter)
+        .or(dead_letter_replay)
+        .or(dead_letter_discard)
+        .or(trade_bars)
+        .or(trade_consistency)
+        .or(klines)
+        .or(klines_live)
+        .or(klines_ws)
+        .or(symbols)
+        .or(symbol_overview)
+        .or(coin_ranks)
+        .or(coin_categories)
+        .or(coins)
 }
 
 fn with_state(
@@ -1,4 +1,13 @@
+pub mod archive_handlers;
+pub mod backfill_handlers;
+pub mod data_quality_handlers;
+pub mod dead_letter_handlers;
+pub mod gap_handlers;
+pub mod kline_handlers;
+pub mod live_handlers;
 pub mod log_handlers;
+pub mod market_data_handlers;
+pub mod trade_handlers;
 
 pub fn index() -> &'static str {
     "Welcome to mini bot!"
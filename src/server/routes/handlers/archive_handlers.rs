@@ -0,0 +1,65 @@
+use crate::collector::archive::tiering::{run_tiering, TieringConfig};
+use crate::domain::repository::archive_checkpoint_repository::ArchiveCheckpointRepository;
+use crate::domain::service::archive_checkpoint_service::ArchiveCheckpointService;
+use crate::global::{get_dispatch_stats, get_mysql_pool};
+use crate::server::response::error_reply;
+use serde::Deserialize;
+use warp::http::StatusCode;
+use warp::{Rejection, Reply};
+
+/// 归档检查点汇总查询参数
+#[derive(Debug, Deserialize)]
+pub struct CheckpointSummaryQuery {
+    pub exchange: Option<String>,
+    pub symbol: Option<String>,
+    /// 仅返回仍有未完成窗口的任务
+    pub outstanding_only: Option<bool>,
+}
+
+/// GET /api/archive/checkpoints：按归档任务统计各状态窗口数量与未完成工作
+pub async fn checkpoint_summary(params: CheckpointSummaryQuery) -> Result<impl Reply, Rejection> {
+    let mut conn = match get_mysql_pool().get() {
+        Ok(conn) => conn,
+        Err(e) => return Ok(error_reply(StatusCode::SERVICE_UNAVAILABLE, e)),
+    };
+    let repo = ArchiveCheckpointRepository::new(&mut conn);
+    let mut service = ArchiveCheckpointService { repo };
+
+    match service.outstanding_summary(params.exchange.as_deref(), params.symbol.as_deref()) {
+        Ok(mut summary) => {
+            if params.outstanding_only.unwrap_or(false) {
+                summary.retain(|s| s.outstanding > 0);
+            }
+            Ok(warp::reply::json(&summary).into_response())
+        }
+        Err(e) => Ok(error_reply(StatusCode::INTERNAL_SERVER_ERROR, e)),
+    }
+}
+
+/// GET /api/archive/dispatcher：调度器排队深度、在途权重与各优先级等待时间
+pub async fn dispatcher_stats() -> Result<impl Reply, Rejection> {
+    Ok(warp::reply::json(&get_dispatch_stats().snapshot()))
+}
+
+/// 冷热分层手动执行参数，未指定时沿用环境变量配置
+#[derive(Debug, Deserialize)]
+pub struct TieringQuery {
+    pub dry_run: Option<bool>,
+    pub max_days: Option<usize>,
+}
+
+/// POST /api/archive/tiering：将超过保留期的 MySQL K线迁移到 ClickHouse 并返回报告，`dry_run=true` 只比对
+pub async fn run_kline_tiering(params: TieringQuery) -> Result<impl Reply, Rejection> {
+    let mut config = TieringConfig::from_env();
+    if let Some(dry_run) = params.dry_run {
+        config.dry_run = dry_run;
+    }
+    if let Some(max_days) = params.max_days {
+        config.max_days = max_days.max(1);
+    }
+
+    match run_tiering(&config).await {
+        Ok(report) => Ok(warp::reply::json(&report).into_response()),
+        Err(e) => Ok(error_reply(StatusCode::INTERNAL_SERVER_ERROR, e)),
+    }
+}
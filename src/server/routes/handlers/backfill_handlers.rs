@@ -0,0 +1,47 @@
+use crate::collector::archive::backfill::{BackfillAction, BackfillRequest};
+use crate::global::get_backfill_manager;
+use crate::server::response::error_reply;
+use warp::http::StatusCode;
+use warp::{Rejection, Reply};
+
+/// POST /api/backfill：按时间区间发起回补任务
+pub async fn start_backfill(req: BackfillRequest) -> Result<impl Reply, Rejection> {
+    match get_backfill_manager().start(req).await {
+        Ok(progress) => Ok(warp::reply::with_status(
+            warp::reply::json(&progress),
+            StatusCode::ACCEPTED,
+        )
+        .into_response()),
+        Err(e) => Ok(error_reply(StatusCode::BAD_REQUEST, e)),
+    }
+}
+
+/// GET /api/backfill：列出所有回补任务及进度
+pub async fn list_backfills() -> Result<impl Reply, Rejection> {
+    let jobs = get_backfill_manager().list().await;
+    Ok(warp::reply::json(&jobs).into_response())
+}
+
+/// GET /api/backfill/{id}：查询回补任务进度
+pub async fn get_backfill(id: String) -> Result<impl Reply, Rejection> {
+    match get_backfill_manager().get(&id).await {
+        Some(progress) => Ok(warp::reply::json(&progress).into_response()),
+        None => Ok(error_reply(StatusCode::NOT_FOUND, "backfill job not found")),
+    }
+}
+
+/// POST /api/backfill/{id}/{pause|resume|cancel}：控制回补任务
+pub async fn control_backfill(id: String, action: String) -> Result<impl Reply, Rejection> {
+    let action = match action.as_str() {
+        "pause" => BackfillAction::Pause,
+        "resume" => BackfillAction::Resume,
+        "cancel" => BackfillAction::Cancel,
+        _ => return Err(warp::reject::not_found()),
+    };
+
+    match get_backfill_manager().control(&id, action).await {
+        Ok(Some(progress)) => Ok(warp::reply::json(&progress).into_response()),
+        Ok(None) => Ok(error_reply(StatusCode::NOT_FOUND, "backfill job not found")),
+        Err(e) => Ok(error_reply(StatusCode::CONFLICT, e)),
+    }
+}
@@ -0,0 +1,68 @@
+use crate::domain::model::kline_quarantine::KlineQuarantineFilter;
+use crate::domain::model::SortOrder;
+use crate::domain::repository::kline_quarantine_repository::KlineQuarantineRepository;
+use crate::domain::service::kline_quarantine_service::KlineQuarantineService;
+use crate::global::get_mysql_pool;
+use crate::server::response::{error_reply, page_params, page_reply};
+use serde::Deserialize;
+use warp::http::StatusCode;
+use warp::{Rejection, Reply};
+
+/// 数据质量汇总查询参数
+#[derive(Debug, Deserialize)]
+pub struct QualitySummaryQuery {
+    pub exchange: Option<String>,
+    pub symbol: Option<String>,
+}
+
+/// 隔离记录分页查询参数
+#[derive(Debug, Deserialize)]
+pub struct QuarantineQuery {
+    pub exchange: Option<String>,
+    pub market_type: Option<String>,
+    pub symbol: Option<String>,
+    pub time_frame: Option<String>,
+    pub rule: Option<String>,
+    pub page: Option<i64>,
+    pub page_size: Option<i64>,
+}
+
+/// GET /api/data-quality/summary：按市场与规则统计被隔离的K线数量
+pub async fn quality_summary(params: QualitySummaryQuery) -> Result<impl Reply, Rejection> {
+    let mut conn = match get_mysql_pool().get() {
+        Ok(conn) => conn,
+        Err(e) => return Ok(error_reply(StatusCode::SERVICE_UNAVAILABLE, e)),
+    };
+    let repo = KlineQuarantineRepository::new(&mut conn);
+    let mut service = KlineQuarantineService { repo };
+
+    match service.quality_summary(params.exchange.as_deref(), params.symbol.as_deref()) {
+        Ok(summary) => Ok(warp::reply::json(&summary).into_response()),
+        Err(e) => Ok(error_reply(StatusCode::INTERNAL_SERVER_ERROR, e)),
+    }
+}
+
+/// GET /api/data-quality/quarantine：分页查询隔离记录，按 close_time 倒序
+pub async fn list_quarantine(params: QuarantineQuery) -> Result<impl Reply, Rejection> {
+    let mut conn = match get_mysql_pool().get() {
+        Ok(conn) => conn,
+        Err(e) => return Ok(error_reply(StatusCode::SERVICE_UNAVAILABLE, e)),
+    };
+    let repo = KlineQuarantineRepository::new(&mut conn);
+    let mut service = KlineQuarantineService { repo };
+
+    let (page, per_page) = page_params(params.page, params.page_size);
+    let filter = KlineQuarantineFilter {
+        exchange: params.exchange,
+        market_type: params.market_type,
+        symbol: params.symbol,
+        time_frame: params.time_frame,
+        rule: params.rule,
+        sort_by_close_time: Some(SortOrder::Desc),
+        page: None,
+        page_size: None,
+    };
+
+    let result = service.query_page_with_total(filter, page - 1, per_page);
+    Ok(page_reply(result, page))
+}
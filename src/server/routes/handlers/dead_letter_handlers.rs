@@ -0,0 +1,50 @@
+use crate::collector::archive::dead_letter;
+use crate::server::response::error_reply;
+use warp::http::StatusCode;
+use warp::{Rejection, Reply};
+
+/// GET /api/dead-letters：列出写库失败的批次（不含消息体）
+pub async fn list_dead_letters() -> Result<impl Reply, Rejection> {
+    match dead_letter::list().await {
+        Ok(letters) => Ok(warp::reply::json(&letters).into_response()),
+        Err(e) => Ok(error_reply(StatusCode::INTERNAL_SERVER_ERROR, e)),
+    }
+}
+
+/// GET /api/dead-letters/{id}：查看死信详情（含消息体）
+pub async fn get_dead_letter(id: String) -> Result<impl Reply, Rejection> {
+    match dead_letter::get(&id).await {
+        Ok(Some(letter)) => Ok(warp::reply::json(&letter).into_response()),
+        Ok(None) => Ok(error_reply(StatusCode::NOT_FOUND, "dead letter not found")),
+        Err(e) => Ok(error_reply(StatusCode::INTERNAL_SERVER_ERROR, e)),
+    }
+}
+
+/// POST /api/dead-letters/{id}/replay：立即重放单条死信
+pub async fn replay_dead_letter(id: String) -> Result<impl Reply, Rejection> {
+    match dead_letter::replay(&id).await {
+        Ok(true) => Ok(warp::reply::json(&serde_json::json!({ "replayed": id })).into_response()),
+        Ok(false) => Ok(error_reply(StatusCode::NOT_FOUND, "dead letter not found")),
+        Err(e) => Ok(error_reply(StatusCode::BAD_GATEWAY, e)),
+    }
+}
+
+/// POST /api/dead-letters/replay：立即重放全部死信
+pub async fn replay_all_dead_letters() -> Result<impl Reply, Rejection> {
+    match dead_letter::replay_all().await {
+        Ok((replayed, failed)) => Ok(warp::reply::json(
+            &serde_json::json!({ "replayed": replayed, "failed": failed }),
+        )
+        .into_response()),
+        Err(e) => Ok(error_reply(StatusCode::INTERNAL_SERVER_ERROR, e)),
+    }
+}
+
+/// DELETE /api/dead-letters/{id}：丢弃死信
+pub async fn discard_dead_letter(id: String) -> Result<impl Reply, Rejection> {
+    match dead_letter::discard(&id).await {
+        Ok(true) => Ok(warp::reply::json(&serde_json::json!({ "discarded": id })).into_response()),
+        Ok(false) => Ok(error_reply(StatusCode::NOT_FOUND, "dead letter not found")),
+        Err(e) => Ok(error_reply(StatusCode::INTERNAL_SERVER_ERROR, e)),
+    }
+}
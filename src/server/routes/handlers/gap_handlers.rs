@@ -0,0 +1,69 @@
+use crate::domain::model::kline_gap::KlineGapFilter;
+use crate::domain::model::SortOrder;
+use crate::domain::service::kline_gap_service::KlineGapService;
+use crate::global::get_mysql_pool;
+use crate::server::response::{error_reply, page_params, page_reply};
+use serde::Deserialize;
+use warp::http::StatusCode;
+use warp::{Rejection, Reply};
+
+/// 数据完整度汇总查询参数
+#[derive(Debug, Deserialize)]
+pub struct GapSummaryQuery {
+    pub exchange: Option<String>,
+    pub symbol: Option<String>,
+}
+
+/// 缺口分页查询参数
+#[derive(Debug, Deserialize)]
+pub struct GapQuery {
+    pub exchange: Option<String>,
+    pub market_type: Option<String>,
+    pub symbol: Option<String>,
+    pub time_frame: Option<String>,
+    /// mysql / clickhouse
+    pub store: Option<String>,
+    /// found / filled / unfillable
+    pub status: Option<String>,
+    pub page: Option<i64>,
+    pub page_size: Option<i64>,
+}
+
+/// GET /api/archive/gaps/summary：按市场与存储统计缺口的待修复、已修复与无法修复数量
+pub async fn gap_summary(params: GapSummaryQuery) -> Result<impl Reply, Rejection> {
+    let mut conn = match get_mysql_pool().get() {
+        Ok(conn) => conn,
+        Err(e) => return Ok(error_reply(StatusCode::SERVICE_UNAVAILABLE, e)),
+    };
+    let mut service = KlineGapService::new(&mut conn);
+
+    match service.completeness_summary(params.exchange.as_deref(), params.symbol.as_deref()) {
+        Ok(summary) => Ok(warp::reply::json(&summary).into_response()),
+        Err(e) => Ok(error_reply(StatusCode::INTERNAL_SERVER_ERROR, e)),
+    }
+}
+
+/// GET /api/archive/gaps：分页查询缺口记录，按缺口起点倒序
+pub async fn list_gaps(params: GapQuery) -> Result<impl Reply, Rejection> {
+    let mut conn = match get_mysql_pool().get() {
+        Ok(conn) => conn,
+        Err(e) => return Ok(error_reply(StatusCode::SERVICE_UNAVAILABLE, e)),
+    };
+    let mut service = KlineGapService::new(&mut conn);
+
+    let (page, per_page) = page_params(params.page, params.page_size);
+    let filter = KlineGapFilter {
+        exchange: params.exchange,
+        market_type: params.market_type,
+        symbol: params.symbol,
+        time_frame: params.time_frame,
+        store: params.store,
+        status: params.status,
+        sort_by_gap_start: Some(SortOrder::Desc),
+        page: None,
+        page_size: None,
+    };
+
+    let result = service.query_page_with_total(filter, page - 1, per_page);
+    Ok(page_reply(result, page))
+}
@@ -0,0 +1,351 @@
+use crate::collector::archive::gap::GapStore;
+use crate::common::utils::get_env_bool;
+use crate::domain::model::market_kline::MarketKlineFilter;
+use crate::domain::model::SortOrder as DomainSortOrder;
+use crate::domain::service::market_kline_service::MarketKlineService;
+use crate::global::{get_ck_db, get_mysql_pool};
+use crate::infra::db::kline_store::KlineSeries;
+use crate::infra::db::types::{PageParams, Paginatable, SortOrder};
+use crate::model::cex::kline::MarketKline;
+use crate::model::market_type::MarketType;
+use crate::model::TimeFrame;
+use crate::server::response::error_reply;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use warp::http::StatusCode;
+use warp::{Rejection, Reply};
+
+const DEFAULT_LIMIT: usize = 500;
+const MAX_LIMIT: usize = 1500;
+
+/// K线查询参数，时间范围按 close_time 且含边界（毫秒）
+#[derive(Debug, Deserialize)]
+pub struct KlineQuery {
+    /// 默认 binance
+    pub exchange: Option<String>,
+    /// spot / usdm / coinm，默认 usdm
+    pub market_type: Option<String>,
+    pub symbol: String,
+    /// K线周期，如 1m、1h、1d
+    pub interval: String,
+    pub start: Option<i64>,
+    pub end: Option<i64>,
+    /// 默认 500，最大 1500
+    pub limit: Option<usize>,
+    /// asc / desc，默认 asc
+    pub order: Option<String>,
+    /// 上一页返回的 next_cursor，需与首次查询使用相同的参数
+    pub cursor: Option<String>,
+    /// mysql（追溯写入的实时数据，默认）/ clickhouse（回溯与分层后的历史数据）
+    pub store: Option<String>,
+}
+
+/// K线查询结果
+///
+/// `total` 为本次查询范围内（带游标时为游标之后，含本页）的K线总数，`next_cursor` 为空表示已无更多数据
+#[derive(Debug, Serialize)]
+pub struct KlinePage {
+    pub data: Vec<MarketKline>,
+    pub total: usize,
+    pub next_cursor: Option<String>,
+}
+
+/// 分页游标：记录上一页最后一根K线的 close_time 与排序方向，编码为 URL 安全的 base64
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct KlineCursor {
+    descending: bool,
+    close_time: i64,
+}
+
+impl KlineCursor {
+    fn encode(&self) -> String {
+        let order = if self.descending { "desc" } else { "asc" };
+        URL_SAFE_NO_PAD.encode(format!("{}:{}", order, self.close_time))
+    }
+
+    fn decode(cursor: &str) -> Result<Self, String> {
+        let invalid = || format!("Invalid cursor: {}", cursor);
+        let raw = URL_SAFE_NO_PAD.decode(cursor).map_err(|_| invalid())?;
+        let raw = String::from_utf8(raw).map_err(|_| invalid())?;
+        let (order, close_time) = raw.split_once(':').ok_or_else(invalid)?;
+        Ok(KlineCursor {
+            descending: parse_descending(order)?,
+            close_time: close_time.parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+fn parse_descending(order: &str) -> Result<bool, String> {
+    match order.to_ascii_lowercase().as_str() {
+        "asc" => Ok(false),
+        "desc" => Ok(true),
+        _ => Err(format!(
+            "Unsupported order: {} (expected asc or desc)",
+            order
+        )),
+    }
+}
+
+/// 校验后的查询：存储、序列、时间范围、条数与排序
+struct KlineRequest {
+    store: GapStore,
+    series: KlineSeries,
+    start: i64,
+    end: i64,
+    limit: usize,
+    descending: bool,
+}
+
+impl KlineRequest {
+    fn parse(params: &KlineQuery) -> Result<Self, String> {
+        let market_type = params
+            .market_type
+            .as_deref()
+            .map_or(Ok(MarketType::UsdM), MarketType::from_str)?;
+        let time_frame = TimeFrame::from_str(&params.interval)?;
+        let store = match params.store.as_deref() {
+            None => GapStore::MySql,
+            Some(name) => {
+                GapStore::from_name(name).ok_or_else(|| format!("Unsupported store: {}", name))?
+            }
+        };
+        let descending = params
+            .order
+            .as_deref()
+            .map_or(Ok(false), parse_descending)?;
+        let limit = params.limit.unwrap_or(DEFAULT_LIMIT);
+        if limit == 0 || limit > MAX_LIMIT {
+            return Err(format!("limit must be between 1 and {}", MAX_LIMIT));
+        }
+
+        let mut start = params.start.unwrap_or(0).max(0);
+        let mut end = params.end.unwrap_or(i64::MAX);
+        if start > end {
+            return Err(format!("start ({}) must not be after end ({})", start, end));
+        }
+
+        // 游标收窄时间范围：升序从上一页之后开始，降序到上一页之前为止
+        if let Some(cursor) = &params.cursor {
+            let cursor = KlineCursor::decode(cursor)?;
+            if cursor.descending != descending {
+                return Err("cursor does not match order".to_string());
+            }
+            if descending {
+                end = end.min(cursor.close_time.saturating_sub(1));
+            } else {
+                start = start.max(cursor.close_time.saturating_add(1));
+            }
+        }
+
+        Ok(KlineRequest {
+            store,
+            series: KlineSeries::new(
+                &params
+                    .exchange
+                    .as_deref()
+                    .unwrap_or("binance")
+                    .to_lowercase(),
+                market_type,
+                &params.symbol.to_uppercase(),
+                time_frame,
+            ),
+            start,
+            end,
+            limit,
+            descending,
+        })
+    }
+}
+
+impl KlineRequest {
+    fn sort_order(&self) -> SortOrder {
+        if self.descending {
+            SortOrder::Desc
+        } else {
+            SortOrder::Asc
+        }
+    }
+
+    /// 经 `MarketKlineService::query_page_with_total` 查询 MySQL 的第一页
+    fn query_mysql(&self) -> anyhow::Result<(Vec<MarketKline>, usize)> {
+        let filter = MarketKlineFilter {
+            exchange: Some(self.series.exchange.clone()),
+            market_type: Some(self.series.market_type.as_str().to_string()),
+            symbol: Some(self.series.symbol.clone()),
+            time_frame: Some(self.series.time_frame.to_str().to_string()),
+            close_time: None,
+            start_close_time: Some(self.start),
+            end_close_time: Some(self.end),
+            sort_by_close_time: Some(match self.sort_order() {
+                SortOrder::Asc => DomainSortOrder::Asc,
+                SortOrder::Desc => DomainSortOrder::Desc,
+            }),
+            page: None,
+            page_size: None,
+        };
+        let mut conn = get_mysql_pool().get()?;
+        let result = MarketKlineService::new(&mut conn).query_page_with_total(
+            filter,
+            0,
+            self.limit as i64,
+        )?;
+        Ok((
+            result.data.iter().map(Into::into).collect(),
+            result.total.max(0) as usize,
+        ))
+    }
+
+    /// 经 `Paginatable<MarketKline>` 查询 ClickHouse 的第一页
+    async fn query_clickhouse(&self) -> anyhow::Result<(Vec<MarketKline>, usize)> {
+        let params = PageParams {
+            limit: self.limit,
+            offset: 0,
+            sort_order: self.sort_order(),
+            start_time: Some(self.start.max(0) as u64),
+            end_time: Some(self.end.max(0) as u64),
+        };
+        let result = get_ck_db()
+            .get_paginated(
+                &self.series.exchange,
+                self.series.market_type.as_str(),
+                &self.series.symbol,
+                self.series.time_frame.to_str(),
+                &params,
+            )
+            .await?;
+        Ok((result.items, result.total))
+    }
+}
+
+/// GET /api/klines：按 close_time 排序查询 MySQL 或 ClickHouse 中的K线，以游标分页并返回总数
+pub async fn query_klines(params: KlineQuery) -> Result<impl Reply, Rejection> {
+    let request = match KlineRequest::parse(&params) {
+        Ok(request) => request,
+        Err(e) => return Ok(error_reply(StatusCode::BAD_REQUEST, e)),
+    };
+    if request.store == GapStore::ClickHouse && !get_env_bool("ENABLE_CLICKHOUSE", true) {
+        return Ok(error_reply(
+            StatusCode::BAD_REQUEST,
+            "ClickHouse is disabled (ENABLE_CLICKHOUSE=false)",
+        ));
+    }
+
+    let result = match request.store {
+        GapStore::MySql => request.query_mysql(),
+        GapStore::ClickHouse => request.query_clickhouse().await,
+    };
+    let (data, total) = match result {
+        Ok(page) => page,
+        Err(e) => return Ok(error_reply(StatusCode::INTERNAL_SERVER_ERROR, e)),
+    };
+
+    // 本页之后仍有数据时返回游标
+    let next_cursor = match data.last() {
+        Some(last) if total > data.len() => Some(
+            KlineCursor {
+                descending: request.descending,
+                close_time: last.close_time,
+            }
+            .encode(),
+        ),
+        _ => None,
+    };
+    Ok(warp::reply::json(&KlinePage {
+        data,
+        total,
+        next_cursor,
+    })
+    .into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(order: Option<&str>, cursor: Option<String>) -> KlineQuery {
+        KlineQuery {
+            exchange: None,
+            market_type: None,
+            symbol: "btcusdt".to_string(),
+            interval: "1m".to_string(),
+            start: Some(1_000),
+            end: Some(9_000),
+            limit: None,
+            order: order.map(str::to_string),
+            cursor,
+            store: None,
+        }
+    }
+
+    #[test]
+    fn test_cursor_round_trip() {
+        for descending in [false, true] {
+            let cursor = KlineCursor {
+                descending,
+                close_time: 1_700_000_059_999,
+            };
+            assert_eq!(KlineCursor::decode(&cursor.encode()), Ok(cursor));
+        }
+        assert!(KlineCursor::decode("not-base64!").is_err());
+        assert!(KlineCursor::decode(&URL_SAFE_NO_PAD.encode("up:1")).is_err());
+        assert!(KlineCursor::decode(&URL_SAFE_NO_PAD.encode("asc:x")).is_err());
+    }
+
+    #[test]
+    fn test_parse_defaults_and_limit_bounds() {
+        let request = KlineRequest::parse(&query(None, None)).unwrap();
+        assert_eq!(request.store, GapStore::MySql);
+        assert_eq!(request.series.exchange, "binance");
+        assert_eq!(request.series.market_type, MarketType::UsdM);
+        assert_eq!(request.series.symbol, "BTCUSDT");
+        assert_eq!(request.limit, DEFAULT_LIMIT);
+        assert!(!request.descending);
+
+        let mut params = query(None, None);
+        for (limit, ok) in [
+            (0, false),
+            (1, true),
+            (MAX_LIMIT, true),
+            (MAX_LIMIT + 1, false),
+        ] {
+            params.limit = Some(limit);
+            assert_eq!(KlineRequest::parse(&params).is_ok(), ok, "limit {}", limit);
+        }
+
+        params.limit = None;
+        params.store = Some("clickhouse".to_string());
+        assert_eq!(
+            KlineRequest::parse(&params).unwrap().store,
+            GapStore::ClickHouse
+        );
+        params.store = Some("redis".to_string());
+        assert!(KlineRequest::parse(&params).is_err());
+
+        params.store = None;
+        params.start = Some(9_001);
+        assert!(KlineRequest::parse(&params).is_err());
+    }
+
+    #[test]
+    fn test_cursor_narrows_range() {
+        let asc = KlineCursor {
+            descending: false,
+            close_time: 5_000,
+        };
+        let request = KlineRequest::parse(&query(Some("asc"), Some(asc.encode()))).unwrap();
+        assert_eq!((request.start, request.end), (5_001, 9_000));
+
+        let desc = KlineCursor {
+            descending: true,
+            close_time: 5_000,
+        };
+        let request = KlineRequest::parse(&query(Some("DESC"), Some(desc.encode()))).unwrap();
+        assert_eq!((request.start, request.end), (1_000, 4_999));
+
+        // 游标排序方向与本次查询不一致
+        assert!(KlineRequest::parse(&query(Some("asc"), Some(desc.encode()))).is_err());
+        assert!(KlineRequest::parse(&query(None, Some(desc.encode()))).is_err());
+    }
+}
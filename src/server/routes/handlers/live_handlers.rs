@@ -0,0 +1,184 @@
+use crate::collector::stream::live::{LiveChannel, LiveEvent, LiveSession};
+use crate::global::get_kline_hub;
+use crate::infra::db::kline_store::KlineStore;
+use crate::server::response::error_reply;
+use futures::{SinkExt, StreamExt};
+use serde::Deserialize;
+use warp::http::StatusCode;
+use warp::sse::Event;
+use warp::ws::{Message, WebSocket, Ws};
+use warp::{Rejection, Reply};
+
+/// SSE 订阅参数
+#[derive(Debug, Deserialize)]
+pub struct LiveKlineQuery {
+    /// 逗号分隔的频道，如 binance:usdm:BTCUSDT:1m,binance:spot:ETHUSDT:5m
+    pub channels: String,
+    /// 订阅时返回的最近K线根数，0 表示不返回快照
+    pub snapshot: Option<usize>,
+}
+
+/// WebSocket 客户端指令，如 {"op":"subscribe","channels":["binance:usdm:BTCUSDT:1m"],"snapshot":100}
+#[derive(Debug, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum LiveCommand {
+    Subscribe {
+        channels: Vec<String>,
+        snapshot: Option<usize>,
+    },
+    Unsubscribe {
+        channels: Vec<String>,
+    },
+}
+
+/// 订阅频道并生成确认与快照事件；失败时返回 Error 事件
+async fn subscribe(
+    session: &LiveSession,
+    channel: &str,
+    snapshot: Option<usize>,
+) -> Vec<LiveEvent> {
+    let channel: LiveChannel = match channel.parse() {
+        Ok(channel) => channel,
+        Err(message) => return vec![LiveEvent::Error { message }],
+    };
+    if let Err(message) = session.subscribe(channel.clone()) {
+        return vec![LiveEvent::Error { message }];
+    }
+
+    let name = channel.to_string();
+    let mut events = vec![LiveEvent::Subscribed {
+        channel: name.clone(),
+    }];
+    let n = get_kline_hub().config().snapshot_len(snapshot);
+    if n > 0 {
+        events.push(
+            match KlineStore::from_env().latest(&channel.series(), n).await {
+                Ok(klines) => LiveEvent::Snapshot {
+                    channel: name,
+                    klines,
+                },
+                Err(e) => LiveEvent::Error {
+                    message: format!("Failed to load snapshot for {}: {}", name, e),
+                },
+            },
+        );
+    }
+    events
+}
+
+fn unsubscribe(session: &LiveSession, channel: &str) -> LiveEvent {
+    match channel.parse::<LiveChannel>() {
+        Ok(channel) => {
+            session.unsubscribe(&channel);
+            LiveEvent::Unsubscribed {
+                channel: channel.to_string(),
+            }
+        }
+        Err(message) => LiveEvent::Error { message },
+    }
+}
+
+/// GET /api/klines/live：以 SSE 推送所订阅频道新写入或更新的K线，订阅时先返回最近 N 根快照
+pub async fn sse_klines(params: LiveKlineQuery) -> Result<impl Reply, Rejection> {
+    let channels: Vec<String> = params
+        .channels
+        .split(',')
+        .map(str::trim)
+        .filter(|c| !c.is_empty())
+        .map(str::to_string)
+        .collect();
+    if channels.is_empty() {
+        return Ok(error_reply(StatusCode::BAD_REQUEST, "channels is required"));
+    }
+    let mut session = match get_kline_hub().connect() {
+        Ok(session) => session,
+        Err(e) => return Ok(error_reply(StatusCode::TOO_MANY_REQUESTS, e)),
+    };
+
+    let stream = async_stream::stream! {
+        for channel in &channels {
+            for event in subscribe(&session, channel, params.snapshot).await {
+                yield Ok::<_, std::convert::Infallible>(sse_event(&event));
+            }
+        }
+        while let Some(event) = session.recv().await {
+            yield Ok(sse_event(&event));
+        }
+    };
+
+    Ok(warp::sse::reply(warp::sse::keep_alive().stream(stream)).into_response())
+}
+
+fn sse_event(event: &LiveEvent) -> Event {
+    Event::default().data(serde_json::to_string(event).unwrap())
+}
+
+/// GET /api/klines/ws：WebSocket 订阅，客户端发送 subscribe / unsubscribe 指令增减频道
+pub async fn ws_klines(ws: Ws) -> Result<impl Reply, Rejection> {
+    Ok(ws.on_upgrade(run_ws_session))
+}
+
+async fn run_ws_session(socket: WebSocket) {
+    let (mut write, mut read) = socket.split();
+    let mut session = match get_kline_hub().connect() {
+        Ok(session) => session,
+        Err(message) => {
+            let _ = send_event(&mut write, &LiveEvent::Error { message }).await;
+            let _ = write.close().await;
+            return;
+        }
+    };
+
+    loop {
+        tokio::select! {
+            incoming = read.next() => {
+                let text = match incoming {
+                    Some(Ok(message)) if message.is_close() => break,
+                    Some(Ok(message)) => match message.to_str() {
+                        Ok(text) => text.to_string(),
+                        // ping / pong / binary 帧忽略
+                        Err(_) => continue,
+                    },
+                    None | Some(Err(_)) => break,
+                };
+
+                let events = match serde_json::from_str::<LiveCommand>(&text) {
+                    Ok(LiveCommand::Subscribe { channels, snapshot }) => {
+                        let mut events = vec![];
+                        for channel in &channels {
+                            events.extend(subscribe(&session, channel, snapshot).await);
+                        }
+                        events
+                    }
+                    Ok(LiveCommand::Unsubscribe { channels }) => channels
+                        .iter()
+                        .map(|channel| unsubscribe(&session, channel))
+                        .collect(),
+                    Err(e) => vec![LiveEvent::Error {
+                        message: format!("Invalid command: {}", e),
+                    }],
+                };
+                for event in &events {
+                    if send_event(&mut write, event).await.is_err() {
+                        return;
+                    }
+                }
+            }
+            event = session.recv() => {
+                let Some(event) = event else { break };
+                if send_event(&mut write, &event).await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+async fn send_event<S>(write: &mut S, event: &LiveEvent) -> Result<(), warp::Error>
+where
+    S: futures::Sink<Message, Error = warp::Error> + Unpin,
+{
+    write
+        .send(Message::text(serde_json::to_string(event).unwrap()))
+        .await
+}
@@ -0,0 +1,366 @@
+use crate::common::utils::normalize_base_asset;
+use crate::domain::model::coin_category::{CoinCategoriesFilter, CoinCategory};
+use crate::domain::model::coin_data_info::{CoinDataInfo, CoinDataInfoFilter};
+use crate::domain::model::coin_rank_info::{CoinRankInfo, CoinRankInfoFilter};
+use crate::domain::model::market_symbol::{
+    encode_market_kline_pk, MarketSymbol, MarketSymbolFilter,
+};
+use crate::domain::model::SortOrder;
+use crate::domain::service::coin_category_service::CoinCategoryService;
+use crate::domain::service::coin_data_info_service::CoinDataInfoService;
+use crate::domain::service::coin_rank_info_service::CoinRankInfoService;
+use crate::domain::service::market_symbol_service::MarketSymbolService;
+use crate::global::get_mysql_pool;
+use crate::model::market_type::MarketType;
+use crate::server::response::{error_reply, page_params, page_reply, parse_sort_order};
+use bigdecimal::BigDecimal;
+use diesel::MysqlConnection;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use warp::http::StatusCode;
+use warp::{Rejection, Reply};
+
+/// 交易对分页查询参数
+#[derive(Debug, Deserialize)]
+pub struct SymbolQuery {
+    pub exchange: Option<String>,
+    pub market_type: Option<String>,
+    pub symbol: Option<String>,
+    /// 如 TRADING
+    pub status: Option<String>,
+    /// 逗号分隔的报价资产，如 USDT,USDC
+    pub quote_assets: Option<String>,
+    pub page: Option<i64>,
+    pub page_size: Option<i64>,
+}
+
+/// 市值排名分页查询参数
+#[derive(Debug, Deserialize)]
+pub struct CoinRankQuery {
+    pub symbol: Option<String>,
+    pub symbol_like: Option<String>,
+    pub min_rank: Option<u32>,
+    pub max_rank: Option<u32>,
+    /// 按排名排序：asc / desc
+    pub order: Option<String>,
+    pub page: Option<i64>,
+    pub page_size: Option<i64>,
+}
+
+/// 板块分页查询参数
+#[derive(Debug, Deserialize)]
+pub struct CoinCategoryQuery {
+    pub name: Option<String>,
+    pub name_like: Option<String>,
+    /// 板块总市值下限（USD）
+    pub min_market_cap: Option<String>,
+    /// 24小时交易量下限（USD）
+    pub min_volume_24h: Option<String>,
+    /// 按板块市值排序：asc / desc
+    pub order: Option<String>,
+    pub page: Option<i64>,
+    pub page_size: Option<i64>,
+}
+
+/// 币种详情分页查询参数
+#[derive(Debug, Deserialize)]
+pub struct CoinDataQuery {
+    pub name: Option<String>,
+    pub name_like: Option<String>,
+    pub symbol: Option<String>,
+    pub market_cap_rank: Option<u32>,
+    /// 按排名排序：asc / desc
+    pub order: Option<String>,
+    pub page: Option<i64>,
+    pub page_size: Option<i64>,
+}
+
+/// 交易对与 CoinGecko 数据的联合视图
+#[derive(Debug, Serialize)]
+pub struct SymbolOverview {
+    pub symbol: MarketSymbol,
+    /// CoinGecko 币种ID，未匹配到时为空
+    pub coin_id: Option<String>,
+    pub rank: Option<CoinRankInfo>,
+    /// 已同步的所属板块，未同步的板块不返回
+    pub categories: Vec<CoinCategory>,
+    /// 英文描述
+    pub description: Option<String>,
+}
+
+fn parse_decimal(field: &str, value: Option<&str>) -> Result<Option<BigDecimal>, String> {
+    value
+        .map(|v| BigDecimal::from_str(v).map_err(|_| format!("Invalid {}: {}", field, v)))
+        .transpose()
+}
+
+/// GET /api/symbols：分页查询交易对
+pub async fn list_symbols(params: SymbolQuery) -> Result<impl Reply, Rejection> {
+    let market_type = match params.market_type.as_deref().map(MarketType::from_str) {
+        None => None,
+        Some(Ok(market_type)) => Some(market_type.as_str().to_string()),
+        Some(Err(e)) => return Ok(error_reply(StatusCode::BAD_REQUEST, e)),
+    };
+    let mut conn = match get_mysql_pool().get() {
+        Ok(conn) => conn,
+        Err(e) => return Ok(error_reply(StatusCode::SERVICE_UNAVAILABLE, e)),
+    };
+
+    let (page, per_page) = page_params(params.page, params.page_size);
+    let filter = MarketSymbolFilter {
+        exchange: params.exchange.map(|v| v.to_lowercase()),
+        market_type,
+        symbol: params.symbol.map(|v| v.to_uppercase()),
+        status: params.status.map(|v| v.to_uppercase()),
+        quote_assets: params.quote_assets.map(|v| {
+            v.split(',')
+                .map(|s| s.trim().to_uppercase())
+                .filter(|s| !s.is_empty())
+                .collect()
+        }),
+        page: None,
+        page_size: None,
+    };
+    let result =
+        MarketSymbolService::new(&mut conn).query_page_with_total(filter, page - 1, per_page);
+    Ok(page_reply(result, page))
+}
+
+/// GET /api/coins/ranks：分页查询 CoinGecko 市值排名
+pub async fn list_coin_ranks(params: CoinRankQuery) -> Result<impl Reply, Rejection> {
+    let sort_by_rank = match parse_sort_order(params.order.as_deref()) {
+        Ok(order) => order,
+        Err(e) => return Ok(error_reply(StatusCode::BAD_REQUEST, e)),
+    };
+    let mut conn = match get_mysql_pool().get() {
+        Ok(conn) => conn,
+        Err(e) => return Ok(error_reply(StatusCode::SERVICE_UNAVAILABLE, e)),
+    };
+
+    let (page, per_page) = page_params(params.page, params.page_size);
+    let filter = CoinRankInfoFilter {
+        symbol: params.symbol.map(|v| v.to_lowercase()),
+        symbol_like: params.symbol_like.map(|v| v.to_lowercase()),
+        min_rank: params.min_rank,
+        max_rank: params.max_rank,
+        sort_by_rank,
+        page: None,
+        page_size: None,
+    };
+    let result =
+        CoinRankInfoService::new(&mut conn).query_page_with_total(filter, page - 1, per_page);
+    Ok(page_reply(result, page))
+}
+
+/// GET /api/coins/categories：分页查询 CoinGecko 板块
+pub async fn list_coin_categories(params: CoinCategoryQuery) -> Result<impl Reply, Rejection> {
+    let filter = match category_filter(&params) {
+        Ok(filter) => filter,
+        Err(e) => return Ok(error_reply(StatusCode::BAD_REQUEST, e)),
+    };
+    let mut conn = match get_mysql_pool().get() {
+        Ok(conn) => conn,
+        Err(e) => return Ok(error_reply(StatusCode::SERVICE_UNAVAILABLE, e)),
+    };
+
+    let (page, per_page) = page_params(params.page, params.page_size);
+    let result =
+        CoinCategoryService::new(&mut conn).query_page_with_total(filter, page - 1, per_page);
+    Ok(page_reply(result, page))
+}
+
+fn category_filter(params: &CoinCategoryQuery) -> Result<CoinCategoriesFilter, String> {
+    Ok(CoinCategoriesFilter {
+        name: params.name.clone(),
+        name_like: params.name_like.clone(),
+        market_cap: parse_decimal("min_market_cap", params.min_market_cap.as_deref())?,
+        volume_24h: parse_decimal("min_volume_24h", params.min_volume_24h.as_deref())?,
+        sort_by_rank: parse_sort_order(params.order.as_deref())?,
+        page: None,
+        page_size: None,
+    })
+}
+
+/// GET /api/coins：分页查询 CoinGecko 币种详情
+pub async fn list_coin_data(params: CoinDataQuery) -> Result<impl Reply, Rejection> {
+    let sort_by_rank = match parse_sort_order(params.order.as_deref()) {
+        Ok(order) => order,
+        Err(e) => return Ok(error_reply(StatusCode::BAD_REQUEST, e)),
+    };
+    let mut conn = match get_mysql_pool().get() {
+        Ok(conn) => conn,
+        Err(e) => return Ok(error_reply(StatusCode::SERVICE_UNAVAILABLE, e)),
+    };
+
+    let (page, per_page) = page_params(params.page, params.page_size);
+    let filter = CoinDataInfoFilter {
+        name: params.name,
+        name_like: params.name_like,
+        symbol: params.symbol.map(|v| v.to_lowercase()),
+        market_cap_rank: params.market_cap_rank,
+        sort_by_rank,
+        page: None,
+        page_size: None,
+    };
+    let result =
+        CoinDataInfoService::new(&mut conn).query_page_with_total(filter, page - 1, per_page);
+    Ok(page_reply(result, page))
+}
+
+/// GET /api/symbols/{exchange}/{market_type}/{symbol}：交易对及其 CoinGecko 排名、板块与描述
+pub async fn symbol_overview(
+    exchange: String,
+    market_type: String,
+    symbol: String,
+) -> Result<impl Reply, Rejection> {
+    let market_type = match MarketType::from_str(&market_type) {
+        Ok(market_type) => market_type,
+        Err(e) => return Ok(error_reply(StatusCode::BAD_REQUEST, e)),
+    };
+    let mut conn = match get_mysql_pool().get() {
+        Ok(conn) => conn,
+        Err(e) => return Ok(error_reply(StatusCode::SERVICE_UNAVAILABLE, e)),
+    };
+
+    let id = encode_market_kline_pk(
+        &exchange.to_lowercase(),
+        market_type.as_str(),
+        &symbol.to_uppercase(),
+    );
+    let market_symbol = match MarketSymbolService::new(&mut conn).get_by_id(&id) {
+        Ok(Some(market_symbol)) => market_symbol,
+        Ok(None) => {
+            return Ok(error_reply(
+                StatusCode::NOT_FOUND,
+                format!("Symbol not found: {} {} {}", exchange, market_type, symbol),
+            ))
+        }
+        Err(e) => return Ok(error_reply(StatusCode::INTERNAL_SERVER_ERROR, e)),
+    };
+
+    match load_overview(&mut conn, market_symbol) {
+        Ok(overview) => Ok(warp::reply::json(&overview).into_response()),
+        Err(e) => Ok(error_reply(StatusCode::INTERNAL_SERVER_ERROR, e)),
+    }
+}
+
+/// 按基础资产匹配 CoinGecko 币种：同代码多个币种时取市值排名最靠前的
+fn load_overview(conn: &mut MysqlConnection, symbol: MarketSymbol) -> AppResult<SymbolOverview> {
+    let code = normalize_base_asset(&symbol.base_asset).to_lowercase();
+
+    let rank = CoinRankInfoService::new(conn)
+        .query_page_with_total(
+            CoinRankInfoFilter {
+                symbol: Some(code.clone()),
+                symbol_like: None,
+                min_rank: Some(1),
+                max_rank: None,
+                sort_by_rank: Some(SortOrder::Asc),
+                page: None,
+                page_size: None,
+            },
+            0,
+            1,
+        )?
+        .data
+        .pop();
+
+    let coin: Option<CoinDataInfo> = {
+        let mut service = CoinDataInfoService::new(conn);
+        match &rank {
+            Some(rank) => service.get_by_id(&rank.id)?,
+            None => service
+                .query_page_with_total(
+                    CoinDataInfoFilter {
+                        name: None,
+                        name_like: None,
+                        symbol: Some(code),
+                        market_cap_rank: None,
+                        sort_by_rank: Some(SortOrder::Asc),
+                        page: None,
+                        page_size: None,
+                    },
+                    0,
+                    1,
+                )?
+                .data
+                .pop(),
+        }
+    };
+
+    let names: Vec<String> = coin
+        .as_ref()
+        .and_then(|c| c.categories.as_ref())
+        .and_then(|v| v.as_array())
+        .map(|names| {
+            names
+                .iter()
+                .filter_map(|n| n.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+    let mut categories = Vec::with_capacity(names.len());
+    let mut service = CoinCategoryService::new(conn);
+    for name in names {
+        let filter = CoinCategoriesFilter {
+            name: Some(name),
+            name_like: None,
+            market_cap: None,
+            volume_24h: None,
+            sort_by_rank: None,
+            page: None,
+            page_size: None,
+        };
+        categories.extend(service.query_page_with_total(filter, 0, 1)?.data);
+    }
+
+    let description = coin
+        .as_ref()
+        .and_then(|c| c.description.as_ref())
+        .and_then(|d| d.get("en"))
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+        .map(str::to_string);
+
+    Ok(SymbolOverview {
+        symbol,
+        coin_id: coin
+            .map(|c| c.id)
+            .or_else(|| rank.as_ref().map(|r| r.id.clone())),
+        rank,
+        categories,
+        description,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn category_query(order: Option<&str>, min_market_cap: Option<&str>) -> CoinCategoryQuery {
+        CoinCategoryQuery {
+            name: None,
+            name_like: Some("meme".to_string()),
+            min_market_cap: min_market_cap.map(str::to_string),
+            min_volume_24h: None,
+            order: order.map(str::to_string),
+            page: None,
+            page_size: None,
+        }
+    }
+
+    #[test]
+    fn test_category_filter() {
+        let filter = category_filter(&category_query(Some("desc"), Some("1000000.5"))).unwrap();
+        assert_eq!(filter.name_like.as_deref(), Some("meme"));
+        assert_eq!(
+            filter.market_cap,
+            Some(BigDecimal::from_str("1000000.5").unwrap())
+        );
+        assert!(filter.volume_24h.is_none());
+        assert!(matches!(filter.sort_by_rank, Some(SortOrder::Desc)));
+
+        assert!(category_filter(&category_query(None, Some("lots"))).is_err());
+        assert!(category_filter(&category_query(Some("up"), None)).is_err());
+    }
+}
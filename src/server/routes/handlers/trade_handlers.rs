@@ -0,0 +1,113 @@
+use crate::collector::trades::bars::{build_bars, BarSpec};
+use crate::collector::trades::consistency::{check_consistency, DEFAULT_TOLERANCE};
+use crate::collector::trades::EXCHANGE;
+use crate::global::get_ck_db;
+use crate::model::market_type::MarketType;
+use crate::model::TimeFrame;
+use crate::server::response::error_reply;
+use serde::Deserialize;
+use std::str::FromStr;
+use warp::http::StatusCode;
+use warp::{Rejection, Reply};
+
+/// 单次查询允许的最大时间跨度（成交数据量大，限制为 24 小时）
+const MAX_RANGE_MS: i64 = 86_400_000;
+
+/// 成交聚合K线查询参数，时间范围为 [start, end)（毫秒）
+#[derive(Debug, Deserialize)]
+pub struct TradeBarsQuery {
+    pub market_type: Option<String>,
+    pub symbol: String,
+    /// time / volume / dollar
+    pub kind: String,
+    /// 时间K线为周期（如 1m），成交量 / 成交额K线为阈值
+    pub size: String,
+    pub start: i64,
+    pub end: i64,
+}
+
+/// 一致性检查参数，时间范围为 [start, end)（毫秒）
+#[derive(Debug, Deserialize)]
+pub struct TradeConsistencyQuery {
+    pub market_type: Option<String>,
+    pub symbol: String,
+    pub period: String,
+    pub start: i64,
+    pub end: i64,
+    pub tolerance: Option<f64>,
+}
+
+fn parse_market_type(market_type: Option<&str>) -> Result<MarketType, String> {
+    market_type.map_or(Ok(MarketType::UsdM), MarketType::from_str)
+}
+
+fn validate_range(start: i64, end: i64) -> Result<(), String> {
+    if start >= end {
+        return Err(format!("start ({}) must be less than end ({})", start, end));
+    }
+    if end - start > MAX_RANGE_MS {
+        return Err(format!("Time range exceeds {} ms", MAX_RANGE_MS));
+    }
+    Ok(())
+}
+
+/// GET /api/trades/bars：由已存储的归集成交聚合时间 / 成交量 / 成交额K线
+pub async fn trade_bars(params: TradeBarsQuery) -> Result<impl Reply, Rejection> {
+    let market_type = match parse_market_type(params.market_type.as_deref()) {
+        Ok(m) => m,
+        Err(e) => return Ok(error_reply(StatusCode::BAD_REQUEST, e)),
+    };
+    let spec = match BarSpec::parse(&params.kind, &params.size) {
+        Ok(spec) => spec,
+        Err(e) => return Ok(error_reply(StatusCode::BAD_REQUEST, e)),
+    };
+    if let Err(e) = validate_range(params.start, params.end) {
+        return Ok(error_reply(StatusCode::BAD_REQUEST, e));
+    }
+
+    let symbol = params.symbol.to_uppercase();
+    match get_ck_db()
+        .query_agg_trades(
+            EXCHANGE,
+            market_type.as_str(),
+            &symbol,
+            params.start,
+            params.end,
+        )
+        .await
+    {
+        Ok(trades) => Ok(warp::reply::json(&build_bars(&trades, &spec)).into_response()),
+        Err(e) => Ok(error_reply(StatusCode::INTERNAL_SERVER_ERROR, e)),
+    }
+}
+
+/// GET /api/trades/consistency：比对成交聚合的时间K线与 market_klines
+pub async fn trade_consistency(params: TradeConsistencyQuery) -> Result<impl Reply, Rejection> {
+    let market_type = match parse_market_type(params.market_type.as_deref()) {
+        Ok(m) => m,
+        Err(e) => return Ok(error_reply(StatusCode::BAD_REQUEST, e)),
+    };
+    let time_frame = match TimeFrame::from_str(&params.period) {
+        Ok(tf) => tf,
+        Err(e) => return Ok(error_reply(StatusCode::BAD_REQUEST, e)),
+    };
+    if let Err(e) = validate_range(params.start, params.end) {
+        return Ok(error_reply(StatusCode::BAD_REQUEST, e));
+    }
+
+    let symbol = params.symbol.to_uppercase();
+    let tolerance = params.tolerance.unwrap_or(DEFAULT_TOLERANCE).max(0.0);
+    match check_consistency(
+        market_type,
+        &symbol,
+        &time_frame,
+        params.start,
+        params.end,
+        tolerance,
+    )
+    .await
+    {
+        Ok(report) => Ok(warp::reply::json(&report).into_response()),
+        Err(e) => Ok(error_reply(StatusCode::INTERNAL_SERVER_ERROR, e)),
+    }
+}